@@ -0,0 +1,68 @@
+use crate::{ UserVotingAccount, VoteMainAccount };
+
+// Result of recounting a voting from its user vote accounts
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TallyReport {
+    pub records_counted: usize,
+    // Indices into `records` of ballots the voting's tally mode rejects
+    pub invalid_records: Vec<usize>,
+    // Recounted from the records, in option order
+    pub expected_tallies: Vec<u128>,
+    pub onchain_tallies: Vec<u128>,
+    // Options whose on-chain tally differs from the recount
+    pub mismatched_options: Vec<u8>,
+    pub tally_state_matches: bool
+}
+
+impl TallyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.invalid_records.is_empty() && self.mismatched_options.is_empty() && self.tally_state_matches
+    }
+}
+
+// Recounts the voting from all of its user vote accounts, fetched by the auditor. Token ballots are
+// counted without user vote accounts, so a voting with a ballot box shows them as a mismatch.
+pub fn verify_tally(records: &[UserVotingAccount], voting: &VoteMainAccount) -> TallyReport {
+    let mut expected_tallies = vec![0; voting.options.len()];
+    let mut expected_state = voting.tally_state.restarted();
+    let mut invalid_records = Vec::new();
+    let mut records_counted = 0;
+
+    for (index, record) in records.iter().enumerate() {
+        let counted = voting.tally_mode.validate_ballot(&record.ballot, voting.options.len())
+            .and_then(|_| voting.tally_mode.accumulate(&mut expected_tallies, &mut expected_state, &record.ballot, record.weight, true));
+
+        match counted {
+            Ok(()) => records_counted += 1,
+            Err(_) => invalid_records.push(index)
+        };
+    };
+
+    let expected_tallies = expected_state.totals(&expected_tallies);
+    let onchain_tallies = voting.tally_state.totals(&voting.tallies);
+    let mismatched_options = (0..voting.options.len())
+        .filter(|option| onchain_tallies.get(*option) != Some(&expected_tallies[*option]))
+        .map(|option| option as u8)
+        .collect();
+
+    // Instant-runoff rankings are kept in no particular order.
+    let mut expected_rankings = expected_state.rankings.clone();
+    let mut onchain_rankings = voting.tally_state.rankings.clone();
+    expected_rankings.sort_by(|a, b| a.ranking.cmp(&b.ranking));
+    onchain_rankings.sort_by(|a, b| a.ranking.cmp(&b.ranking));
+    let tally_state_matches = expected_state.scores == voting.tally_state.scores
+        && expected_rankings == onchain_rankings
+        && expected_state.spoiled == voting.tally_state.spoiled
+        && expected_state.spoiled_weight == voting.tally_state.spoiled_weight
+        && expected_state.cast_weight == voting.tally_state.cast_weight;
+
+    TallyReport {
+        records_counted,
+        invalid_records,
+        expected_tallies,
+        onchain_tallies,
+        mismatched_options,
+        tally_state_matches
+    }
+}
@@ -0,0 +1,56 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{ keccak::hash, program_error::ProgramError, pubkey::Pubkey };
+
+use crate::{ Errors, MAX_OPTIONS };
+
+// Buckets a voting can spread its records over, and the slots of a bucket
+pub const MAX_RECORD_BUCKETS: u8 = 64;
+pub const BUCKET_SLOTS: usize = 128;
+pub const RECORD_SLOT_SPACE: usize = 32 + 8 + 8 + 1 + MAX_OPTIONS;
+
+// A voter's ballot in a shared bucket, in place of a user vote account. Empty slots have the default voter.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecordSlot {
+    pub voter: Pubkey,
+    pub weight: u64,
+    // See `UserVotingAccount::vote_index`
+    pub vote_index: u64,
+    pub ballot_len: u8,
+    // Padded to `MAX_OPTIONS`, so every slot has the same size
+    pub ballot: [u8; MAX_OPTIONS]
+}
+
+impl RecordSlot {
+    // `ballot` is validated, so it's at most `MAX_OPTIONS` long
+    pub fn new(voter: Pubkey, weight: u64, vote_index: u64, ballot: &[u8]) -> Self {
+        let mut slot = Self { voter, weight, vote_index, ballot_len: ballot.len() as u8, ballot: [0; MAX_OPTIONS] };
+        slot.ballot[..ballot.len()].copy_from_slice(ballot);
+
+        slot
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voter == Pubkey::default()
+    }
+
+    pub fn ballot(&self) -> &[u8] {
+        &self.ballot[..self.ballot_len as usize]
+    }
+}
+
+pub fn bucket_of(voter: &Pubkey, buckets: u8) -> u8 {
+    hash(voter.as_ref()).0[0] % buckets
+}
+
+// Open addressing: probing starts at a slot picked by the voter's hash and moves on to the next slot
+// while it's taken by another voter. Returns the voter's slot, or the free slot their ballot goes to.
+pub fn probe(slots: &[RecordSlot], voter: &Pubkey) -> Result<usize, ProgramError> {
+    let digest = hash(voter.as_ref()).0;
+    let start = u16::from_le_bytes([digest[1], digest[2]]) as usize;
+
+    (0..slots.len())
+        .map(|step| (start + step) % slots.len())
+        .find(|index| slots[*index].voter == *voter || slots[*index].is_empty())
+        .ok_or(Errors::BucketFull.into())
+}
@@ -0,0 +1,93 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey
+};
+
+use crate::{ state::StateVersion, AccountType, Errors };
+
+// Instructions take a fixed list of accounts, so extra accounts are rejected instead of ignored.
+pub fn assert_accounts_len(accounts: &[AccountInfo], expected: usize) -> ProgramResult {
+    if accounts.len() < expected {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if accounts.len() > expected {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    Ok(())
+}
+
+pub fn assert_signer(account: &AccountInfo) -> ProgramResult {
+    if account.is_signer == false {
+        return Err(Errors::UserSigningNeeded.into());
+    };
+
+    Ok(())
+}
+
+// User and PDA accounts report different errors, so the caller picks which one applies.
+pub fn assert_writable(account: &AccountInfo, error: Errors) -> ProgramResult {
+    if account.is_writable == false {
+        return Err(error.into());
+    };
+
+    Ok(())
+}
+
+pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner != owner {
+        return Err(Errors::InvalidAccountOwner.into());
+    };
+
+    Ok(())
+}
+
+// Checks a CPI target is the expected program, a spoofed account with the right key can't be executable.
+pub fn assert_program(account: &AccountInfo, program_id: &Pubkey, error: Errors) -> ProgramResult {
+    if account.key != program_id || account.executable == false {
+        return Err(error.into());
+    };
+
+    Ok(())
+}
+
+// Takes the result of one of the `find_*_pda` helpers and returns the canonical bump, for signing
+// CPIs that create the account.
+pub fn assert_pda(account: &AccountInfo, (pda_addr, bump): (Pubkey, u8)) -> Result<u8, ProgramError> {
+    if *account.key != pda_addr {
+        return Err(Errors::InvalidPdaAddress.into());
+    };
+
+    Ok(bump)
+}
+
+// Created accounts are zeroed, so any set byte means live state would be overwritten.
+pub fn assert_uninitialized(account: &AccountInfo) -> ProgramResult {
+    if account.data.borrow().iter().any(|byte| *byte != 0) {
+        return Err(Errors::AlreadyInitialized.into());
+    };
+
+    Ok(())
+}
+
+// Accounts are read in the current layout only, one in another layout would be parsed at the wrong
+// offsets.
+pub fn assert_account_type(data: &[u8], account_type: AccountType) -> ProgramResult {
+    if AccountType::from_discriminator(data) != Some(account_type) {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    assert_current_version(data)
+}
+
+// For loaders that accept several account types. The version byte follows the discriminator.
+pub fn assert_current_version(data: &[u8]) -> ProgramResult {
+    if data.get(8).copied().and_then(StateVersion::from_byte) != Some(StateVersion::CURRENT) {
+        return Err(Errors::UnknownAccountVersion.into());
+    };
+
+    Ok(())
+}
@@ -0,0 +1,2775 @@
+// Results carry the RPC client's own `ClientError` so callers can match on its kind, boxing it would
+// hide that behind another type.
+#![allow(clippy::result_large_err)]
+
+use borsh::{ BorshDeserialize, BorshSerialize };
+use num_traits::FromPrimitive;
+use solana_client::{
+    client_error::{ ClientErrorKind, Result as ClientResult },
+    nonce_utils,
+    rpc_client::RpcClient,
+    rpc_config::{ RpcAccountInfoConfig, RpcSimulateTransactionConfig },
+    rpc_response::RpcSimulateTransactionResult
+};
+use solana_program::{
+    address_lookup_table::{
+        instruction::{ create_lookup_table, extend_lookup_table },
+        state::AddressLookupTable,
+        AddressLookupTableAccount
+    },
+    bpf_loader_upgradeable,
+    borsh0_10::try_from_slice_unchecked,
+    instruction::{ AccountMeta, Instruction, InstructionError },
+    keccak::{ hash, hashv },
+    message::{ v0, Message, VersionedMessage },
+    pubkey::Pubkey,
+    system_instruction::{ advance_nonce_account, transfer },
+    system_program,
+    sysvar
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    signature::{ Signature, Signer },
+    transaction::{ Transaction, VersionedTransaction }
+};
+
+use crate::{
+    draw::RandomDraw,
+    tally::{ shard_of, Apportionment, SeatMethod, TiePolicy },
+    AccountType,
+    Electorate,
+    Errors,
+    InstructionType,
+    find_membership_pda,
+    voting_account_space,
+    find_config_pda,
+    find_user_vote_pda,
+    find_voter_index_pda,
+    voter_index_entries,
+    VoterIndexAccount,
+    find_slot_pda,
+    find_slot_claim_pda,
+    VoteInstruction,
+    CastEnvelopeInstruction,
+    CommitVoteInstruction,
+    RevealVoteInstruction,
+    vote_commitment,
+    SubmitEnvelopeResultsInstruction,
+    UpdateVoteInstruction,
+    AddWeightInstruction,
+    GetVoteWeightInstruction,
+    ClaimSlotInstruction,
+    weight::WeightProof,
+    eligibility::EligibilityProof,
+    execution::{ AllowedAction, ProposalAccountMeta, ProposalInstruction, TemplateValue },
+    find_proposal_pda,
+    find_proposal_template_pda,
+    find_execution_allowlist_pda,
+    find_governance_pda,
+    find_voter_allowlist_pda,
+    find_voter_allowlist_page_pda,
+    find_vote_bucket_pda,
+    find_title_reservation_pda,
+    find_faucet_pda,
+    find_faucet_claim_pda,
+    find_voting_registry_pda,
+    find_voting_registry_page_pda,
+    registry_entries,
+    RegistryEntry,
+    VotingRegistryAccount,
+    MAX_REGISTRY_PAGE_ENTRIES,
+    find_voting_archive_pda,
+    find_record_tree_pda,
+    find_flag_pda,
+    find_incentive_pool_pda,
+    find_localized_metadata_pda,
+    SetLocalizedMetadataInstruction,
+    find_notification_target_pda,
+    SetNotificationTargetInstruction,
+    FundIncentivesInstruction,
+    FundRewardsInstruction,
+    BoostInstruction,
+    HideVotingInstruction,
+    ModerationStatus,
+    merkle::{ record_leaf, RecordTree, RECORD_TREE_DEPTH },
+    VerifyBallotProofInstruction,
+    UserVotingAccount,
+    EnvelopeAccount,
+    VoteBucketAccount,
+    voting_title_seed,
+    ReserveTitleInstruction,
+    CloneVotingInstruction,
+    CreateElectionInstruction,
+    ExtendVotingInstruction,
+    find_election_pda,
+    find_voting_pda,
+    find_creator_stats_pda,
+    find_title_index_pda,
+    find_realm_pda,
+    find_realm_voting_pda,
+    PublishVotingInstruction,
+    buckets::bucket_of,
+    AllocateVoteBucketInstruction,
+    AppendAllowlistChunkInstruction,
+    SetAllowlistRootInstruction,
+    SealVoterRollInstruction,
+    EditVotingInstruction,
+    UpdateTitleInstruction,
+    VotingTitle,
+    SetLateRegistrationInstruction,
+    MAX_ALLOWLIST_CHUNK,
+    MAX_ALLOWLIST_PAGE_VOTERS,
+    AddProposalInstructionInstruction,
+    AddTemplateInstructionInstruction,
+    ProposalAccount,
+    VoteMainAccount,
+    VotingStatus,
+    GetOptionCapacityInstruction,
+    GetPercentagesInstruction,
+    find_tally_shard_pda,
+    find_recount_pda,
+    find_reward_escrow_pda,
+    find_reward_claim_pda,
+    find_boost_pda,
+    find_boost_deposit_pda,
+    find_ballot_box_pda,
+    find_metadata_pda,
+    find_kiosk_pda,
+    find_poll_card_pda,
+    VoteByTransferClaimInstruction,
+    LinkProposalNoteInstruction,
+    AllocateTallyShardInstruction,
+    CastShardedVoteInstruction,
+    CreateVotingInstruction,
+    CreateRealmInstruction,
+    CreateRealmVotingInstruction,
+    VotingSettingsOverrides,
+    eligibility::EligibilityPolicy,
+    tally::TallyMode,
+    weight::WeightSource,
+    DEFAULT_VOTING_SETTINGS,
+    INSTRUCTION_HEADER_LEN
+};
+
+// Compute unit ceiling of each instruction, with headroom for the memo CPI
+pub const VOTE_COMPUTE_UNITS: u32 = 40_000;
+
+// Percentile of the recent prioritization fees paid for the same writable accounts
+const PRIORITY_FEE_PERCENTILE: usize = 75;
+
+// The program's error behind a failed instruction, e.g. from `TransactionError::InstructionError`. `None`
+// for runtime errors and codes the program doesn't know, such as those of a newer version.
+pub fn decode_error(error: &InstructionError) -> Option<Errors> {
+    match error {
+        InstructionError::Custom(code) => Errors::from_u32(*code),
+        _ => None
+    }
+}
+
+// An open voting starting now and ending `duration` seconds later, one vote per voter counted with
+// `TallyMode::Simple`. Ballots can be updated until it ends.
+pub fn create_voting(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    title: String,
+    description: String,
+    options: Vec<String>,
+    duration: u64
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(find_voting_pda(&title, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(0, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CreateVoting.header();
+    CreateVotingInstruction {
+        starts_at: 0,
+        ends_at: duration,
+        title,
+        description,
+        options,
+        co_creators: Vec::new(),
+        creator_threshold: 1,
+        weight_source: WeightSource::Fixed(1),
+        tally_mode: TallyMode::Simple,
+        eligibility: EligibilityPolicy { clauses: Vec::new() },
+        option_caps: Vec::new(),
+        signup_slots: 0,
+        random_draw: false,
+        oracle_condition: None,
+        relative_times: true,
+        weight_mints: Vec::new(),
+        weight_precision: None,
+        large_supply: false,
+        record_buckets: 0,
+        draft: false,
+        required_stages: 0,
+        envelope_key: None,
+        tie_policy: TiePolicy::LowestIndex,
+        dependency: None,
+        program_creator: None,
+        turnout_milestones: None,
+        hide_tallies: false,
+        result_attestors: None,
+        tally_shards: 0,
+        weight_cap_per_slot: 0,
+        reject_encumbered_tokens: false,
+        reveal_window: 0,
+        winners_count: 1,
+        apportionment: None,
+        min_participation: 0,
+        pass_threshold_bps: 0,
+        electorate: None,
+        quorum_bps: 0
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// A realm named `name` with the default voting settings, none of them locked, open to every creator for free
+pub fn create_realm(program_id: &Pubkey, admin: &Pubkey, name: String) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*admin, true),
+        AccountMeta::new(find_realm_pda(&name, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CreateRealm.header();
+    CreateRealmInstruction { name, defaults: DEFAULT_VOTING_SETTINGS, locked_settings: 0, creation_fee: 0, creators_gated: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// The voting `voting_id` of `realm`, its `votings_count` before the voting is created, otherwise like
+// `create_voting` with the realm's settings
+#[allow(clippy::too_many_arguments)]
+pub fn create_realm_voting(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    realm: &Pubkey,
+    realm_admin: &Pubkey,
+    voting_id: u64,
+    title: String,
+    options: Vec<String>,
+    duration: u64
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*realm, false),
+        AccountMeta::new(*realm_admin, false),
+        AccountMeta::new(find_realm_voting_pda(realm, voting_id, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(0, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let payload = CreateVotingInstruction::new(title, options, 0, duration);
+    let mut data = InstructionType::CreateRealmVoting.header();
+    CreateRealmVotingInstruction {
+        starts_at: payload.starts_at,
+        ends_at: payload.ends_at,
+        title: payload.title,
+        description: payload.description,
+        options: payload.options,
+        co_creators: payload.co_creators,
+        weight_source: payload.weight_source,
+        tally_mode: payload.tally_mode,
+        eligibility: payload.eligibility,
+        option_caps: payload.option_caps,
+        signup_slots: payload.signup_slots,
+        random_draw: payload.random_draw,
+        oracle_condition: payload.oracle_condition,
+        relative_times: true,
+        weight_mints: payload.weight_mints,
+        weight_precision: payload.weight_precision,
+        large_supply: payload.large_supply,
+        record_buckets: payload.record_buckets,
+        draft: payload.draft,
+        required_stages: payload.required_stages,
+        envelope_key: payload.envelope_key,
+        tie_policy: payload.tie_policy,
+        dependency: payload.dependency,
+        program_creator: payload.program_creator,
+        turnout_milestones: payload.turnout_milestones,
+        hide_tallies: payload.hide_tallies,
+        result_attestors: payload.result_attestors,
+        tally_shards: payload.tally_shards,
+        weight_cap_per_slot: payload.weight_cap_per_slot,
+        reject_encumbered_tokens: payload.reject_encumbered_tokens,
+        reveal_window: payload.reveal_window,
+        min_participation: payload.min_participation,
+        pass_threshold_bps: payload.pass_threshold_bps,
+        apportionment: payload.apportionment,
+        winners_count: payload.winners_count,
+        electorate: payload.electorate,
+        quorum_bps: payload.quorum_bps,
+        overrides: VotingSettingsOverrides {
+            min_voting_time: None,
+            max_voting_time: None,
+            creator_threshold: None,
+            allow_updates: None,
+            members_only: None
+        }
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Proofs some votings need with the ballot, see `WeightSource::MerkleSnapshot` and `EligibilityPolicy`
+#[derive(Default)]
+pub struct VoteProofs {
+    pub weight: Option<WeightProof>,
+    pub eligibility: Option<EligibilityProof>
+}
+
+// `voting` is `find_voting_pda(&vote_title)` for votings outside a realm and `find_realm_voting_pda` in one.
+// The voting is added to the first page of the voter index, see `set_voter_index_page`. An empty `ballot`
+// is a spoiled one, counted in turnout but towards no option.
+pub fn vote(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    ballot: Vec<u8>,
+    vote_title: String,
+    memo: bool,
+    proofs: VoteProofs
+) -> Instruction {
+    let (user_vote, _) = find_user_vote_pda(voting, user, program_id);
+    let (voter_index, _) = find_voter_index_pda(user, 0, program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(user_vote, false),
+        AccountMeta::new(voter_index, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    if memo {
+        accounts.push(AccountMeta::new_readonly(spl_memo::id(), false));
+    };
+
+    let mut data = InstructionType::Vote.header();
+    VoteInstruction { ballot, vote_title, memo, weight_proof: proofs.weight, eligibility_proof: proofs.eligibility, dry_run: false, voter_index_page: 0 }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Replaces the ballot `user` cast with `vote`, the weight it was counted with stays
+pub fn update_vote(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey, ballot: Vec<u8>, memo: bool) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_user_vote_pda(voting, user, program_id).0, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    if memo {
+        accounts.push(AccountMeta::new_readonly(spl_memo::id(), false));
+    };
+
+    let mut data = InstructionType::UpdateVote.header();
+    UpdateVoteInstruction { ballot, memo, dry_run: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// `envelope` is the ballot sealed to the voting's `envelope_key` with libsodium's `crypto_box_seal`. Gate
+// and weight accounts are added the same way as for `vote`.
+pub fn cast_envelope(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    envelope: Vec<u8>,
+    vote_title: String,
+    proofs: VoteProofs
+) -> Instruction {
+    let (user_vote, _) = find_user_vote_pda(voting, user, program_id);
+    let (voter_index, _) = find_voter_index_pda(user, 0, program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(user_vote, false),
+        AccountMeta::new(voter_index, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CastEnvelope.header();
+    CastEnvelopeInstruction { envelope, vote_title, weight_proof: proofs.weight, eligibility_proof: proofs.eligibility, voter_index_page: 0, dry_run: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Commits to `ballot` on a commit-reveal voting. `salt` should be random and kept with the ballot, both
+// are needed by `reveal_vote` once the voting ended. Gate and weight accounts are added as for `vote`.
+pub fn commit_vote(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    ballot: &[u8],
+    salt: &[u8; 32],
+    vote_title: String,
+    proofs: VoteProofs
+) -> Instruction {
+    let (user_vote, _) = find_user_vote_pda(voting, user, program_id);
+    let (voter_index, _) = find_voter_index_pda(user, 0, program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(user_vote, false),
+        AccountMeta::new(voter_index, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CommitVote.header();
+    CommitVoteInstruction {
+        commitment: vote_commitment(ballot, salt, user),
+        vote_title,
+        weight_proof: proofs.weight,
+        eligibility_proof: proofs.eligibility,
+        voter_index_page: 0,
+        dry_run: false
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Counts the ballot committed with `commit_vote`, between `ends_at` and the end of the reveal window
+pub fn reveal_vote(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey, ballot: Vec<u8>, salt: [u8; 32]) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_user_vote_pda(voting, user, program_id).0, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::RevealVote.header();
+    RevealVoteInstruction { ballot, salt }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// The transfer to the voting's kiosk, the memo carrying the ballot and the claim recording it, in the
+// order `vote_by_transfer_claim` expects. Servers answering Solana Pay transaction requests for a QR code
+// return them to the scanning wallet. Gate and weight accounts are added to the claim as for `vote`.
+pub fn vote_by_transfer(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    ballot: &[u8],
+    lamports: u64,
+    vote_title: String,
+    proofs: VoteProofs
+) -> Vec<Instruction> {
+    let (kiosk, _) = find_kiosk_pda(voting, program_id);
+    let memo = ballot.iter().map(|option| option.to_string()).collect::<Vec<_>>().join(",");
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(kiosk, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_user_vote_pda(voting, user, program_id).0, false),
+        AccountMeta::new(find_voter_index_pda(user, 0, program_id).0, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::VoteByTransferClaim.header();
+    VoteByTransferClaimInstruction {
+        vote_title,
+        weight_proof: proofs.weight,
+        eligibility_proof: proofs.eligibility,
+        voter_index_page: 0,
+        dry_run: false
+    }.serialize(&mut data).unwrap();
+
+    vec![
+        transfer(user, &kiosk, lamports),
+        spl_memo::build_memo(memo.as_bytes(), &[]),
+        Instruction { program_id: *program_id, accounts, data }
+    ]
+}
+
+pub fn withdraw_kiosk(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_kiosk_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::WithdrawKiosk.header() }
+}
+
+// Tops up the faucet `claim_faucet` pays from, it's a plain system transfer
+pub fn fund_faucet(program_id: &Pubkey, funder: &Pubkey, lamports: u64) -> Instruction {
+    transfer(funder, &find_faucet_pda(program_id).0, lamports)
+}
+
+// Pays `wallet` the rent of its first vote on `voting`, see `faucet_drip`. Programs built without the
+// `devnet` feature fail it with `FaucetDisabled`.
+pub fn claim_faucet(program_id: &Pubkey, wallet: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*wallet, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new_readonly(find_user_vote_pda(voting, wallet, program_id).0, false),
+        AccountMeta::new(find_faucet_pda(program_id).0, false),
+        AccountMeta::new(find_faucet_claim_pda(wallet, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::ClaimFaucet.header() }
+}
+
+// Ballots of sharded votings, counted in the voter's shard. Gate and weight accounts are added the same
+// way as for `vote`, the voting is added to the first page of the voter index.
+pub fn cast_sharded_vote(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    tally_shards: u8,
+    ballot: Vec<u8>,
+    proofs: VoteProofs
+) -> Instruction {
+    let (user_vote, _) = find_user_vote_pda(voting, user, program_id);
+    let (voter_index, _) = find_voter_index_pda(user, 0, program_id);
+    let (shard, _) = find_tally_shard_pda(voting, shard_of(user, tally_shards), program_id);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(user_vote, false),
+        AccountMeta::new(voter_index, false),
+        AccountMeta::new(shard, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CastShardedVote.header();
+    CastShardedVoteInstruction { ballot, weight_proof: proofs.weight, eligibility_proof: proofs.eligibility, voter_index_page: 0, dry_run: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Points a `vote`, `cast_envelope` or `cast_sharded_vote` instruction at another page of the voter index, the one from
+// `voter_index_tail` once the voter took part in more than `MAX_VOTER_INDEX_PAGE_ENTRIES` votings. Other
+// instructions are left unchanged.
+pub fn set_voter_index_page(mut instruction: Instruction, user: &Pubkey, page: u16) -> Instruction {
+    let (header, payload) = instruction.data.split_at(INSTRUCTION_HEADER_LEN.min(instruction.data.len()));
+    let payload = match InstructionType::from_discriminator(header) {
+        Some(InstructionType::Vote) => VoteInstruction::try_from_slice(payload)
+            .map(|payload| VoteInstruction { voter_index_page: page, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::CastEnvelope) => CastEnvelopeInstruction::try_from_slice(payload)
+            .map(|payload| CastEnvelopeInstruction { voter_index_page: page, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::CastShardedVote) => CastShardedVoteInstruction::try_from_slice(payload)
+            .map(|payload| CastShardedVoteInstruction { voter_index_page: page, ..payload }.try_to_vec().unwrap()),
+        _ => return instruction
+    };
+
+    if let Ok(payload) = payload {
+        instruction.data.truncate(INSTRUCTION_HEADER_LEN);
+        instruction.data.extend(payload);
+        // After the config, the user, the voting and the user vote account
+        instruction.accounts[4].pubkey = find_voter_index_pda(user, page, &instruction.program_id).0;
+    };
+
+    instruction
+}
+
+// Points a `create_voting` or `clone_voting` instruction at another page of the voting registry, the one
+// from `voting_registry_page` once the registry lists more than `MAX_REGISTRY_PAGE_ENTRIES` votings
+pub fn set_registry_page(mut instruction: Instruction, page: u32) -> Instruction {
+    let first_page = find_voting_registry_page_pda(0, &instruction.program_id).0;
+    let registry_page = find_voting_registry_page_pda(page, &instruction.program_id).0;
+    for account in instruction.accounts.iter_mut().filter(|account| account.pubkey == first_page) {
+        account.pubkey = registry_page;
+    };
+
+    instruction
+}
+
+// Rewrites the payload of a `create_voting` instruction, other instructions are left unchanged
+fn edit_create_voting(mut instruction: Instruction, edit: impl FnOnce(CreateVotingInstruction) -> CreateVotingInstruction) -> Instruction {
+    let (header, payload) = instruction.data.split_at(INSTRUCTION_HEADER_LEN.min(instruction.data.len()));
+    if InstructionType::from_discriminator(header) != Some(InstructionType::CreateVoting) {
+        return instruction;
+    };
+
+    if let Ok(payload) = CreateVotingInstruction::try_from_slice(payload) {
+        instruction.data.truncate(INSTRUCTION_HEADER_LEN);
+        edit(payload).serialize(&mut instruction.data).unwrap();
+    };
+
+    instruction
+}
+
+// Elects the `winners_count` options with the highest counts of a `create_voting` instruction, see
+// `tally::top_options`
+pub fn set_winners_count(instruction: Instruction, winners_count: u8) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { winners_count, ..payload })
+}
+
+// Weighs the ballots of a `create_voting` instruction by `weight_source`, see `add_weight_account`
+pub fn set_weight_source(instruction: Instruction, weight_source: WeightSource) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { weight_source, ..payload })
+}
+
+// Has finalization share `seats` seats out among the options of a `create_voting` instruction, see
+// `tally::apportion`
+pub fn set_apportionment(instruction: Instruction, method: SeatMethod, seats: u16) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { apportionment: Some(Apportionment { method, seats }), ..payload })
+}
+
+// Sets the quorum and pass threshold of a `create_voting` instruction, see `VotingOutcome`
+pub fn set_pass_rules(instruction: Instruction, min_participation: u32, pass_threshold_bps: u16) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { min_participation, pass_threshold_bps, ..payload })
+}
+
+// Measures the turnout of a `create_voting` instruction against `electorate`, the voting needing
+// `quorum_bps` of it to pass
+pub fn set_electorate(instruction: Instruction, electorate: Electorate, quorum_bps: u16) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { electorate: Some(electorate), quorum_bps, ..payload })
+}
+
+// Opens `signup_slots` first-come slots on a `create_voting` instruction, see `claim_slot`
+pub fn set_signup_slots(instruction: Instruction, signup_slots: u32) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { signup_slots, ..payload })
+}
+
+// Has the voters of a `create_voting` instruction commit to their ballots and reveal them within
+// `reveal_window` seconds of the end, see `commit_vote`
+pub fn set_reveal_window(instruction: Instruction, reveal_window: u64) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { reveal_window, ..payload })
+}
+
+// Seals the ballots of a `create_voting` instruction to the creator's X25519 `envelope_key`, see
+// `cast_envelope`
+pub fn set_envelope_key(instruction: Instruction, envelope_key: [u8; 32]) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { envelope_key: Some(envelope_key), ..payload })
+}
+
+// Withholds the running tallies of a `create_voting` instruction until `ends_at`
+pub fn set_hide_tallies(instruction: Instruction) -> Instruction {
+    edit_create_voting(instruction, |payload| CreateVotingInstruction { hide_tallies: true, ..payload })
+}
+
+// `commitment` is keccak over the decrypted ballots, each prefixed with its length, in casting order
+pub fn submit_envelope_results(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, tallies: Vec<u64>, commitment: [u8; 32]) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false)
+    ];
+
+    let mut data = InstructionType::SubmitEnvelopeResults.header();
+    SubmitEnvelopeResultsInstruction { tallies, commitment }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Commitment over the decrypted ballots of an envelope voting, for `submit_envelope_results`
+pub fn envelope_commitment(ballots: &[Vec<u8>]) -> [u8; 32] {
+    let mut preimage = Vec::new();
+    for ballot in ballots.iter() {
+        preimage.push(ballot.len() as u8);
+        preimage.extend_from_slice(ballot);
+    };
+
+    hash(&preimage).0
+}
+
+// Credits up to the voting's `weight_cap_per_slot` more of the voter's balance to their ballot, once per
+// slot. The weight accounts are added with `add_weight_account`, as for `vote`.
+pub fn add_weight(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_user_vote_pda(voting, user, program_id).0, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::AddWeight.header();
+    AddWeightInstruction { dry_run: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Simulate it to read the user's weight from the return data, before casting the ballot. The weight
+// accounts are added with `add_weight_account`.
+pub fn get_vote_weight(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey, weight_proof: Option<WeightProof>) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, false),
+        AccountMeta::new_readonly(*voting, false)
+    ];
+
+    let mut data = InstructionType::GetVoteWeight.header();
+    GetVoteWeightInstruction { weight_proof }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Simulate it to read the tallies, the voter count and the current winner from the return data. Fails
+// until `ends_at` for votings hiding their tallies.
+pub fn get_results(program_id: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*voting, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::GetResults.header() }
+}
+
+// Simulate it to read the user's ballot from the return data. Fails for users who haven't voted, their
+// user vote account doesn't exist.
+pub fn get_my_vote(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*user, false),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new_readonly(find_user_vote_pda(voting, user, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::GetMyVote.header() }
+}
+
+// Simulate it to read the cap, tally and remaining capacity of option `index` from the return data
+pub fn get_option_capacity(program_id: &Pubkey, voting: &Pubkey, index: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*voting, false)
+    ];
+
+    let mut data = InstructionType::GetOptionCapacity.header();
+    GetOptionCapacityInstruction { index }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Simulate it to read the creator's dashboard numbers from the return data. Fails for creators who
+// never opened a voting, they have no stats account.
+pub fn get_creator_summary(program_id: &Pubkey, creator: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_creator_stats_pda(creator, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::GetCreatorSummary.header() }
+}
+
+// Simulate it to read each option's share of the tallies with `decimals` decimals, rounded the way
+// `tally::percentages` documents. Fails until `ends_at` for votings hiding their tallies.
+pub fn get_percentages(program_id: &Pubkey, voting: &Pubkey, decimals: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*voting, false)
+    ];
+
+    let mut data = InstructionType::GetPercentages.header();
+    GetPercentagesInstruction { decimals }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Turns a ballot, slot claim or `add_weight` instruction into a dry run, for simulating it before the user
+// signs. The simulation's return data is a `VotePreviewReturnData`, without tallies for envelopes,
+// commitments and sharded ballots. Other instructions are returned unchanged.
+pub fn dry_run(mut instruction: Instruction) -> Instruction {
+    let (header, payload) = instruction.data.split_at(INSTRUCTION_HEADER_LEN.min(instruction.data.len()));
+    let data = match InstructionType::from_discriminator(header) {
+        Some(InstructionType::Vote) => VoteInstruction::try_from_slice(payload)
+            .map(|payload| VoteInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::UpdateVote) => UpdateVoteInstruction::try_from_slice(payload)
+            .map(|payload| UpdateVoteInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::CastEnvelope) => CastEnvelopeInstruction::try_from_slice(payload)
+            .map(|payload| CastEnvelopeInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::CastShardedVote) => CastShardedVoteInstruction::try_from_slice(payload)
+            .map(|payload| CastShardedVoteInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::ClaimSlot) => ClaimSlotInstruction::try_from_slice(payload)
+            .map(|payload| ClaimSlotInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::VoteByTransferClaim) => VoteByTransferClaimInstruction::try_from_slice(payload)
+            .map(|payload| VoteByTransferClaimInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::CommitVote) => CommitVoteInstruction::try_from_slice(payload)
+            .map(|payload| CommitVoteInstruction { dry_run: true, ..payload }.try_to_vec().unwrap()),
+        Some(InstructionType::AddWeight) => Ok(AddWeightInstruction { dry_run: true }.try_to_vec().unwrap()),
+        _ => return instruction
+    };
+
+    if let Ok(data) = data {
+        instruction.data.truncate(INSTRUCTION_HEADER_LEN);
+        instruction.data.extend(data);
+    };
+
+    instruction
+}
+
+// `slot` is the voting's current `claimed_slots`, the claim fails if another one lands first.
+// Members-only and gated votings take the same extra accounts as `vote`.
+pub fn claim_slot(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    slot: u32,
+    eligibility_proof: Option<EligibilityProof>
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new(find_slot_pda(voting, slot, program_id).0, false),
+        AccountMeta::new(find_slot_claim_pda(voting, user, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::ClaimSlot.header();
+    ClaimSlotInstruction { eligibility_proof, dry_run: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Converts an instruction into the format proposals store. Signers other than the realm's governance
+// PDA are rejected by `add_proposal_instruction`.
+pub fn proposal_instruction(instruction: &Instruction) -> ProposalInstruction {
+    ProposalInstruction {
+        program_id: instruction.program_id,
+        accounts: instruction.accounts.iter().map(|meta| ProposalAccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable
+        }).collect(),
+        data: instruction.data.clone()
+    }
+}
+
+// `user` is the voting's creator, `option` the option that has to win for the proposal to execute.
+pub fn add_proposal_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    realm: &Pubkey,
+    option: u8,
+    instruction: &Instruction
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_proposal_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(find_execution_allowlist_pda(realm, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::AddProposalInstruction.header();
+    AddProposalInstructionInstruction { option, instruction: proposal_instruction(instruction) }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Instantiates the realm's template `template_id` with one value per placeholder.
+pub fn add_template_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    voting: &Pubkey,
+    realm: &Pubkey,
+    option: u8,
+    template_id: u16,
+    values: Vec<TemplateValue>
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_proposal_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(find_execution_allowlist_pda(realm, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(find_proposal_template_pda(realm, template_id, program_id).0, false)
+    ];
+
+    let mut data = InstructionType::AddTemplateInstruction.header();
+    AddTemplateInstructionInstruction { option, template_id, values }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Hands the program's upgrades to the realm: the current upgrade authority signs, afterwards only passed
+// proposals of the realm can upgrade the program.
+pub fn bootstrap_upgrade_governance(program_id: &Pubkey, upgrade_authority: &Pubkey, realm: &Pubkey) -> Instruction {
+    bpf_loader_upgradeable::set_upgrade_authority(program_id, upgrade_authority, Some(&find_governance_pda(realm, program_id).0))
+}
+
+// Allowlist entries for the upgrade instructions, see `set_execution_allowlist`
+pub fn upgrade_governance_actions(program_id: &Pubkey) -> Vec<AllowedAction> {
+    [InstructionType::UpgradeProgram, InstructionType::SetUpgradeAuthority]
+        .iter()
+        .map(|instruction_type| AllowedAction { program_id: *program_id, data_prefix: instruction_type.header() })
+        .collect()
+}
+
+fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+// For `add_proposal_instruction`. The buffer's authority has to be set to the realm's governance PDA
+// before the proposal executes, the spill account receives the buffer's lamports.
+pub fn upgrade_program(program_id: &Pubkey, realm: &Pubkey, buffer: &Pubkey, spill: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_governance_pda(realm, program_id).0, true),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(program_data_address(program_id), false),
+        AccountMeta::new(*program_id, false),
+        AccountMeta::new(*buffer, false),
+        AccountMeta::new(*spill, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::UpgradeProgram.header() }
+}
+
+// For `add_proposal_instruction`, `None` makes the program immutable.
+pub fn set_upgrade_authority(program_id: &Pubkey, realm: &Pubkey, new_authority: Option<&Pubkey>) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_governance_pda(realm, program_id).0, true),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(program_data_address(program_id), false),
+        AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false)
+    ];
+    if let Some(new_authority) = new_authority {
+        accounts.push(AccountMeta::new_readonly(*new_authority, false));
+    };
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::SetUpgradeAuthority.header() }
+}
+
+// Simulates the proposal's instructions that are still to execute, as the governance PDA would run
+// them, so voters can see a failing payload before approving it. Signatures aren't verified, so the
+// governance PDA can appear as a signer.
+pub fn simulate_execution(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    voting: &Pubkey,
+    fee_payer: &Pubkey
+) -> ClientResult<RpcSimulateTransactionResult> {
+    let data = rpc.get_account_data(&find_proposal_pda(voting, program_id).0)?;
+    if AccountType::from_discriminator(&data) != Some(AccountType::Proposal) {
+        return Err(ClientErrorKind::Custom("Account is not a proposal.".to_string()).into());
+    };
+
+    let proposal = try_from_slice_unchecked::<ProposalAccount>(&data)
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+    let instructions: Vec<Instruction> = proposal.instructions
+        .iter()
+        .skip(proposal.next_instruction as usize)
+        .map(ProposalInstruction::to_instruction)
+        .collect();
+
+    let transaction = Transaction::new_unsigned(Message::new(&instructions, Some(fee_payer)));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    Ok(rpc.simulate_transaction_with_config(&transaction, config)?.value)
+}
+
+// Result of a finalized voting as seen at `slot`, for off-chain consumers that trust the attester
+// instead of reading the chain themselves
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResultAttestation {
+    pub program_id: Pubkey,
+    pub voting: Pubkey,
+    // The winning option, `None` when the voting was finalized without a winner
+    pub outcome: Option<u8>,
+    pub total_voters: u64,
+    pub vote_chain: [u8; 32],
+    pub slot: u64
+}
+
+// The attester signs the borsh serialized attestation
+#[derive(Debug, Clone)]
+pub struct SignedResultAttestation {
+    pub attestation: ResultAttestation,
+    pub attester: Pubkey,
+    pub signature: Signature
+}
+
+impl SignedResultAttestation {
+    pub fn verify(&self) -> bool {
+        self.signature.verify(self.attester.as_ref(), &self.attestation.try_to_vec().unwrap())
+    }
+}
+
+// Reads the voting at `min_slot` or later and signs its result. Fails unless the account is a
+// finalized voting of the program.
+pub fn attest_result(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    voting: &Pubkey,
+    min_slot: u64,
+    attester: &impl Signer
+) -> ClientResult<SignedResultAttestation> {
+    let config = RpcAccountInfoConfig {
+        min_context_slot: Some(min_slot),
+        ..RpcAccountInfoConfig::default()
+    };
+    let response = rpc.get_account_with_config(voting, config)?;
+    let account = response.value
+        .ok_or(ClientErrorKind::Custom("Voting account not found.".to_string()))?;
+
+    if account.owner != *program_id || AccountType::from_discriminator(&account.data) != Some(AccountType::Vote) {
+        return Err(ClientErrorKind::Custom("Account is not a voting.".to_string()).into());
+    };
+
+    let voting_data = try_from_slice_unchecked::<VoteMainAccount>(&account.data)
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+    if voting_data.status != VotingStatus::Finalized {
+        return Err(ClientErrorKind::Custom("Voting is not finalized.".to_string()).into());
+    };
+
+    if voting_data.result_attestors.as_ref().is_some_and(|result_attestors| result_attestors.is_met() == false) {
+        return Err(ClientErrorKind::Custom("Voting result is waiting for its attestors.".to_string()).into());
+    };
+
+    let attestation = ResultAttestation {
+        program_id: *program_id,
+        voting: *voting,
+        outcome: voting_data.winning_option(),
+        total_voters: voting_data.total_voters,
+        vote_chain: voting_data.vote_chain,
+        slot: response.context.slot
+    };
+    let signature = attester.sign_message(&attestation.try_to_vec().unwrap());
+
+    Ok(SignedResultAttestation { attestation, attester: attester.pubkey(), signature })
+}
+
+// Votings `voter` cast a ballot on, oldest first. Pages of the voter index are fetched as the iteration
+// reaches them, so callers can stop early without loading the whole history.
+pub struct VoterHistory<'a> {
+    rpc: &'a RpcClient,
+    program_id: Pubkey,
+    next_page: Option<Pubkey>,
+    entries: std::vec::IntoIter<Pubkey>
+}
+
+impl VoterHistory<'_> {
+    fn fetch_page(&self, page: &Pubkey) -> ClientResult<Option<(VoterIndexAccount, Vec<Pubkey>)>> {
+        let Some(account) = self.rpc.get_account_with_config(page, RpcAccountInfoConfig::default())?.value else {
+            return Ok(None);
+        };
+
+        if account.owner != self.program_id || AccountType::from_discriminator(&account.data) != Some(AccountType::VoterIndex) {
+            return Err(ClientErrorKind::Custom("Account is not a voter index page.".to_string()).into());
+        };
+
+        let header = try_from_slice_unchecked::<VoterIndexAccount>(&account.data)
+            .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+        let entries = voter_index_entries(&account.data);
+
+        Ok(Some((header, entries)))
+    }
+}
+
+impl Iterator for VoterHistory<'_> {
+    type Item = ClientResult<Pubkey>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(voting) = self.entries.next() {
+                return Some(Ok(voting));
+            };
+
+            let page = self.next_page.take()?;
+            match self.fetch_page(&page) {
+                Ok(Some((header, entries))) => {
+                    self.next_page = (header.next != Pubkey::default()).then_some(header.next);
+                    self.entries = entries.into_iter();
+                },
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err))
+            };
+        }
+    }
+}
+
+pub fn voter_history<'a>(rpc: &'a RpcClient, program_id: &Pubkey, voter: &Pubkey) -> VoterHistory<'a> {
+    VoterHistory {
+        rpc,
+        program_id: *program_id,
+        next_page: Some(find_voter_index_pda(voter, 0, program_id).0),
+        entries: Vec::new().into_iter()
+    }
+}
+
+// Page of the voter index the voter's next ballot goes to, for `set_voter_index_page`
+pub fn voter_index_tail(rpc: &RpcClient, program_id: &Pubkey, voter: &Pubkey) -> ClientResult<u16> {
+    let history = voter_history(rpc, program_id, voter);
+    let mut page = 0u16;
+    while let Some((header, _)) = history.fetch_page(&find_voter_index_pda(voter, page, program_id).0)? {
+        if header.next == Pubkey::default() {
+            break;
+        };
+        page += 1;
+    };
+
+    Ok(page)
+}
+
+// Page of the voting registry the next voting goes to, for `set_registry_page`
+pub fn voting_registry_page(rpc: &RpcClient, program_id: &Pubkey) -> ClientResult<u32> {
+    let Some(account) = rpc.get_account_with_config(&find_voting_registry_pda(program_id).0, RpcAccountInfoConfig::default())?.value else {
+        return Ok(0);
+    };
+
+    let registry = try_from_slice_unchecked::<VotingRegistryAccount>(&account.data)
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+
+    Ok((registry.votings_count / MAX_REGISTRY_PAGE_ENTRIES) as u32)
+}
+
+// Votings listed on a page of the voting registry, in creation order. Empty for a page no voting reached.
+pub fn registry_page_entries(rpc: &RpcClient, program_id: &Pubkey, page: u32) -> ClientResult<Vec<RegistryEntry>> {
+    let page = find_voting_registry_page_pda(page, program_id).0;
+    let Some(account) = rpc.get_account_with_config(&page, RpcAccountInfoConfig::default())?.value else {
+        return Ok(Vec::new());
+    };
+
+    if account.owner != *program_id || AccountType::from_discriminator(&account.data) != Some(AccountType::VotingRegistryPage) {
+        return Err(ClientErrorKind::Custom("Account is not a voting registry page.".to_string()).into());
+    };
+
+    Ok(registry_entries(&account.data))
+}
+
+// Rent `create_voting` moves from the creator into the new voting account, the config's creation deposit
+// comes on top of it.
+pub fn voting_account_rent(
+    rpc: &RpcClient,
+    description: &str,
+    options: &[String],
+    co_creators_count: usize
+) -> ClientResult<u64> {
+    let options_len = options.iter().map(|option| option.len()).sum();
+    let space = voting_account_space(description.len(), options_len, options.len(), co_creators_count)
+        .ok_or(ClientErrorKind::Custom("Description, options or co-creators exceed the program limits.".to_string()))?;
+
+    rpc.get_minimum_balance_for_rent_exemption(space)
+}
+
+// Votings with incentives need their pool right after the other `vote` or `cast_envelope` accounts, before
+// the dependency.
+pub fn add_incentive_pool(vote_instruction: &mut Instruction, program_id: &Pubkey, voting: &Pubkey) {
+    vote_instruction.accounts.push(AccountMeta::new(find_incentive_pool_pda(voting, program_id).0, false));
+}
+
+// Adds `amount` to the rewards shared by the winning option's voters, until the voting ends
+pub fn fund_rewards(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_reward_escrow_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::FundRewards.header();
+    FundRewardsInstruction { amount }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Pays `user` their share of the rewards, pro-rata to the weight they gave the winning option
+pub fn claim_winnings(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new_readonly(find_user_vote_pda(voting, user, program_id).0, false),
+        AccountMeta::new(find_reward_escrow_pda(voting, program_id).0, false),
+        AccountMeta::new(find_reward_claim_pda(voting, user, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::ClaimWinnings.header() }
+}
+
+// Adds `amount` to the voting's boost until it ends, kept apart per booster for refunds
+pub fn boost(program_id: &Pubkey, booster: &Pubkey, voting: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*booster, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_boost_pda(voting, program_id).0, false),
+        AccountMeta::new(find_boost_deposit_pda(voting, booster, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::Boost.header();
+    BoostInstruction { amount }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Anyone can send it once the voting closed, `treasury` is the config admin
+pub fn release_boost(program_id: &Pubkey, voting: &Pubkey, treasury: &Pubkey) -> Instruction {
+    let config = find_config_pda(program_id).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(config, false),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_boost_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(config, false),
+        AccountMeta::new(*treasury, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::ReleaseBoost.header() }
+}
+
+// Returns the deposit of `booster` and its rent once the voting is cancelled
+pub fn refund_boost(program_id: &Pubkey, booster: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*booster, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_boost_pda(voting, program_id).0, false),
+        AccountMeta::new(find_boost_deposit_pda(voting, booster, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::RefundBoost.header() }
+}
+
+// `lamports_per_vote` must match the voting's `incentive_per_vote` once set
+pub fn fund_incentives(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, lamports_per_vote: u64, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_incentive_pool_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::FundIncentives.header();
+    FundIncentivesInstruction { lamports_per_vote, amount }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+pub fn withdraw_incentives(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_incentive_pool_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::WithdrawIncentives.header() }
+}
+
+// Empty `title` and `description` remove the language and refund its rent
+pub fn set_localized_metadata(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    voting: &Pubkey,
+    language: &str,
+    title: String,
+    description: String
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_localized_metadata_pda(voting, language, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::SetLocalizedMetadata.header();
+    SetLocalizedMetadataInstruction { language: language.to_string(), title, description }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Points off-chain notifiers at `webhook_commitment` and `notify` for the voting, both `None` remove
+// the target and refund its rent
+pub fn set_notification_target(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    voting: &Pubkey,
+    webhook_commitment: Option<[u8; 32]>,
+    notify: Option<Pubkey>
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_notification_target_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::SetNotificationTarget.header();
+    SetNotificationTargetInstruction { webhook_commitment, notify }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Votings with a dependency need the voting they depend on right after the other `vote` accounts, before
+// the membership. Pass `find_voting_archive_pda(dependency_voting)` once that voting is archived.
+pub fn add_dependency(vote_instruction: &mut Instruction, dependency_voting: &Pubkey) {
+    vote_instruction.accounts.push(AccountMeta::new_readonly(*dependency_voting, false));
+}
+
+// One batch of `merkleize_records`, `records` being the user vote accounts, envelopes or vote buckets
+// holding the next ballots in `vote_index` order. A bucket is repeated for each of its ballots.
+pub fn merkleize_records(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, records: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_record_tree_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    accounts.extend(records.iter().map(|record| AccountMeta::new_readonly(*record, false)));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::MerkleizeRecords.header() }
+}
+
+// Locks a 0.01 SOL deposit until `close_flag`, which takes the same accounts once the voting is closed
+pub fn flag_voting(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_flag_pda(voting, user, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::FlagVoting.header() }
+}
+
+pub fn close_flag(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_flag_pda(voting, user, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::CloseFlag.header() }
+}
+
+// `realm` is `None` when the program admin moderates, otherwise the realm admin of a realm voting signs
+pub fn hide_voting(program_id: &Pubkey, admin: &Pubkey, voting: &Pubkey, realm: Option<&Pubkey>, hidden: bool) -> Instruction {
+    let authority = realm.copied().unwrap_or_else(|| find_config_pda(program_id).0);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new_readonly(authority, false)
+    ];
+
+    let mut data = InstructionType::HideVoting.header();
+    HideVotingInstruction { hidden }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Permissionless once the voting ended. The creator gets the creation deposit back within the config's
+// refund window, after it `deposit_recipient` can be anyone. Oracle votings add their feed account.
+pub fn finalize_voting(program_id: &Pubkey, voting: &Pubkey, creator: &Pubkey, deposit_recipient: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(*deposit_recipient, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::FinalizeVoting.header() }
+}
+
+// `user` is the creator of the finalized voting, which must have ended at least 30 days ago
+pub fn archive_voting(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_voting_archive_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::ArchiveVoting.header() }
+}
+
+// Moves one of the program's accounts to the current layout, see `state::StateVersion`. `user` pays for
+// any room the upgrade adds.
+pub fn migrate_account(program_id: &Pubkey, user: &Pubkey, account: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::MigrateAccount.header() }
+}
+
+// `user` is the voter, once the voting is closed, cancelled or archived
+pub fn close_user_vote(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_user_vote_pda(voting, user, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::CloseUserVote.header() }
+}
+
+// `user` is the creator of the finalized or cancelled voting, which must have ended at least 30 days ago.
+// `payer` is the voting's payer, refunded the rent.
+pub fn close_voting(program_id: &Pubkey, user: &Pubkey, voting: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(*payer, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::CloseVoting.header() }
+}
+
+// `votings` are the races, all created by `user`, in the order the election lists them
+pub fn create_election(program_id: &Pubkey, user: &Pubkey, id: u64, name: String, votings: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(find_election_pda(user, id, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    accounts.extend(votings.iter().map(|voting| AccountMeta::new_readonly(*voting, false)));
+
+    let mut data = InstructionType::CreateElection.header();
+    CreateElectionInstruction { id, name }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Anyone can send it once every race is closed, `votings` in the same order as for `create_election`
+pub fn finalize_election(program_id: &Pubkey, election: &Pubkey, votings: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*election, false)
+    ];
+    accounts.extend(votings.iter().map(|voting| AccountMeta::new_readonly(*voting, false)));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::FinalizeElection.header() }
+}
+
+// `attestor` is one of the voting's `ResultAttestors`, co-signing its finalized result on-chain. Unlike
+// `attest_result`, which signs a result off-chain.
+pub fn attest_finalization(program_id: &Pubkey, attestor: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*attestor, true),
+        AccountMeta::new(*voting, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::AttestResult.header() }
+}
+
+// `creator` cancels the voting before `ends_at`. `restore_voting` reopens it, only before `starts_at`.
+pub fn cancel_voting(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    status_change(program_id, creator, voting, InstructionType::CancelVoting)
+}
+
+pub fn restore_voting(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    status_change(program_id, creator, voting, InstructionType::RestoreVoting)
+}
+
+fn status_change(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, instruction: InstructionType) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: instruction.header() }
+}
+
+// `ends_at` must be later than the current one, at most `MAX_VOTING_TIME` after `starts_at`. Realm
+// votings pass their `realm` and stay within its maximum voting time, a realm locking the voting time
+// rejects the extension.
+pub fn extend_voting(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, realm: Option<&Pubkey>, ends_at: u64) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false)
+    ];
+    if let Some(realm) = realm {
+        accounts.push(AccountMeta::new_readonly(*realm, false));
+    };
+
+    let mut data = InstructionType::ExtendVoting.header();
+    ExtendVotingInstruction { ends_at }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// `moderator` is `None` when the creator signs, otherwise the config PDA for the program admin or the
+// voting's realm for its admin, as for `hide_voting`
+pub fn freeze_voting(program_id: &Pubkey, signer: &Pubkey, voting: &Pubkey, moderator: Option<&Pubkey>) -> Instruction {
+    set_frozen(program_id, signer, voting, moderator, InstructionType::FreezeVoting)
+}
+
+// Takes the config first, the admin can keep votings frozen. `freeze_voting` can't be disabled.
+pub fn unfreeze_voting(program_id: &Pubkey, signer: &Pubkey, voting: &Pubkey, moderator: Option<&Pubkey>) -> Instruction {
+    set_frozen(program_id, signer, voting, moderator, InstructionType::UnfreezeVoting)
+}
+
+fn set_frozen(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    voting: &Pubkey,
+    moderator: Option<&Pubkey>,
+    instruction: InstructionType
+) -> Instruction {
+    let mut accounts = Vec::new();
+    if instruction == InstructionType::UnfreezeVoting {
+        accounts.push(AccountMeta::new_readonly(find_config_pda(program_id).0, false));
+    };
+    accounts.extend([
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false)
+    ]);
+    accounts.extend(moderator.map(|authority| AccountMeta::new_readonly(*authority, false)));
+
+    Instruction { program_id: *program_id, accounts, data: instruction.header() }
+}
+
+// Permissionless, brings the voting's status in line with the clock
+pub fn sync_status(program_id: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::SyncStatus.header() }
+}
+
+// Permissionless, creates the voting's poll card with `payer` paying the rent, or refreshes it
+pub fn sync_poll_card(program_id: &Pubkey, payer: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::SyncPollCard.header() }
+}
+
+// Permissionless once the voting ended 180 days ago. `voters` are those whose user vote records get
+// closed, a transaction fits a few dozen of them. `payer` and `creator` are the voting's, they're
+// ignored once the voting is a tombstone.
+pub fn gc_expired(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    voting: &Pubkey,
+    payer: &Pubkey,
+    creator: &Pubkey,
+    voters: &[Pubkey]
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(*payer, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false)
+    ];
+    for voter in voters.iter() {
+        accounts.push(AccountMeta::new(find_user_vote_pda(voting, voter, program_id).0, false));
+        accounts.push(AccountMeta::new(*voter, false));
+    };
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::GcExpired.header() }
+}
+
+// Members-only votings need the voter's membership account after the other `vote` accounts and the
+// dependency.
+pub fn add_membership(vote_instruction: &mut Instruction, program_id: &Pubkey, realm: &Pubkey, user: &Pubkey) {
+    let (membership, _) = find_membership_pda(realm, user, program_id);
+    vote_instruction.accounts.push(AccountMeta::new_readonly(membership, false));
+}
+
+// Token and stake accounts of the gates in the chosen eligibility clause, in order, after the membership.
+pub fn add_gate_account(vote_instruction: &mut Instruction, gate_account: &Pubkey) {
+    vote_instruction.accounts.push(AccountMeta::new_readonly(*gate_account, false));
+}
+
+// The token account, stake account or plugin voter weight record of the voting's weight source, last.
+// Votings with a weight precision also take the mint after the token account, and `StakedSol` votings
+// the stake pool after a pool token account. Works on `get_vote_weight` instructions too.
+pub fn add_weight_account(vote_instruction: &mut Instruction, weight_account: &Pubkey) {
+    vote_instruction.accounts.push(AccountMeta::new_readonly(*weight_account, false));
+}
+
+// Gate accounts of a `Gate::UploadedAllowlist` with uploaded pages, `position` being the voter's index in
+// upload order. An allowlist set as a merkle root only takes the allowlist account, with the proof in
+// `EligibilityProof::allowlist_proof`.
+pub fn add_allowlist_gate(vote_instruction: &mut Instruction, program_id: &Pubkey, voting: &Pubkey, position: u32) {
+    let page = (position as usize / MAX_ALLOWLIST_PAGE_VOTERS) as u16;
+    vote_instruction.accounts.push(AccountMeta::new_readonly(find_voter_allowlist_pda(voting, program_id).0, false));
+    vote_instruction.accounts.push(AccountMeta::new_readonly(find_voter_allowlist_page_pda(voting, page, program_id).0, false));
+}
+
+// One `append_allowlist_chunk` per chunk, to send in order. `uploaded` is the allowlist's current
+// `voters_count`, chunks are cut at page boundaries. After `starts_at` it only works within the late
+// registration window, see `set_late_registration`.
+pub fn append_allowlist(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, voters: &[Pubkey], uploaded: u32) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut position = uploaded as usize;
+    let mut remaining = voters;
+    while remaining.is_empty() == false {
+        let page_room = MAX_ALLOWLIST_PAGE_VOTERS - position % MAX_ALLOWLIST_PAGE_VOTERS;
+        let (chunk, rest) = remaining.split_at(page_room.min(MAX_ALLOWLIST_CHUNK).min(remaining.len()));
+        let page = (position / MAX_ALLOWLIST_PAGE_VOTERS) as u16;
+
+        let accounts = vec![
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*voting, false),
+            AccountMeta::new(find_voter_allowlist_pda(voting, program_id).0, false),
+            AccountMeta::new(find_voter_allowlist_page_pda(voting, page, program_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+
+        let mut data = InstructionType::AppendAllowlistChunk.header();
+        AppendAllowlistChunkInstruction { voters: chunk.to_vec() }.serialize(&mut data).unwrap();
+        instructions.push(Instruction { program_id: *program_id, accounts, data });
+
+        position += chunk.len();
+        remaining = rest;
+    };
+
+    instructions
+}
+
+// Build the root with `AllowlistTree`
+pub fn set_allowlist_root(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, root: [u8; 32], voters_count: u32) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_voter_allowlist_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::SetAllowlistRoot.header();
+    SetAllowlistRootInstruction { root, voters_count }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Seals the roll of voters eligible for the open voting, build the root with `AllowlistTree`. Ballots then
+// carry the voter's proof as `EligibilityProof::roll_proof`.
+pub fn seal_voter_roll(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, root: [u8; 32], voters_count: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false)
+    ];
+
+    let mut data = InstructionType::SealVoterRoll.header();
+    SealVoterRollInstruction { root, voters_count }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Replaces the description of a voting that hasn't started, the creator pays for any room it needs
+pub fn edit_voting(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, description: String) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::EditVoting.header();
+    EditVotingInstruction { description }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Changes the case or spacing of the title of a voting that hasn't started, see `UpdateTitleInstruction`
+pub fn update_title(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, title: String) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::UpdateTitle.header();
+    UpdateTitleInstruction { title }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Merkle tree over an allowlist, for `Gate::Allowlist` roots, `set_allowlist_root` and `seal_voter_roll`.
+// Leaves are keccak(voter) and pairs are hashed sorted, an odd node is carried up to the next level as is.
+pub struct AllowlistTree {
+    voters: Vec<Pubkey>,
+    // Leaves first, the root last
+    levels: Vec<Vec<[u8; 32]>>
+}
+
+impl AllowlistTree {
+    pub fn new(voters: &[Pubkey]) -> Self {
+        let mut levels = vec![ voters.iter().map(|voter| hash(voter.as_ref()).0).collect::<Vec<_>>() ];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] if left <= right => hashv(&[left, right]).0,
+                    [left, right] => hashv(&[right, left]).0,
+                    _ => pair[0]
+                })
+                .collect();
+            levels.push(next);
+        };
+
+        Self { voters: voters.to_vec(), levels }
+    }
+
+    // All zeros for an empty allowlist
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1].first().copied().unwrap_or_default()
+    }
+
+    // `None` if the voter isn't on the allowlist
+    pub fn proof(&self, voter: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let mut index = self.voters.iter().position(|listed| listed == voter)?;
+        let mut proof = Vec::new();
+        for level in self.levels[..self.levels.len() - 1].iter() {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            };
+            index /= 2;
+        };
+
+        Some(proof)
+    }
+}
+
+// A vote record as `merkleize_records` hashes it, from a fetched user vote account, envelope or bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteRecord {
+    pub voter: Pubkey,
+    pub vote_index: u64,
+    pub weight: u64,
+    pub ballot: Vec<u8>
+}
+
+impl VoteRecord {
+    // The user vote account's payer is the voter
+    pub fn from_user_vote(record: &UserVotingAccount) -> Self {
+        Self { voter: record.payer, vote_index: record.vote_index, weight: record.weight, ballot: record.ballot.clone() }
+    }
+
+    pub fn from_envelope(envelope: &EnvelopeAccount) -> Self {
+        Self { voter: envelope.voter, vote_index: envelope.vote_index, weight: envelope.weight, ballot: envelope.envelope.clone() }
+    }
+
+    pub fn from_bucket(bucket: &VoteBucketAccount) -> Vec<Self> {
+        bucket.slots.iter()
+            .filter(|slot| slot.is_empty() == false)
+            .map(|slot| Self { voter: slot.voter, vote_index: slot.vote_index, weight: slot.weight, ballot: slot.ballot().to_vec() })
+            .collect()
+    }
+
+    pub fn leaf(&self) -> [u8; 32] {
+        record_leaf(&self.voter, self.vote_index, self.weight, &self.ballot)
+    }
+}
+
+// The tree `merkleize_records` builds, rebuilt from all of the voting's records. Its root matches the
+// voting's `records_root` only if no record is missing.
+pub struct VoteRecordTree {
+    // Leaves in `vote_index` order first, the root last
+    levels: Vec<Vec<[u8; 32]>>,
+    // Hash of an empty subtree at each level
+    zeros: Vec<[u8; 32]>
+}
+
+impl VoteRecordTree {
+    pub fn new(records: &[VoteRecord]) -> Self {
+        let mut records = records.to_vec();
+        records.sort_by_key(|record| record.vote_index);
+
+        let mut zeros = vec![[0u8; 32]];
+        let mut levels = vec![ records.iter().map(VoteRecord::leaf).collect::<Vec<_>>() ];
+        for level in 0..RECORD_TREE_DEPTH {
+            let next = levels[level]
+                .chunks(2)
+                .map(|pair| hashv(&[&pair[0], pair.get(1).unwrap_or(&zeros[level])]).0)
+                .collect();
+            levels.push(next);
+            zeros.push(hashv(&[&zeros[level], &zeros[level]]).0);
+        };
+
+        Self { levels, zeros }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[RECORD_TREE_DEPTH].first().copied().unwrap_or(self.zeros[RECORD_TREE_DEPTH])
+    }
+
+    // `None` if no record has this index
+    pub fn proof(&self, vote_index: u64) -> Option<Vec<[u8; 32]>> {
+        let mut index = usize::try_from(vote_index).ok()?;
+        self.levels[0].get(index)?;
+
+        let mut proof = Vec::new();
+        for level in 0..RECORD_TREE_DEPTH {
+            proof.push(self.levels[level].get(index ^ 1).copied().unwrap_or(self.zeros[level]));
+            index /= 2;
+        };
+
+        Some(proof)
+    }
+}
+
+// Read-only, `voting` may also be the voting's archive. Fails unless the record is in the records root.
+pub fn verify_ballot_proof(program_id: &Pubkey, voting: &Pubkey, record: &VoteRecord, proof: Vec<[u8; 32]>) -> Instruction {
+    let mut data = InstructionType::VerifyBallotProof.header();
+    VerifyBallotProofInstruction {
+        voter: record.voter,
+        vote_index: record.vote_index,
+        weight: record.weight,
+        ballot: record.ballot.clone(),
+        proof
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts: vec![ AccountMeta::new_readonly(*voting, false) ], data }
+}
+
+// Before the voting starts, on an uploaded allowlist. `percent` of the voting window, 0 closes it at `starts_at`.
+pub fn set_late_registration(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, percent: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_voter_allowlist_pda(voting, program_id).0, false)
+    ];
+
+    let mut data = InstructionType::SetLateRegistration.header();
+    SetLateRegistrationInstruction { percent }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+pub fn seal_allowlist(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_voter_allowlist_pda(voting, program_id).0, false)
+    ];
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::SealAllowlist.header() }
+}
+
+// `user` must be the creator of `source`, a voting outside a realm. `co_creators` are the source's
+// co-creators, in order, signing the same way as for `create_voting`.
+pub fn clone_voting(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    source: &Pubkey,
+    title: String,
+    starts_at: u64,
+    ends_at: u64,
+    co_creators: &[Pubkey]
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*source, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(find_voting_pda(&title, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(user, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(0, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    accounts.extend(co_creators.iter().map(|co_creator| AccountMeta::new_readonly(*co_creator, true)));
+
+    let mut data = InstructionType::CloneVoting.header();
+    CloneVotingInstruction { title, starts_at, ends_at, relative_times: false }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Votings with `record_buckets` take the voter's bucket in place of the user vote account, for `vote`
+// and `update_vote` instructions
+pub fn use_vote_bucket(vote_instruction: &mut Instruction, program_id: &Pubkey, voting: &Pubkey, user: &Pubkey, record_buckets: u8) {
+    let (user_vote, _) = find_user_vote_pda(voting, user, program_id);
+    let (bucket, _) = find_vote_bucket_pda(voting, bucket_of(user, record_buckets), program_id);
+    for account in vote_instruction.accounts.iter_mut().filter(|account| account.pubkey == user_vote) {
+        account.pubkey = bucket;
+    };
+}
+
+// One per bucket index below the voting's `record_buckets`, before ballots land in it
+pub fn allocate_vote_bucket(program_id: &Pubkey, payer: &Pubkey, voting: &Pubkey, index: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_vote_bucket_pda(voting, index, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::AllocateVoteBucket.header();
+    AllocateVoteBucketInstruction { index }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// `mint` is a Metaplex NFT whose update authority is the creator
+pub fn link_proposal_note(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, mint: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new_readonly(find_metadata_pda(mint).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::LinkProposalNote.header();
+    LinkProposalNoteInstruction { mint: *mint }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Every shard from 0 to `tally_shards` should be allocated before the voting starts, ballots of voters in
+// a missing one fail
+pub fn allocate_tally_shard(program_id: &Pubkey, payer: &Pubkey, voting: &Pubkey, index: u8) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*voting, false),
+        AccountMeta::new(find_tally_shard_pda(voting, index, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::AllocateTallyShard.header();
+    AllocateTallyShardInstruction { index }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Anyone can send it once the voting ended, `payer` covers the voting growing with the merged counts
+pub fn merge_tally_shards(program_id: &Pubkey, payer: &Pubkey, voting: &Pubkey, tally_shards: u8) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+    accounts.extend((0..tally_shards).map(|index| AccountMeta::new_readonly(find_tally_shard_pda(voting, index, program_id).0, false)));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::MergeTallyShards.header() }
+}
+
+fn recount_accounts(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_recount_pda(voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ]
+}
+
+// Starts the shadow tallies from the voting's ballot box, discarding a recount in progress
+pub fn start_recount(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    let mut accounts = recount_accounts(program_id, creator, voting);
+    accounts.push(AccountMeta::new_readonly(find_ballot_box_pda(voting, program_id).0, false));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::StartRecount.header() }
+}
+
+// `user_votes` are counted in one pass, sorted by address across every call
+pub fn recount_votes(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, user_votes: &[Pubkey]) -> Instruction {
+    let mut accounts = recount_accounts(program_id, creator, voting);
+    accounts.extend(user_votes.iter().map(|user_vote| AccountMeta::new_readonly(*user_vote, false)));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::RecountVotes.header() }
+}
+
+// Swaps the recounted tallies in once every voter is counted, the recount's rent goes back to `creator`
+pub fn complete_recount(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey) -> Instruction {
+    let mut accounts = recount_accounts(program_id, creator, voting);
+    accounts.push(AccountMeta::new(find_poll_card_pda(voting, program_id).0, false));
+
+    Instruction { program_id: *program_id, accounts, data: InstructionType::CompleteRecount.header() }
+}
+
+// Only the title's hash goes on-chain, the title stays private until the voting is created
+pub fn reserve_title(program_id: &Pubkey, user: &Pubkey, title: &str) -> Instruction {
+    let title_hash = voting_title_seed(title);
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new(find_title_reservation_pda(&title_hash, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::ReserveTitle.header();
+    ReserveTitleInstruction { title_hash }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+pub fn publish_voting(program_id: &Pubkey, creator: &Pubkey, voting: &Pubkey, starts_at: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*voting, false),
+        AccountMeta::new(find_poll_card_pda(voting, program_id).0, false)
+    ];
+
+    let mut data = InstructionType::PublishVoting.header();
+    PublishVotingInstruction { starts_at }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Returns an unsigned transaction for an instruction built with `vote`, with the compute budget and
+// the latest blockhash set.
+pub fn build_vote_tx(
+    rpc: &RpcClient,
+    user: &Pubkey,
+    vote_instruction: Instruction
+) -> ClientResult<Transaction> {
+    build_tx(rpc, user, vote_instruction, VOTE_COMPUTE_UNITS)
+}
+
+// Like `build_vote_tx`, but uses the durable nonce instead of a recent blockhash, so the transaction
+// can be signed offline and submitted later. The nonce authority must also sign.
+pub fn build_vote_tx_with_nonce(
+    rpc: &RpcClient,
+    user: &Pubkey,
+    vote_instruction: Instruction,
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey
+) -> ClientResult<Transaction> {
+    build_nonce_tx(rpc, user, vote_instruction, VOTE_COMPUTE_UNITS, nonce_account, nonce_authority)
+}
+
+fn build_tx(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instruction: Instruction,
+    compute_units: u32
+) -> ClientResult<Transaction> {
+    let instructions = with_compute_budget(rpc, instruction, compute_units)?;
+
+    let mut transaction = Transaction::new_unsigned(Message::new(&instructions, Some(payer)));
+    transaction.message.recent_blockhash = rpc.get_latest_blockhash()?;
+
+    Ok(transaction)
+}
+
+fn build_nonce_tx(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instruction: Instruction,
+    compute_units: u32,
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey
+) -> ClientResult<Transaction> {
+    let nonce = nonce_utils::get_account(rpc, nonce_account)
+        .and_then(|account| nonce_utils::data_from_account(&account))
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+
+    // The runtime only accepts a durable nonce when advancing it is the first instruction.
+    let mut instructions = vec![ advance_nonce_account(nonce_account, nonce_authority) ];
+    instructions.extend(with_compute_budget(rpc, instruction, compute_units)?);
+
+    let mut transaction = Transaction::new_unsigned(Message::new(&instructions, Some(payer)));
+    transaction.message.recent_blockhash = nonce.blockhash();
+
+    Ok(transaction)
+}
+
+fn with_compute_budget(
+    rpc: &RpcClient,
+    instruction: Instruction,
+    compute_units: u32
+) -> ClientResult<Vec<Instruction>> {
+    let priority_fee = priority_fee(rpc, &instruction)?;
+
+    Ok(vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        instruction
+    ])
+}
+
+// Micro-lamports per compute unit, based on the fees recently paid to write the same accounts.
+fn priority_fee(rpc: &RpcClient, instruction: &Instruction) -> ClientResult<u64> {
+    let writable: Vec<Pubkey> = instruction.accounts
+        .iter()
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+
+    let mut fees: Vec<u64> = rpc.get_recent_prioritization_fees(&writable)?
+        .iter()
+        .map(|fee| fee.prioritization_fee)
+        .filter(|fee| *fee > 0)
+        .collect();
+    if fees.is_empty() {
+        return Ok(0);
+    };
+
+    fees.sort_unstable();
+
+    Ok(fees[(fees.len() - 1) * PRIORITY_FEE_PERCENTILE / 100])
+}
+
+// Accounts shared by most instructions. Per-voting accounts can be added with `extra_addresses`.
+pub fn common_accounts(program_id: &Pubkey) -> Vec<Pubkey> {
+    vec![
+        find_config_pda(program_id).0,
+        system_program::ID,
+        spl_memo::id(),
+        spl_token_2022::id()
+    ]
+}
+
+// Instructions creating a lookup table with the common accounts, and the table's address. The table
+// is only usable from the slot after it was extended.
+pub fn create_lookup_table_instructions(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+    extra_addresses: &[Pubkey]
+) -> (Vec<Instruction>, Pubkey) {
+    let (create, lookup_table) = create_lookup_table(*authority, *payer, recent_slot);
+
+    let mut addresses = common_accounts(program_id);
+    addresses.extend_from_slice(extra_addresses);
+    let extend = extend_lookup_table(lookup_table, *authority, Some(*payer), addresses);
+
+    (vec![ create, extend ], lookup_table)
+}
+
+pub fn extend_lookup_table_instruction(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    addresses: Vec<Pubkey>
+) -> Instruction {
+    extend_lookup_table(*lookup_table, *authority, Some(*payer), addresses)
+}
+
+pub fn fetch_lookup_table(rpc: &RpcClient, lookup_table: &Pubkey) -> ClientResult<AddressLookupTableAccount> {
+    let data = rpc.get_account_data(lookup_table)?;
+    let table = AddressLookupTable::deserialize(&data)
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+
+    Ok(AddressLookupTableAccount {
+        key: *lookup_table,
+        addresses: table.addresses.to_vec()
+    })
+}
+
+// Returns an unsigned v0 transaction resolving accounts through `lookup_table`, with the latest blockhash set.
+pub fn build_v0_tx(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_table: &AddressLookupTableAccount
+) -> ClientResult<VersionedTransaction> {
+    let message = v0::Message::try_compile(
+        payer,
+        instructions,
+        std::slice::from_ref(lookup_table),
+        rpc.get_latest_blockhash()?
+    ).map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+
+    Ok(VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::V0(message)
+    })
+}
+
+enum Field {
+    // Size taken from the borsh encoding of a reference value
+    Fixed(&'static str, &'static str, usize),
+    // Every field after a variable one has a variable offset
+    Variable(&'static str, &'static str)
+}
+
+fn fixed<T: BorshSerialize>(name: &'static str, ty: &'static str, value: T) -> Field {
+    Field::Fixed(name, ty, value.try_to_vec().unwrap().len())
+}
+
+// Fields of every account in Borsh order, the source of LAYOUT.md and of the TypeScript and Python layouts
+fn account_layouts() -> Vec<(&'static str, Vec<Field>)> {
+    vec![
+        ("ConfigAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("admin", "Pubkey", Pubkey::default()),
+            fixed("disabled_instructions", "u128", 0u128),
+            fixed("max_active_votings", "u32", 0u32),
+            fixed("start_time_tolerance", "u64", 0u64),
+            Field::Variable("pending_admin", "Option<Pubkey>"),
+            fixed("creation_deposit", "u64", 0u64),
+            fixed("deposit_refund_window", "u64", 0u64)
+        ]),
+        ("VoteMainAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("creator", "Pubkey", Pubkey::default()),
+            fixed("payer", "Pubkey", Pubkey::default()),
+            fixed("starts_at", "u64", 0u64),
+            fixed("ends_at", "u64", 0u64),
+            fixed("status", "VotingStatus", VotingStatus::Active),
+            fixed("realm", "Pubkey", Pubkey::default()),
+            fixed("realm_voting_id", "u64", 0u64),
+            fixed("members_only", "bool", false),
+            fixed("allow_updates", "bool", false),
+            fixed("title", "VotingTitle", VotingTitle::default()),
+            Field::Variable("description", "String"),
+            Field::Variable("options", "Vec<String>"),
+            Field::Variable("tallies", "Vec<u64>"),
+            fixed("total_voters", "u64", 0u64),
+            fixed("vote_chain", "[u8; 32]", [0u8; 32]),
+            Field::Variable("option_caps", "Vec<u64>"),
+            Field::Variable("co_creators", "Vec<Pubkey>"),
+            fixed("creator_threshold", "u8", 0u8),
+            Field::Variable("weight_source", "WeightSource"),
+            Field::Variable("weight_mints", "Vec<MintRatio>"),
+            Field::Variable("weight_precision", "Option<u8>"),
+            Field::Variable("tally_mode", "TallyMode"),
+            Field::Variable("eligibility", "EligibilityPolicy"),
+            fixed("signup_slots", "u32", 0u32),
+            fixed("claimed_slots", "u32", 0u32),
+            Field::Variable("random_draw", "Option<RandomDraw>"),
+            Field::Variable("oracle_condition", "Option<OracleCondition>"),
+            fixed("condition_met", "bool", false),
+            fixed("record_buckets", "u8", 0u8),
+            fixed("locked", "bool", false),
+            fixed("init_stage", "u8", 0u8),
+            fixed("required_stages", "u8", 0u8),
+            Field::Variable("envelope_key", "Option<[u8; 32]>"),
+            fixed("envelope_weight", "u64", 0u64),
+            Field::Variable("envelope_commitment", "Option<[u8; 32]>"),
+            fixed("tie_policy", "TiePolicy", TiePolicy::LowestIndex),
+            Field::Variable("changed_at", "Vec<u64>"),
+            fixed("tie_draw", "RandomDraw", RandomDraw::default()),
+            Field::Variable("dependency", "Option<VotingDependency>"),
+            Field::Variable("creator_program", "Option<Pubkey>"),
+            Field::Variable("records_root", "Option<[u8; 32]>"),
+            fixed("flag_count", "u32", 0u32),
+            fixed("moderation", "ModerationStatus", ModerationStatus::Visible),
+            fixed("incentive_per_vote", "u64", 0u64),
+            Field::Variable("turnout_milestones", "Option<TurnoutMilestones>"),
+            fixed("hide_tallies", "bool", false),
+            fixed("creation_deposit", "u64", 0u64),
+            fixed("deposit_refund_window", "u64", 0u64),
+            Field::Variable("result_attestors", "Option<ResultAttestors>"),
+            fixed("tally_shards", "u8", 0u8),
+            fixed("shards_merged", "bool", false),
+            fixed("weight_cap_per_slot", "u64", 0u64),
+            fixed("reject_encumbered_tokens", "bool", false),
+            Field::Variable("proposal_note", "Option<Pubkey>"),
+            fixed("reveal_window", "u64", 0u64),
+            fixed("commitments", "u64", 0u64),
+            fixed("winners_count", "u8", 0u8),
+            Field::Variable("winners", "Vec<u8>"),
+            Field::Variable("apportionment", "Option<Apportionment>"),
+            Field::Variable("seats", "Vec<u16>"),
+            Field::Variable("voter_roll", "Option<VoterRoll>"),
+            Field::Variable("frozen_at", "Option<u64>"),
+            fixed("min_participation", "u32", 0u32),
+            fixed("pass_threshold_bps", "u16", 0u16),
+            Field::Variable("electorate", "Option<Electorate>"),
+            fixed("quorum_bps", "u16", 0u16),
+            Field::Variable("outcome", "Option<VotingOutcome>"),
+            Field::Variable("result", "Option<u8>"),
+            Field::Variable("tally_state", "TallyState")
+        ]),
+        ("RealmAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("admin", "Pubkey", Pubkey::default()),
+            fixed("defaults", "VotingSettings", DEFAULT_VOTING_SETTINGS),
+            fixed("locked_settings", "u8", 0u8),
+            fixed("creation_fee", "u64", 0u64),
+            fixed("creators_gated", "bool", false),
+            fixed("votings_count", "u64", 0u64),
+            fixed("members_count", "u64", 0u64),
+            Field::Variable("name", "String")
+        ]),
+        ("MembershipAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("realm", "Pubkey", Pubkey::default()),
+            fixed("member", "Pubkey", Pubkey::default()),
+            fixed("joined_at", "u64", 0u64)
+        ]),
+        ("UserVotingAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("payer", "Pubkey", Pubkey::default()),
+            fixed("first_voted_at", "u64", 0u64),
+            fixed("last_time_voted", "u64", 0u64),
+            fixed("times_updated", "u16", 0u16),
+            fixed("choice", "u8", 0u8),
+            fixed("weight", "u64", 0u64),
+            fixed("credited_slot", "u64", 0u64),
+            fixed("draw_offset", "u64", 0u64),
+            fixed("vote_index", "u64", 0u64),
+            Field::Variable("commitment", "Option<[u8; 32]>"),
+            Field::Variable("ballot", "Vec<u8>"),
+            fixed("voted_to", "VotingTitle", VotingTitle::default())
+        ]),
+        ("TallySnapshotAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("last_taken_at", "u64", 0u64),
+            fixed("next_index", "u8", 0u8),
+            Field::Variable("snapshots", "Vec<TallySnapshot>")
+        ]),
+        ("RewardEscrowAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("total_funded", "u64", 0u64),
+            fixed("total_claimed", "u64", 0u64),
+            fixed("in_progress", "bool", false)
+        ]),
+        ("RewardClaimAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("amount", "u64", 0u64)
+        ]),
+        ("BoostAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("amount", "u64", 0u64),
+            fixed("released", "bool", false),
+            fixed("in_progress", "bool", false)
+        ]),
+        ("BoostDepositAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("amount", "u64", 0u64)
+        ]),
+        ("VoterIndexAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voter", "Pubkey", Pubkey::default()),
+            fixed("page", "u16", 0u16),
+            fixed("next", "Pubkey", Pubkey::default()),
+            // Raw keys up to the end of the account, without a length prefix
+            Field::Variable("votings", "[Pubkey]")
+        ]),
+        ("CreatorStatsAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("creator", "Pubkey", Pubkey::default()),
+            fixed("active_votings", "u32", 0u32),
+            fixed("created_votings", "u32", 0u32),
+            fixed("finalized_votings", "u32", 0u32),
+            fixed("cancelled_votings", "u32", 0u32),
+            fixed("total_voters", "u64", 0u64),
+            fixed("flags", "u32", 0u32)
+        ]),
+        ("SlotAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("claimant", "Pubkey", Pubkey::default()),
+            fixed("slot", "u32", 0u32),
+            fixed("claimed_at", "u64", 0u64)
+        ]),
+        ("SlotClaimAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("slot", "u32", 0u32)
+        ]),
+        ("ExecutionAllowlistAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("realm", "Pubkey", Pubkey::default()),
+            Field::Variable("actions", "Vec<AllowedAction>")
+        ]),
+        ("ProposalAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("option", "u8", 0u8),
+            fixed("next_instruction", "u16", 0u16),
+            fixed("in_progress", "bool", false),
+            Field::Variable("instructions", "Vec<ProposalInstruction>")
+        ]),
+        ("ProposalTemplateAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("realm", "Pubkey", Pubkey::default()),
+            fixed("id", "u16", 0u16),
+            Field::Variable("instruction", "ProposalInstruction"),
+            Field::Variable("params", "Vec<TemplateParam>")
+        ]),
+        ("VotingArchiveAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("creator", "Pubkey", Pubkey::default()),
+            Field::Variable("title", "String"),
+            Field::Variable("winning_option", "Option<u8>"),
+            Field::Variable("tallies", "Vec<u128>"),
+            fixed("total_voters", "u64", 0u64),
+            fixed("vote_chain", "[u8; 32]", [0u8; 32]),
+            Field::Variable("records_root", "Option<[u8; 32]>"),
+            fixed("archived_at", "u64", 0u64)
+        ]),
+        ("ElectionAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("creator", "Pubkey", Pubkey::default()),
+            fixed("id", "u64", 0u64),
+            Field::Variable("name", "String"),
+            fixed("finalized_at", "u64", 0u64),
+            Field::Variable("races", "Vec<ElectionRace>")
+        ]),
+        ("RecordTreeAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("tree", "RecordTree", RecordTree::default())
+        ]),
+        ("FlagAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("flagger", "Pubkey", Pubkey::default()),
+            fixed("flagged_at", "u64", 0u64)
+        ]),
+        ("IncentivePoolAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("total_funded", "u64", 0u64),
+            fixed("total_paid", "u64", 0u64),
+            fixed("in_progress", "bool", false)
+        ]),
+        ("BallotBoxAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("mint", "Pubkey", Pubkey::default()),
+            Field::Variable("counted", "Vec<u64>")
+        ]),
+        ("RecountAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("last_record", "Pubkey", Pubkey::default()),
+            fixed("records_counted", "u64", 0u64),
+            Field::Variable("tallies", "Vec<u64>"),
+            Field::Variable("tally_state", "TallyState")
+        ]),
+        ("VotingMetadataAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            Field::Variable("timezone", "String"),
+            Field::Variable("schedule", "String")
+        ]),
+        ("LocalizedMetadataAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            Field::Variable("language", "String"),
+            Field::Variable("title", "String"),
+            Field::Variable("description", "String")
+        ]),
+        ("VoterAllowlistAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("voters_count", "u32", 0u32),
+            fixed("sealed", "bool", false),
+            Field::Variable("root", "Option<[u8; 32]>"),
+            fixed("late_registration", "u8", 0u8),
+            Field::Variable("late_chunks", "Vec<LateAllowlistChunk>")
+        ]),
+        ("VoterAllowlistPageAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("page", "u16", 0u16),
+            Field::Variable("voters", "Vec<Pubkey>")
+        ]),
+        ("VoteBucketAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("index", "u8", 0u8),
+            Field::Variable("slots", "Vec<RecordSlot>")
+        ]),
+        ("TallyShardAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("index", "u8", 0u8),
+            fixed("voters", "u64", 0u64),
+            fixed("updates", "u64", 0u64),
+            fixed("vote_chain", "[u8; 32]", [0u8; 32]),
+            Field::Variable("tallies", "Vec<u64>"),
+            Field::Variable("tally_state", "TallyState")
+        ]),
+        ("TitleIndexAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default())
+        ]),
+        ("TitleReservationAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("reserver", "Pubkey", Pubkey::default()),
+            fixed("expires_at", "u64", 0u64)
+        ]),
+        ("EnvelopeAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voter", "Pubkey", Pubkey::default()),
+            fixed("weight", "u64", 0u64),
+            fixed("cast_at", "u64", 0u64),
+            fixed("vote_index", "u64", 0u64),
+            Field::Variable("envelope", "Vec<u8>")
+        ]),
+        ("FaucetClaimAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("wallet", "Pubkey", Pubkey::default()),
+            fixed("last_claimed_at", "u64", 0u64),
+            fixed("claims", "u32", 0u32)
+        ]),
+        ("VotingRegistryAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("votings_count", "u64", 0u64)
+        ]),
+        ("VotingRegistryPageAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("page", "u32", 0u32),
+            // Raw entries up to the end of the account, without a length prefix
+            Field::Variable("entries", "[RegistryEntry]")
+        ]),
+        ("NotificationTargetAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            Field::Variable("webhook_commitment", "Option<[u8; 32]>"),
+            Field::Variable("notify", "Option<Pubkey>"),
+            fixed("updated_at", "u64", 0u64)
+        ]),
+        ("PollCardAccount", vec![
+            fixed("discriminator", "[u8; 8]", [0u8; 8]),
+            fixed("version", "u8", 0u8),
+            fixed("voting", "Pubkey", Pubkey::default()),
+            fixed("status", "VotingStatus", VotingStatus::Active),
+            fixed("ends_at", "u64", 0u64),
+            fixed("leading_option", "u8", 0u8),
+            fixed("total_voters", "u64", 0u64),
+            fixed("updated_at", "u64", 0u64)
+        ])
+    ]
+}
+
+// (name, Rust type, offset, size) of each field, `None` where it depends on the data
+fn field_offsets(fields: &[Field]) -> Vec<(&'static str, &'static str, Option<usize>, Option<usize>)> {
+    let mut offset = Some(0);
+    fields.iter()
+        .map(|field| {
+            let at = offset;
+            match field {
+                Field::Fixed(name, ty, size) => {
+                    offset = offset.map(|offset| offset + size);
+                    (*name, *ty, at, Some(*size))
+                },
+                Field::Variable(name, ty) => {
+                    offset = None;
+                    (*name, *ty, at, None)
+                }
+            }
+        })
+        .collect()
+}
+
+// Markdown byte-offset table of every account, for `getProgramAccounts` filters and off-chain decoders.
+// LAYOUT.md is generated from it.
+pub fn layout_table() -> String {
+    let mut table = String::new();
+    for (account, fields) in account_layouts().iter() {
+        table.push_str(&format!("## {}\n\n| Field | Type | Offset | Size |\n|---|---|---|---|\n", account));
+
+        for (name, ty, offset, size) in field_offsets(fields) {
+            let variable = |value: Option<usize>| value.map_or("variable".to_string(), |value| value.to_string());
+            table.push_str(&format!("| {} | {} | {} | {} |\n", name, ty, variable(offset), variable(size)));
+        };
+
+        table.push('\n');
+    };
+
+    table
+}
+
+// TypeScript and Python types of the Rust field types. Pubkeys stay raw bytes, program enums and
+// structs are left untyped, their encoding is in the Rust definitions.
+fn typescript_type(ty: &str) -> String {
+    if let Some(inner) = ty.strip_prefix("Option<").and_then(|ty| ty.strip_suffix('>')) {
+        return format!("{} | null", typescript_type(inner));
+    };
+
+    if let Some(inner) = ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+        return format!("({})[]", typescript_type(inner));
+    };
+
+    match ty {
+        "u8" | "u16" | "u32" | "i16" | "i32" => "number".to_string(),
+        "u64" | "u128" | "i64" => "bigint".to_string(),
+        "bool" => "boolean".to_string(),
+        "String" | "VotingTitle" => "string".to_string(),
+        "Pubkey" => "Uint8Array".to_string(),
+        "[Pubkey]" => "Uint8Array[]".to_string(),
+        _ if ty.starts_with("[u8;") => "Uint8Array".to_string(),
+        _ => "unknown".to_string()
+    }
+}
+
+fn python_type(ty: &str) -> String {
+    if let Some(inner) = ty.strip_prefix("Option<").and_then(|ty| ty.strip_suffix('>')) {
+        return format!("Optional[{}]", python_type(inner));
+    };
+
+    if let Some(inner) = ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+        return format!("List[{}]", python_type(inner));
+    };
+
+    match ty {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i16" | "i32" | "i64" => "int".to_string(),
+        "bool" => "bool".to_string(),
+        "String" | "VotingTitle" => "str".to_string(),
+        "Pubkey" => "bytes".to_string(),
+        "[Pubkey]" => "List[bytes]".to_string(),
+        _ if ty.starts_with("[u8;") => "bytes".to_string(),
+        _ => "Any".to_string()
+    }
+}
+
+// TypeScript interfaces of every account, with the offsets of the fields before the first variable one.
+// layouts.ts is generated from it.
+pub fn typescript_layouts() -> String {
+    let mut source = String::from("// Generated with `client::typescript_layouts()`, see LAYOUT.md. Do not edit.\n");
+    for (account, fields) in account_layouts().iter() {
+        let fields = field_offsets(fields);
+
+        source.push_str(&format!("\nexport interface {} {{\n", account));
+        for (name, ty, _, _) in fields.iter() {
+            source.push_str(&format!("  {}: {}; // {}\n", name, typescript_type(ty), ty));
+        };
+        source.push_str("}\n");
+
+        source.push_str(&format!("\nexport const {}Offsets = {{\n", account));
+        for (name, _, offset, _) in fields.iter() {
+            if let Some(offset) = offset {
+                source.push_str(&format!("  {}: {},\n", name, offset));
+            };
+        };
+        source.push_str("} as const;\n");
+    };
+
+    source.push_str("\n// Code of each program error, with its name and message\nexport const ProgramErrors: Record<number, [string, string]> = {\n");
+    for error in Errors::all() {
+        source.push_str(&format!("  {}: [\"{:?}\", {:?}],\n", error.code(), error, error.to_string()));
+    };
+    source.push_str("};\n");
+
+    source
+}
+
+// Python dataclasses of every account, with the offsets of the fields before the first variable one.
+// layouts.py is generated from it.
+pub fn python_layouts() -> String {
+    let mut source = String::from("# Generated with `client::python_layouts()`, see LAYOUT.md. Do not edit.\n\n");
+    source.push_str("from dataclasses import dataclass\nfrom typing import Any, ClassVar, Dict, List, Optional, Tuple\n");
+    for (account, fields) in account_layouts().iter() {
+        let fields = field_offsets(fields);
+
+        source.push_str(&format!("\n\n@dataclass\nclass {}:\n", account));
+        for (name, ty, _, _) in fields.iter() {
+            source.push_str(&format!("    {}: {}  # {}\n", name, python_type(ty), ty));
+        };
+
+        let offsets: Vec<String> = fields.iter()
+            .filter_map(|(name, _, offset, _)| offset.map(|offset| format!("\"{}\": {}", name, offset)))
+            .collect();
+        source.push_str(&format!("\n    OFFSETS: ClassVar[Dict[str, int]] = {{{}}}\n", offsets.join(", ")));
+    };
+
+    source.push_str("\n\n# Code of each program error, with its name and message\nPROGRAM_ERRORS: Dict[int, Tuple[str, str]] = {\n");
+    for error in Errors::all() {
+        source.push_str(&format!("    {}: (\"{:?}\", {:?}),\n", error.code(), error, error.to_string()));
+    };
+    source.push_str("}\n");
+
+    source
+}
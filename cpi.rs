@@ -0,0 +1,72 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{ AccountMeta, Instruction },
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program
+};
+
+use crate::{
+    find_config_pda,
+    find_creator_stats_pda,
+    find_title_index_pda,
+    find_title_reservation_pda,
+    find_voting_pda,
+    find_voting_registry_page_pda,
+    find_voting_registry_pda,
+    voting_title_seed,
+    CreateVotingInstruction,
+    InstructionType,
+    ProgramCreator
+};
+
+// A one person one vote voting opened by another program
+pub struct ProgramVoting {
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    // Seconds after the creation time, so the caller doesn't need the clock
+    pub starts_in: u64,
+    pub duration: u64,
+    // Page of the voting registry taking the next voting, `votings_count / MAX_REGISTRY_PAGE_ENTRIES` of
+    // the `VotingRegistryAccount`
+    pub registry_page: u32
+}
+
+// `create_voting` with the PDA of `creator_seeds` (bump included) as the creator. The calling program
+// invokes it with `invoke_signed` and the same seeds, passing the accounts in the returned order. The
+// creator PDA pays the rent and the config's creation deposit, so it must hold enough lamports and no data.
+pub fn create_voting(
+    program_id: &Pubkey,
+    creator_program: &Pubkey,
+    creator_seeds: &[&[u8]],
+    voting: ProgramVoting
+) -> Result<Instruction, ProgramError> {
+    let creator = Pubkey::create_program_address(creator_seeds, creator_program)?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(creator, true),
+        AccountMeta::new(find_voting_pda(&voting.title, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(&creator, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&voting.title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&voting.title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(voting.registry_page, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CreateVoting.header();
+    let (starts_at, ends_at) = (voting.starts_in, voting.starts_in.saturating_add(voting.duration));
+    CreateVotingInstruction {
+        description: voting.description,
+        relative_times: true,
+        program_creator: Some(ProgramCreator {
+            program_id: *creator_program,
+            seeds: creator_seeds.iter().map(|seed| seed.to_vec()).collect()
+        }),
+        ..CreateVotingInstruction::new(voting.title, voting.options, starts_at, ends_at)
+    }.serialize(&mut data)?;
+
+    Ok(Instruction { program_id: *program_id, accounts, data })
+}
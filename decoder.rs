@@ -0,0 +1,254 @@
+use borsh::BorshDeserialize;
+use solana_program::{ borsh0_10::try_from_slice_unchecked, program_error::ProgramError, pubkey::Pubkey };
+
+use crate::{
+    AccountType,
+    BallotBoxAccount,
+    BoostAccount,
+    BoostDepositAccount,
+    ConfigAccount,
+    CreatorStatsAccount,
+    ElectionAccount,
+    ExecutionAllowlistAccount,
+    FlagAccount,
+    IncentivePoolAccount,
+    FaucetClaimAccount,
+    LocalizedMetadataAccount,
+    MembershipAccount,
+    NotificationTargetAccount,
+    PollCardAccount,
+    registry_entries,
+    RegistryEntry,
+    ProposalAccount,
+    ProposalTemplateAccount,
+    RealmAccount,
+    RecordTreeAccount,
+    RecountAccount,
+    RewardClaimAccount,
+    RewardEscrowAccount,
+    SlotAccount,
+    SlotClaimAccount,
+    TallyShardAccount,
+    TallySnapshotAccount,
+    UserVotingAccount,
+    VoteMainAccount,
+    VoteBucketAccount,
+    TitleIndexAccount,
+    TitleReservationAccount,
+    EnvelopeAccount,
+    VoterAllowlistAccount,
+    VoterAllowlistPageAccount,
+    VoterIndexAccount,
+    voter_index_entries,
+    VotingArchiveAccount,
+    VotingMetadataAccount,
+    VotingRegistryAccount,
+    VotingRegistryPageAccount,
+    ShardVoteEvent,
+    TurnoutMilestoneEvent,
+    VoteCastEvent,
+    VoteUpdatedEvent,
+    VotingCreatedEvent,
+    VotingFinalizedEvent,
+    VotingFreezeEvent,
+    NotificationTargetEvent,
+    NOTIFICATION_TARGET_EVENT_TAG,
+    NOTIFICATION_TARGET_EVENT_VERSION,
+    SHARD_VOTE_EVENT_TAG,
+    SHARD_VOTE_EVENT_VERSION,
+    TURNOUT_EVENT_TAG,
+    TURNOUT_EVENT_VERSION,
+    VOTE_CAST_EVENT_TAG,
+    VOTE_CAST_EVENT_VERSION,
+    VOTE_UPDATED_EVENT_TAG,
+    VOTE_UPDATED_EVENT_VERSION,
+    VOTING_CREATED_EVENT_TAG,
+    VOTING_CREATED_EVENT_VERSION,
+    VOTING_FINALIZED_EVENT_TAG,
+    VOTING_FINALIZED_EVENT_VERSION,
+    VOTING_FREEZE_EVENT_TAG,
+    VOTING_FREEZE_EVENT_VERSION
+};
+
+// An account of the program, decoded by its discriminator
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DecodedAccount {
+    Config(ConfigAccount),
+    Voting(Box<VoteMainAccount>),
+    UserVote(UserVotingAccount),
+    TallySnapshot(TallySnapshotAccount),
+    RewardEscrow(RewardEscrowAccount),
+    RewardClaim(RewardClaimAccount),
+    Realm(RealmAccount),
+    Membership(MembershipAccount),
+    Boost(BoostAccount),
+    BoostDeposit(BoostDepositAccount),
+    // The page header and its voting keys
+    VoterIndex(VoterIndexAccount, Vec<Pubkey>),
+    Slot(SlotAccount),
+    SlotClaim(SlotClaimAccount),
+    ExecutionAllowlist(ExecutionAllowlistAccount),
+    Proposal(ProposalAccount),
+    BallotBox(BallotBoxAccount),
+    Recount(RecountAccount),
+    CreatorStats(CreatorStatsAccount),
+    VotingMetadata(VotingMetadataAccount),
+    VoterAllowlist(VoterAllowlistAccount),
+    VoterAllowlistPage(VoterAllowlistPageAccount),
+    VoteBucket(VoteBucketAccount),
+    TallyShard(TallyShardAccount),
+    TitleIndex(TitleIndexAccount),
+    TitleReservation(TitleReservationAccount),
+    Envelope(EnvelopeAccount),
+    ProposalTemplate(ProposalTemplateAccount),
+    VotingArchive(VotingArchiveAccount),
+    RecordTree(RecordTreeAccount),
+    Flag(FlagAccount),
+    IncentivePool(IncentivePoolAccount),
+    LocalizedMetadata(LocalizedMetadataAccount),
+    FaucetClaim(FaucetClaimAccount),
+    Election(ElectionAccount),
+    VotingRegistry(VotingRegistryAccount),
+    VotingRegistryPage(VotingRegistryPageAccount, Vec<RegistryEntry>),
+    NotificationTarget(NotificationTargetAccount),
+    PollCard(PollCardAccount)
+}
+
+// Decodes an account update, e.g. from a Geyser plugin or `getProgramAccounts`. `None` for accounts
+// of other programs, closed accounts and data without a known discriminator. Voter weight records
+// are owned by their plugin programs, so they're never decoded here.
+pub fn decode_account(program_id: &Pubkey, owner: &Pubkey, data: &[u8]) -> Result<Option<DecodedAccount>, ProgramError> {
+    if owner != program_id {
+        return Ok(None);
+    };
+
+    let account_type = match AccountType::from_discriminator(data) {
+        Some(account_type) => account_type,
+        None => return Ok(None)
+    };
+
+    let decoded = match account_type {
+        AccountType::Config => DecodedAccount::Config(try_from_slice_unchecked(data)?),
+        AccountType::Vote => DecodedAccount::Voting(Box::new(try_from_slice_unchecked(data)?)),
+        AccountType::UserVoting => DecodedAccount::UserVote(try_from_slice_unchecked(data)?),
+        AccountType::TallySnapshot => DecodedAccount::TallySnapshot(try_from_slice_unchecked(data)?),
+        AccountType::RewardEscrow => DecodedAccount::RewardEscrow(try_from_slice_unchecked(data)?),
+        AccountType::RewardClaim => DecodedAccount::RewardClaim(try_from_slice_unchecked(data)?),
+        AccountType::Realm => DecodedAccount::Realm(try_from_slice_unchecked(data)?),
+        AccountType::Membership => DecodedAccount::Membership(try_from_slice_unchecked(data)?),
+        AccountType::Boost => DecodedAccount::Boost(try_from_slice_unchecked(data)?),
+        AccountType::BoostDeposit => DecodedAccount::BoostDeposit(try_from_slice_unchecked(data)?),
+        AccountType::VoterIndex => DecodedAccount::VoterIndex(try_from_slice_unchecked(data)?, voter_index_entries(data)),
+        AccountType::VoterWeightRecord => return Ok(None),
+        AccountType::Slot => DecodedAccount::Slot(try_from_slice_unchecked(data)?),
+        AccountType::SlotClaim => DecodedAccount::SlotClaim(try_from_slice_unchecked(data)?),
+        AccountType::ExecutionAllowlist => DecodedAccount::ExecutionAllowlist(try_from_slice_unchecked(data)?),
+        AccountType::Proposal => DecodedAccount::Proposal(try_from_slice_unchecked(data)?),
+        AccountType::BallotBox => DecodedAccount::BallotBox(try_from_slice_unchecked(data)?),
+        AccountType::Recount => DecodedAccount::Recount(try_from_slice_unchecked(data)?),
+        AccountType::CreatorStats => DecodedAccount::CreatorStats(try_from_slice_unchecked(data)?),
+        AccountType::VotingMetadata => DecodedAccount::VotingMetadata(try_from_slice_unchecked(data)?),
+        AccountType::VoterAllowlist => DecodedAccount::VoterAllowlist(try_from_slice_unchecked(data)?),
+        AccountType::VoterAllowlistPage => DecodedAccount::VoterAllowlistPage(try_from_slice_unchecked(data)?),
+        AccountType::VoteBucket => DecodedAccount::VoteBucket(try_from_slice_unchecked(data)?),
+        AccountType::TallyShard => DecodedAccount::TallyShard(try_from_slice_unchecked(data)?),
+        AccountType::TitleIndex => DecodedAccount::TitleIndex(try_from_slice_unchecked(data)?),
+        AccountType::TitleReservation => DecodedAccount::TitleReservation(try_from_slice_unchecked(data)?),
+        AccountType::Envelope => DecodedAccount::Envelope(try_from_slice_unchecked(data)?),
+        AccountType::ProposalTemplate => DecodedAccount::ProposalTemplate(try_from_slice_unchecked(data)?),
+        AccountType::VotingArchive => DecodedAccount::VotingArchive(try_from_slice_unchecked(data)?),
+        AccountType::RecordTree => DecodedAccount::RecordTree(try_from_slice_unchecked(data)?),
+        AccountType::Flag => DecodedAccount::Flag(try_from_slice_unchecked(data)?),
+        AccountType::IncentivePool => DecodedAccount::IncentivePool(try_from_slice_unchecked(data)?),
+        AccountType::LocalizedMetadata => DecodedAccount::LocalizedMetadata(try_from_slice_unchecked(data)?),
+        AccountType::FaucetClaim => DecodedAccount::FaucetClaim(try_from_slice_unchecked(data)?),
+        AccountType::Election => DecodedAccount::Election(try_from_slice_unchecked(data)?),
+        AccountType::VotingRegistry => DecodedAccount::VotingRegistry(try_from_slice_unchecked(data)?),
+        AccountType::VotingRegistryPage => DecodedAccount::VotingRegistryPage(try_from_slice_unchecked(data)?, registry_entries(data)),
+        AccountType::NotificationTarget => DecodedAccount::NotificationTarget(try_from_slice_unchecked(data)?),
+        AccountType::PollCard => DecodedAccount::PollCard(try_from_slice_unchecked(data)?),
+        // Shrunk to its discriminator, the outcome is in the `VotingArchive`
+        AccountType::ArchivedVoting => return Ok(None)
+    };
+
+    Ok(Some(decoded))
+}
+
+// An event of the program, decoded by its tag
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DecodedEvent {
+    TurnoutMilestone(TurnoutMilestoneEvent),
+    ShardVote(ShardVoteEvent),
+    VotingCreated(VotingCreatedEvent),
+    VoteCast(VoteCastEvent),
+    VoteUpdated(VoteUpdatedEvent),
+    VotingFinalized(VotingFinalizedEvent),
+    VotingFreeze(VotingFreezeEvent),
+    NotificationTarget(NotificationTargetEvent)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VersionedEvent {
+    // 0 for events logged before they carried a version
+    pub version: u8,
+    pub event: DecodedEvent
+}
+
+// Decodes the `sol_log_data` fields of an event, i.e. the base64 decoded parts of its `Program data:` log
+// line. Events logged before versioning have no version field, they're version 0 and read as version 1.
+// Versions newer than the decoder only appended fields, their known fields are read and the rest skipped
+// unless `reject_newer` is set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EventDecoder {
+    pub reject_newer: bool
+}
+
+impl EventDecoder {
+    // `None` for data logged by other programs or with an unknown tag
+    pub fn decode(&self, fields: &[&[u8]]) -> Result<Option<VersionedEvent>, ProgramError> {
+        let (tag, version, payload) = match fields {
+            [tag, payload] => (*tag, 0, *payload),
+            [tag, [version], payload] => (*tag, *version, *payload),
+            _ => return Ok(None)
+        };
+
+        let latest = match tag {
+            TURNOUT_EVENT_TAG => TURNOUT_EVENT_VERSION,
+            SHARD_VOTE_EVENT_TAG => SHARD_VOTE_EVENT_VERSION,
+            VOTING_CREATED_EVENT_TAG => VOTING_CREATED_EVENT_VERSION,
+            VOTE_CAST_EVENT_TAG => VOTE_CAST_EVENT_VERSION,
+            VOTE_UPDATED_EVENT_TAG => VOTE_UPDATED_EVENT_VERSION,
+            VOTING_FINALIZED_EVENT_TAG => VOTING_FINALIZED_EVENT_VERSION,
+            VOTING_FREEZE_EVENT_TAG => VOTING_FREEZE_EVENT_VERSION,
+            NOTIFICATION_TARGET_EVENT_TAG => NOTIFICATION_TARGET_EVENT_VERSION,
+            _ => return Ok(None)
+        };
+
+        if self.reject_newer && version > latest {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        // Every version so far shares the layout of the latest one, a version changing it gets its own arm.
+        let event = match tag {
+            TURNOUT_EVENT_TAG => DecodedEvent::TurnoutMilestone(read_prefix(payload)?),
+            SHARD_VOTE_EVENT_TAG => DecodedEvent::ShardVote(read_prefix(payload)?),
+            VOTING_CREATED_EVENT_TAG => DecodedEvent::VotingCreated(read_prefix(payload)?),
+            VOTE_CAST_EVENT_TAG => DecodedEvent::VoteCast(read_prefix(payload)?),
+            VOTE_UPDATED_EVENT_TAG => DecodedEvent::VoteUpdated(read_prefix(payload)?),
+            VOTING_FINALIZED_EVENT_TAG => DecodedEvent::VotingFinalized(read_prefix(payload)?),
+            VOTING_FREEZE_EVENT_TAG => DecodedEvent::VotingFreeze(read_prefix(payload)?),
+            NOTIFICATION_TARGET_EVENT_TAG => DecodedEvent::NotificationTarget(read_prefix(payload)?),
+            _ => return Ok(None)
+        };
+
+        Ok(Some(VersionedEvent { version, event }))
+    }
+}
+
+// Reads the fields `T` knows of, leaving the ones a newer version appended
+fn read_prefix<T: BorshDeserialize>(payload: &[u8]) -> Result<T, ProgramError> {
+    Ok(T::deserialize(&mut &payload[..])?)
+}
@@ -0,0 +1,103 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    account_info::AccountInfo,
+    keccak::hashv,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    slot_hashes::MAX_ENTRIES as MAX_SLOT_HASHES,
+    sysvar::slot_hashes
+};
+
+use crate::Errors;
+
+// Slots between committing to a draw and revealing it, so the hash isn't known at commit time
+pub const REVEAL_DELAY_SLOTS: u64 = 10;
+// Max serialized size of an `Option<RandomDraw>`
+pub const RANDOM_DRAW_SPACE: usize = 1 + 8 + 8 + 1 + 8 + 32;
+
+// Weighted random draw among the voters of a closed voting. Every voter holds the tickets
+// [`draw_offset`, `draw_offset + weight`) of its user vote account, a ticket is drawn from the hash
+// of a slot committed to after the voting closed, then the holder is recorded as the winner.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RandomDraw {
+    // Summed weight of all voters, the next voter's tickets start here
+    pub total_weight: u64,
+    // Slot whose hash decides the draw, 0 until committed
+    pub reveal_slot: u64,
+    pub drawn: bool,
+    pub ticket: u64,
+    // `Pubkey::default()` until the holder of the ticket claims it
+    pub winner: Pubkey
+}
+
+impl RandomDraw {
+    // A commitment can be renewed once its slot hash fell out of the `SlotHashes` sysvar
+    pub fn commit(&mut self, current_slot: u64) -> Result<u64, ProgramError> {
+        if self.drawn {
+            return Err(Errors::AlreadyDrawn.into());
+        };
+
+        if self.reveal_slot != 0 && current_slot <= self.reveal_slot.saturating_add(MAX_SLOT_HASHES as u64) {
+            return Err(Errors::DrawAlreadyCommitted.into());
+        };
+
+        self.reveal_slot = current_slot + REVEAL_DELAY_SLOTS;
+
+        Ok(self.reveal_slot)
+    }
+
+    pub fn reveal(&mut self, voting: &Pubkey, slot_hashes: &AccountInfo, current_slot: u64) -> Result<u64, ProgramError> {
+        if self.drawn {
+            return Err(Errors::AlreadyDrawn.into());
+        };
+
+        if self.reveal_slot == 0 || current_slot <= self.reveal_slot {
+            return Err(Errors::DrawNotRevealable.into());
+        };
+
+        if self.total_weight == 0 {
+            return Err(Errors::NoDrawTickets.into());
+        };
+
+        let slot_hash = find_slot_hash(slot_hashes, self.reveal_slot)?;
+        let random = hashv(&[ &slot_hash, voting.as_ref() ]).to_bytes();
+        self.ticket = u64::from_le_bytes(random[..8].try_into().unwrap()) % self.total_weight;
+        self.drawn = true;
+
+        Ok(self.ticket)
+    }
+
+    pub fn holds_ticket(&self, draw_offset: u64, weight: u64) -> bool {
+        self.drawn && self.ticket >= draw_offset && self.ticket - draw_offset < weight
+    }
+}
+
+// Hash of the committed slot, or of the first slot after it when it was skipped. Reads the raw
+// sysvar data, deserializing all of `SlotHashes` doesn't fit the compute budget.
+fn find_slot_hash(slot_hashes: &AccountInfo, slot: u64) -> Result<[u8; 32], ProgramError> {
+    if *slot_hashes.key != slot_hashes::ID {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    let data = slot_hashes.data.borrow();
+    let len = data.get(..8).ok_or(ProgramError::InvalidAccountData)?;
+    let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+
+    // Entries are (slot, hash), newest first. The slot is only covered while an entry at or before it
+    // is still kept, otherwise the first kept slot could be picked by waiting.
+    let mut found = None;
+    for entry in data[8..].chunks_exact(40).take(len) {
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if entry_slot < slot {
+            return found.ok_or(Errors::DrawExpired.into());
+        };
+
+        found = Some(entry[8..].try_into().unwrap());
+        if entry_slot == slot {
+            return Ok(entry[8..].try_into().unwrap());
+        };
+    };
+
+    Err(Errors::DrawExpired.into())
+}
@@ -0,0 +1,155 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    account_info::{ next_account_info, AccountInfo },
+    borsh0_10::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    keccak::hash,
+    program_error::ProgramError,
+    pubkey::Pubkey
+};
+
+use crate::{
+    checks::{ assert_account_type, assert_owned_by },
+    weight::{ active_stake, token_balance, verify_merkle_proof },
+    AccountType,
+    Errors,
+    VoterAllowlistAccount,
+    VoterAllowlistPageAccount
+};
+
+// Gates across all clauses of a policy
+pub const MAX_GATES: usize = 4;
+// Max serialized size of an `EligibilityPolicy`
+pub const ELIGIBILITY_POLICY_SPACE: usize = 4 + MAX_GATES * (4 + GATE_SPACE);
+const GATE_SPACE: usize = 1 + 32 + 8;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Gate {
+    // At least `min_amount` of the mint in the voter's token account, an NFT is a mint with `min_amount` 1
+    TokenBalance { mint: Pubkey, min_amount: u64 },
+    // An active stake account with the voter as staker and at least `min_lamports` delegated
+    Stake { min_lamports: u64 },
+    // The voter is a leaf of the merkle root, leaves are keccak(voter)
+    Allowlist([u8; 32]),
+    // The voter is on the voting's sealed `VoterAllowlistAccount`. Its gate accounts are the allowlist
+    // and the page listing the voter, or only the allowlist when it's a merkle root.
+    UploadedAllowlist
+}
+
+// Clauses are OR-ed and the gates of a clause AND-ed, e.g. `[[token, allowlist], [stake]]` is
+// "token AND allowlisted, OR stake". No clauses means anyone can vote.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EligibilityPolicy {
+    pub clauses: Vec<Vec<Gate>>
+}
+
+// Sent with the ballot when the voting has an eligibility policy or a sealed `VoterRoll`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct EligibilityProof {
+    // The clause the voter satisfies, its gate accounts follow in order
+    pub clause: u8,
+    // For the clause's `Allowlist` gate, or its `UploadedAllowlist` gate when the allowlist is a merkle root
+    pub allowlist_proof: Vec<[u8; 32]>,
+    // Against the voting's `VoterRoll`, empty without one
+    pub roll_proof: Vec<[u8; 32]>
+}
+
+impl EligibilityPolicy {
+    pub fn validate(&self) -> ProgramResult {
+        let gates_count: usize = self.clauses.iter().map(|clause| clause.len()).sum();
+        if gates_count > MAX_GATES || self.clauses.iter().any(|clause| clause.is_empty()) {
+            return Err(Errors::InvalidEligibilityPolicy.into());
+        };
+
+        Ok(())
+    }
+
+    // Takes the gate accounts of the chosen clause from `accounts`
+    pub fn check<'a>(
+        &self,
+        program_id: &Pubkey,
+        voting: &Pubkey,
+        voter: &Pubkey,
+        accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+        proof: Option<&EligibilityProof>
+    ) -> ProgramResult {
+        let proof = match (self.clauses.is_empty(), proof) {
+            (true, None) => return Ok(()),
+            (false, Some(proof)) => proof,
+            _ => return Err(Errors::NotEligible.into())
+        };
+
+        let clause = self.clauses.get(proof.clause as usize).ok_or(Errors::NotEligible)?;
+        for gate in clause.iter() {
+            let passed = match gate {
+                Gate::TokenBalance { mint, min_amount } => {
+                    token_balance(next_account_info(accounts)?, mint, voter, Errors::NotEligible)? >= *min_amount
+                },
+                Gate::Stake { min_lamports } => {
+                    active_stake(next_account_info(accounts)?, voter, Errors::NotEligible)? >= *min_lamports
+                },
+                Gate::Allowlist(root) => {
+                    verify_merkle_proof(hash(voter.as_ref()).0, &proof.allowlist_proof, root)
+                },
+                Gate::UploadedAllowlist => {
+                    on_uploaded_allowlist(program_id, voting, voter, accounts, &proof.allowlist_proof)?
+                }
+            };
+
+            // Allowlisted votings tell a voter missing from the list apart from one failing another gate
+            if passed == false {
+                let error = match gate {
+                    Gate::Allowlist(_) | Gate::UploadedAllowlist => Errors::NotAllowlisted,
+                    Gate::TokenBalance { .. } | Gate::Stake { .. } => Errors::NotEligible
+                };
+
+                return Err(error.into());
+            };
+        };
+
+        Ok(())
+    }
+}
+
+// Both accounts are only written by the program, so matching `voting` is enough to tie them to the voting
+fn on_uploaded_allowlist<'a>(
+    program_id: &Pubkey,
+    voting: &Pubkey,
+    voter: &Pubkey,
+    accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+    proof: &[[u8; 32]]
+) -> Result<bool, ProgramError> {
+    let allowlist = next_account_info(accounts)?;
+    assert_owned_by(allowlist, program_id)?;
+
+    let allowlist_data = {
+        let data = allowlist.data.borrow();
+        assert_account_type(&data, AccountType::VoterAllowlist)?;
+
+        try_from_slice_unchecked::<VoterAllowlistAccount>(&data)?
+    };
+
+    if allowlist_data.voting != *voting {
+        return Err(Errors::NotAllowlisted.into());
+    };
+
+    if allowlist_data.sealed == false {
+        return Err(Errors::AllowlistNotSealed.into());
+    };
+
+    if let Some(root) = allowlist_data.root.as_ref() {
+        return Ok(verify_merkle_proof(hash(voter.as_ref()).0, proof, root));
+    };
+
+    let page = next_account_info(accounts)?;
+    assert_owned_by(page, program_id)?;
+
+    let data = page.data.borrow();
+    assert_account_type(&data, AccountType::VoterAllowlistPage)?;
+
+    let page_data = try_from_slice_unchecked::<VoterAllowlistPageAccount>(&data)?;
+
+    Ok(page_data.voting == *voting && page_data.voters.contains(voter))
+}
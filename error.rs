@@ -0,0 +1,433 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{ PrintProgramError, ProgramError }
+};
+use thiserror::Error;
+
+// Codes are part of the client API, new errors are appended and existing codes never change. Handlers
+// return them as `ProgramError::Custom(code)`, clients read the code back with `Errors::from_u32`.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, FromPrimitive)]
+pub enum Errors {
+    #[error("Starting time < Current time")]
+    InvalidStartingTime = 0,
+    #[error("Ending time < Starting time")]
+    InvalidEndingTime = 1,
+    #[error("Max voting time exceeded.")]
+    MaxVotingTimeExceeded = 2,
+    #[error("Invalid system program account.")]
+    InvalidSystemProgram = 3,
+    #[error("Invalid PDA seeds.")]
+    InvalidPdaAddress = 4,
+    #[error("User must be signer.")]
+    UserSigningNeeded = 5,
+    #[error("User's account must be writable.")]
+    UsersAccountMustBeMutable = 6,
+    #[error("PDA's account must be writable.")]
+    PDAsAccountMustBeMutable = 7,
+    #[error("Title must be between 10 and 50 characters")]
+    TitleInvalidLength = 8,
+    #[error("Invalid account owner.")]
+    InvalidAccountOwner = 9,
+    #[error("Voting has not started yet.")]
+    VotingNotStarted = 10,
+    #[error("Voting has been ended.")]
+    VotingEnded = 11,
+    #[error("Signer is not the voting creator.")]
+    NotVotingCreator = 12,
+    #[error("Voting has already started.")]
+    VotingAlreadyStarted = 13,
+    #[error("Description length > 256")]
+    DescriptionTooLong = 14,
+    #[error("Options count must be at least 2")]
+    InvalidOptionsCount = 15,
+    #[error("Option length must be between 1 and 32")]
+    OptionInvalidLength = 16,
+    #[error("Invalid option index.")]
+    InvalidOptionIndex = 17,
+    #[error("Invalid option order.")]
+    InvalidOptionOrder = 18,
+    #[error("Instruction is disabled.")]
+    InstructionDisabled = 19,
+    #[error("Invalid program data account.")]
+    InvalidProgramData = 20,
+    #[error("Signer is not the program upgrade authority.")]
+    NotUpgradeAuthority = 21,
+    #[error("Signer is not the config admin.")]
+    NotConfigAdmin = 22,
+    #[error("Signer is not the pending config admin.")]
+    NotPendingAdmin = 23,
+    #[error("Voting has been cancelled.")]
+    VotingCancelled = 24,
+    #[error("Voting has been finalized.")]
+    VotingFinalized = 25,
+    #[error("Tally snapshot interval has not passed yet.")]
+    SnapshotTooEarly = 26,
+    #[error("Voting has not ended yet.")]
+    VotingNotEnded = 27,
+    #[error("Certificate already claimed.")]
+    CertificateAlreadyClaimed = 28,
+    #[error("Invalid token program account.")]
+    InvalidTokenProgram = 29,
+    #[error("Voting has no winning option.")]
+    NoWinningOption = 30,
+    #[error("Ballot is not on the winning option.")]
+    NotWinningVoter = 31,
+    #[error("Reward already claimed.")]
+    RewardAlreadyClaimed = 32,
+    #[error("Reward amount must be > 0")]
+    InvalidRewardAmount = 33,
+    #[error("Invalid memo program account.")]
+    InvalidMemoProgram = 34,
+    #[error("Account would not be rent exempt.")]
+    NotRentExempt = 35,
+    #[error("Invalid co-creator accounts.")]
+    InvalidCoCreators = 36,
+    #[error("Creator threshold must be between 1 and the creators count")]
+    InvalidCreatorThreshold = 37,
+    #[error("Not enough creator signatures.")]
+    NotEnoughCreatorSignatures = 38,
+    #[error("Realm name length must be between 1 and 32")]
+    RealmNameInvalidLength = 39,
+    #[error("Invalid realm settings.")]
+    InvalidRealmSettings = 40,
+    #[error("Signer is not the realm admin.")]
+    NotRealmAdmin = 41,
+    #[error("Signer is not a realm member.")]
+    NotRealmMember = 42,
+    #[error("Realm setting is locked.")]
+    RealmSettingLocked = 43,
+    #[error("Voting time is below the minimum.")]
+    VotingTimeTooShort = 44,
+    #[error("Vote updates are disabled for this voting.")]
+    VoteUpdatesDisabled = 45,
+    #[error("Boost amount must be greater than zero.")]
+    InvalidBoostAmount = 46,
+    #[error("Boost was already released.")]
+    BoostAlreadyReleased = 47,
+    #[error("Voting is not cancelled.")]
+    VotingNotCancelled = 48,
+    #[error("Treasury must be the config admin.")]
+    InvalidTreasury = 49,
+    #[error("Options count exceeds the maximum.")]
+    TooManyOptions = 50,
+    #[error("Title can't contain control characters or start or end with whitespace.")]
+    TitleInvalidCharacters = 51,
+    #[error("Account is already initialized.")]
+    AlreadyInitialized = 52,
+    #[error("Weight source is invalid.")]
+    InvalidWeightSource = 53,
+    #[error("Weight account doesn't belong to the voter.")]
+    InvalidWeightAccount = 54,
+    #[error("Weight proof is invalid.")]
+    InvalidWeightProof = 55,
+    #[error("Vote weight must be greater than zero.")]
+    ZeroVoteWeight = 56,
+    #[error("Tally mode is invalid.")]
+    InvalidTallyMode = 57,
+    #[error("Ballot doesn't match the tally mode.")]
+    InvalidBallot = 58,
+    #[error("Ballot ranks more options than allowed.")]
+    RankDepthExceeded = 59,
+    #[error("Voting has too many distinct rankings.")]
+    TooManyRankings = 60,
+    #[error("Eligibility policy is invalid.")]
+    InvalidEligibilityPolicy = 61,
+    #[error("Voter doesn't meet the eligibility policy.")]
+    NotEligible = 62,
+    #[error("Option reached its cap.")]
+    OptionFull = 63,
+    #[error("Option caps don't match the options.")]
+    InvalidOptionCaps = 64,
+    #[error("Signup voting only takes slot claims.")]
+    SignupVoting = 65,
+    #[error("Voting is not a signup voting.")]
+    NotSignupVoting = 66,
+    #[error("All slots are claimed.")]
+    SlotsFull = 67,
+    #[error("Voting has no random draw.")]
+    DrawNotEnabled = 68,
+    #[error("Draw is already committed.")]
+    DrawAlreadyCommitted = 69,
+    #[error("Draw can't be revealed yet.")]
+    DrawNotRevealable = 70,
+    #[error("Committed slot hash is no longer available, commit again.")]
+    DrawExpired = 71,
+    #[error("Nobody voted, there is nothing to draw.")]
+    NoDrawTickets = 72,
+    #[error("Winner was already drawn.")]
+    AlreadyDrawn = 73,
+    #[error("Voter doesn't hold the drawn ticket.")]
+    NotDrawWinner = 74,
+    #[error("Draw winner is already recorded.")]
+    DrawAlreadyClaimed = 75,
+    #[error("Oracle condition is invalid.")]
+    InvalidOracleCondition = 76,
+    #[error("Oracle feed doesn't match the condition.")]
+    InvalidOracleFeed = 77,
+    #[error("Oracle feed price is stale or not trading.")]
+    StaleOracleFeed = 78,
+    #[error("Voting must be finalized first.")]
+    VotingNotFinalized = 79,
+    #[error("Execution allowlist is invalid.")]
+    InvalidExecutionAllowlist = 80,
+    #[error("Only realm votings can execute instructions.")]
+    NotRealmVoting = 81,
+    #[error("Proposal instruction is invalid.")]
+    InvalidProposalInstruction = 82,
+    #[error("Instruction is not on the realm's execution allowlist.")]
+    ActionNotAllowed = 83,
+    #[error("Proposal instructions must be for the same option.")]
+    ProposalOptionMismatch = 84,
+    #[error("Proposal has too many instructions.")]
+    TooManyProposalInstructions = 85,
+    #[error("Proposal option didn't win.")]
+    ProposalNotPassed = 86,
+    #[error("All proposal instructions are executed.")]
+    ProposalExecuted = 87,
+    #[error("Accounts don't match the proposal instruction.")]
+    InvalidInstructionAccounts = 88,
+    #[error("Token ballots can't be used with gated, signup or quadratic votings.")]
+    TokenBallotsUnsupported = 89,
+    #[error("Token account isn't the option's ballot token account.")]
+    InvalidBallotTokenAccount = 90,
+    #[error("Recount hasn't counted every voter.")]
+    RecountIncomplete = 91,
+    #[error("Recounted records must be in ascending key order.")]
+    InvalidRecountOrder = 92,
+    #[error("Creator has too many active votings.")]
+    TooManyActiveVotings = 93,
+    #[error("Start time tolerance is too large.")]
+    InvalidStartTimeTolerance = 94,
+    #[error("Timezone or schedule is too long.")]
+    DisplayHintTooLong = 95,
+    #[error("Invalid weight mints.")]
+    InvalidWeightMints = 96,
+    #[error("Invalid BPF upgradeable loader program.")]
+    InvalidLoaderProgram = 97,
+    #[error("Tally overflow.")]
+    TallyOverflow = 98,
+    #[error("Voter allowlist is sealed.")]
+    AllowlistSealed = 99,
+    #[error("Voter allowlist isn't sealed yet.")]
+    AllowlistNotSealed = 100,
+    #[error("Allowlist chunk is empty, too large or crosses a page.")]
+    InvalidAllowlistChunk = 101,
+    #[error("Voter allowlist already exists.")]
+    AllowlistInUse = 102,
+    #[error("Invalid record buckets.")]
+    InvalidRecordBuckets = 103,
+    #[error("Vote bucket is full.")]
+    BucketFull = 104,
+    #[error("Title is reserved by another creator.")]
+    TitleReserved = 105,
+    #[error("Voting is a draft.")]
+    VotingIsDraft = 106,
+    #[error("Voting is not a draft.")]
+    VotingNotDraft = 107,
+    #[error("Voting configuration is locked.")]
+    VotingLocked = 108,
+    #[error("Invalid required setup stages.")]
+    InvalidRequiredStages = 109,
+    #[error("Voting setup is incomplete.")]
+    SetupIncomplete = 110,
+    #[error("Invalid envelope voting settings.")]
+    InvalidEnvelopeSettings = 111,
+    #[error("Envelope votings only take sealed ballots.")]
+    EnvelopeVoting = 112,
+    #[error("Voting is not an envelope voting.")]
+    NotEnvelopeVoting = 113,
+    #[error("Invalid envelope.")]
+    InvalidEnvelope = 114,
+    #[error("Envelope results don't match the sealed ballots.")]
+    InvalidEnvelopeResults = 115,
+    #[error("Envelope results haven't been submitted.")]
+    EnvelopeResultsMissing = 116,
+    #[error("Invalid tie policy.")]
+    InvalidTiePolicy = 117,
+    #[error("Voting is not tied.")]
+    NoTie = 118,
+    #[error("Invalid voting dependency.")]
+    InvalidDependency = 119,
+    #[error("Voting dependency is not met.")]
+    DependencyNotMet = 120,
+    #[error("Invalid proposal template.")]
+    InvalidProposalTemplate = 121,
+    #[error("Template value is out of bounds.")]
+    InvalidTemplateValue = 122,
+    #[error("Creator is not a PDA of the given program.")]
+    InvalidProgramCreator = 123,
+    #[error("Realm votings can't be cloned.")]
+    RealmVotingNotCloneable = 124,
+    #[error("Voting can't be archived yet.")]
+    ArchiveTooEarly = 125,
+    #[error("Realm votings can't be archived.")]
+    RealmVotingNotArchivable = 126,
+    #[error("Vote records must be merkleized in vote_index order.")]
+    InvalidRecordOrder = 127,
+    #[error("Vote records are already merkleized.")]
+    RecordsMerkleized = 128,
+    #[error("Vote records are not merkleized.")]
+    RecordsNotMerkleized = 129,
+    #[error("Ballot is not in the vote records.")]
+    InvalidBallotProof = 130,
+    #[error("Voting is already flagged by this user.")]
+    AlreadyFlagged = 131,
+    #[error("Only the program or realm admin can moderate the voting.")]
+    NotModerator = 132,
+    #[error("Invalid vote incentive.")]
+    InvalidIncentive = 133,
+    #[error("Invalid turnout milestones.")]
+    InvalidTurnoutMilestones = 134,
+    #[error("Finalization can't share a transaction with a ballot for the voting.")]
+    BallotInFinalizeTransaction = 135,
+    #[error("Proposal is already executing an instruction.")]
+    ProposalInProgress = 136,
+    #[error("Tallies are hidden until the voting ends.")]
+    TalliesHidden = 137,
+    #[error("Invalid language tag.")]
+    InvalidLanguage = 138,
+    #[error("Voter index page is full, the next page takes the voting.")]
+    VoterIndexPageFull = 139,
+    #[error("Creation deposit needs a refund window of at least a day.")]
+    InvalidCreationDeposit = 140,
+    #[error("Creation deposit must be refunded to the creator.")]
+    InvalidDepositRecipient = 141,
+    #[error("Account isn't the payer of the swept account.")]
+    InvalidPayer = 142,
+    #[error("Voting can only be swept 180 days after it ended.")]
+    VotingNotExpired = 143,
+    #[error("Vote record belongs to another voter.")]
+    NotVoteRecordOwner = 144,
+    #[error("Invalid result attestors.")]
+    InvalidResultAttestors = 145,
+    #[error("Signer isn't an attestor of the voting result.")]
+    NotResultAttestor = 146,
+    #[error("Result already attested by this attestor.")]
+    ResultAlreadyAttested = 147,
+    #[error("Result is waiting for its attestors.")]
+    ResultNotAttested = 148,
+    #[error("Percentages have at most 6 decimals.")]
+    InvalidPercentageDecimals = 149,
+    #[error("Invalid tally shards.")]
+    InvalidTallyShards = 150,
+    #[error("Not supported by sharded votings.")]
+    ShardedVoting = 151,
+    #[error("Tally shards are not merged yet.")]
+    ShardsNotMerged = 152,
+    #[error("Tally shards are already merged.")]
+    ShardsMerged = 153,
+    #[error("Invalid weight cap.")]
+    InvalidWeightCap = 154,
+    #[error("Weight was already credited in this slot.")]
+    WeightAlreadyCredited = 155,
+    #[error("No weight left to credit.")]
+    WeightFullyCredited = 156,
+    #[error("Weight token account is frozen.")]
+    FrozenWeightAccount = 157,
+    #[error("The creator is not the update authority of the proposal note.")]
+    InvalidProposalNote = 158,
+    #[error("Expected a transfer to the kiosk and a memo with the ballot before the claim.")]
+    InvalidKioskTransfer = 159,
+    #[error("Voting can't be closed yet.")]
+    CloseTooEarly = 160,
+    #[error("Wallet claimed from the faucet less than a day ago.")]
+    FaucetCooldown = 161,
+    #[error("Faucet doesn't hold enough lamports for the claim.")]
+    FaucetEmpty = 162,
+    #[error("Faucet is only available in devnet builds.")]
+    FaucetDisabled = 163,
+    #[error("Commit-reveal votings only take committed ballots.")]
+    CommitRevealVoting = 164,
+    #[error("Voting is not a commit-reveal voting.")]
+    NotCommitRevealVoting = 165,
+    #[error("Invalid reveal window.")]
+    InvalidRevealWindow = 166,
+    #[error("Committed ballots can still be revealed.")]
+    RevealWindowOpen = 167,
+    #[error("Reveal window is closed.")]
+    RevealWindowClosed = 168,
+    #[error("Ballot is already revealed.")]
+    BallotRevealed = 169,
+    #[error("Ballot and salt don't match the commitment.")]
+    InvalidReveal = 170,
+    #[error("Instruction payload version is not supported.")]
+    UnsupportedPayloadVersion = 171,
+    #[error("Invalid election name or races.")]
+    InvalidElection = 172,
+    #[error("Election is already finalized.")]
+    ElectionFinalized = 173,
+    #[error("Voter is not on the voting's allowlist.")]
+    NotAllowlisted = 174,
+    #[error("Winners count is 0, more than the options, or more than 1 for a voting without plain counts.")]
+    InvalidWinnersCount = 175,
+    #[error("Apportionment needs 1 to 1000 seats and a voting that isn't ranked.")]
+    InvalidApportionment = 176,
+    #[error("Voter roll is already sealed.")]
+    VoterRollSealed = 177,
+    #[error("Voter roll can only be sealed before the voting's first ballot.")]
+    VoterRollClosed = 178,
+    #[error("Voter is not on the voting's sealed voter roll.")]
+    NotOnVoterRoll = 179,
+    #[error("The voting is frozen.")]
+    VotingFrozen = 180,
+    #[error("The voting isn't frozen.")]
+    VotingNotFrozen = 181,
+    #[error("Late registration needs an uploaded allowlist and at most 100% of the voting window.")]
+    InvalidLateRegistration = 182,
+    #[error("The allowlist's late registration window is closed.")]
+    LateRegistrationClosed = 183,
+    #[error("Pass threshold is more than 10000 basis points.")]
+    InvalidPassThreshold = 184,
+    #[error("Title is longer than 50 characters or 128 bytes.")]
+    TitleTooLong = 185,
+    #[error("User already voted.")]
+    UserAlreadyVoted = 186,
+    #[error("Instruction data is shorter than its discriminator.")]
+    InstructionDataTooShort = 187,
+    #[error("Electorate is empty, or the quorum is more than 10000 basis points or set without an electorate.")]
+    InvalidElectorate = 188,
+    #[error("Account is already in the current layout.")]
+    AccountAlreadyMigrated = 189,
+    #[error("Account data matches no known layout.")]
+    UnknownAccountVersion = 190,
+    #[error("Account is not the voting's poll card.")]
+    InvalidPollCard = 191,
+    #[error("Account is in the middle of a transfer of another instruction.")]
+    AccountInProgress = 192
+}
+
+impl Errors {
+    // The code of `ProgramError::Custom`
+    pub const fn code(self) -> u32 {
+        self as u32
+    }
+
+    // Every error in code order. Codes run from 0 without gaps, `tests/fixtures/errors.golden` pins them.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..).map_while(Self::from_u32)
+    }
+}
+
+impl From<Errors> for ProgramError {
+    fn from(error: Errors) -> Self {
+        ProgramError::Custom(error.code())
+    }
+}
+
+impl<T> DecodeError<T> for Errors {
+    fn type_of() -> &'static str {
+        "Errors"
+    }
+}
+
+impl PrintProgramError for Errors {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -0,0 +1,76 @@
+// Setup shared by the scenarios. The program runs natively inside `solana-program-test`, no SBF build
+// is needed, and every instruction goes through a real transaction.
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey
+};
+use solana_program_test::{ processor, ProgramTest, ProgramTestContext };
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{ Keypair, Signer },
+    transaction::Transaction
+};
+
+use solana_voting_program::{ client::get_results, ResultsReturnData };
+
+pub type ScenarioResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+pub async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("solana_voting_program", solana_voting_program::id(), processor!(process_instruction));
+    program_test.prefer_bpf(false);
+
+    program_test.start_with_context().await
+}
+
+// The program ties the account slice to the lifetime of the accounts, the test runtime hands them over
+// with two lifetimes. The slice never outlives the accounts, so the shorter one fits both.
+fn process_instruction<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+
+    solana_voting_program::process_instruction(program_id, accounts, data)
+}
+
+// Sends `instructions` in one transaction paid by the context's payer
+pub async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> ScenarioResult {
+    let mut all_signers = vec![ &context.payer ];
+    all_signers.extend_from_slice(signers);
+
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+    context.banks_client.process_transaction(transaction).await?;
+
+    Ok(())
+}
+
+pub async fn results(context: &mut ProgramTestContext, voting: &Pubkey) -> ScenarioResult<ResultsReturnData> {
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[ get_results(&solana_voting_program::id(), voting) ],
+        Some(&context.payer.pubkey()),
+        &[ &context.payer ],
+        blockhash
+    );
+
+    let simulation = context.banks_client.simulate_transaction(transaction).await?;
+    let return_data = simulation.simulation_details
+        .and_then(|details| details.return_data)
+        .ok_or("no return data")?;
+
+    Ok(ResultsReturnData::try_from_slice(&return_data.data)?)
+}
+
+pub async fn now(context: &mut ProgramTestContext) -> ScenarioResult<u64> {
+    Ok(context.banks_client.get_sysvar::<Clock>().await?.unix_timestamp as u64)
+}
+
+// Moves the cluster clock to `unix_timestamp`, the program reads the time from the clock sysvar
+pub async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: u64) -> ScenarioResult {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp = unix_timestamp as i64;
+    context.set_sysvar(&clock);
+
+    Ok(())
+}
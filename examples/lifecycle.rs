@@ -0,0 +1,103 @@
+// A voting from creation to closing: the creator opens it, voters cast their ballots, one of them
+// changes their mind, the voting is finalized once it ended, the voters take back the rent of their vote
+// records and the creator closes the voting a month later. Every instruction comes from the `client`
+// builders.
+//
+// cargo run -p voting-examples --example lifecycle
+use solana_program::system_instruction;
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{
+        close_user_vote,
+        close_voting,
+        create_voting,
+        finalize_voting,
+        sync_poll_card,
+        update_vote,
+        vote,
+        VoteProofs
+    },
+    decoder::{ decode_account, DecodedAccount },
+    find_poll_card_pda,
+    find_user_vote_pda,
+    find_voting_pda,
+    ARCHIVE_DELAY
+};
+use voting_examples::{ now, results, send, start, warp_to, ScenarioResult };
+
+const TITLE: &str = "Lunch on Fridays";
+const OPTIONS: [&str; 3] = ["Pizza", "Sushi", "Salad"];
+const VOTERS: usize = 12;
+const DURATION: u64 = 3 * 24 * 60 * 60;
+// Enough for the voter's user vote account, voter index and fees
+const VOTER_FUNDING: u64 = 100_000_000;
+
+#[tokio::main]
+async fn main() -> ScenarioResult {
+    let program_id = solana_voting_program::id();
+    let mut context = start().await;
+
+    let creator = Keypair::new();
+    let voters = (0..VOTERS).map(|_| Keypair::new()).collect::<Vec<_>>();
+
+    let mut fundings = vec![ system_instruction::transfer(&context.payer.pubkey(), &creator.pubkey(), VOTER_FUNDING * 10) ];
+    fundings.extend(voters.iter().map(|voter| system_instruction::transfer(&context.payer.pubkey(), &voter.pubkey(), VOTER_FUNDING)));
+    send(&mut context, &fundings, &[]).await?;
+
+    // Created open, the poll card follows it from the first ballot on
+    let (voting, _) = find_voting_pda(TITLE, &program_id);
+    let options = OPTIONS.iter().map(|option| option.to_string()).collect();
+    let created_at = now(&mut context).await?;
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), "Where the team orders from".to_string(), options, DURATION),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ &creator ]).await?;
+    println!("Created {} at {}", TITLE, voting);
+
+    // Each voter signs their own ballot, the options in turn
+    for (position, voter) in voters.iter().enumerate() {
+        let ballot = vec![ (position % OPTIONS.len()) as u8 ];
+        let instruction = vote(&program_id, &voter.pubkey(), &voting, ballot, TITLE.to_string(), false, VoteProofs::default());
+        send(&mut context, &[ instruction ], &[ voter ]).await?;
+    };
+    println!("{} ballots cast", VOTERS);
+
+    // The last salad voter goes with pizza after all
+    let changed = &voters[VOTERS - 1];
+    send(&mut context, &[ update_vote(&program_id, &changed.pubkey(), &voting, vec![ 0 ], false) ], &[ changed ]).await?;
+
+    let results = results(&mut context, &voting).await?;
+    println!("Tallies before the end: {:?}", results);
+    assert_eq!(results.total_voters, VOTERS as u64);
+    assert_eq!(results.tallies, vec![ 5, 4, 3 ]);
+    assert_eq!(results.winning_option, Some(0));
+    assert_eq!(results.spoiled_ballots, 0);
+
+    // Anyone can finalize once it ended, the creator gets back the creation deposit if there is one
+    warp_to(&mut context, created_at + DURATION + 1).await?;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await?;
+
+    let poll_card = context.banks_client.get_account(find_poll_card_pda(&voting, &program_id).0).await?.ok_or("poll card missing")?;
+    match decode_account(&program_id, &poll_card.owner, &poll_card.data)? {
+        Some(DecodedAccount::PollCard(poll_card)) => println!("Poll card after finalizing: {:?}", poll_card),
+        decoded => return Err(format!("not a poll card: {:?}", decoded).into())
+    };
+
+    // Vote records can be closed as soon as the voting is finalized
+    for voter in voters.iter() {
+        send(&mut context, &[ close_user_vote(&program_id, &voter.pubkey(), &voting) ], &[ voter ]).await?;
+
+        let user_vote = find_user_vote_pda(&voting, &voter.pubkey(), &program_id).0;
+        assert!(context.banks_client.get_account(user_vote).await?.is_none());
+    };
+    println!("{} vote records closed", VOTERS);
+
+    // The voting itself only once the claims had time to land, its rent goes back to the creator
+    warp_to(&mut context, created_at + DURATION + ARCHIVE_DELAY + 1).await?;
+    let balance = context.banks_client.get_balance(creator.pubkey()).await?;
+    send(&mut context, &[ close_voting(&program_id, &creator.pubkey(), &voting, &creator.pubkey()) ], &[ &creator ]).await?;
+    println!("Voting closed, {} lamports refunded", context.banks_client.get_balance(creator.pubkey()).await? - balance);
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{ AccountMeta, Instruction },
+    program_error::ProgramError,
+    pubkey::Pubkey
+};
+
+use crate::Errors;
+
+// Entries of a realm's execution allowlist
+pub const MAX_ALLOWED_ACTIONS: usize = 16;
+// Longest instruction data prefix an entry can pin, an Anchor discriminator
+pub const MAX_DATA_PREFIX_LENGTH: usize = 8;
+// Limits of a proposal's instructions, each one has to fit a single `execute_next_instruction`
+pub const MAX_PROPOSAL_INSTRUCTIONS: usize = 8;
+pub const MAX_PROPOSAL_INSTRUCTION_ACCOUNTS: usize = 16;
+pub const MAX_PROPOSAL_INSTRUCTION_DATA: usize = 512;
+// Placeholders of a proposal template, and the accounts an account placeholder can pick from
+pub const MAX_TEMPLATE_PARAMS: usize = 4;
+pub const MAX_TEMPLATE_CHOICES: usize = 8;
+
+// A program passed proposals may call, optionally only the instructions starting with `data_prefix`
+// (e.g. a 1 byte SPL instruction tag or an 8 byte Anchor discriminator). An empty prefix allows any
+// instruction of the program.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AllowedAction {
+    pub program_id: Pubkey,
+    pub data_prefix: Vec<u8>
+}
+
+pub fn validate_allowlist(actions: &[AllowedAction]) -> ProgramResult {
+    if actions.len() > MAX_ALLOWED_ACTIONS
+        || actions.iter().any(|action| action.data_prefix.len() > MAX_DATA_PREFIX_LENGTH)
+    {
+        return Err(Errors::InvalidExecutionAllowlist.into());
+    };
+
+    Ok(())
+}
+
+// Whether an instruction with this program and data is pre-approved by the allowlist
+pub fn permits(actions: &[AllowedAction], program_id: &Pubkey, data: &[u8]) -> bool {
+    actions.iter().any(|action| action.program_id == *program_id && data.starts_with(&action.data_prefix))
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool
+}
+
+// An instruction a passed proposal executes. Only the realm's governance PDA can sign, the program
+// signs for it on execution.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProposalInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>
+}
+
+impl ProposalInstruction {
+    pub fn validate(&self, governance: &Pubkey) -> ProgramResult {
+        if self.accounts.len() > MAX_PROPOSAL_INSTRUCTION_ACCOUNTS
+            || self.data.len() > MAX_PROPOSAL_INSTRUCTION_DATA
+            || self.accounts.iter().any(|meta| meta.is_signer && meta.pubkey != *governance)
+        {
+            return Err(Errors::InvalidProposalInstruction.into());
+        };
+
+        Ok(())
+    }
+
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.iter().map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable
+            }).collect(),
+            data: self.data.clone()
+        }
+    }
+}
+
+// A placeholder of a proposal template, filled when a voting instantiates the template
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TemplateParam {
+    // A little-endian u64 at `offset` of the instruction data, within `min..=max`
+    Amount { offset: u16, min: u64, max: u64 },
+    // The account at `index`, one of `choices` or any account if empty
+    Account { index: u8, choices: Vec<Pubkey> }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TemplateValue {
+    Amount(u64),
+    Account(Pubkey)
+}
+
+pub fn validate_template(instruction: &ProposalInstruction, params: &[TemplateParam]) -> ProgramResult {
+    let valid = params.len() <= MAX_TEMPLATE_PARAMS && params.iter().all(|param| match param {
+        TemplateParam::Amount { offset, min, max } => *offset as usize + 8 <= instruction.data.len() && min <= max,
+        TemplateParam::Account { index, choices } => {
+            // The governance PDA is the only signer, a placeholder can't swap it out
+            instruction.accounts.get(*index as usize).is_some_and(|meta| meta.is_signer == false)
+                && choices.len() <= MAX_TEMPLATE_CHOICES
+        }
+    });
+    if valid == false {
+        return Err(Errors::InvalidProposalTemplate.into());
+    };
+
+    Ok(())
+}
+
+// The template's instruction with one value per placeholder, in order
+pub fn fill_template(
+    instruction: &ProposalInstruction,
+    params: &[TemplateParam],
+    values: &[TemplateValue]
+) -> Result<ProposalInstruction, ProgramError> {
+    if values.len() != params.len() {
+        return Err(Errors::InvalidTemplateValue.into());
+    };
+
+    let mut instruction = instruction.clone();
+    for (param, value) in params.iter().zip(values) {
+        match (param, value) {
+            (TemplateParam::Amount { offset, min, max }, TemplateValue::Amount(amount)) if (*min..=*max).contains(amount) => {
+                let offset = *offset as usize;
+                instruction.data[offset..offset + 8].copy_from_slice(&amount.to_le_bytes());
+            },
+            (TemplateParam::Account { index, choices }, TemplateValue::Account(pubkey))
+                if choices.is_empty() || choices.contains(pubkey) =>
+            {
+                instruction.accounts[*index as usize].pubkey = *pubkey;
+            },
+            _ => return Err(Errors::InvalidTemplateValue.into())
+        };
+    }
+
+    Ok(instruction)
+}
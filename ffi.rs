@@ -0,0 +1,168 @@
+use std::slice;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{ decoder::{ decode_account, DecodedAccount }, AccountType, ModerationStatus };
+
+// Every function returns one of these codes. The structs only grow at the end, so callers built against
+// an older layout keep working.
+pub const FFI_OK: i32 = 0;
+// A null pointer, or an option index past the voting's options
+pub const FFI_INVALID_ARGUMENT: i32 = 1;
+// The bytes don't start with the discriminator of the requested account
+pub const FFI_WRONG_ACCOUNT_TYPE: i32 = 2;
+pub const FFI_DECODE_ERROR: i32 = 3;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FfiVoting {
+    pub creator: [u8; 32],
+    pub payer: [u8; 32],
+    pub starts_at: u64,
+    pub ends_at: u64,
+    // `Active`, `Cancelled`, `Finalized`, `Draft`, `Pending` and `Ended`, from 0
+    pub status: u8,
+    // All zeros outside a realm
+    pub realm: [u8; 32],
+    pub realm_voting_id: u64,
+    pub total_voters: u64,
+    pub options_count: u32,
+    // -1 without a winner
+    pub winning_option: i16,
+    pub vote_chain: [u8; 32],
+    pub hidden: bool,
+    pub hide_tallies: bool
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FfiUserVote {
+    pub payer: [u8; 32],
+    pub first_voted_at: u64,
+    pub last_time_voted: u64,
+    pub times_updated: u16,
+    pub choice: u8,
+    pub weight: u64,
+    pub vote_index: u64
+}
+
+// Tallies of large-supply votings exceed 64 bits, they're split in halves
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FfiTally {
+    pub low: u64,
+    pub high: u64
+}
+
+/// Discriminator of the account as a little-endian `u64`, see `AccountType`. 0 for unknown bytes.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn voting_account_type(data: *const u8, len: usize) -> u64 {
+    if data.is_null() {
+        return 0;
+    };
+
+    let data = slice::from_raw_parts(data, len);
+    AccountType::from_discriminator(data).map_or(0, |account_type| account_type as u64)
+}
+
+/// # Safety
+/// `data` must point to `len` readable bytes and `out` to a writable `FfiVoting`.
+#[no_mangle]
+pub unsafe extern "C" fn voting_decode_voting(data: *const u8, len: usize, out: *mut FfiVoting) -> i32 {
+    if out.is_null() {
+        return FFI_INVALID_ARGUMENT;
+    };
+
+    let voting = match decode(data, len) {
+        Ok(DecodedAccount::Voting(voting)) => voting,
+        Ok(_) => return FFI_WRONG_ACCOUNT_TYPE,
+        Err(code) => return code
+    };
+
+    *out = FfiVoting {
+        creator: voting.creator.to_bytes(),
+        payer: voting.payer.to_bytes(),
+        starts_at: voting.starts_at,
+        ends_at: voting.ends_at,
+        status: voting.status as u8,
+        realm: voting.realm.to_bytes(),
+        realm_voting_id: voting.realm_voting_id,
+        total_voters: voting.total_voters,
+        options_count: voting.options.len() as u32,
+        winning_option: voting.winning_option().map_or(-1, i16::from),
+        vote_chain: voting.vote_chain,
+        hidden: voting.moderation == ModerationStatus::Hidden,
+        hide_tallies: voting.hide_tallies
+    };
+
+    FFI_OK
+}
+
+/// # Safety
+/// `data` must point to `len` readable bytes and `out` to a writable `FfiTally`.
+#[no_mangle]
+pub unsafe extern "C" fn voting_decode_tally(data: *const u8, len: usize, option: u32, out: *mut FfiTally) -> i32 {
+    if out.is_null() {
+        return FFI_INVALID_ARGUMENT;
+    };
+
+    let voting = match decode(data, len) {
+        Ok(DecodedAccount::Voting(voting)) => voting,
+        Ok(_) => return FFI_WRONG_ACCOUNT_TYPE,
+        Err(code) => return code
+    };
+
+    if option as usize >= voting.tallies.len() {
+        return FFI_INVALID_ARGUMENT;
+    };
+
+    let total = voting.tally_state.total(&voting.tallies, option as u8);
+    *out = FfiTally { low: total as u64, high: (total >> 64) as u64 };
+
+    FFI_OK
+}
+
+/// # Safety
+/// `data` must point to `len` readable bytes and `out` to a writable `FfiUserVote`.
+#[no_mangle]
+pub unsafe extern "C" fn voting_decode_user_vote(data: *const u8, len: usize, out: *mut FfiUserVote) -> i32 {
+    if out.is_null() {
+        return FFI_INVALID_ARGUMENT;
+    };
+
+    let user_vote = match decode(data, len) {
+        Ok(DecodedAccount::UserVote(user_vote)) => user_vote,
+        Ok(_) => return FFI_WRONG_ACCOUNT_TYPE,
+        Err(code) => return code
+    };
+
+    *out = FfiUserVote {
+        payer: user_vote.payer.to_bytes(),
+        first_voted_at: user_vote.first_voted_at,
+        last_time_voted: user_vote.last_time_voted,
+        times_updated: user_vote.times_updated,
+        choice: user_vote.choice,
+        weight: user_vote.weight,
+        vote_index: user_vote.vote_index
+    };
+
+    FFI_OK
+}
+
+// The callers already filtered the accounts by owner, so the program is passed as the owner too.
+unsafe fn decode(data: *const u8, len: usize) -> Result<DecodedAccount, i32> {
+    if data.is_null() {
+        return Err(FFI_INVALID_ARGUMENT);
+    };
+
+    let data = slice::from_raw_parts(data, len);
+    let program_id: Pubkey = crate::ID;
+    match decode_account(&program_id, &program_id, data) {
+        Ok(Some(decoded)) => Ok(decoded),
+        Ok(None) => Err(FFI_WRONG_ACCOUNT_TYPE),
+        Err(_) => Err(FFI_DECODE_ERROR)
+    }
+}
@@ -0,0 +1,180 @@
+// Handlers driven through `process` with a fixed clock, outside the runtime: no validator, no warping.
+// Only handlers that write their accounts in place run here, reallocs and CPIs need the runtime.
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo,
+    borsh0_10::try_from_slice_unchecked,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program::ID as system_program_address
+};
+
+use crate::{ draw::RandomDraw, tally::TiePolicy, time_windows::create_instruction, * };
+
+const TITLE: &str = "Lunch on Fridays";
+const STARTS_AT: u64 = 1_000;
+const ENDS_AT: u64 = 2_000;
+
+pub(crate) fn sysvars(unix_timestamp: u64) -> Sysvars {
+    Sysvars {
+        clock: Clock { unix_timestamp: unix_timestamp as i64, ..Clock::default() },
+        rent: Rent::default()
+    }
+}
+
+// Accounts live for the whole test, `process` ties them to the lifetime of the slice
+pub(crate) fn account(key: Pubkey, owner: Pubkey, data: Vec<u8>, is_signer: bool, is_writable: bool) -> AccountInfo<'static> {
+    AccountInfo::new(
+        Box::leak(Box::new(key)),
+        is_signer,
+        is_writable,
+        Box::leak(Box::new(1_000_000_000)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0
+    )
+}
+
+pub(crate) fn program(key: Pubkey) -> AccountInfo<'static> {
+    AccountInfo { executable: true, ..account(key, Pubkey::default(), Vec::new(), false, false) }
+}
+
+struct Fixture {
+    creator: AccountInfo<'static>,
+    voting: AccountInfo<'static>,
+    poll_card: AccountInfo<'static>,
+    config: AccountInfo<'static>
+}
+
+// A voting from `STARTS_AT` to `ENDS_AT` sized like `create_voting` sizes it, without a poll card or config
+fn fixture() -> Fixture {
+    let creator = Pubkey::new_unique();
+    let voting = find_voting_pda(TITLE, &ID).0;
+    let voting_account = VoteMainAccount::new(creator, create_instruction(STARTS_AT, ENDS_AT), Pubkey::default(), 0, &DEFAULT_VOTING_SETTINGS).unwrap();
+
+    let options_len = voting_account.options.iter().map(|option| option.len()).sum();
+    let mut data = vec![ 0; voting_account_space(0, options_len, voting_account.options.len(), 0).unwrap() ];
+    voting_account.serialize(&mut &mut data[..]).unwrap();
+
+    Fixture {
+        creator: account(creator, system_program_address, Vec::new(), true, true),
+        voting: account(voting, ID, data, false, true),
+        poll_card: account(find_poll_card_pda(&voting, &ID).0, system_program_address, Vec::new(), false, true),
+        config: account(find_config_pda(&ID).0, system_program_address, Vec::new(), false, false)
+    }
+}
+
+impl Fixture {
+    fn voting_data(&self) -> VoteMainAccount {
+        try_from_slice_unchecked(&self.voting.data.borrow()).unwrap()
+    }
+}
+
+fn run(accounts: Vec<AccountInfo<'static>>, data: Vec<u8>, current_time: u64) -> ProgramResult {
+    process(&ID, Box::leak(accounts.into_boxed_slice()), &data, &sysvars(current_time))
+}
+
+fn sync_status(fixture: &Fixture, current_time: u64) -> ProgramResult {
+    let data = InstructionType::SyncStatus.header();
+    run(vec![ fixture.config.clone(), fixture.voting.clone(), fixture.poll_card.clone() ], data, current_time)
+}
+
+#[test]
+fn status_moves_with_the_injected_clock() {
+    let fixture = fixture();
+
+    for (current_time, status) in [
+        (0, VotingStatus::Pending),
+        (STARTS_AT - 1, VotingStatus::Pending),
+        (STARTS_AT, VotingStatus::Active),
+        (ENDS_AT - 1, VotingStatus::Active),
+        (ENDS_AT, VotingStatus::Ended),
+        (u64::MAX >> 1, VotingStatus::Ended)
+    ] {
+        sync_status(&fixture, current_time).unwrap();
+        assert_eq!(fixture.voting_data().status, status, "at {}", current_time);
+    };
+}
+
+#[test]
+fn extension_needs_a_running_voting() {
+    let fixture = fixture();
+    let extend = |ends_at: u64, current_time: u64| {
+        let mut data = InstructionType::ExtendVoting.header();
+        ExtendVotingInstruction { ends_at }.serialize(&mut data).unwrap();
+        let accounts = vec![ fixture.config.clone(), fixture.creator.clone(), fixture.voting.clone(), fixture.poll_card.clone() ];
+
+        run(accounts, data, current_time)
+    };
+
+    // Past the cap counted from the start, not from the current time
+    assert_eq!(extend(STARTS_AT + MAX_VOTING_TIME as u64 + 1, ENDS_AT - 1), Err(Errors::MaxVotingTimeExceeded.into()));
+    assert_eq!(extend(ENDS_AT, ENDS_AT - 1), Err(Errors::InvalidEndingTime.into()));
+    assert_eq!(extend(ENDS_AT + 1, ENDS_AT), Err(Errors::VotingEnded.into()));
+
+    extend(STARTS_AT + MAX_VOTING_TIME as u64, ENDS_AT - 1).unwrap();
+    assert_eq!(fixture.voting_data().ends_at, STARTS_AT + MAX_VOTING_TIME as u64);
+}
+
+#[test]
+fn freeze_records_the_injected_time() {
+    let fixture = fixture();
+    let freeze = |current_time: u64| {
+        let data = InstructionType::FreezeVoting.header();
+        run(vec![ fixture.creator.clone(), fixture.voting.clone(), fixture.poll_card.clone() ], data, current_time)
+    };
+
+    assert_eq!(freeze(ENDS_AT), Err(Errors::VotingEnded.into()));
+    assert!(fixture.voting_data().frozen_at.is_none());
+
+    freeze(STARTS_AT + 5).unwrap();
+    assert_eq!(fixture.voting_data().frozen_at, Some(STARTS_AT + 5));
+    assert_eq!(freeze(STARTS_AT + 6), Err(Errors::VotingFrozen.into()));
+    assert_eq!(fixture.voting_data().check_accepts_votes(STARTS_AT + 6), Err(ProgramError::from(Errors::VotingFrozen)));
+}
+
+#[test]
+fn envelope_results_reset_a_tie_draw() {
+    let fixture = fixture();
+    let mut voting_account = fixture.voting_data();
+    voting_account.envelope_key = Some([ 7; 32 ]);
+    voting_account.envelope_weight = 2;
+    voting_account.tie_policy = TiePolicy::Random;
+    voting_account.tie_draw = RandomDraw { drawn: true, ticket: 1, ..RandomDraw::default() };
+
+    // Room for the key and the commitment
+    let mut data = vec![ 0; fixture.voting.data_len() + 64 ];
+    voting_account.serialize(&mut &mut data[..]).unwrap();
+    let voting = account(*fixture.voting.key, ID, data, false, true);
+
+    let mut data = InstructionType::SubmitEnvelopeResults.header();
+    SubmitEnvelopeResultsInstruction { tallies: vec![ 1, 1 ], commitment: [ 0; 32 ] }.serialize(&mut data).unwrap();
+    let accounts = vec![ fixture.config.clone(), fixture.creator.clone(), voting.clone(), fixture.poll_card.clone() ];
+    run(accounts, data, ENDS_AT).unwrap();
+
+    let voting_account: VoteMainAccount = try_from_slice_unchecked(&voting.data.borrow()).unwrap();
+    assert!(voting_account.tie_draw.drawn == false);
+    assert_eq!(voting_account.top_option(), None);
+}
+
+#[test]
+fn a_drawn_option_outside_the_tie_wins_nothing() {
+    let mut voting_account = fixture().voting_data();
+    voting_account.tie_policy = TiePolicy::Random;
+    voting_account.tallies = vec![ 1, 1 ];
+    voting_account.status = VotingStatus::Ended;
+
+    voting_account.tie_draw = RandomDraw { drawn: true, ticket: 1, ..RandomDraw::default() };
+    assert_eq!(voting_account.top_option(), Some(1));
+
+    // Left over from tallies replaced without a reset
+    voting_account.tallies = vec![ 2, 1 ];
+    voting_account.tie_draw.ticket = 5;
+    assert_eq!(voting_account.top_option(), Some(0));
+    voting_account.tallies = vec![ 1, 1 ];
+    assert_eq!(voting_account.top_option(), None);
+}
@@ -0,0 +1,306 @@
+// `ClientResult` as in `client`, the RPC errors reach the caller unchanged
+#![allow(clippy::result_large_err)]
+
+use std::{ thread, time::Duration };
+
+use borsh::BorshSerialize;
+use solana_client::{
+    client_error::{ ClientErrorKind, Result as ClientResult },
+    rpc_client::RpcClient
+};
+use solana_program::{
+    borsh0_10::try_from_slice_unchecked,
+    instruction::{ AccountMeta, Instruction },
+    keccak::hash,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program
+};
+use solana_sdk::{
+    signature::{ keypair_from_seed, Keypair, Signer },
+    transaction::Transaction
+};
+
+use crate::{
+    client::{
+        add_allowlist_gate,
+        append_allowlist,
+        finalize_voting,
+        publish_voting,
+        seal_allowlist,
+        sync_status,
+        vote,
+        voting_registry_page,
+        AllowlistTree,
+        VoteProofs
+    },
+    decoder::{ decode_account, DecodedAccount },
+    eligibility::{ EligibilityPolicy, EligibilityProof, Gate },
+    find_config_pda,
+    find_creator_stats_pda,
+    find_title_index_pda,
+    find_title_reservation_pda,
+    find_voting_registry_pda,
+    find_voting_registry_page_pda,
+    find_user_vote_pda,
+    find_voter_allowlist_page_pda,
+    find_voter_allowlist_pda,
+    find_voter_index_pda,
+    find_voting_pda,
+    voting_title_seed,
+    CreateVotingInstruction,
+    InstructionType,
+    VoteMainAccount
+};
+
+// Voters of the fixtures, they vote for the options in turn
+const FIXTURE_VOTERS: usize = 3;
+const FIXTURE_OPTIONS: [&str; 3] = ["Yes", "No", "Abstain"];
+// Sent to each voter for the rent of its user vote accounts and voter index
+const VOTER_FUNDING: u64 = 100_000_000;
+// Long enough to cast the fixture ballots, the generator waits for these votings to end
+const SHORT_DURATION: u64 = 20;
+const LONG_DURATION: u64 = 30 * 24 * 60 * 60;
+const PENDING_DELAY: u64 = 24 * 60 * 60;
+// Only named by the token gated voting, nobody holds it
+const FIXTURE_MINT: Pubkey = Pubkey::new_from_array([7; 32]);
+// `getMultipleAccounts` limit
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+// An account of the fixtures. With the `serde` feature it serializes to the JSON frontends and indexers
+// load in their integration tests.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fixture {
+    // e.g. "active/voting" or "allowlist_root/user_vote/1"
+    pub name: String,
+    pub address: Pubkey,
+    pub account: DecodedAccount
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Draft,
+    Pending,
+    Active,
+    Ended,
+    Finalized
+}
+
+struct FixtureVoting {
+    name: &'static str,
+    stage: Stage,
+    gate: Option<Gate>,
+    // The voters can't pass the token and stake gates
+    voted: bool
+}
+
+impl FixtureVoting {
+    fn title(&self) -> String {
+        format!("fixture-{}", self.name)
+    }
+
+    fn duration(&self) -> u64 {
+        match self.stage {
+            Stage::Ended | Stage::Finalized => SHORT_DURATION,
+            _ => LONG_DURATION
+        }
+    }
+
+    // Uploaded allowlists are filled in while the voting is a draft, it's published afterwards
+    fn created_as_draft(&self) -> bool {
+        self.stage == Stage::Draft || self.gate == Some(Gate::UploadedAllowlist)
+    }
+}
+
+// Creates one voting per status and gate with `payer` as the creator, casts the ballots of the fixture
+// voters and returns the resulting accounts in creation order. Run it against a fresh
+// `solana-test-validator` with the program deployed at `program_id`, the titles are fixed so a second run
+// on the same ledger fails. Keys, titles and ballots are the same on every run, only the timestamps and
+// the lamports paid as fees differ.
+pub fn generate_fixtures(rpc: &RpcClient, program_id: &Pubkey, payer: &Keypair) -> ClientResult<Vec<Fixture>> {
+    let voters = fixture_voters()?;
+    let voter_keys = voters.iter().map(|voter| voter.pubkey()).collect::<Vec<_>>();
+    let allowlist = AllowlistTree::new(&voter_keys);
+
+    let fundings = voter_keys.iter()
+        .map(|voter| system_instruction::transfer(&payer.pubkey(), voter, VOTER_FUNDING))
+        .collect::<Vec<_>>();
+    send(rpc, payer, &fundings, &[])?;
+
+    // The short votings first, so their ballots land before they end
+    let votings = [
+        FixtureVoting { name: "ended", stage: Stage::Ended, gate: None, voted: true },
+        FixtureVoting { name: "finalized", stage: Stage::Finalized, gate: None, voted: true },
+        FixtureVoting { name: "active", stage: Stage::Active, gate: None, voted: true },
+        FixtureVoting { name: "pending", stage: Stage::Pending, gate: None, voted: false },
+        FixtureVoting { name: "draft", stage: Stage::Draft, gate: None, voted: false },
+        FixtureVoting { name: "allowlist_root", stage: Stage::Active, gate: Some(Gate::Allowlist(allowlist.root())), voted: true },
+        FixtureVoting { name: "uploaded_allowlist", stage: Stage::Active, gate: Some(Gate::UploadedAllowlist), voted: true },
+        FixtureVoting { name: "token_balance", stage: Stage::Active, gate: Some(Gate::TokenBalance { mint: FIXTURE_MINT, min_amount: 1 }), voted: false },
+        FixtureVoting { name: "stake", stage: Stage::Active, gate: Some(Gate::Stake { min_lamports: 1_000_000_000 }), voted: false }
+    ];
+
+    let mut fixtures = Vec::new();
+    let mut addresses = vec![
+        ("config".to_string(), find_config_pda(program_id).0),
+        ("creator_stats".to_string(), find_creator_stats_pda(&payer.pubkey(), program_id).0)
+    ];
+    for fixture in votings.iter() {
+        let title = fixture.title();
+        let (voting, _) = find_voting_pda(&title, program_id);
+        let registry_page = voting_registry_page(rpc, program_id)?;
+        send(rpc, payer, &[ create_voting(program_id, &payer.pubkey(), fixture, registry_page) ], &[])?;
+
+        if fixture.gate == Some(Gate::UploadedAllowlist) {
+            let mut instructions = append_allowlist(program_id, &payer.pubkey(), &voting, &voter_keys, 0);
+            instructions.push(seal_allowlist(program_id, &payer.pubkey(), &voting));
+            instructions.push(publish_voting(program_id, &payer.pubkey(), &voting, cluster_time(rpc)?));
+            send(rpc, payer, &instructions, &[])?;
+
+            addresses.push((format!("{}/allowlist", fixture.name), find_voter_allowlist_pda(&voting, program_id).0));
+            addresses.push((format!("{}/allowlist_page/0", fixture.name), find_voter_allowlist_page_pda(&voting, 0, program_id).0));
+        };
+
+        addresses.push((format!("{}/voting", fixture.name), voting));
+
+        if fixture.voted {
+            for (position, voter) in voters.iter().enumerate() {
+                let instruction = vote_instruction(program_id, &voter.pubkey(), &voting, fixture, &allowlist, position);
+                send(rpc, payer, &[ instruction ], &[ voter ])?;
+
+                addresses.push((format!("{}/user_vote/{}", fixture.name, position), find_user_vote_pda(&voting, &voter.pubkey(), program_id).0));
+            };
+        };
+    };
+
+    for fixture in votings.iter().filter(|fixture| fixture.duration() == SHORT_DURATION) {
+        let (voting, _) = find_voting_pda(&fixture.title(), program_id);
+        let voting_data = try_from_slice_unchecked::<VoteMainAccount>(&rpc.get_account_data(&voting)?)
+            .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+
+        // The block time lags the clock sysvar by a moment at most
+        while cluster_time(rpc)? <= voting_data.ends_at {
+            thread::sleep(Duration::from_secs(1));
+        };
+
+        let instruction = match fixture.stage {
+            Stage::Finalized => finalize_voting(program_id, &voting, &payer.pubkey(), &payer.pubkey()),
+            _ => sync_status(program_id, &voting)
+        };
+        send(rpc, payer, &[ instruction ], &[])?;
+    };
+
+    for (position, voter) in voter_keys.iter().enumerate() {
+        addresses.push((format!("voter_index/{}", position), find_voter_index_pda(voter, 0, program_id).0));
+    };
+
+    for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let keys = chunk.iter().map(|(_, address)| *address).collect::<Vec<_>>();
+        let accounts = rpc.get_multiple_accounts(&keys)?;
+
+        for ((name, address), account) in chunk.iter().zip(accounts) {
+            // The config is only there when the program was initialized
+            let Some(account) = account else {
+                continue;
+            };
+
+            let decoded = decode_account(program_id, &account.owner, &account.data)
+                .map_err(|err| ClientErrorKind::Custom(format!("{}: {}", name, err)))?;
+            if let Some(account) = decoded {
+                fixtures.push(Fixture { name: name.clone(), address: *address, account });
+            };
+        };
+    };
+
+    Ok(fixtures)
+}
+
+fn fixture_voters() -> ClientResult<Vec<Keypair>> {
+    let mut voters = Vec::new();
+    for index in 0..FIXTURE_VOTERS {
+        let seed = hash(format!("fixture-voter-{}", index).as_bytes()).0;
+        voters.push(keypair_from_seed(&seed).map_err(|err| ClientErrorKind::Custom(err.to_string()))?);
+    };
+
+    Ok(voters)
+}
+
+fn create_voting(program_id: &Pubkey, creator: &Pubkey, fixture: &FixtureVoting, registry_page: u32) -> Instruction {
+    let title = fixture.title();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(find_voting_pda(&title, program_id).0, false),
+        AccountMeta::new(find_creator_stats_pda(creator, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(registry_page, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let starts_in = match fixture.stage {
+        Stage::Pending => PENDING_DELAY,
+        _ => 0
+    };
+
+    let mut data = InstructionType::CreateVoting.header();
+    let options = FIXTURE_OPTIONS.iter().map(|option| option.to_string()).collect();
+    CreateVotingInstruction {
+        description: format!("Fixture voting, {:?}", fixture.stage),
+        eligibility: EligibilityPolicy { clauses: fixture.gate.iter().map(|gate| vec![ gate.clone() ]).collect() },
+        relative_times: true,
+        draft: fixture.created_as_draft(),
+        ..CreateVotingInstruction::new(title, options, starts_in, starts_in + fixture.duration())
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// The voter at `position` of the fixture voters, which is also its position on the uploaded allowlist
+fn vote_instruction(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    voting: &Pubkey,
+    fixture: &FixtureVoting,
+    allowlist: &AllowlistTree,
+    position: usize
+) -> Instruction {
+    let eligibility = fixture.gate.as_ref().map(|gate| EligibilityProof {
+        clause: 0,
+        allowlist_proof: match gate {
+            Gate::Allowlist(_) => allowlist.proof(voter).unwrap_or_default(),
+            _ => Vec::new()
+        },
+        roll_proof: Vec::new()
+    });
+
+    let ballot = vec![ (position % FIXTURE_OPTIONS.len()) as u8 ];
+    let mut instruction = vote(program_id, voter, voting, ballot, fixture.title(), false, VoteProofs { weight: None, eligibility });
+    if fixture.gate == Some(Gate::UploadedAllowlist) {
+        add_allowlist_gate(&mut instruction, program_id, voting, position as u32);
+    };
+
+    instruction
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, instructions: &[Instruction], signers: &[&Keypair]) -> ClientResult<()> {
+    let mut all_signers = vec![ payer ];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &all_signers,
+        rpc.get_latest_blockhash()?
+    );
+    rpc.send_and_confirm_transaction(&transaction)?;
+
+    Ok(())
+}
+
+fn cluster_time(rpc: &RpcClient) -> ClientResult<u64> {
+    Ok(rpc.get_block_time(rpc.get_slot()?)? as u64)
+}
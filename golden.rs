@@ -0,0 +1,873 @@
+// Golden layouts: a reference instance of every account and instruction, serialized and compared with the
+// bytes checked in under tests/fixtures. A field added, removed, reordered or retyped changes the bytes of
+// deployed accounts and of the instructions clients already send, so it has to show up here first.
+//
+// After an intended layout change, regenerate the fixtures and review their diff:
+// GOLDEN_BLESS=1 cargo test golden
+use std::{ collections::BTreeMap, fs, path::PathBuf };
+
+use borsh::BorshSerialize;
+use solana_program::{ keccak::hash, pubkey::Pubkey };
+
+use crate::{
+    buckets::RecordSlot,
+    draw::RandomDraw,
+    eligibility::{ EligibilityPolicy, EligibilityProof, Gate },
+    execution::{ AllowedAction, ProposalAccountMeta, ProposalInstruction, TemplateParam, TemplateValue },
+    merkle::RecordTree,
+    oracle::{ Comparison, OracleCondition },
+    state::StateVersion,
+    tally::{ Apportionment, RankedMethod, RankingCount, SeatMethod, TallyMode, TallyState, TiePolicy },
+    weight::{ MintRatio, WeightProof, WeightSource },
+    *
+};
+
+// Distinct bytes per field, so two fields swapping places change the fixture
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([ byte; 32 ])
+}
+
+fn bytes32(byte: u8) -> [u8; 32] {
+    [ byte; 32 ]
+}
+
+fn header(account_type: AccountType) -> ([u8; 8], u8) {
+    (account_type.discriminator(), StateVersion::CURRENT as u8)
+}
+
+fn encode<T: BorshSerialize>(value: &T) -> Vec<u8> {
+    value.try_to_vec().unwrap()
+}
+
+fn tally_state() -> TallyState {
+    TallyState {
+        scores: vec![ 3, 4 ],
+        rankings: vec![ RankingCount { ranking: vec![ 1, 0 ], weight: 5 } ],
+        large_supply: true,
+        high: vec![ 6 ],
+        spoiled: 7,
+        cast_weight: 8,
+        spoiled_weight: 9
+    }
+}
+
+fn random_draw() -> RandomDraw {
+    RandomDraw { total_weight: 11, reveal_slot: 12, drawn: true, ticket: 13, winner: key(14) }
+}
+
+fn voting_settings() -> VotingSettings {
+    VotingSettings { min_voting_time: 60, max_voting_time: 3_600, creator_threshold: 2, allow_updates: true, members_only: false }
+}
+
+fn oracle_condition() -> OracleCondition {
+    OracleCondition { feed: key(21), comparison: Comparison::Below, threshold: -22, expo: -8, max_staleness: 23 }
+}
+
+fn eligibility_policy() -> EligibilityPolicy {
+    EligibilityPolicy {
+        clauses: vec![
+            vec![ Gate::TokenBalance { mint: key(24), min_amount: 25 }, Gate::Stake { min_lamports: 26 } ],
+            vec![ Gate::Allowlist(bytes32(27)), Gate::UploadedAllowlist ]
+        ]
+    }
+}
+
+fn weight_proof() -> Option<WeightProof> {
+    Some(WeightProof { weight: 31, proof: vec![ bytes32(32) ] })
+}
+
+fn eligibility_proof() -> Option<EligibilityProof> {
+    Some(EligibilityProof { clause: 1, allowlist_proof: vec![ bytes32(33) ], roll_proof: vec![ bytes32(34) ] })
+}
+
+fn proposal_instruction() -> ProposalInstruction {
+    ProposalInstruction {
+        program_id: key(41),
+        accounts: vec![ ProposalAccountMeta { pubkey: key(42), is_signer: true, is_writable: false } ],
+        data: vec![ 43, 44 ]
+    }
+}
+
+fn template_params() -> Vec<TemplateParam> {
+    vec![
+        TemplateParam::Amount { offset: 1, min: 45, max: 46 },
+        TemplateParam::Account { index: 0, choices: vec![ key(47) ] }
+    ]
+}
+
+fn account_layouts() -> Vec<(AccountType, Vec<u8>)> {
+    AccountType::ALL.into_iter()
+        .filter_map(|account_type| reference_account(account_type).map(|bytes| (account_type, bytes)))
+        .collect()
+}
+
+// `None` for the kinds without a layout of their own: the archived voting tombstone is its discriminator
+// alone, the voter weight record follows the governance add-in's layout
+fn reference_account(account_type: AccountType) -> Option<Vec<u8>> {
+    let (discriminator, version) = header(account_type);
+
+    let bytes = match account_type {
+        AccountType::Config => encode(&ConfigAccount {
+            discriminator,
+            version,
+            admin: key(1),
+            disabled_instructions: 1 << 100 | 2,
+            max_active_votings: 3,
+            start_time_tolerance: 4,
+            pending_admin: Some(key(5)),
+            creation_deposit: 6,
+            deposit_refund_window: 7
+        }),
+        AccountType::Vote => encode(&VoteMainAccount {
+            discriminator,
+            version,
+            creator: key(1),
+            payer: key(2),
+            starts_at: 3,
+            ends_at: 4,
+            status: VotingStatus::Ended,
+            realm: key(5),
+            realm_voting_id: 6,
+            members_only: true,
+            allow_updates: false,
+            title: VotingTitle::new("Lunch on Fridays").unwrap(),
+            description: "Where the team orders from".to_string(),
+            options: vec![ "Pizza".to_string(), "Sushi".to_string() ],
+            tallies: vec![ 7, 8 ],
+            total_voters: 9,
+            vote_chain: bytes32(10),
+            option_caps: vec![ 0, 15 ],
+            co_creators: vec![ key(16) ],
+            creator_threshold: 1,
+            weight_source: WeightSource::TokenBalance(key(17)),
+            weight_mints: vec![ MintRatio { mint: key(18), ratio_bps: 19 } ],
+            weight_precision: Some(6),
+            tally_mode: TallyMode::Ranked(RankedMethod::Borda),
+            eligibility: eligibility_policy(),
+            signup_slots: 20,
+            claimed_slots: 21,
+            random_draw: Some(random_draw()),
+            oracle_condition: Some(oracle_condition()),
+            condition_met: true,
+            record_buckets: 2,
+            locked: true,
+            init_stage: 3,
+            required_stages: 5,
+            envelope_key: Some(bytes32(34)),
+            envelope_weight: 35,
+            envelope_commitment: Some(bytes32(36)),
+            tie_policy: TiePolicy::EarliestLeader,
+            changed_at: vec![ 37, 38 ],
+            tie_draw: random_draw(),
+            dependency: Some(VotingDependency { voting: key(39), option: 1 }),
+            creator_program: Some(key(40)),
+            records_root: Some(bytes32(48)),
+            flag_count: 49,
+            moderation: ModerationStatus::Hidden,
+            incentive_per_vote: 50,
+            turnout_milestones: Some(TurnoutMilestones { electorate: 51, percents: vec![ 25, 50 ], quorum: 52 }),
+            hide_tallies: true,
+            creation_deposit: 53,
+            deposit_refund_window: 54,
+            result_attestors: Some(ResultAttestors { attestors: vec![ key(55) ], threshold: 1, attested: 0 }),
+            tally_shards: 4,
+            shards_merged: true,
+            weight_cap_per_slot: 56,
+            reject_encumbered_tokens: true,
+            proposal_note: Some(key(57)),
+            reveal_window: 58,
+            commitments: 59,
+            frozen_at: Some(60),
+            min_participation: 61,
+            pass_threshold_bps: 62,
+            electorate: Some(Electorate::Weight(67)),
+            quorum_bps: 68,
+            outcome: Some(VotingOutcome::Rejected),
+            result: Some(1),
+            apportionment: Some(Apportionment { method: SeatMethod::DHondt, seats: 63 }),
+            seats: vec![ 40, 23 ],
+            winners_count: 2,
+            winners: vec![ 1, 0 ],
+            voter_roll: Some(VoterRoll { root: bytes32(64), voters_count: 65, sealed_at: 66 }),
+            tally_state: tally_state()
+        }),
+        AccountType::UserVoting => encode(&UserVotingAccount {
+            discriminator,
+            version,
+            payer: key(1),
+            first_voted_at: 2,
+            last_time_voted: 3,
+            times_updated: 4,
+            choice: 1,
+            weight: 5,
+            credited_slot: 6,
+            draw_offset: 7,
+            vote_index: 8,
+            commitment: Some(bytes32(9)),
+            ballot: vec![ 1, 0 ],
+            voted_to: VotingTitle::new("Lunch on Fridays").unwrap()
+        }),
+        AccountType::TallySnapshot => encode(&TallySnapshotAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            last_taken_at: 2,
+            next_index: 1,
+            snapshots: vec![ TallySnapshot { taken_at: 3, tallies: vec![ 4, 5 ], high: vec![ 6 ] } ]
+        }),
+        AccountType::RewardEscrow => encode(&RewardEscrowAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            total_funded: 2,
+            total_claimed: 3,
+            in_progress: true
+        }),
+        AccountType::RewardClaim => encode(&RewardClaimAccount { discriminator, version, amount: 1 }),
+        AccountType::Realm => encode(&RealmAccount {
+            discriminator,
+            version,
+            admin: key(1),
+            defaults: voting_settings(),
+            locked_settings: 2,
+            creation_fee: 3,
+            creators_gated: true,
+            votings_count: 4,
+            members_count: 5,
+            name: "Team realm".to_string()
+        }),
+        AccountType::Membership => encode(&MembershipAccount {
+            discriminator,
+            version,
+            realm: key(1),
+            member: key(2),
+            joined_at: 3
+        }),
+        AccountType::Boost => encode(&BoostAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            amount: 2,
+            released: true,
+            in_progress: true
+        }),
+        AccountType::BoostDeposit => encode(&BoostDepositAccount { discriminator, version, amount: 1 }),
+        AccountType::VoterIndex => encode(&VoterIndexAccount { discriminator, version, voter: key(1), page: 2, next: key(3) }),
+        AccountType::VoterWeightRecord => return None,
+        AccountType::Slot => encode(&SlotAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            claimant: key(2),
+            slot: 3,
+            claimed_at: 4
+        }),
+        AccountType::SlotClaim => encode(&SlotClaimAccount { discriminator, version, slot: 1 }),
+        AccountType::ExecutionAllowlist => encode(&ExecutionAllowlistAccount {
+            discriminator,
+            version,
+            realm: key(1),
+            actions: vec![ AllowedAction { program_id: key(2), data_prefix: vec![ 3, 4 ] } ]
+        }),
+        AccountType::Proposal => encode(&ProposalAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            option: 2,
+            next_instruction: 3,
+            in_progress: true,
+            instructions: vec![ proposal_instruction() ]
+        }),
+        AccountType::BallotBox => encode(&BallotBoxAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            mint: key(2),
+            counted: vec![ 3, 4 ]
+        }),
+        AccountType::Recount => encode(&RecountAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            last_record: key(2),
+            records_counted: 3,
+            tallies: vec![ 4, 5 ],
+            tally_state: tally_state()
+        }),
+        AccountType::CreatorStats => encode(&CreatorStatsAccount {
+            discriminator,
+            version,
+            creator: key(1),
+            active_votings: 2,
+            created_votings: 3,
+            finalized_votings: 4,
+            cancelled_votings: 5,
+            total_voters: 6,
+            flags: 7
+        }),
+        AccountType::VotingMetadata => encode(&VotingMetadataAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            timezone: "Europe/Berlin".to_string(),
+            schedule: "Every Friday".to_string()
+        }),
+        AccountType::VoterAllowlist => encode(&VoterAllowlistAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            voters_count: 2,
+            sealed: true,
+            root: Some(bytes32(3)),
+            late_registration: 4,
+            late_chunks: vec![ LateAllowlistChunk { first_voter: 5, added_at: 6 } ]
+        }),
+        AccountType::VoterAllowlistPage => encode(&VoterAllowlistPageAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            page: 2,
+            voters: vec![ key(3), key(4) ]
+        }),
+        AccountType::VoteBucket => encode(&VoteBucketAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            index: 2,
+            slots: vec![ RecordSlot { voter: key(3), weight: 4, vote_index: 5, ballot_len: 1, ballot: [ 6; MAX_OPTIONS ] } ]
+        }),
+        AccountType::TitleIndex => encode(&TitleIndexAccount { discriminator, version, voting: key(1) }),
+        AccountType::TitleReservation => encode(&TitleReservationAccount { discriminator, version, reserver: key(1), expires_at: 2 }),
+        AccountType::Envelope => encode(&EnvelopeAccount {
+            discriminator,
+            version,
+            voter: key(1),
+            weight: 2,
+            cast_at: 3,
+            vote_index: 4,
+            envelope: vec![ 5, 6, 7 ]
+        }),
+        AccountType::ProposalTemplate => encode(&ProposalTemplateAccount {
+            discriminator,
+            version,
+            realm: key(1),
+            id: 2,
+            instruction: proposal_instruction(),
+            params: template_params()
+        }),
+        AccountType::VotingArchive => encode(&VotingArchiveAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            creator: key(2),
+            title: "Lunch on Fridays".to_string(),
+            winning_option: Some(1),
+            tallies: vec![ 3, u128::MAX ],
+            total_voters: 4,
+            vote_chain: bytes32(5),
+            records_root: Some(bytes32(6)),
+            archived_at: 7
+        }),
+        AccountType::ArchivedVoting => return None,
+        AccountType::RecordTree => encode(&RecordTreeAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            tree: RecordTree { leaves: 2, frontier: vec![ bytes32(3) ] }
+        }),
+        AccountType::Flag => encode(&FlagAccount { discriminator, version, voting: key(1), flagger: key(2), flagged_at: 3 }),
+        AccountType::IncentivePool => encode(&IncentivePoolAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            total_funded: 2,
+            total_paid: 3,
+            in_progress: true
+        }),
+        AccountType::LocalizedMetadata => encode(&LocalizedMetadataAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            language: "de".to_string(),
+            title: "Mittagessen am Freitag".to_string(),
+            description: "Wo das Team bestellt".to_string()
+        }),
+        AccountType::TallyShard => encode(&TallyShardAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            index: 2,
+            voters: 3,
+            updates: 4,
+            vote_chain: bytes32(5),
+            tallies: vec![ 6, 7 ],
+            tally_state: tally_state()
+        }),
+        AccountType::Election => encode(&ElectionAccount {
+            discriminator,
+            version,
+            creator: key(1),
+            id: 2,
+            name: "Board election".to_string(),
+            finalized_at: 3,
+            races: vec![ ElectionRace { voting: key(4), winning_option: Some(0), winner: "Pizza".to_string(), total_voters: 5 } ]
+        }),
+        AccountType::PollCard => encode(&PollCardAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            status: VotingStatus::Finalized,
+            ends_at: 2,
+            leading_option: 1,
+            total_voters: 3,
+            updated_at: 4
+        }),
+        AccountType::VotingRegistry => encode(&VotingRegistryAccount { discriminator, version, votings_count: 1 }),
+        AccountType::VotingRegistryPage => encode(&VotingRegistryPageAccount { discriminator, version, page: 2 }),
+        AccountType::FaucetClaim => encode(&FaucetClaimAccount { discriminator, version, wallet: key(1), last_claimed_at: 2, claims: 3 }),
+        AccountType::NotificationTarget => encode(&NotificationTargetAccount {
+            discriminator,
+            version,
+            voting: key(1),
+            webhook_commitment: Some(bytes32(2)),
+            notify: Some(key(3)),
+            updated_at: 4
+        })
+    };
+
+    Some(bytes)
+}
+
+fn create_voting_instruction() -> CreateVotingInstruction {
+    CreateVotingInstruction {
+        starts_at: 1,
+        ends_at: 2,
+        title: "Lunch on Fridays".to_string(),
+        description: "Where the team orders from".to_string(),
+        options: vec![ "Pizza".to_string(), "Sushi".to_string() ],
+        co_creators: vec![ key(3) ],
+        creator_threshold: 1,
+        weight_source: WeightSource::MerkleSnapshot(bytes32(4)),
+        tally_mode: TallyMode::Threshold(5),
+        eligibility: eligibility_policy(),
+        option_caps: vec![ 6, 0 ],
+        signup_slots: 7,
+        random_draw: true,
+        oracle_condition: Some(oracle_condition()),
+        relative_times: true,
+        weight_mints: vec![ MintRatio { mint: key(8), ratio_bps: 9 } ],
+        weight_precision: Some(2),
+        large_supply: true,
+        record_buckets: 3,
+        draft: true,
+        required_stages: 4,
+        envelope_key: Some(bytes32(10)),
+        tie_policy: TiePolicy::Random,
+        dependency: Some(VotingDependency { voting: key(11), option: 0 }),
+        program_creator: Some(ProgramCreator { program_id: key(12), seeds: vec![ b"creator".to_vec() ] }),
+        turnout_milestones: Some(TurnoutMilestones { electorate: 13, percents: vec![ 50 ], quorum: 14 }),
+        hide_tallies: true,
+        result_attestors: Some(ResultAttestors { attestors: vec![ key(15) ], threshold: 1, attested: 0 }),
+        tally_shards: 2,
+        weight_cap_per_slot: 16,
+        reject_encumbered_tokens: true,
+        reveal_window: 17,
+        min_participation: 18,
+        pass_threshold_bps: 19,
+        apportionment: Some(Apportionment { method: SeatMethod::SainteLague, seats: 20 }),
+        winners_count: 21,
+        electorate: Some(Electorate::Voters(22)),
+        quorum_bps: 23
+    }
+}
+
+fn create_realm_voting_instruction() -> CreateRealmVotingInstruction {
+    let CreateVotingInstruction {
+        starts_at,
+        ends_at,
+        title,
+        description,
+        options,
+        co_creators,
+        weight_source,
+        tally_mode,
+        eligibility,
+        option_caps,
+        signup_slots,
+        random_draw,
+        oracle_condition,
+        relative_times,
+        weight_mints,
+        weight_precision,
+        large_supply,
+        record_buckets,
+        draft,
+        required_stages,
+        envelope_key,
+        tie_policy,
+        dependency,
+        program_creator,
+        turnout_milestones,
+        hide_tallies,
+        result_attestors,
+        tally_shards,
+        weight_cap_per_slot,
+        reject_encumbered_tokens,
+        reveal_window,
+        min_participation,
+        pass_threshold_bps,
+        apportionment,
+        winners_count,
+        electorate,
+        quorum_bps,
+        ..
+    } = create_voting_instruction();
+
+    CreateRealmVotingInstruction {
+        starts_at,
+        ends_at,
+        title,
+        description,
+        options,
+        co_creators,
+        weight_source,
+        tally_mode,
+        eligibility,
+        option_caps,
+        signup_slots,
+        random_draw,
+        oracle_condition,
+        relative_times,
+        weight_mints,
+        weight_precision,
+        large_supply,
+        record_buckets,
+        draft,
+        required_stages,
+        envelope_key,
+        tie_policy,
+        dependency,
+        program_creator,
+        turnout_milestones,
+        hide_tallies,
+        result_attestors,
+        tally_shards,
+        weight_cap_per_slot,
+        reject_encumbered_tokens,
+        reveal_window,
+        min_participation,
+        pass_threshold_bps,
+        apportionment,
+        winners_count,
+        electorate,
+        quorum_bps,
+        overrides: VotingSettingsOverrides {
+            min_voting_time: Some(60),
+            max_voting_time: None,
+            creator_threshold: Some(2),
+            allow_updates: None,
+            members_only: Some(true)
+        }
+    }
+}
+
+// Instruction data as clients send it, the discriminator followed by the arguments
+fn reference_instruction(instruction_type: InstructionType) -> Vec<u8> {
+    let arguments = match instruction_type {
+        InstructionType::CreateVoting => encode(&create_voting_instruction()),
+        InstructionType::Vote => encode(&VoteInstruction {
+            ballot: vec![ 1 ],
+            vote_title: "Lunch on Fridays".to_string(),
+            memo: true,
+            weight_proof: weight_proof(),
+            eligibility_proof: eligibility_proof(),
+            dry_run: false,
+            voter_index_page: 2
+        }),
+        InstructionType::UpdateVote => encode(&UpdateVoteInstruction { ballot: vec![ 0, 1 ], memo: false, dry_run: true }),
+        InstructionType::EditVoting => encode(&EditVotingInstruction { description: "Where the team orders from".to_string() }),
+        InstructionType::AddOption => encode(&AddOptionInstruction { option: "Salad".to_string(), cap: 1 }),
+        InstructionType::RemoveOption => encode(&RemoveOptionInstruction { index: 1 }),
+        InstructionType::SetOptionOrder => encode(&SetOptionOrderInstruction { order: vec![ 1, 0 ] }),
+        InstructionType::SetInstructionFlags => encode(&SetInstructionFlagsInstruction { disabled_instructions: 1 << 100 | 1 }),
+        InstructionType::ProposeAdmin => encode(&ProposeAdminInstruction { new_admin: key(1) }),
+        InstructionType::FundRewards => encode(&FundRewardsInstruction { amount: 1 }),
+        InstructionType::CreateRealm => encode(&CreateRealmInstruction {
+            name: "Team realm".to_string(),
+            defaults: voting_settings(),
+            locked_settings: 1,
+            creation_fee: 2,
+            creators_gated: true
+        }),
+        InstructionType::UpdateRealm => encode(&UpdateRealmInstruction {
+            defaults: voting_settings(),
+            locked_settings: 1,
+            creation_fee: 2,
+            creators_gated: false
+        }),
+        InstructionType::CreateRealmVoting => encode(&create_realm_voting_instruction()),
+        InstructionType::Boost => encode(&BoostInstruction { amount: 1 }),
+        InstructionType::GetOptionCapacity => encode(&GetOptionCapacityInstruction { index: 1 }),
+        InstructionType::GetVoteWeight => encode(&GetVoteWeightInstruction { weight_proof: weight_proof() }),
+        InstructionType::ClaimSlot => encode(&ClaimSlotInstruction { eligibility_proof: eligibility_proof(), dry_run: false }),
+        InstructionType::SetExecutionAllowlist => encode(&SetExecutionAllowlistInstruction {
+            actions: vec![ AllowedAction { program_id: key(1), data_prefix: vec![ 2 ] } ]
+        }),
+        InstructionType::AddProposalInstruction => encode(&AddProposalInstructionInstruction {
+            option: 1,
+            instruction: proposal_instruction()
+        }),
+        InstructionType::EnableTokenBallots => encode(&EnableTokenBallotsInstruction { mint: key(1) }),
+        InstructionType::SetMaxActiveVotings => encode(&SetMaxActiveVotingsInstruction { max_active_votings: 1 }),
+        InstructionType::SetStartTimeTolerance => encode(&SetStartTimeToleranceInstruction { start_time_tolerance: 1 }),
+        InstructionType::SetVotingMetadata => encode(&SetVotingMetadataInstruction {
+            timezone: "Europe/Berlin".to_string(),
+            schedule: "Every Friday".to_string()
+        }),
+        InstructionType::AppendAllowlistChunk => encode(&AppendAllowlistChunkInstruction { voters: vec![ key(1), key(2) ] }),
+        InstructionType::SetAllowlistRoot => encode(&SetAllowlistRootInstruction { root: bytes32(1), voters_count: 2 }),
+        InstructionType::SealVoterRoll => encode(&SealVoterRollInstruction { root: bytes32(1), voters_count: 2 }),
+        InstructionType::UpdateTitle => encode(&UpdateTitleInstruction { title: "Lunch On Fridays".to_string() }),
+        InstructionType::SetNotificationTarget => encode(&SetNotificationTargetInstruction {
+            webhook_commitment: Some(bytes32(1)),
+            notify: Some(key(2))
+        }),
+        InstructionType::AllocateVoteBucket => encode(&AllocateVoteBucketInstruction { index: 1 }),
+        InstructionType::ReserveTitle => encode(&ReserveTitleInstruction { title_hash: bytes32(1) }),
+        InstructionType::PublishVoting => encode(&PublishVotingInstruction { starts_at: 1 }),
+        InstructionType::CastEnvelope => encode(&CastEnvelopeInstruction {
+            envelope: vec![ 1, 2, 3 ],
+            vote_title: "Lunch on Fridays".to_string(),
+            weight_proof: weight_proof(),
+            eligibility_proof: eligibility_proof(),
+            voter_index_page: 4,
+            dry_run: false
+        }),
+        InstructionType::SubmitEnvelopeResults => encode(&SubmitEnvelopeResultsInstruction { tallies: vec![ 1, 2 ], commitment: bytes32(3) }),
+        InstructionType::SetProposalTemplate => encode(&SetProposalTemplateInstruction {
+            id: 1,
+            instruction: proposal_instruction(),
+            params: template_params()
+        }),
+        InstructionType::AddTemplateInstruction => encode(&AddTemplateInstructionInstruction {
+            option: 1,
+            template_id: 2,
+            values: vec![ TemplateValue::Amount(3), TemplateValue::Account(key(4)) ]
+        }),
+        InstructionType::CloneVoting => encode(&CloneVotingInstruction {
+            title: "Dinner on Fridays".to_string(),
+            starts_at: 1,
+            ends_at: 2,
+            relative_times: true
+        }),
+        InstructionType::VerifyBallotProof => encode(&VerifyBallotProofInstruction {
+            voter: key(1),
+            vote_index: 2,
+            weight: 3,
+            ballot: vec![ 0 ],
+            proof: vec![ bytes32(4) ]
+        }),
+        InstructionType::HideVoting => encode(&HideVotingInstruction { hidden: true }),
+        InstructionType::FundIncentives => encode(&FundIncentivesInstruction { lamports_per_vote: 1, amount: 2 }),
+        InstructionType::SetLocalizedMetadata => encode(&SetLocalizedMetadataInstruction {
+            language: "de".to_string(),
+            title: "Mittagessen am Freitag".to_string(),
+            description: "Wo das Team bestellt".to_string()
+        }),
+        InstructionType::SetCreationDeposit => encode(&SetCreationDepositInstruction { creation_deposit: 1, deposit_refund_window: 2 }),
+        InstructionType::GetPercentages => encode(&GetPercentagesInstruction { decimals: 2 }),
+        InstructionType::AllocateTallyShard => encode(&AllocateTallyShardInstruction { index: 1 }),
+        InstructionType::CastShardedVote => encode(&CastShardedVoteInstruction {
+            ballot: vec![ 1 ],
+            weight_proof: weight_proof(),
+            eligibility_proof: eligibility_proof(),
+            voter_index_page: 2,
+            dry_run: true
+        }),
+        InstructionType::AddWeight => encode(&AddWeightInstruction { dry_run: true }),
+        InstructionType::LinkProposalNote => encode(&LinkProposalNoteInstruction { mint: key(1) }),
+        InstructionType::VoteByTransferClaim => encode(&VoteByTransferClaimInstruction {
+            vote_title: "Lunch on Fridays".to_string(),
+            weight_proof: weight_proof(),
+            eligibility_proof: eligibility_proof(),
+            voter_index_page: 1,
+            dry_run: true
+        }),
+        InstructionType::CommitVote => encode(&CommitVoteInstruction {
+            commitment: bytes32(1),
+            vote_title: "Lunch on Fridays".to_string(),
+            weight_proof: weight_proof(),
+            eligibility_proof: eligibility_proof(),
+            voter_index_page: 2,
+            dry_run: false
+        }),
+        InstructionType::RevealVote => encode(&RevealVoteInstruction { ballot: vec![ 1 ], salt: bytes32(2) }),
+        InstructionType::CreateElection => encode(&CreateElectionInstruction { id: 1, name: "Board election".to_string() }),
+        InstructionType::ExtendVoting => encode(&ExtendVotingInstruction { ends_at: 1 }),
+        InstructionType::SetLateRegistration => encode(&SetLateRegistrationInstruction { percent: 25 }),
+        InstructionType::InitializeConfig
+        | InstructionType::AcceptAdmin
+        | InstructionType::GetMyVote
+        | InstructionType::SnapshotTally
+        | InstructionType::ClaimCertificate
+        | InstructionType::ClaimWinnings
+        | InstructionType::TopUpRent
+        | InstructionType::JoinRealm
+        | InstructionType::LeaveRealm
+        | InstructionType::ReleaseBoost
+        | InstructionType::RefundBoost
+        | InstructionType::CommitDraw
+        | InstructionType::RevealDraw
+        | InstructionType::ClaimDraw
+        | InstructionType::FinalizeVoting
+        | InstructionType::ExecuteNextInstruction
+        | InstructionType::CountTokenBallots
+        | InstructionType::StartRecount
+        | InstructionType::RecountVotes
+        | InstructionType::CompleteRecount
+        | InstructionType::UpgradeProgram
+        | InstructionType::SetUpgradeAuthority
+        | InstructionType::SealAllowlist
+        | InstructionType::CommitTieBreak
+        | InstructionType::RevealTieBreak
+        | InstructionType::ArchiveVoting
+        | InstructionType::MerkleizeRecords
+        | InstructionType::FlagVoting
+        | InstructionType::CloseFlag
+        | InstructionType::WithdrawIncentives
+        | InstructionType::GetResults
+        | InstructionType::GcExpired
+        | InstructionType::SyncStatus
+        | InstructionType::AttestResult
+        | InstructionType::CancelVoting
+        | InstructionType::RestoreVoting
+        | InstructionType::MergeTallyShards
+        | InstructionType::WithdrawKiosk
+        | InstructionType::CloseUserVote
+        | InstructionType::CloseVoting
+        | InstructionType::GetCreatorSummary
+        | InstructionType::FinalizeElection
+        | InstructionType::FreezeVoting
+        | InstructionType::UnfreezeVoting
+        | InstructionType::MigrateAccount
+        | InstructionType::SyncPollCard
+        | InstructionType::ClaimFaucet => Vec::new()
+    };
+
+    [ instruction_type.header(), arguments ].concat()
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// One `<name> <hex>` line per layout, sorted by name so the diff of a regenerated file stays readable
+fn check_fixture(file: &str, layouts: Vec<(String, Vec<u8>)>) {
+    let path = fixture_path(file);
+    let expected = layouts.into_iter()
+        .map(|(name, bytes)| (name, to_hex(&bytes)))
+        .collect::<BTreeMap<_, _>>();
+
+    if std::env::var_os("GOLDEN_BLESS").is_some() {
+        let lines = expected.iter().map(|(name, hex)| format!("{} {}\n", name, hex)).collect::<String>();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, lines).unwrap();
+        return;
+    };
+
+    let contents = fs::read_to_string(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+    let fixtures = contents.lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(name, hex)| (name.to_string(), hex.to_string()))
+        .collect::<BTreeMap<_, _>>();
+
+    for (name, hex) in expected.iter() {
+        match fixtures.get(name) {
+            Some(fixture) => assert_eq!(fixture, hex, "layout of {} changed, see golden.rs", name),
+            None => panic!("{} has no fixture in {}, see golden.rs", name, file)
+        };
+    };
+    for name in fixtures.keys() {
+        assert!(expected.contains_key(name), "{} in {} has no reference value", name, file);
+    };
+}
+
+#[test]
+fn account_layouts_match_fixtures() {
+    let layouts = account_layouts().into_iter()
+        .map(|(account_type, bytes)| (format!("{:?}", account_type), bytes))
+        .collect();
+
+    check_fixture("accounts.golden", layouts);
+}
+
+#[test]
+fn instruction_layouts_match_fixtures() {
+    let layouts = InstructionType::ALL.into_iter()
+        .map(|instruction_type| (format!("{:?}", instruction_type), reference_instruction(instruction_type)))
+        .collect();
+
+    check_fixture("instructions.golden", layouts);
+}
+
+// Codes are what clients match on, a renumbered error breaks them
+#[test]
+fn error_codes_match_fixtures() {
+    let codes = Errors::all()
+        .map(|error| (format!("{:?}", error), error.code().to_le_bytes().to_vec()))
+        .collect();
+
+    check_fixture("errors.golden", codes);
+}
+
+// The reference instructions are what clients send, the program has to read them back
+#[test]
+fn reference_instructions_unpack() {
+    for instruction_type in InstructionType::ALL {
+        let data = reference_instruction(instruction_type);
+        assert!(VotingInstruction::unpack(&data).is_ok(), "{:?} doesn't unpack", instruction_type);
+    };
+}
+
+// A reference instruction one byte short of its layout, the header alone without its version byte
+#[test]
+fn short_payloads_are_too_short() {
+    for instruction_type in InstructionType::ALL {
+        let mut data = reference_instruction(instruction_type);
+        data.pop();
+        let error = VotingInstruction::unpack(&data).err();
+        assert_eq!(error, Some(Errors::InstructionDataTooShort.into()), "{:?}", instruction_type);
+    };
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for character in name.chars() {
+        if character.is_ascii_uppercase() && snake.is_empty() == false {
+            snake.push('_');
+        };
+        snake.push(character.to_ascii_lowercase());
+    };
+
+    snake
+}
+
+fn derived_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    hash(format!("{}:{}", namespace, snake_case(name)).as_bytes()).to_bytes()[..8].try_into().unwrap()
+}
+
+#[test]
+fn discriminators_are_derived_from_names() {
+    for account_type in AccountType::ALL {
+        let expected = derived_discriminator("account", &format!("{:?}", account_type));
+        assert_eq!(account_type.discriminator(), expected, "{:?}", account_type);
+    };
+    for instruction_type in InstructionType::ALL {
+        let expected = derived_discriminator("instruction", &format!("{:?}", instruction_type));
+        assert_eq!(instruction_type.discriminator(), expected, "{:?}", instruction_type);
+    };
+}
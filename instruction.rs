@@ -0,0 +1,1768 @@
+use std::io::{ self, Read };
+
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{ AccountMeta, Instruction },
+    log::sol_log_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program
+};
+
+use crate::{
+    eligibility::{ EligibilityPolicy, EligibilityProof },
+    error::Errors,
+    execution::{ AllowedAction, ProposalInstruction, TemplateParam, TemplateValue },
+    oracle::OracleCondition,
+    state::*,
+    tally::{ Apportionment, TallyMode, TiePolicy },
+    weight::{ MintRatio, WeightProof, WeightSource }
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CreateVotingInstruction {
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    // Other creators whose signatures count towards `creator_threshold`, the signer is always a creator
+    pub co_creators: Vec<Pubkey>,
+    pub creator_threshold: u8,
+    pub weight_source: WeightSource,
+    pub tally_mode: TallyMode,
+    pub eligibility: EligibilityPolicy,
+    // Empty for no caps, otherwise one per option with 0 leaving the option uncapped
+    pub option_caps: Vec<u64>,
+    // Slots of a signup voting, 0 for a regular voting
+    pub signup_slots: u32,
+    // Draws a winner among the voters, weighted by vote weight, once the voting is closed
+    pub random_draw: bool,
+    // Checked by `finalize_voting`, the voting has no winner if it isn't met
+    pub oracle_condition: Option<OracleCondition>,
+    // `starts_at` and `ends_at` are seconds after the creation time instead of timestamps, so a clock
+    // running behind the cluster can't push the start into the past
+    pub relative_times: bool,
+    // Accepted mints of a `WeightSource::MultiToken` or `WeightSource::StakedSol` voting, empty otherwise
+    pub weight_mints: Vec<MintRatio>,
+    // Token weights are converted to this many decimals, see `WeightSource::resolve`. `None` counts raw amounts.
+    pub weight_precision: Option<u8>,
+    // Option tallies count up to u128 instead of u64, for tokens with huge supplies. Only for `Simple`,
+    // `Threshold` and `Approval` votings.
+    pub large_supply: bool,
+    // Buckets shared by the voters' records, 0 for a user vote account per voter. See `VoteBucketAccount`.
+    pub record_buckets: u8,
+    // Created as a draft, which takes no votes and can be edited at any time until `publish_voting`
+    pub draft: bool,
+    // `STAGE_*` bits a draft must complete before it can be published, 0 for other votings
+    pub required_stages: u8,
+    // X25519 public key of the creator for an envelope voting, see `cast_envelope`. Only for `Simple`,
+    // `Threshold` and `Approval` votings.
+    pub envelope_key: Option<[u8; 32]>,
+    pub tie_policy: TiePolicy,
+    // The voting only takes ballots once another voting was finalized with the given outcome
+    pub dependency: Option<VotingDependency>,
+    // Set when the signer is a PDA of another program creating the voting through CPI
+    pub program_creator: Option<ProgramCreator>,
+    // Turnouts announced with a `TurnoutMilestoneEvent` once reached
+    pub turnout_milestones: Option<TurnoutMilestones>,
+    // Running tallies are withheld until `ends_at`, see `VoteMainAccount::check_tallies_visible`. Can't
+    // be combined with option caps, which tell when an option fills up.
+    pub hide_tallies: bool,
+    // Attestors who must co-sign the finalized result before it counts, with nobody attested yet
+    pub result_attestors: Option<ResultAttestors>,
+    // Counter accounts the ballots are spread over by voter, so ballots of a popular voting don't all wait
+    // for its write lock. 0 counts in the voting, otherwise 2 to `tally::MAX_TALLY_SHARDS`, see
+    // `cast_sharded_vote`.
+    pub tally_shards: u8,
+    // Weight credited to a voter per slot on token-weighted votings, 0 for no cap. The rest of the balance
+    // is credited by `add_weight` in later slots, so weight borrowed for a single slot barely counts.
+    pub weight_cap_per_slot: u64,
+    // Token-weighted votings only, see `WeightSettings::reject_encumbered`
+    pub reject_encumbered_tokens: bool,
+    // Seconds after `ends_at` to reveal ballots committed with `commit_vote`, up to `MAX_REVEAL_WINDOW`.
+    // 0 takes plain ballots.
+    pub reveal_window: u64,
+    // Options elected on finalization, 1 for a single winner. More only for `Simple`, `Approval` and
+    // `Quadratic` votings with `TiePolicy::LowestIndex`, see `tally::top_options`.
+    pub winners_count: u8,
+    // Seats to share out among the options on finalization, see `tally::apportion`. Not for ranked votings.
+    pub apportionment: Option<Apportionment>,
+    // Voters, spoiled ballots included, the voting needs to pass. 0 for no quorum.
+    pub min_participation: u32,
+    // Share of all option tallies in basis points the winning option needs to pass, 0 for a plurality
+    pub pass_threshold_bps: u16,
+    // Denominator of the turnout, see `VoteMainAccount::turnout_bps`. `None` measures nothing.
+    pub electorate: Option<Electorate>,
+    // Turnout in basis points of the electorate the voting needs to pass, 0 for no quorum. Checked
+    // along with `min_participation`.
+    pub quorum_bps: u16
+}
+
+impl CreateVotingInstruction {
+    // A one person one vote voting between two timestamps with every other setting off, the builders
+    // change what they need with struct update syntax
+    pub fn new(title: String, options: Vec<String>, starts_at: u64, ends_at: u64) -> Self {
+        Self {
+            starts_at,
+            ends_at,
+            title,
+            description: String::new(),
+            options,
+            co_creators: Vec::new(),
+            creator_threshold: 1,
+            weight_source: WeightSource::Fixed(1),
+            tally_mode: TallyMode::Simple,
+            eligibility: EligibilityPolicy { clauses: Vec::new() },
+            option_caps: Vec::new(),
+            signup_slots: 0,
+            random_draw: false,
+            oracle_condition: None,
+            relative_times: false,
+            weight_mints: Vec::new(),
+            weight_precision: None,
+            large_supply: false,
+            record_buckets: 0,
+            draft: false,
+            required_stages: 0,
+            envelope_key: None,
+            tie_policy: TiePolicy::LowestIndex,
+            dependency: None,
+            program_creator: None,
+            turnout_milestones: None,
+            hide_tallies: false,
+            result_attestors: None,
+            tally_shards: 0,
+            weight_cap_per_slot: 0,
+            reject_encumbered_tokens: false,
+            reveal_window: 0,
+            winners_count: 1,
+            apportionment: None,
+            min_participation: 0,
+            pass_threshold_bps: 0,
+            electorate: None,
+            quorum_bps: 0
+        }
+    }
+
+    // Decodes a payload of `payload_version`. Version 1 has none of the settings version 2 added: a single
+    // winner, no seats and no quorum.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 2 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            starts_at: BorshDeserialize::deserialize_reader(reader)?,
+            ends_at: BorshDeserialize::deserialize_reader(reader)?,
+            title: BorshDeserialize::deserialize_reader(reader)?,
+            description: BorshDeserialize::deserialize_reader(reader)?,
+            options: BorshDeserialize::deserialize_reader(reader)?,
+            co_creators: BorshDeserialize::deserialize_reader(reader)?,
+            creator_threshold: BorshDeserialize::deserialize_reader(reader)?,
+            weight_source: BorshDeserialize::deserialize_reader(reader)?,
+            tally_mode: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility: BorshDeserialize::deserialize_reader(reader)?,
+            option_caps: BorshDeserialize::deserialize_reader(reader)?,
+            signup_slots: BorshDeserialize::deserialize_reader(reader)?,
+            random_draw: BorshDeserialize::deserialize_reader(reader)?,
+            oracle_condition: BorshDeserialize::deserialize_reader(reader)?,
+            relative_times: BorshDeserialize::deserialize_reader(reader)?,
+            weight_mints: BorshDeserialize::deserialize_reader(reader)?,
+            weight_precision: BorshDeserialize::deserialize_reader(reader)?,
+            large_supply: BorshDeserialize::deserialize_reader(reader)?,
+            record_buckets: BorshDeserialize::deserialize_reader(reader)?,
+            draft: BorshDeserialize::deserialize_reader(reader)?,
+            required_stages: BorshDeserialize::deserialize_reader(reader)?,
+            envelope_key: BorshDeserialize::deserialize_reader(reader)?,
+            tie_policy: BorshDeserialize::deserialize_reader(reader)?,
+            dependency: BorshDeserialize::deserialize_reader(reader)?,
+            program_creator: BorshDeserialize::deserialize_reader(reader)?,
+            turnout_milestones: BorshDeserialize::deserialize_reader(reader)?,
+            hide_tallies: BorshDeserialize::deserialize_reader(reader)?,
+            result_attestors: BorshDeserialize::deserialize_reader(reader)?,
+            tally_shards: BorshDeserialize::deserialize_reader(reader)?,
+            weight_cap_per_slot: BorshDeserialize::deserialize_reader(reader)?,
+            reject_encumbered_tokens: BorshDeserialize::deserialize_reader(reader)?,
+            reveal_window: BorshDeserialize::deserialize_reader(reader)?,
+            winners_count: 1,
+            apportionment: None,
+            min_participation: 0,
+            pass_threshold_bps: 0,
+            electorate: None,
+            quorum_bps: 0
+        })
+    }
+
+    pub(crate) fn resolve_times(&mut self, current_time: u64) -> ProgramResult {
+        if self.relative_times {
+            self.starts_at = current_time.checked_add(self.starts_at).ok_or(ProgramError::ArithmeticOverflow)?;
+            self.ends_at = current_time.checked_add(self.ends_at).ok_or(ProgramError::ArithmeticOverflow)?;
+            self.relative_times = false;
+        };
+
+        Ok(())
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct EditVotingInstruction {
+    pub description: String
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AddOptionInstruction {
+    pub option: String,
+    // 0 for an uncapped option
+    pub cap: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct RemoveOptionInstruction {
+    pub index: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetOptionOrderInstruction {
+    pub order: Vec<u8>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetInstructionFlagsInstruction {
+    pub disabled_instructions: u128
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ProposeAdminInstruction {
+    pub new_admin: Pubkey
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct VoteInstruction {
+    // Option indices, see `TallyMode`, empty to spoil the ballot
+    pub ballot: Vec<u8>,
+    pub vote_title: String,
+    // Opt-in, the memo program is then passed after the system program
+    pub memo: bool,
+    // Only for `WeightSource::MerkleSnapshot` votings
+    pub weight_proof: Option<WeightProof>,
+    // Only for votings with an eligibility policy
+    pub eligibility_proof: Option<EligibilityProof>,
+    // Validates the ballot and returns a `VotePreviewReturnData` without writing anything
+    pub dry_run: bool,
+    // Page of the voter index taking the voting, the first one that isn't full
+    pub voter_index_page: u16
+}
+
+// The voting is checked against the PDA of its stored title, the voter already named it on the first vote
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct UpdateVoteInstruction {
+    pub ballot: Vec<u8>,
+    pub memo: bool,
+    pub dry_run: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct FundRewardsInstruction {
+    pub amount: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CreateRealmInstruction {
+    pub name: String,
+    pub defaults: VotingSettings,
+    pub locked_settings: u8,
+    pub creation_fee: u64,
+    pub creators_gated: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct UpdateRealmInstruction {
+    pub defaults: VotingSettings,
+    pub locked_settings: u8,
+    pub creation_fee: u64,
+    pub creators_gated: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct VotingSettingsOverrides {
+    pub min_voting_time: Option<u64>,
+    pub max_voting_time: Option<u64>,
+    pub creator_threshold: Option<u8>,
+    pub allow_updates: Option<bool>,
+    pub members_only: Option<bool>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CreateRealmVotingInstruction {
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    pub co_creators: Vec<Pubkey>,
+    pub weight_source: WeightSource,
+    pub tally_mode: TallyMode,
+    pub eligibility: EligibilityPolicy,
+    pub option_caps: Vec<u64>,
+    pub signup_slots: u32,
+    pub random_draw: bool,
+    pub oracle_condition: Option<OracleCondition>,
+    pub relative_times: bool,
+    pub weight_mints: Vec<MintRatio>,
+    pub weight_precision: Option<u8>,
+    pub large_supply: bool,
+    pub record_buckets: u8,
+    pub draft: bool,
+    pub required_stages: u8,
+    pub envelope_key: Option<[u8; 32]>,
+    pub tie_policy: TiePolicy,
+    pub dependency: Option<VotingDependency>,
+    pub program_creator: Option<ProgramCreator>,
+    // The electorate is replaced by the realm's `members_count`
+    pub turnout_milestones: Option<TurnoutMilestones>,
+    pub hide_tallies: bool,
+    pub result_attestors: Option<ResultAttestors>,
+    pub tally_shards: u8,
+    pub weight_cap_per_slot: u64,
+    pub reject_encumbered_tokens: bool,
+    pub reveal_window: u64,
+    pub winners_count: u8,
+    pub apportionment: Option<Apportionment>,
+    pub min_participation: u32,
+    pub pass_threshold_bps: u16,
+    pub electorate: Option<Electorate>,
+    pub quorum_bps: u16,
+    pub overrides: VotingSettingsOverrides
+}
+
+impl CreateRealmVotingInstruction {
+    // Decodes a payload of `payload_version`, see `CreateVotingInstruction::read`. Version 2 added its
+    // settings before `overrides`.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 2 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            starts_at: BorshDeserialize::deserialize_reader(reader)?,
+            ends_at: BorshDeserialize::deserialize_reader(reader)?,
+            title: BorshDeserialize::deserialize_reader(reader)?,
+            description: BorshDeserialize::deserialize_reader(reader)?,
+            options: BorshDeserialize::deserialize_reader(reader)?,
+            co_creators: BorshDeserialize::deserialize_reader(reader)?,
+            weight_source: BorshDeserialize::deserialize_reader(reader)?,
+            tally_mode: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility: BorshDeserialize::deserialize_reader(reader)?,
+            option_caps: BorshDeserialize::deserialize_reader(reader)?,
+            signup_slots: BorshDeserialize::deserialize_reader(reader)?,
+            random_draw: BorshDeserialize::deserialize_reader(reader)?,
+            oracle_condition: BorshDeserialize::deserialize_reader(reader)?,
+            relative_times: BorshDeserialize::deserialize_reader(reader)?,
+            weight_mints: BorshDeserialize::deserialize_reader(reader)?,
+            weight_precision: BorshDeserialize::deserialize_reader(reader)?,
+            large_supply: BorshDeserialize::deserialize_reader(reader)?,
+            record_buckets: BorshDeserialize::deserialize_reader(reader)?,
+            draft: BorshDeserialize::deserialize_reader(reader)?,
+            required_stages: BorshDeserialize::deserialize_reader(reader)?,
+            envelope_key: BorshDeserialize::deserialize_reader(reader)?,
+            tie_policy: BorshDeserialize::deserialize_reader(reader)?,
+            dependency: BorshDeserialize::deserialize_reader(reader)?,
+            program_creator: BorshDeserialize::deserialize_reader(reader)?,
+            turnout_milestones: BorshDeserialize::deserialize_reader(reader)?,
+            hide_tallies: BorshDeserialize::deserialize_reader(reader)?,
+            result_attestors: BorshDeserialize::deserialize_reader(reader)?,
+            tally_shards: BorshDeserialize::deserialize_reader(reader)?,
+            weight_cap_per_slot: BorshDeserialize::deserialize_reader(reader)?,
+            reject_encumbered_tokens: BorshDeserialize::deserialize_reader(reader)?,
+            reveal_window: BorshDeserialize::deserialize_reader(reader)?,
+            winners_count: 1,
+            apportionment: None,
+            min_participation: 0,
+            pass_threshold_bps: 0,
+            electorate: None,
+            quorum_bps: 0,
+            overrides: BorshDeserialize::deserialize_reader(reader)?
+        })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct BoostInstruction {
+    pub amount: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct GetOptionCapacityInstruction {
+    pub index: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct GetVoteWeightInstruction {
+    // Only for `WeightSource::MerkleSnapshot` votings
+    pub weight_proof: Option<WeightProof>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ClaimSlotInstruction {
+    // Only for votings with an eligibility policy
+    pub eligibility_proof: Option<EligibilityProof>,
+    // Checks the claim and returns a `VotePreviewReturnData` without writing anything
+    pub dry_run: bool
+}
+
+impl ClaimSlotInstruction {
+    // Decodes a payload of `payload_version`. Before version 3 there is no `dry_run` and the slot is claimed.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self { eligibility_proof: BorshDeserialize::deserialize_reader(reader)?, dry_run: false })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetExecutionAllowlistInstruction {
+    // Replaces the whole allowlist, empty to allow nothing
+    pub actions: Vec<AllowedAction>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AddProposalInstructionInstruction {
+    // The option that has to win for the proposal to execute, the same for all its instructions
+    pub option: u8,
+    pub instruction: ProposalInstruction
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct EnableTokenBallotsInstruction {
+    // The ballot token, every unit sent to an option's ballot address counts as one vote weight
+    pub mint: Pubkey
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetMaxActiveVotingsInstruction {
+    // 0 removes the cap
+    pub max_active_votings: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetStartTimeToleranceInstruction {
+    // Seconds, at most `MAX_START_TIME_TOLERANCE`
+    pub start_time_tolerance: u64
+}
+
+// Empty strings clear a hint
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetVotingMetadataInstruction {
+    pub timezone: String,
+    pub schedule: String
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AppendAllowlistChunkInstruction {
+    // At most `MAX_ALLOWLIST_CHUNK`, and only up to the end of the current page
+    pub voters: Vec<Pubkey>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetAllowlistRootInstruction {
+    pub root: [u8; 32],
+    // Leaves under the root, informational
+    pub voters_count: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SealVoterRollInstruction {
+    // Merkle root over the eligible voters, see `VoterRoll`
+    pub root: [u8; 32],
+    pub voters_count: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct UpdateTitleInstruction {
+    // Has to normalize to the current title, which the voting's address is derived from
+    pub title: String
+}
+
+// Both `None` remove the target and refund its rent
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetNotificationTargetInstruction {
+    pub webhook_commitment: Option<[u8; 32]>,
+    pub notify: Option<Pubkey>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AllocateVoteBucketInstruction {
+    pub index: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ReserveTitleInstruction {
+    // `voting_title_seed` of the title
+    pub title_hash: [u8; 32]
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct PublishVotingInstruction {
+    // The voting keeps the duration it was created with
+    pub starts_at: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CastEnvelopeInstruction {
+    // The ballot sealed to the voting's `envelope_key`
+    pub envelope: Vec<u8>,
+    pub vote_title: String,
+    pub weight_proof: Option<WeightProof>,
+    pub eligibility_proof: Option<EligibilityProof>,
+    pub voter_index_page: u16,
+    // Validates the envelope and returns a `VotePreviewReturnData` without writing anything
+    pub dry_run: bool
+}
+
+impl CastEnvelopeInstruction {
+    // Decodes a payload of `payload_version`. Before version 3 there is no `dry_run` and the envelope is cast.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            envelope: BorshDeserialize::deserialize_reader(reader)?,
+            vote_title: BorshDeserialize::deserialize_reader(reader)?,
+            weight_proof: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility_proof: BorshDeserialize::deserialize_reader(reader)?,
+            voter_index_page: BorshDeserialize::deserialize_reader(reader)?,
+            dry_run: false
+        })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SubmitEnvelopeResultsInstruction {
+    pub tallies: Vec<u64>,
+    // Hash of the decrypted ballots in the order they were cast, anyone can check it once the creator
+    // publishes the secret key
+    pub commitment: [u8; 32]
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetProposalTemplateInstruction {
+    // Picked by the realm admin, setting an existing id replaces that template
+    pub id: u16,
+    pub instruction: ProposalInstruction,
+    // Placeholders of `instruction`, their bytes or pubkeys in it are overwritten on instantiation
+    pub params: Vec<TemplateParam>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AddTemplateInstructionInstruction {
+    pub option: u8,
+    pub template_id: u16,
+    // One per placeholder of the template, in order
+    pub values: Vec<TemplateValue>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CloneVotingInstruction {
+    // Title of the new voting, the rest of its configuration comes from the source voting
+    pub title: String,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    // Same as `CreateVotingInstruction::relative_times`
+    pub relative_times: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct VerifyBallotProofInstruction {
+    pub voter: Pubkey,
+    pub vote_index: u64,
+    pub weight: u64,
+    // As recorded, the sealed envelope for envelope votings
+    pub ballot: Vec<u8>,
+    // From the leaf up, see `client::VoteRecordTree::proof`
+    pub proof: Vec<[u8; 32]>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct HideVotingInstruction {
+    // False shows the voting again
+    pub hidden: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct FundIncentivesInstruction {
+    // Set by the first funding, later top-ups have to pass the same amount
+    pub lamports_per_vote: u64,
+    pub amount: u64
+}
+
+// An empty title and description remove the language
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetLocalizedMetadataInstruction {
+    pub language: String,
+    pub title: String,
+    pub description: String
+}
+
+// Applies to votings created afterwards, existing ones keep the deposit they were created with
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetCreationDepositInstruction {
+    // Lamports, 0 disables the deposit
+    pub creation_deposit: u64,
+    // Seconds after the end of a voting, at least `MIN_DEPOSIT_REFUND_WINDOW` when there's a deposit
+    pub deposit_refund_window: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct GetPercentagesInstruction {
+    // Up to `tally::MAX_PERCENTAGE_DECIMALS`
+    pub decimals: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AllocateTallyShardInstruction {
+    pub index: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CastShardedVoteInstruction {
+    pub ballot: Vec<u8>,
+    pub weight_proof: Option<WeightProof>,
+    pub eligibility_proof: Option<EligibilityProof>,
+    pub voter_index_page: u16,
+    pub dry_run: bool
+}
+
+impl CastShardedVoteInstruction {
+    // Decodes a payload of `payload_version`, see `CastEnvelopeInstruction::read`
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            ballot: BorshDeserialize::deserialize_reader(reader)?,
+            weight_proof: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility_proof: BorshDeserialize::deserialize_reader(reader)?,
+            voter_index_page: BorshDeserialize::deserialize_reader(reader)?,
+            dry_run: false
+        })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AddWeightInstruction {
+    pub dry_run: bool
+}
+
+impl AddWeightInstruction {
+    // Decodes a payload of `payload_version`, the payload is empty before version 3
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self { dry_run: false })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct LinkProposalNoteInstruction {
+    pub mint: Pubkey
+}
+
+// The ballot comes from the memo of the transfer to the kiosk, the rest is the same as for `vote`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct VoteByTransferClaimInstruction {
+    pub vote_title: String,
+    pub weight_proof: Option<WeightProof>,
+    pub eligibility_proof: Option<EligibilityProof>,
+    pub voter_index_page: u16,
+    pub dry_run: bool
+}
+
+impl VoteByTransferClaimInstruction {
+    // Decodes a payload of `payload_version`. Before version 3 there is no `dry_run` and the ballot is cast.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            vote_title: BorshDeserialize::deserialize_reader(reader)?,
+            weight_proof: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility_proof: BorshDeserialize::deserialize_reader(reader)?,
+            voter_index_page: BorshDeserialize::deserialize_reader(reader)?,
+            dry_run: false
+        })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CommitVoteInstruction {
+    // `vote_commitment` of the ballot, with a random salt the voter keeps for `reveal_vote`
+    pub commitment: [u8; 32],
+    pub vote_title: String,
+    pub weight_proof: Option<WeightProof>,
+    pub eligibility_proof: Option<EligibilityProof>,
+    pub voter_index_page: u16,
+    // Checks the commitment and returns a `VotePreviewReturnData` without writing anything
+    pub dry_run: bool
+}
+
+impl CommitVoteInstruction {
+    // Decodes a payload of `payload_version`. Before version 3 there is no `dry_run` and the ballot is committed.
+    fn read(reader: &mut impl Read, payload_version: u8) -> io::Result<Self> {
+        if payload_version >= 3 {
+            return Self::deserialize_reader(reader);
+        };
+
+        Ok(Self {
+            commitment: BorshDeserialize::deserialize_reader(reader)?,
+            vote_title: BorshDeserialize::deserialize_reader(reader)?,
+            weight_proof: BorshDeserialize::deserialize_reader(reader)?,
+            eligibility_proof: BorshDeserialize::deserialize_reader(reader)?,
+            voter_index_page: BorshDeserialize::deserialize_reader(reader)?,
+            dry_run: false
+        })
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct RevealVoteInstruction {
+    pub ballot: Vec<u8>,
+    pub salt: [u8; 32]
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct CreateElectionInstruction {
+    // Picked by the creator, the election PDA is derived from it
+    pub id: u64,
+    pub name: String
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ExtendVotingInstruction {
+    pub ends_at: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SetLateRegistrationInstruction {
+    // Percentage of the voting window, at most 100
+    pub percent: u8
+}
+
+#[derive(Debug)]
+pub enum VotingInstruction {
+    CreateVoting(CreateVotingInstruction),
+    Vote(VoteInstruction),
+    UpdateVote(UpdateVoteInstruction),
+    EditVoting(EditVotingInstruction),
+    AddOption(AddOptionInstruction),
+    RemoveOption(RemoveOptionInstruction),
+    SetOptionOrder(SetOptionOrderInstruction),
+    InitializeConfig,
+    SetInstructionFlags(SetInstructionFlagsInstruction),
+    ProposeAdmin(ProposeAdminInstruction),
+    AcceptAdmin,
+    GetMyVote,
+    SnapshotTally,
+    ClaimCertificate,
+    FundRewards(FundRewardsInstruction),
+    ClaimWinnings,
+    TopUpRent,
+    CreateRealm(CreateRealmInstruction),
+    UpdateRealm(UpdateRealmInstruction),
+    CreateRealmVoting(CreateRealmVotingInstruction),
+    JoinRealm,
+    LeaveRealm,
+    Boost(BoostInstruction),
+    ReleaseBoost,
+    RefundBoost,
+    GetOptionCapacity(GetOptionCapacityInstruction),
+    ClaimSlot(ClaimSlotInstruction),
+    CommitDraw,
+    RevealDraw,
+    ClaimDraw,
+    FinalizeVoting,
+    SetExecutionAllowlist(SetExecutionAllowlistInstruction),
+    AddProposalInstruction(AddProposalInstructionInstruction),
+    ExecuteNextInstruction,
+    EnableTokenBallots(EnableTokenBallotsInstruction),
+    CountTokenBallots,
+    StartRecount,
+    RecountVotes,
+    CompleteRecount,
+    SetMaxActiveVotings(SetMaxActiveVotingsInstruction),
+    SetStartTimeTolerance(SetStartTimeToleranceInstruction),
+    SetVotingMetadata(SetVotingMetadataInstruction),
+    UpgradeProgram,
+    SetUpgradeAuthority,
+    GetVoteWeight(GetVoteWeightInstruction),
+    AppendAllowlistChunk(AppendAllowlistChunkInstruction),
+    SealAllowlist,
+    SetAllowlistRoot(SetAllowlistRootInstruction),
+    AllocateVoteBucket(AllocateVoteBucketInstruction),
+    ReserveTitle(ReserveTitleInstruction),
+    PublishVoting(PublishVotingInstruction),
+    CastEnvelope(CastEnvelopeInstruction),
+    SubmitEnvelopeResults(SubmitEnvelopeResultsInstruction),
+    CommitTieBreak,
+    RevealTieBreak,
+    SetProposalTemplate(SetProposalTemplateInstruction),
+    AddTemplateInstruction(AddTemplateInstructionInstruction),
+    CloneVoting(CloneVotingInstruction),
+    ArchiveVoting,
+    MerkleizeRecords,
+    VerifyBallotProof(VerifyBallotProofInstruction),
+    FlagVoting,
+    CloseFlag,
+    HideVoting(HideVotingInstruction),
+    FundIncentives(FundIncentivesInstruction),
+    WithdrawIncentives,
+    GetResults,
+    SetLocalizedMetadata(SetLocalizedMetadataInstruction),
+    SetCreationDeposit(SetCreationDepositInstruction),
+    GcExpired,
+    SyncStatus,
+    AttestResult,
+    GetPercentages(GetPercentagesInstruction),
+    CancelVoting,
+    RestoreVoting,
+    AllocateTallyShard(AllocateTallyShardInstruction),
+    CastShardedVote(CastShardedVoteInstruction),
+    MergeTallyShards,
+    AddWeight(AddWeightInstruction),
+    LinkProposalNote(LinkProposalNoteInstruction),
+    VoteByTransferClaim(VoteByTransferClaimInstruction),
+    WithdrawKiosk,
+    CloseUserVote,
+    CloseVoting,
+    ClaimFaucet,
+    GetCreatorSummary,
+    CommitVote(CommitVoteInstruction),
+    RevealVote(RevealVoteInstruction),
+    CreateElection(CreateElectionInstruction),
+    FinalizeElection,
+    ExtendVoting(ExtendVotingInstruction),
+    SealVoterRoll(SealVoterRollInstruction),
+    FreezeVoting,
+    UnfreezeVoting,
+    SetLateRegistration(SetLateRegistrationInstruction),
+    UpdateTitle(UpdateTitleInstruction),
+    MigrateAccount,
+    SetNotificationTarget(SetNotificationTargetInstruction),
+    SyncPollCard
+}
+
+// Instruction data is the discriminator, the `payload_version` the client was built against and the borsh
+// payload. A new version only adds fields to the payloads it changes. A payload is decoded by the layout of
+// its version, with the fields added since at the value that keeps what the older client asked for.
+//
+// 1: the first layouts
+// 2: `winners_count`, `apportionment`, `min_participation`, `pass_threshold_bps`, `electorate` and
+//    `quorum_bps` on `CreateVotingInstruction` and `CreateRealmVotingInstruction`
+// 3: `dry_run` on `CastEnvelopeInstruction`, `CastShardedVoteInstruction`, `ClaimSlotInstruction`,
+//    `VoteByTransferClaimInstruction` and `CommitVoteInstruction`, and `AddWeightInstruction`
+pub const PAYLOAD_VERSION: u8 = 3;
+
+pub const INSTRUCTION_HEADER_LEN: usize = 8 + 1;
+
+// Reads a payload, remembering whether it ran out of bytes
+struct PayloadReader<'a> {
+    data: &'a [u8],
+    exhausted: bool
+}
+
+impl Read for PayloadReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.data.read(buf)?;
+        self.exhausted |= read < buf.len();
+
+        Ok(read)
+    }
+}
+
+// Decodes a payload with `read`. One cut short of its layout fails with `InstructionDataTooShort`.
+fn read_payload_with<'a, T>(data: &'a [u8], read: impl FnOnce(&mut PayloadReader<'a>) -> io::Result<T>) -> Result<T, ProgramError> {
+    let mut reader = PayloadReader { data, exhausted: false };
+
+    match read(&mut reader) {
+        Ok(payload) => Ok(payload),
+        Err(_) if reader.exhausted => Err(Errors::InstructionDataTooShort.into()),
+        Err(error) => Err(error.into())
+    }
+}
+
+// Decodes a payload whose layout no version changed
+fn read_payload<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    read_payload_with(data, T::deserialize_reader)
+}
+
+impl VotingInstruction {
+    pub(crate) fn unpack(instruction_data: &[u8]) -> Result<Self, ProgramError> {
+        if instruction_data.len() < 8 {
+            return Err(Errors::InstructionDataTooShort.into());
+        };
+
+        let instruction_type = InstructionType::from_discriminator(instruction_data).ok_or(ProgramError::InvalidInstructionData)?;
+        let payload_version = *instruction_data.get(8).ok_or(Errors::InstructionDataTooShort)?;
+        if payload_version == 0 || payload_version > PAYLOAD_VERSION {
+            return Err(Errors::UnsupportedPayloadVersion.into());
+        };
+        let data = &instruction_data[INSTRUCTION_HEADER_LEN..];
+
+        let instruction = match instruction_type {
+            InstructionType::CreateVoting => Self::CreateVoting(
+                read_payload_with(data, |reader| CreateVotingInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::Vote => Self::Vote(read_payload(data)?),
+            InstructionType::UpdateVote => Self::UpdateVote(read_payload(data)?),
+            InstructionType::EditVoting => Self::EditVoting(read_payload(data)?),
+            InstructionType::AddOption => Self::AddOption(read_payload(data)?),
+            InstructionType::RemoveOption => Self::RemoveOption(read_payload(data)?),
+            InstructionType::SetOptionOrder => Self::SetOptionOrder(read_payload(data)?),
+            InstructionType::InitializeConfig => Self::InitializeConfig,
+            InstructionType::SetInstructionFlags => Self::SetInstructionFlags(read_payload(data)?),
+            InstructionType::ProposeAdmin => Self::ProposeAdmin(read_payload(data)?),
+            InstructionType::AcceptAdmin => Self::AcceptAdmin,
+            InstructionType::GetMyVote => Self::GetMyVote,
+            InstructionType::SnapshotTally => Self::SnapshotTally,
+            InstructionType::ClaimCertificate => Self::ClaimCertificate,
+            InstructionType::FundRewards => Self::FundRewards(read_payload(data)?),
+            InstructionType::ClaimWinnings => Self::ClaimWinnings,
+            InstructionType::TopUpRent => Self::TopUpRent,
+            InstructionType::CreateRealm => Self::CreateRealm(read_payload(data)?),
+            InstructionType::UpdateRealm => Self::UpdateRealm(read_payload(data)?),
+            InstructionType::CreateRealmVoting => Self::CreateRealmVoting(
+                read_payload_with(data, |reader| CreateRealmVotingInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::JoinRealm => Self::JoinRealm,
+            InstructionType::LeaveRealm => Self::LeaveRealm,
+            InstructionType::Boost => Self::Boost(read_payload(data)?),
+            InstructionType::ReleaseBoost => Self::ReleaseBoost,
+            InstructionType::RefundBoost => Self::RefundBoost,
+            InstructionType::GetOptionCapacity => Self::GetOptionCapacity(read_payload(data)?),
+            InstructionType::ClaimSlot => Self::ClaimSlot(
+                read_payload_with(data, |reader| ClaimSlotInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::CommitDraw => Self::CommitDraw,
+            InstructionType::RevealDraw => Self::RevealDraw,
+            InstructionType::ClaimDraw => Self::ClaimDraw,
+            InstructionType::FinalizeVoting => Self::FinalizeVoting,
+            InstructionType::SetExecutionAllowlist => Self::SetExecutionAllowlist(read_payload(data)?),
+            InstructionType::AddProposalInstruction => Self::AddProposalInstruction(read_payload(data)?),
+            InstructionType::ExecuteNextInstruction => Self::ExecuteNextInstruction,
+            InstructionType::EnableTokenBallots => Self::EnableTokenBallots(read_payload(data)?),
+            InstructionType::CountTokenBallots => Self::CountTokenBallots,
+            InstructionType::StartRecount => Self::StartRecount,
+            InstructionType::RecountVotes => Self::RecountVotes,
+            InstructionType::CompleteRecount => Self::CompleteRecount,
+            InstructionType::SetMaxActiveVotings => Self::SetMaxActiveVotings(read_payload(data)?),
+            InstructionType::SetStartTimeTolerance => Self::SetStartTimeTolerance(read_payload(data)?),
+            InstructionType::SetVotingMetadata => Self::SetVotingMetadata(read_payload(data)?),
+            InstructionType::UpgradeProgram => Self::UpgradeProgram,
+            InstructionType::SetUpgradeAuthority => Self::SetUpgradeAuthority,
+            InstructionType::GetVoteWeight => Self::GetVoteWeight(read_payload(data)?),
+            InstructionType::AppendAllowlistChunk => Self::AppendAllowlistChunk(read_payload(data)?),
+            InstructionType::SealAllowlist => Self::SealAllowlist,
+            InstructionType::SetAllowlistRoot => Self::SetAllowlistRoot(read_payload(data)?),
+            InstructionType::AllocateVoteBucket => Self::AllocateVoteBucket(read_payload(data)?),
+            InstructionType::ReserveTitle => Self::ReserveTitle(read_payload(data)?),
+            InstructionType::PublishVoting => Self::PublishVoting(read_payload(data)?),
+            InstructionType::CastEnvelope => Self::CastEnvelope(
+                read_payload_with(data, |reader| CastEnvelopeInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::SubmitEnvelopeResults => Self::SubmitEnvelopeResults(read_payload(data)?),
+            InstructionType::CommitTieBreak => Self::CommitTieBreak,
+            InstructionType::RevealTieBreak => Self::RevealTieBreak,
+            InstructionType::SetProposalTemplate => Self::SetProposalTemplate(read_payload(data)?),
+            InstructionType::AddTemplateInstruction => Self::AddTemplateInstruction(read_payload(data)?),
+            InstructionType::CloneVoting => Self::CloneVoting(read_payload(data)?),
+            InstructionType::ArchiveVoting => Self::ArchiveVoting,
+            InstructionType::MerkleizeRecords => Self::MerkleizeRecords,
+            InstructionType::VerifyBallotProof => Self::VerifyBallotProof(read_payload(data)?),
+            InstructionType::FlagVoting => Self::FlagVoting,
+            InstructionType::CloseFlag => Self::CloseFlag,
+            InstructionType::HideVoting => Self::HideVoting(read_payload(data)?),
+            InstructionType::FundIncentives => Self::FundIncentives(read_payload(data)?),
+            InstructionType::WithdrawIncentives => Self::WithdrawIncentives,
+            InstructionType::GetResults => Self::GetResults,
+            InstructionType::SetLocalizedMetadata => Self::SetLocalizedMetadata(read_payload(data)?),
+            InstructionType::SetCreationDeposit => Self::SetCreationDeposit(read_payload(data)?),
+            InstructionType::GcExpired => Self::GcExpired,
+            InstructionType::SyncStatus => Self::SyncStatus,
+            InstructionType::AttestResult => Self::AttestResult,
+            InstructionType::GetPercentages => Self::GetPercentages(read_payload(data)?),
+            InstructionType::CancelVoting => Self::CancelVoting,
+            InstructionType::RestoreVoting => Self::RestoreVoting,
+            InstructionType::AllocateTallyShard => Self::AllocateTallyShard(read_payload(data)?),
+            InstructionType::CastShardedVote => Self::CastShardedVote(
+                read_payload_with(data, |reader| CastShardedVoteInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::MergeTallyShards => Self::MergeTallyShards,
+            InstructionType::AddWeight => Self::AddWeight(
+                read_payload_with(data, |reader| AddWeightInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::LinkProposalNote => Self::LinkProposalNote(read_payload(data)?),
+            InstructionType::VoteByTransferClaim => Self::VoteByTransferClaim(
+                read_payload_with(data, |reader| VoteByTransferClaimInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::WithdrawKiosk => Self::WithdrawKiosk,
+            InstructionType::CloseUserVote => Self::CloseUserVote,
+            InstructionType::CloseVoting => Self::CloseVoting,
+            InstructionType::ClaimFaucet => Self::ClaimFaucet,
+            InstructionType::GetCreatorSummary => Self::GetCreatorSummary,
+            InstructionType::CommitVote => Self::CommitVote(
+                read_payload_with(data, |reader| CommitVoteInstruction::read(reader, payload_version))?
+            ),
+            InstructionType::RevealVote => Self::RevealVote(read_payload(data)?),
+            InstructionType::CreateElection => Self::CreateElection(read_payload(data)?),
+            InstructionType::FinalizeElection => Self::FinalizeElection,
+            InstructionType::ExtendVoting => Self::ExtendVoting(read_payload(data)?),
+            InstructionType::SealVoterRoll => Self::SealVoterRoll(read_payload(data)?),
+            InstructionType::FreezeVoting => Self::FreezeVoting,
+            InstructionType::UnfreezeVoting => Self::UnfreezeVoting,
+            InstructionType::SetLateRegistration => Self::SetLateRegistration(read_payload(data)?),
+            InstructionType::UpdateTitle => Self::UpdateTitle(read_payload(data)?),
+            InstructionType::MigrateAccount => Self::MigrateAccount,
+            InstructionType::SetNotificationTarget => Self::SetNotificationTarget(read_payload(data)?),
+            InstructionType::SyncPollCard => Self::SyncPollCard
+        };
+
+        Ok(instruction)
+    }
+
+    // Bit of the instruction in `ConfigAccount::disabled_instructions`, config and read-only instructions can't be disabled.
+    // Neither can `FreezeVoting`: it stops ballots during an incident, which is when the admin disables instructions.
+    pub(crate) fn flag(&self) -> Option<u128> {
+        match self {
+            Self::CreateVoting(_) => Some(CREATE_VOTING_FLAG),
+            Self::Vote(_) => Some(VOTE_FLAG),
+            Self::UpdateVote(_) => Some(UPDATE_VOTE_FLAG),
+            Self::EditVoting(_) => Some(EDIT_VOTING_FLAG),
+            Self::AddOption(_) => Some(ADD_OPTION_FLAG),
+            Self::RemoveOption(_) => Some(REMOVE_OPTION_FLAG),
+            Self::SetOptionOrder(_) => Some(SET_OPTION_ORDER_FLAG),
+            Self::SnapshotTally => Some(SNAPSHOT_TALLY_FLAG),
+            Self::ClaimCertificate => Some(CLAIM_CERTIFICATE_FLAG),
+            Self::FundRewards(_) => Some(FUND_REWARDS_FLAG),
+            Self::ClaimWinnings => Some(CLAIM_WINNINGS_FLAG),
+            Self::TopUpRent => Some(TOP_UP_RENT_FLAG),
+            Self::CreateRealm(_) => Some(CREATE_REALM_FLAG),
+            Self::UpdateRealm(_) => Some(UPDATE_REALM_FLAG),
+            Self::CreateRealmVoting(_) => Some(CREATE_REALM_VOTING_FLAG),
+            Self::JoinRealm => Some(JOIN_REALM_FLAG),
+            Self::LeaveRealm => Some(LEAVE_REALM_FLAG),
+            Self::Boost(_) => Some(BOOST_FLAG),
+            Self::ReleaseBoost => Some(RELEASE_BOOST_FLAG),
+            Self::RefundBoost => Some(REFUND_BOOST_FLAG),
+            Self::ClaimSlot(_) => Some(CLAIM_SLOT_FLAG),
+            Self::CommitDraw => Some(COMMIT_DRAW_FLAG),
+            Self::RevealDraw => Some(REVEAL_DRAW_FLAG),
+            Self::ClaimDraw => Some(CLAIM_DRAW_FLAG),
+            Self::FinalizeVoting => Some(FINALIZE_VOTING_FLAG),
+            Self::SetExecutionAllowlist(_) => Some(SET_EXECUTION_ALLOWLIST_FLAG),
+            Self::AddProposalInstruction(_) => Some(ADD_PROPOSAL_INSTRUCTION_FLAG),
+            Self::ExecuteNextInstruction => Some(EXECUTE_NEXT_INSTRUCTION_FLAG),
+            Self::EnableTokenBallots(_) => Some(ENABLE_TOKEN_BALLOTS_FLAG),
+            Self::CountTokenBallots => Some(COUNT_TOKEN_BALLOTS_FLAG),
+            Self::StartRecount => Some(START_RECOUNT_FLAG),
+            Self::RecountVotes => Some(RECOUNT_VOTES_FLAG),
+            Self::CompleteRecount => Some(COMPLETE_RECOUNT_FLAG),
+            Self::SetVotingMetadata(_) => Some(SET_VOTING_METADATA_FLAG),
+            Self::AppendAllowlistChunk(_) => Some(APPEND_ALLOWLIST_CHUNK_FLAG),
+            Self::SealAllowlist => Some(SEAL_ALLOWLIST_FLAG),
+            Self::SetAllowlistRoot(_) => Some(SET_ALLOWLIST_ROOT_FLAG),
+            Self::AllocateVoteBucket(_) => Some(ALLOCATE_VOTE_BUCKET_FLAG),
+            Self::ReserveTitle(_) => Some(RESERVE_TITLE_FLAG),
+            Self::PublishVoting(_) => Some(PUBLISH_VOTING_FLAG),
+            Self::CastEnvelope(_) => Some(CAST_ENVELOPE_FLAG),
+            Self::SubmitEnvelopeResults(_) => Some(SUBMIT_ENVELOPE_RESULTS_FLAG),
+            Self::CommitTieBreak => Some(COMMIT_TIE_BREAK_FLAG),
+            Self::RevealTieBreak => Some(REVEAL_TIE_BREAK_FLAG),
+            Self::SetProposalTemplate(_) => Some(SET_PROPOSAL_TEMPLATE_FLAG),
+            Self::AddTemplateInstruction(_) => Some(ADD_TEMPLATE_INSTRUCTION_FLAG),
+            Self::CloneVoting(_) => Some(CLONE_VOTING_FLAG),
+            Self::ArchiveVoting => Some(ARCHIVE_VOTING_FLAG),
+            Self::MerkleizeRecords => Some(MERKLEIZE_RECORDS_FLAG),
+            Self::FlagVoting => Some(FLAG_VOTING_FLAG),
+            Self::FundIncentives(_) => Some(FUND_INCENTIVES_FLAG),
+            Self::SetLocalizedMetadata(_) => Some(SET_LOCALIZED_METADATA_FLAG),
+            Self::GcExpired => Some(GC_EXPIRED_FLAG),
+            Self::AttestResult => Some(ATTEST_RESULT_FLAG),
+            Self::CancelVoting => Some(CANCEL_VOTING_FLAG),
+            Self::RestoreVoting => Some(RESTORE_VOTING_FLAG),
+            Self::AllocateTallyShard(_) => Some(ALLOCATE_TALLY_SHARD_FLAG),
+            Self::CastShardedVote(_) => Some(CAST_SHARDED_VOTE_FLAG),
+            Self::MergeTallyShards => Some(MERGE_TALLY_SHARDS_FLAG),
+            Self::AddWeight(_) => Some(ADD_WEIGHT_FLAG),
+            Self::LinkProposalNote(_) => Some(LINK_PROPOSAL_NOTE_FLAG),
+            Self::VoteByTransferClaim(_) => Some(VOTE_BY_TRANSFER_CLAIM_FLAG),
+            Self::WithdrawKiosk => Some(WITHDRAW_KIOSK_FLAG),
+            Self::CloseUserVote => Some(CLOSE_USER_VOTE_FLAG),
+            Self::CloseVoting => Some(CLOSE_VOTING_FLAG),
+            Self::ClaimFaucet => Some(CLAIM_FAUCET_FLAG),
+            Self::CommitVote(_) => Some(COMMIT_VOTE_FLAG),
+            Self::RevealVote(_) => Some(REVEAL_VOTE_FLAG),
+            Self::CreateElection(_) => Some(CREATE_ELECTION_FLAG),
+            Self::FinalizeElection => Some(FINALIZE_ELECTION_FLAG),
+            Self::ExtendVoting(_) => Some(EXTEND_VOTING_FLAG),
+            Self::SealVoterRoll(_) => Some(SEAL_VOTER_ROLL_FLAG),
+            Self::SetLateRegistration(_) => Some(SET_LATE_REGISTRATION_FLAG),
+            Self::UpdateTitle(_) => Some(UPDATE_TITLE_FLAG),
+            Self::SetNotificationTarget(_) => Some(SET_NOTIFICATION_TARGET_FLAG),
+            Self::WithdrawIncentives => Some(WITHDRAW_INCENTIVES_FLAG),
+            Self::CloseFlag => Some(CLOSE_FLAG_FLAG),
+            Self::SyncStatus => Some(SYNC_STATUS_FLAG),
+            Self::UnfreezeVoting => Some(UNFREEZE_VOTING_FLAG),
+            Self::MigrateAccount => Some(MIGRATE_ACCOUNT_FLAG),
+            Self::SyncPollCard => Some(SYNC_POLL_CARD_FLAG),
+            Self::InitializeConfig
+            | Self::SetInstructionFlags(_)
+            | Self::ProposeAdmin(_)
+            | Self::AcceptAdmin
+            | Self::GetMyVote
+            | Self::GetOptionCapacity(_)
+            | Self::SetMaxActiveVotings(_)
+            | Self::SetStartTimeTolerance(_)
+            | Self::UpgradeProgram
+            | Self::SetUpgradeAuthority
+            | Self::GetVoteWeight(_)
+            | Self::VerifyBallotProof(_)
+            | Self::HideVoting(_)
+            | Self::GetResults
+            | Self::SetCreationDeposit(_)
+            | Self::GetPercentages(_)
+            | Self::GetCreatorSummary
+            | Self::FreezeVoting => None
+        }
+    }
+}
+
+// Events are logged with `sol_log_data` as their tag, their version as a single byte and the borsh encoded
+// event. A new version only appends fields, `decoder::EventDecoder` reads every version logged so far.
+
+// Logged by the instructions counting a new ballot
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TurnoutMilestoneEvent {
+    pub voting: Pubkey,
+    pub milestone: TurnoutMilestone,
+    pub total_voters: u64,
+    pub electorate: u64
+}
+
+pub const TURNOUT_EVENT_TAG: &[u8] = b"turnout_milestone";
+pub const TURNOUT_EVENT_VERSION: u8 = 1;
+
+// Logged by `cast_sharded_vote`, so operators can spot shards taking more than their share of the
+// ballots and pick the shard count of later votings from it
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ShardVoteEvent {
+    pub voting: Pubkey,
+    pub shard: u8,
+    pub tally_shards: u8,
+    // Counters of the shard after the ballot
+    pub shard_voters: u64,
+    pub shard_updates: u64,
+    // The ballot replaced the voter's earlier one
+    pub update: bool
+}
+
+pub const SHARD_VOTE_EVENT_TAG: &[u8] = b"shard_vote";
+pub const SHARD_VOTE_EVENT_VERSION: u8 = 1;
+
+// Logged when the voting account is created, drafts included
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingCreatedEvent {
+    pub voting: Pubkey,
+    pub creator: Pubkey,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub timestamp: u64
+}
+
+pub const VOTING_CREATED_EVENT_TAG: &[u8] = b"voting_created";
+pub const VOTING_CREATED_EVENT_VERSION: u8 = 1;
+
+// Logged once a new ballot is counted, committed ballots when they're revealed. `choice` is the first
+// option of the ballot, `None` for spoiled and sealed ballots and while the voting hides its tallies.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoteCastEvent {
+    pub voting: Pubkey,
+    pub voter: Pubkey,
+    pub choice: Option<u8>,
+    pub weight: u64,
+    pub timestamp: u64
+}
+
+pub const VOTE_CAST_EVENT_TAG: &[u8] = b"vote_cast";
+pub const VOTE_CAST_EVENT_VERSION: u8 = 1;
+
+// Logged when a voter replaces their ballot, `choice` as for `VoteCastEvent`
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoteUpdatedEvent {
+    pub voting: Pubkey,
+    pub voter: Pubkey,
+    pub choice: Option<u8>,
+    pub timestamp: u64
+}
+
+pub const VOTE_UPDATED_EVENT_TAG: &[u8] = b"vote_updated";
+pub const VOTE_UPDATED_EVENT_VERSION: u8 = 1;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingFinalizedEvent {
+    pub voting: Pubkey,
+    pub winning_option: Option<u8>,
+    pub total_voters: u64,
+    pub timestamp: u64
+}
+
+pub const VOTING_FINALIZED_EVENT_TAG: &[u8] = b"voting_finalized";
+pub const VOTING_FINALIZED_EVENT_VERSION: u8 = 1;
+
+// Logged by `freeze_voting` and `unfreeze_voting`, the audit trail of a voting's freezes
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingFreezeEvent {
+    pub voting: Pubkey,
+    // The creator, the program admin or the realm admin
+    pub authority: Pubkey,
+    pub frozen: bool,
+    pub timestamp: u64
+}
+
+pub const VOTING_FREEZE_EVENT_TAG: &[u8] = b"voting_freeze";
+pub const VOTING_FREEZE_EVENT_VERSION: u8 = 1;
+
+// Logged by `set_notification_target`, both `None` once the target is removed. Notifiers pick up new
+// targets from it without polling the accounts.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NotificationTargetEvent {
+    pub voting: Pubkey,
+    pub creator: Pubkey,
+    pub webhook_commitment: Option<[u8; 32]>,
+    pub notify: Option<Pubkey>,
+    pub timestamp: u64
+}
+
+pub const NOTIFICATION_TARGET_EVENT_TAG: &[u8] = b"notification_target";
+pub const NOTIFICATION_TARGET_EVENT_VERSION: u8 = 1;
+
+// Returned by `get_my_vote`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MyVoteReturnData {
+    pub choice: u8,
+    pub weight: u64,
+    pub first_voted_at: u64,
+    pub last_time_voted: u64,
+    pub times_updated: u16
+}
+
+// Returned by `get_option_capacity`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OptionCapacityReturnData {
+    pub cap: u64,
+    pub tally: u128,
+    // `None` for an uncapped option
+    pub remaining: Option<u64>
+}
+
+// Returned by `get_vote_weight`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoteWeightReturnData {
+    pub weight: u64
+}
+
+// Returned by dry runs of the ballot instructions and `add_weight`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotePreviewReturnData {
+    pub weight: u64,
+    // The tallies once the ballot is counted, empty while they are hidden
+    pub tallies: Vec<u128>
+}
+
+// Returned by `get_results`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResultsReturnData {
+    pub tallies: Vec<u128>,
+    pub total_voters: u64,
+    // The winner as of now, `None` without one
+    pub winning_option: Option<u8>,
+    // The winners as of now, the recorded ones once the voting is finalized. Best first.
+    pub winners: Vec<u8>,
+    // Same, empty without an apportionment
+    pub seats: Vec<u16>,
+    // As of now, the recorded one once the voting is finalized
+    pub outcome: VotingOutcome,
+    // Counted in `total_voters`, see `TallyState::spoiled`
+    pub spoiled_ballots: u64,
+    // Weight of the spoiled ballots, see `TallyState::spoiled_weight`
+    pub spoiled_weight: u128,
+    // See `VoteMainAccount::turnout_bps`
+    pub turnout_bps: Option<u16>
+}
+
+// Returned by `get_percentages`, see `tally::percentages` for the rounding
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PercentagesReturnData {
+    pub decimals: u8,
+    // In option order, adding up to 100 * 10^decimals once anything was counted
+    pub percentages: Vec<u64>
+}
+
+// Returned by `get_creator_summary`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CreatorSummaryReturnData {
+    pub creator: Pubkey,
+    pub active_votings: u32,
+    // Finalized or cancelled, votings swept by `gc_expired` before finalizing aren't counted
+    pub ended_votings: u32,
+    pub finalized_votings: u32,
+    pub cancelled_votings: u32,
+    pub created_votings: u32,
+    // Over finalized votings
+    pub total_voters: u64,
+    pub flags: u32
+}
+
+// Instruction flags, a set bit in `ConfigAccount::disabled_instructions` disables the instruction
+pub const CREATE_VOTING_FLAG: u128 = 1 << 0;
+pub const VOTE_FLAG: u128 = 1 << 1;
+
+pub const UPDATE_VOTE_FLAG: u128 = 1 << 2;
+pub const EDIT_VOTING_FLAG: u128 = 1 << 3;
+
+pub const ADD_OPTION_FLAG: u128 = 1 << 4;
+pub const REMOVE_OPTION_FLAG: u128 = 1 << 5;
+
+pub const SET_OPTION_ORDER_FLAG: u128 = 1 << 6;
+pub const SNAPSHOT_TALLY_FLAG: u128 = 1 << 7;
+
+pub const CLAIM_CERTIFICATE_FLAG: u128 = 1 << 8;
+pub const FUND_REWARDS_FLAG: u128 = 1 << 9;
+
+pub const CLAIM_WINNINGS_FLAG: u128 = 1 << 10;
+pub const TOP_UP_RENT_FLAG: u128 = 1 << 11;
+
+pub const CREATE_REALM_FLAG: u128 = 1 << 12;
+pub const UPDATE_REALM_FLAG: u128 = 1 << 13;
+
+pub const CREATE_REALM_VOTING_FLAG: u128 = 1 << 14;
+pub const JOIN_REALM_FLAG: u128 = 1 << 15;
+
+pub const LEAVE_REALM_FLAG: u128 = 1 << 16;
+pub const BOOST_FLAG: u128 = 1 << 17;
+
+pub const RELEASE_BOOST_FLAG: u128 = 1 << 18;
+pub const REFUND_BOOST_FLAG: u128 = 1 << 19;
+
+pub const CLAIM_SLOT_FLAG: u128 = 1 << 20;
+pub const COMMIT_DRAW_FLAG: u128 = 1 << 21;
+
+pub const REVEAL_DRAW_FLAG: u128 = 1 << 22;
+pub const CLAIM_DRAW_FLAG: u128 = 1 << 23;
+
+pub const FINALIZE_VOTING_FLAG: u128 = 1 << 24;
+pub const SET_EXECUTION_ALLOWLIST_FLAG: u128 = 1 << 25;
+
+pub const ADD_PROPOSAL_INSTRUCTION_FLAG: u128 = 1 << 26;
+pub const EXECUTE_NEXT_INSTRUCTION_FLAG: u128 = 1 << 27;
+
+pub const ENABLE_TOKEN_BALLOTS_FLAG: u128 = 1 << 28;
+pub const COUNT_TOKEN_BALLOTS_FLAG: u128 = 1 << 29;
+
+pub const START_RECOUNT_FLAG: u128 = 1 << 30;
+pub const RECOUNT_VOTES_FLAG: u128 = 1 << 31;
+
+pub const COMPLETE_RECOUNT_FLAG: u128 = 1 << 32;
+pub const SET_VOTING_METADATA_FLAG: u128 = 1 << 33;
+
+pub const APPEND_ALLOWLIST_CHUNK_FLAG: u128 = 1 << 34;
+pub const SEAL_ALLOWLIST_FLAG: u128 = 1 << 35;
+
+pub const SET_ALLOWLIST_ROOT_FLAG: u128 = 1 << 36;
+pub const ALLOCATE_VOTE_BUCKET_FLAG: u128 = 1 << 37;
+
+pub const RESERVE_TITLE_FLAG: u128 = 1 << 38;
+pub const PUBLISH_VOTING_FLAG: u128 = 1 << 39;
+
+pub const CAST_ENVELOPE_FLAG: u128 = 1 << 40;
+pub const SUBMIT_ENVELOPE_RESULTS_FLAG: u128 = 1 << 41;
+
+pub const COMMIT_TIE_BREAK_FLAG: u128 = 1 << 42;
+pub const REVEAL_TIE_BREAK_FLAG: u128 = 1 << 43;
+
+pub const SET_PROPOSAL_TEMPLATE_FLAG: u128 = 1 << 44;
+pub const ADD_TEMPLATE_INSTRUCTION_FLAG: u128 = 1 << 45;
+
+pub const CLONE_VOTING_FLAG: u128 = 1 << 46;
+pub const ARCHIVE_VOTING_FLAG: u128 = 1 << 47;
+
+pub const MERKLEIZE_RECORDS_FLAG: u128 = 1 << 48;
+pub const FLAG_VOTING_FLAG: u128 = 1 << 49;
+
+pub const FUND_INCENTIVES_FLAG: u128 = 1 << 50;
+pub const SET_LOCALIZED_METADATA_FLAG: u128 = 1 << 51;
+
+pub const GC_EXPIRED_FLAG: u128 = 1 << 52;
+pub const ATTEST_RESULT_FLAG: u128 = 1 << 53;
+
+pub const CANCEL_VOTING_FLAG: u128 = 1 << 54;
+pub const RESTORE_VOTING_FLAG: u128 = 1 << 55;
+
+pub const ALLOCATE_TALLY_SHARD_FLAG: u128 = 1 << 56;
+pub const CAST_SHARDED_VOTE_FLAG: u128 = 1 << 57;
+
+pub const MERGE_TALLY_SHARDS_FLAG: u128 = 1 << 58;
+pub const ADD_WEIGHT_FLAG: u128 = 1 << 59;
+
+pub const LINK_PROPOSAL_NOTE_FLAG: u128 = 1 << 60;
+pub const VOTE_BY_TRANSFER_CLAIM_FLAG: u128 = 1 << 61;
+
+pub const WITHDRAW_KIOSK_FLAG: u128 = 1 << 62;
+pub const CLOSE_USER_VOTE_FLAG: u128 = 1 << 63;
+
+pub const CLOSE_VOTING_FLAG: u128 = 1 << 64;
+pub const CLAIM_FAUCET_FLAG: u128 = 1 << 65;
+
+pub const COMMIT_VOTE_FLAG: u128 = 1 << 66;
+pub const REVEAL_VOTE_FLAG: u128 = 1 << 67;
+
+pub const CREATE_ELECTION_FLAG: u128 = 1 << 68;
+pub const FINALIZE_ELECTION_FLAG: u128 = 1 << 69;
+pub const EXTEND_VOTING_FLAG: u128 = 1 << 70;
+pub const SEAL_VOTER_ROLL_FLAG: u128 = 1 << 71;
+pub const SET_LATE_REGISTRATION_FLAG: u128 = 1 << 72;
+pub const UPDATE_TITLE_FLAG: u128 = 1 << 73;
+pub const SET_NOTIFICATION_TARGET_FLAG: u128 = 1 << 74;
+pub const WITHDRAW_INCENTIVES_FLAG: u128 = 1 << 75;
+pub const CLOSE_FLAG_FLAG: u128 = 1 << 76;
+pub const SYNC_STATUS_FLAG: u128 = 1 << 77;
+pub const UNFREEZE_VOTING_FLAG: u128 = 1 << 78;
+pub const MIGRATE_ACCOUNT_FLAG: u128 = 1 << 79;
+pub const SYNC_POLL_CARD_FLAG: u128 = 1 << 80;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u64)]
+pub enum InstructionType {
+    CreateVoting = u64::from_le_bytes([68, 240, 219, 178, 99, 147, 42, 139]),
+    Vote = u64::from_le_bytes([156, 177, 116, 223, 171, 21, 181, 52]),
+    UpdateVote = u64::from_le_bytes([63, 162, 103, 31, 90, 173, 26, 118]),
+    EditVoting = u64::from_le_bytes([6, 157, 209, 70, 133, 193, 95, 119]),
+    AddOption = u64::from_le_bytes([226, 248, 62, 203, 159, 242, 102, 125]),
+    RemoveOption = u64::from_le_bytes([76, 132, 111, 103, 113, 26, 145, 188]),
+    SetOptionOrder = u64::from_le_bytes([145, 5, 245, 32, 64, 247, 219, 34]),
+    InitializeConfig = u64::from_le_bytes([177, 2, 150, 2, 206, 117, 96, 170]),
+    SetInstructionFlags = u64::from_le_bytes([251, 111, 159, 69, 223, 124, 147, 137]),
+    ProposeAdmin = u64::from_le_bytes([125, 227, 142, 122, 135, 210, 68, 224]),
+    AcceptAdmin = u64::from_le_bytes([131, 40, 89, 238, 225, 51, 71, 164]),
+    GetMyVote = u64::from_le_bytes([228, 249, 155, 105, 61, 15, 212, 246]),
+    SnapshotTally = u64::from_le_bytes([50, 62, 97, 40, 219, 2, 130, 190]),
+    ClaimCertificate = u64::from_le_bytes([145, 30, 149, 55, 166, 173, 143, 236]),
+    FundRewards = u64::from_le_bytes([192, 183, 90, 13, 128, 172, 176, 188]),
+    ClaimWinnings = u64::from_le_bytes([181, 196, 232, 174, 61, 151, 132, 161]),
+    TopUpRent = u64::from_le_bytes([98, 2, 241, 31, 114, 93, 240, 137]),
+    CreateRealm = u64::from_le_bytes([246, 214, 217, 124, 161, 108, 69, 72]),
+    UpdateRealm = u64::from_le_bytes([61, 147, 71, 137, 30, 31, 8, 124]),
+    CreateRealmVoting = u64::from_le_bytes([122, 227, 27, 45, 44, 10, 208, 86]),
+    JoinRealm = u64::from_le_bytes([41, 172, 58, 123, 186, 109, 112, 66]),
+    LeaveRealm = u64::from_le_bytes([231, 218, 22, 188, 160, 25, 211, 77]),
+    Boost = u64::from_le_bytes([83, 193, 234, 184, 99, 186, 216, 246]),
+    ReleaseBoost = u64::from_le_bytes([141, 17, 214, 196, 167, 160, 250, 216]),
+    RefundBoost = u64::from_le_bytes([164, 22, 185, 57, 53, 11, 221, 133]),
+    GetOptionCapacity = u64::from_le_bytes([249, 225, 231, 60, 135, 173, 82, 124]),
+    ClaimSlot = u64::from_le_bytes([174, 240, 61, 186, 127, 87, 156, 119]),
+    CommitDraw = u64::from_le_bytes([56, 200, 171, 173, 252, 34, 177, 61]),
+    RevealDraw = u64::from_le_bytes([15, 131, 116, 89, 128, 148, 8, 199]),
+    ClaimDraw = u64::from_le_bytes([7, 23, 242, 16, 186, 234, 96, 12]),
+    FinalizeVoting = u64::from_le_bytes([145, 125, 187, 15, 45, 117, 111, 61]),
+    SetExecutionAllowlist = u64::from_le_bytes([92, 44, 126, 195, 126, 139, 32, 78]),
+    AddProposalInstruction = u64::from_le_bytes([147, 110, 116, 16, 219, 168, 65, 101]),
+    ExecuteNextInstruction = u64::from_le_bytes([164, 37, 2, 71, 123, 184, 24, 2]),
+    EnableTokenBallots = u64::from_le_bytes([43, 255, 143, 88, 198, 38, 171, 202]),
+    CountTokenBallots = u64::from_le_bytes([2, 111, 164, 53, 211, 17, 43, 3]),
+    StartRecount = u64::from_le_bytes([197, 226, 32, 158, 15, 238, 159, 33]),
+    RecountVotes = u64::from_le_bytes([212, 254, 231, 3, 94, 196, 137, 204]),
+    CompleteRecount = u64::from_le_bytes([241, 81, 198, 205, 247, 76, 137, 218]),
+    SetMaxActiveVotings = u64::from_le_bytes([28, 155, 18, 137, 25, 91, 111, 234]),
+    SetStartTimeTolerance = u64::from_le_bytes([200, 13, 127, 246, 98, 199, 21, 205]),
+    SetVotingMetadata = u64::from_le_bytes([184, 243, 63, 70, 111, 240, 136, 249]),
+    UpgradeProgram = u64::from_le_bytes([177, 92, 230, 34, 107, 61, 111, 244]),
+    SetUpgradeAuthority = u64::from_le_bytes([15, 88, 245, 97, 1, 157, 175, 95]),
+    GetVoteWeight = u64::from_le_bytes([121, 32, 33, 204, 14, 166, 116, 33]),
+    AppendAllowlistChunk = u64::from_le_bytes([94, 119, 14, 238, 174, 243, 127, 30]),
+    SealAllowlist = u64::from_le_bytes([188, 205, 122, 209, 45, 27, 5, 119]),
+    SetAllowlistRoot = u64::from_le_bytes([6, 243, 185, 151, 52, 45, 98, 131]),
+    AllocateVoteBucket = u64::from_le_bytes([18, 193, 164, 53, 174, 74, 6, 190]),
+    ReserveTitle = u64::from_le_bytes([239, 158, 173, 245, 16, 181, 16, 63]),
+    PublishVoting = u64::from_le_bytes([255, 80, 28, 43, 227, 160, 247, 173]),
+    CastEnvelope = u64::from_le_bytes([147, 129, 174, 196, 31, 6, 198, 156]),
+    SubmitEnvelopeResults = u64::from_le_bytes([157, 107, 169, 115, 43, 79, 116, 103]),
+    CommitTieBreak = u64::from_le_bytes([116, 194, 167, 230, 234, 2, 240, 123]),
+    RevealTieBreak = u64::from_le_bytes([235, 20, 57, 33, 147, 126, 238, 195]),
+    SetProposalTemplate = u64::from_le_bytes([239, 216, 84, 122, 243, 210, 33, 212]),
+    AddTemplateInstruction = u64::from_le_bytes([5, 107, 141, 229, 183, 239, 59, 247]),
+    CloneVoting = u64::from_le_bytes([151, 22, 224, 49, 99, 213, 239, 73]),
+    ArchiveVoting = u64::from_le_bytes([50, 157, 75, 101, 92, 185, 165, 208]),
+    MerkleizeRecords = u64::from_le_bytes([19, 236, 23, 177, 28, 45, 43, 111]),
+    VerifyBallotProof = u64::from_le_bytes([222, 203, 203, 202, 189, 99, 167, 77]),
+    FlagVoting = u64::from_le_bytes([220, 173, 48, 147, 51, 145, 240, 12]),
+    CloseFlag = u64::from_le_bytes([219, 114, 5, 207, 85, 171, 34, 162]),
+    HideVoting = u64::from_le_bytes([223, 95, 251, 106, 175, 75, 204, 61]),
+    FundIncentives = u64::from_le_bytes([238, 125, 201, 142, 128, 137, 249, 147]),
+    WithdrawIncentives = u64::from_le_bytes([221, 228, 62, 251, 7, 126, 190, 57]),
+    GetResults = u64::from_le_bytes([78, 147, 115, 114, 235, 207, 205, 58]),
+    SetLocalizedMetadata = u64::from_le_bytes([174, 22, 189, 57, 28, 92, 158, 69]),
+    SetCreationDeposit = u64::from_le_bytes([27, 139, 143, 163, 17, 215, 4, 3]),
+    GcExpired = u64::from_le_bytes([60, 177, 15, 152, 99, 91, 202, 146]),
+    SyncStatus = u64::from_le_bytes([194, 166, 98, 195, 98, 207, 36, 23]),
+    AttestResult = u64::from_le_bytes([239, 92, 45, 137, 126, 132, 17, 52]),
+    GetPercentages = u64::from_le_bytes([202, 42, 225, 94, 48, 43, 102, 201]),
+    CancelVoting = u64::from_le_bytes([225, 13, 60, 104, 27, 70, 211, 119]),
+    RestoreVoting = u64::from_le_bytes([156, 130, 226, 189, 196, 195, 179, 94]),
+    AllocateTallyShard = u64::from_le_bytes([104, 136, 63, 244, 237, 123, 101, 78]),
+    CastShardedVote = u64::from_le_bytes([146, 112, 148, 175, 148, 164, 242, 250]),
+    MergeTallyShards = u64::from_le_bytes([20, 145, 66, 128, 229, 167, 29, 138]),
+    AddWeight = u64::from_le_bytes([24, 152, 192, 47, 117, 215, 33, 12]),
+    LinkProposalNote = u64::from_le_bytes([107, 182, 185, 222, 7, 250, 228, 152]),
+    VoteByTransferClaim = u64::from_le_bytes([41, 255, 248, 29, 229, 191, 161, 111]),
+    WithdrawKiosk = u64::from_le_bytes([15, 131, 193, 244, 191, 177, 180, 61]),
+    CloseUserVote = u64::from_le_bytes([102, 251, 157, 228, 187, 41, 123, 3]),
+    CloseVoting = u64::from_le_bytes([134, 34, 188, 143, 182, 1, 37, 135]),
+    ClaimFaucet = u64::from_le_bytes([28, 169, 237, 27, 27, 57, 249, 179]),
+    GetCreatorSummary = u64::from_le_bytes([20, 122, 115, 46, 157, 170, 28, 70]),
+    CommitVote = u64::from_le_bytes([184, 90, 115, 15, 171, 76, 66, 135]),
+    RevealVote = u64::from_le_bytes([27, 81, 15, 55, 10, 138, 105, 133]),
+    CreateElection = u64::from_le_bytes([184, 193, 203, 219, 106, 202, 145, 82]),
+    FinalizeElection = u64::from_le_bytes([186, 25, 220, 35, 104, 77, 152, 194]),
+    ExtendVoting = u64::from_le_bytes([60, 238, 144, 165, 55, 180, 83, 246]),
+    SealVoterRoll = u64::from_le_bytes([205, 175, 205, 55, 18, 231, 93, 10]),
+    FreezeVoting = u64::from_le_bytes([90, 207, 0, 160, 212, 57, 166, 232]),
+    UnfreezeVoting = u64::from_le_bytes([133, 94, 66, 62, 49, 136, 97, 12]),
+    SetLateRegistration = u64::from_le_bytes([201, 143, 224, 36, 217, 40, 50, 40]),
+    UpdateTitle = u64::from_le_bytes([225, 173, 144, 74, 183, 192, 79, 188]),
+    MigrateAccount = u64::from_le_bytes([159, 238, 75, 98, 177, 188, 13, 202]),
+    SetNotificationTarget = u64::from_le_bytes([20, 141, 129, 61, 0, 40, 54, 168]),
+    SyncPollCard = u64::from_le_bytes([165, 109, 234, 237, 91, 144, 183, 121])
+}
+
+impl InstructionType {
+    pub const ALL: [Self; 99] = [
+        Self::CreateVoting,
+        Self::Vote,
+        Self::UpdateVote,
+        Self::EditVoting,
+        Self::AddOption,
+        Self::RemoveOption,
+        Self::SetOptionOrder,
+        Self::InitializeConfig,
+        Self::SetInstructionFlags,
+        Self::ProposeAdmin,
+        Self::AcceptAdmin,
+        Self::GetMyVote,
+        Self::SnapshotTally,
+        Self::ClaimCertificate,
+        Self::FundRewards,
+        Self::ClaimWinnings,
+        Self::TopUpRent,
+        Self::CreateRealm,
+        Self::UpdateRealm,
+        Self::CreateRealmVoting,
+        Self::JoinRealm,
+        Self::LeaveRealm,
+        Self::Boost,
+        Self::ReleaseBoost,
+        Self::RefundBoost,
+        Self::GetOptionCapacity,
+        Self::ClaimSlot,
+        Self::CommitDraw,
+        Self::RevealDraw,
+        Self::ClaimDraw,
+        Self::FinalizeVoting,
+        Self::SetExecutionAllowlist,
+        Self::AddProposalInstruction,
+        Self::ExecuteNextInstruction,
+        Self::EnableTokenBallots,
+        Self::CountTokenBallots,
+        Self::StartRecount,
+        Self::RecountVotes,
+        Self::CompleteRecount,
+        Self::SetMaxActiveVotings,
+        Self::SetStartTimeTolerance,
+        Self::SetVotingMetadata,
+        Self::UpgradeProgram,
+        Self::SetUpgradeAuthority,
+        Self::GetVoteWeight,
+        Self::AppendAllowlistChunk,
+        Self::SealAllowlist,
+        Self::SetAllowlistRoot,
+        Self::AllocateVoteBucket,
+        Self::ReserveTitle,
+        Self::PublishVoting,
+        Self::CastEnvelope,
+        Self::SubmitEnvelopeResults,
+        Self::CommitTieBreak,
+        Self::RevealTieBreak,
+        Self::SetProposalTemplate,
+        Self::AddTemplateInstruction,
+        Self::CloneVoting,
+        Self::ArchiveVoting,
+        Self::MerkleizeRecords,
+        Self::VerifyBallotProof,
+        Self::FlagVoting,
+        Self::CloseFlag,
+        Self::HideVoting,
+        Self::FundIncentives,
+        Self::WithdrawIncentives,
+        Self::GetResults,
+        Self::SetLocalizedMetadata,
+        Self::SetCreationDeposit,
+        Self::GcExpired,
+        Self::SyncStatus,
+        Self::AttestResult,
+        Self::GetPercentages,
+        Self::CancelVoting,
+        Self::RestoreVoting,
+        Self::AllocateTallyShard,
+        Self::CastShardedVote,
+        Self::MergeTallyShards,
+        Self::AddWeight,
+        Self::LinkProposalNote,
+        Self::VoteByTransferClaim,
+        Self::WithdrawKiosk,
+        Self::CloseUserVote,
+        Self::CloseVoting,
+        Self::ClaimFaucet,
+        Self::GetCreatorSummary,
+        Self::CommitVote,
+        Self::RevealVote,
+        Self::CreateElection,
+        Self::FinalizeElection,
+        Self::ExtendVoting,
+        Self::SealVoterRoll,
+        Self::FreezeVoting,
+        Self::UnfreezeVoting,
+        Self::SetLateRegistration,
+        Self::UpdateTitle,
+        Self::MigrateAccount,
+        Self::SetNotificationTarget,
+        Self::SyncPollCard
+    ];
+
+    pub const fn discriminator(self) -> [u8; 8] {
+        (self as u64).to_le_bytes()
+    }
+
+    // The discriminator and the payload version, what the instruction data starts with
+    pub fn header(self) -> Vec<u8> {
+        let mut header = self.discriminator().to_vec();
+        header.push(PAYLOAD_VERSION);
+
+        header
+    }
+
+    pub fn from_discriminator(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        Self::ALL.into_iter().find(|ty| ty.discriminator() == discriminator)
+    }
+
+    // Instructions that cast, change or count a ballot
+    pub const fn is_ballot(self) -> bool {
+        matches!(
+            self,
+            Self::Vote
+                | Self::UpdateVote
+                | Self::ClaimSlot
+                | Self::CountTokenBallots
+                | Self::CastEnvelope
+                | Self::CastShardedVote
+                | Self::AddWeight
+                | Self::VoteByTransferClaim
+                | Self::CommitVote
+                | Self::RevealVote
+        )
+    }
+}
+
+// A one person one vote voting of `payer` between the `starts_at` and `ends_at` timestamps, listed on the
+// first page of the voting registry
+pub fn create_voting(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    title: String,
+    options: Vec<String>,
+    starts_at: u64,
+    ends_at: u64
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(find_voting_address(&title, program_id), false),
+        AccountMeta::new(find_creator_stats_pda(payer, program_id).0, false),
+        AccountMeta::new(find_title_index_pda(&title, program_id).0, false),
+        AccountMeta::new(find_title_reservation_pda(&voting_title_seed(&title), program_id).0, false),
+        AccountMeta::new(find_voting_registry_pda(program_id).0, false),
+        AccountMeta::new(find_voting_registry_page_pda(0, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::CreateVoting.header();
+    CreateVotingInstruction::new(title, options, starts_at, ends_at).serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// A ballot of `voter` for the options at the `ballot` indexes of the voting titled `vote_title`, without
+// proofs or memo, see `client::vote` for those
+pub fn vote(program_id: &Pubkey, voter: &Pubkey, vote_title: String, ballot: Vec<u8>) -> Instruction {
+    let voting = find_voting_address(&vote_title, program_id);
+    let accounts = vec![
+        AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+        AccountMeta::new(*voter, true),
+        AccountMeta::new(voting, false),
+        AccountMeta::new(find_user_vote_address(&voting, voter, program_id), false),
+        AccountMeta::new(find_voter_index_pda(voter, 0, program_id).0, false),
+        AccountMeta::new(find_poll_card_pda(&voting, program_id).0, false),
+        AccountMeta::new_readonly(system_program::ID, false)
+    ];
+
+    let mut data = InstructionType::Vote.header();
+    VoteInstruction {
+        ballot,
+        vote_title,
+        memo: false,
+        weight_proof: None,
+        eligibility_proof: None,
+        dry_run: false,
+        voter_index_page: 0
+    }.serialize(&mut data).unwrap();
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// Logs `event` as its tag, its version and its borsh encoding, see `decoder::EventDecoder`
+pub(crate) fn log_event(tag: &[u8], version: u8, event: &impl BorshSerialize) -> ProgramResult {
+    sol_log_data(&[tag, &[version], &event.try_to_vec()?]);
+
+    Ok(())
+}
@@ -0,0 +1,940 @@
+// Generated with `client::typescript_layouts()`, see LAYOUT.md. Do not edit.
+
+export interface ConfigAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  admin: Uint8Array; // Pubkey
+  disabled_instructions: bigint; // u128
+  max_active_votings: number; // u32
+  start_time_tolerance: bigint; // u64
+  pending_admin: Uint8Array | null; // Option<Pubkey>
+  creation_deposit: bigint; // u64
+  deposit_refund_window: bigint; // u64
+}
+
+export const ConfigAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  admin: 9,
+  disabled_instructions: 41,
+  max_active_votings: 57,
+  start_time_tolerance: 61,
+  pending_admin: 69,
+} as const;
+
+export interface VoteMainAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  creator: Uint8Array; // Pubkey
+  payer: Uint8Array; // Pubkey
+  starts_at: bigint; // u64
+  ends_at: bigint; // u64
+  status: unknown; // VotingStatus
+  realm: Uint8Array; // Pubkey
+  realm_voting_id: bigint; // u64
+  members_only: boolean; // bool
+  allow_updates: boolean; // bool
+  title: string; // VotingTitle
+  description: string; // String
+  options: (string)[]; // Vec<String>
+  tallies: (bigint)[]; // Vec<u64>
+  total_voters: bigint; // u64
+  vote_chain: Uint8Array; // [u8; 32]
+  option_caps: (bigint)[]; // Vec<u64>
+  co_creators: (Uint8Array)[]; // Vec<Pubkey>
+  creator_threshold: number; // u8
+  weight_source: unknown; // WeightSource
+  weight_mints: (unknown)[]; // Vec<MintRatio>
+  weight_precision: number | null; // Option<u8>
+  tally_mode: unknown; // TallyMode
+  eligibility: unknown; // EligibilityPolicy
+  signup_slots: number; // u32
+  claimed_slots: number; // u32
+  random_draw: unknown | null; // Option<RandomDraw>
+  oracle_condition: unknown | null; // Option<OracleCondition>
+  condition_met: boolean; // bool
+  record_buckets: number; // u8
+  locked: boolean; // bool
+  init_stage: number; // u8
+  required_stages: number; // u8
+  envelope_key: Uint8Array | null; // Option<[u8; 32]>
+  envelope_weight: bigint; // u64
+  envelope_commitment: Uint8Array | null; // Option<[u8; 32]>
+  tie_policy: unknown; // TiePolicy
+  changed_at: (bigint)[]; // Vec<u64>
+  tie_draw: unknown; // RandomDraw
+  dependency: unknown | null; // Option<VotingDependency>
+  creator_program: Uint8Array | null; // Option<Pubkey>
+  records_root: Uint8Array | null; // Option<[u8; 32]>
+  flag_count: number; // u32
+  moderation: unknown; // ModerationStatus
+  incentive_per_vote: bigint; // u64
+  turnout_milestones: unknown | null; // Option<TurnoutMilestones>
+  hide_tallies: boolean; // bool
+  creation_deposit: bigint; // u64
+  deposit_refund_window: bigint; // u64
+  result_attestors: unknown | null; // Option<ResultAttestors>
+  tally_shards: number; // u8
+  shards_merged: boolean; // bool
+  weight_cap_per_slot: bigint; // u64
+  reject_encumbered_tokens: boolean; // bool
+  proposal_note: Uint8Array | null; // Option<Pubkey>
+  reveal_window: bigint; // u64
+  commitments: bigint; // u64
+  winners_count: number; // u8
+  winners: (number)[]; // Vec<u8>
+  apportionment: unknown | null; // Option<Apportionment>
+  seats: (number)[]; // Vec<u16>
+  voter_roll: unknown | null; // Option<VoterRoll>
+  frozen_at: bigint | null; // Option<u64>
+  min_participation: number; // u32
+  pass_threshold_bps: number; // u16
+  electorate: unknown | null; // Option<Electorate>
+  quorum_bps: number; // u16
+  outcome: unknown | null; // Option<VotingOutcome>
+  result: number | null; // Option<u8>
+  tally_state: unknown; // TallyState
+}
+
+export const VoteMainAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  creator: 9,
+  payer: 41,
+  starts_at: 73,
+  ends_at: 81,
+  status: 89,
+  realm: 90,
+  realm_voting_id: 122,
+  members_only: 130,
+  allow_updates: 131,
+  title: 132,
+  description: 261,
+} as const;
+
+export interface RealmAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  admin: Uint8Array; // Pubkey
+  defaults: unknown; // VotingSettings
+  locked_settings: number; // u8
+  creation_fee: bigint; // u64
+  creators_gated: boolean; // bool
+  votings_count: bigint; // u64
+  members_count: bigint; // u64
+  name: string; // String
+}
+
+export const RealmAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  admin: 9,
+  defaults: 41,
+  locked_settings: 60,
+  creation_fee: 61,
+  creators_gated: 69,
+  votings_count: 70,
+  members_count: 78,
+  name: 86,
+} as const;
+
+export interface MembershipAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  realm: Uint8Array; // Pubkey
+  member: Uint8Array; // Pubkey
+  joined_at: bigint; // u64
+}
+
+export const MembershipAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  realm: 9,
+  member: 41,
+  joined_at: 73,
+} as const;
+
+export interface UserVotingAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  payer: Uint8Array; // Pubkey
+  first_voted_at: bigint; // u64
+  last_time_voted: bigint; // u64
+  times_updated: number; // u16
+  choice: number; // u8
+  weight: bigint; // u64
+  credited_slot: bigint; // u64
+  draw_offset: bigint; // u64
+  vote_index: bigint; // u64
+  commitment: Uint8Array | null; // Option<[u8; 32]>
+  ballot: (number)[]; // Vec<u8>
+  voted_to: string; // VotingTitle
+}
+
+export const UserVotingAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  payer: 9,
+  first_voted_at: 41,
+  last_time_voted: 49,
+  times_updated: 57,
+  choice: 59,
+  weight: 60,
+  credited_slot: 68,
+  draw_offset: 76,
+  vote_index: 84,
+  commitment: 92,
+} as const;
+
+export interface TallySnapshotAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  last_taken_at: bigint; // u64
+  next_index: number; // u8
+  snapshots: (unknown)[]; // Vec<TallySnapshot>
+}
+
+export const TallySnapshotAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  last_taken_at: 41,
+  next_index: 49,
+  snapshots: 50,
+} as const;
+
+export interface RewardEscrowAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  total_funded: bigint; // u64
+  total_claimed: bigint; // u64
+  in_progress: boolean; // bool
+}
+
+export const RewardEscrowAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  total_funded: 41,
+  total_claimed: 49,
+  in_progress: 57,
+} as const;
+
+export interface RewardClaimAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  amount: bigint; // u64
+}
+
+export const RewardClaimAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  amount: 9,
+} as const;
+
+export interface BoostAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  amount: bigint; // u64
+  released: boolean; // bool
+  in_progress: boolean; // bool
+}
+
+export const BoostAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  amount: 41,
+  released: 49,
+  in_progress: 50,
+} as const;
+
+export interface BoostDepositAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  amount: bigint; // u64
+}
+
+export const BoostDepositAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  amount: 9,
+} as const;
+
+export interface VoterIndexAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voter: Uint8Array; // Pubkey
+  page: number; // u16
+  next: Uint8Array; // Pubkey
+  votings: Uint8Array[]; // [Pubkey]
+}
+
+export const VoterIndexAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voter: 9,
+  page: 41,
+  next: 43,
+  votings: 75,
+} as const;
+
+export interface CreatorStatsAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  creator: Uint8Array; // Pubkey
+  active_votings: number; // u32
+  created_votings: number; // u32
+  finalized_votings: number; // u32
+  cancelled_votings: number; // u32
+  total_voters: bigint; // u64
+  flags: number; // u32
+}
+
+export const CreatorStatsAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  creator: 9,
+  active_votings: 41,
+  created_votings: 45,
+  finalized_votings: 49,
+  cancelled_votings: 53,
+  total_voters: 57,
+  flags: 65,
+} as const;
+
+export interface SlotAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  claimant: Uint8Array; // Pubkey
+  slot: number; // u32
+  claimed_at: bigint; // u64
+}
+
+export const SlotAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  claimant: 41,
+  slot: 73,
+  claimed_at: 77,
+} as const;
+
+export interface SlotClaimAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  slot: number; // u32
+}
+
+export const SlotClaimAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  slot: 9,
+} as const;
+
+export interface ExecutionAllowlistAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  realm: Uint8Array; // Pubkey
+  actions: (unknown)[]; // Vec<AllowedAction>
+}
+
+export const ExecutionAllowlistAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  realm: 9,
+  actions: 41,
+} as const;
+
+export interface ProposalAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  option: number; // u8
+  next_instruction: number; // u16
+  in_progress: boolean; // bool
+  instructions: (unknown)[]; // Vec<ProposalInstruction>
+}
+
+export const ProposalAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  option: 41,
+  next_instruction: 42,
+  in_progress: 44,
+  instructions: 45,
+} as const;
+
+export interface ProposalTemplateAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  realm: Uint8Array; // Pubkey
+  id: number; // u16
+  instruction: unknown; // ProposalInstruction
+  params: (unknown)[]; // Vec<TemplateParam>
+}
+
+export const ProposalTemplateAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  realm: 9,
+  id: 41,
+  instruction: 43,
+} as const;
+
+export interface VotingArchiveAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  creator: Uint8Array; // Pubkey
+  title: string; // String
+  winning_option: number | null; // Option<u8>
+  tallies: (bigint)[]; // Vec<u128>
+  total_voters: bigint; // u64
+  vote_chain: Uint8Array; // [u8; 32]
+  records_root: Uint8Array | null; // Option<[u8; 32]>
+  archived_at: bigint; // u64
+}
+
+export const VotingArchiveAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  creator: 41,
+  title: 73,
+} as const;
+
+export interface ElectionAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  creator: Uint8Array; // Pubkey
+  id: bigint; // u64
+  name: string; // String
+  finalized_at: bigint; // u64
+  races: (unknown)[]; // Vec<ElectionRace>
+}
+
+export const ElectionAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  creator: 9,
+  id: 41,
+  name: 49,
+} as const;
+
+export interface RecordTreeAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  tree: unknown; // RecordTree
+}
+
+export const RecordTreeAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  tree: 41,
+} as const;
+
+export interface FlagAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  flagger: Uint8Array; // Pubkey
+  flagged_at: bigint; // u64
+}
+
+export const FlagAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  flagger: 41,
+  flagged_at: 73,
+} as const;
+
+export interface IncentivePoolAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  total_funded: bigint; // u64
+  total_paid: bigint; // u64
+  in_progress: boolean; // bool
+}
+
+export const IncentivePoolAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  total_funded: 41,
+  total_paid: 49,
+  in_progress: 57,
+} as const;
+
+export interface BallotBoxAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  mint: Uint8Array; // Pubkey
+  counted: (bigint)[]; // Vec<u64>
+}
+
+export const BallotBoxAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  mint: 41,
+  counted: 73,
+} as const;
+
+export interface RecountAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  last_record: Uint8Array; // Pubkey
+  records_counted: bigint; // u64
+  tallies: (bigint)[]; // Vec<u64>
+  tally_state: unknown; // TallyState
+}
+
+export const RecountAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  last_record: 41,
+  records_counted: 73,
+  tallies: 81,
+} as const;
+
+export interface VotingMetadataAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  timezone: string; // String
+  schedule: string; // String
+}
+
+export const VotingMetadataAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  timezone: 41,
+} as const;
+
+export interface LocalizedMetadataAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  language: string; // String
+  title: string; // String
+  description: string; // String
+}
+
+export const LocalizedMetadataAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  language: 41,
+} as const;
+
+export interface VoterAllowlistAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  voters_count: number; // u32
+  sealed: boolean; // bool
+  root: Uint8Array | null; // Option<[u8; 32]>
+  late_registration: number; // u8
+  late_chunks: (unknown)[]; // Vec<LateAllowlistChunk>
+}
+
+export const VoterAllowlistAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  voters_count: 41,
+  sealed: 45,
+  root: 46,
+} as const;
+
+export interface VoterAllowlistPageAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  page: number; // u16
+  voters: (Uint8Array)[]; // Vec<Pubkey>
+}
+
+export const VoterAllowlistPageAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  page: 41,
+  voters: 43,
+} as const;
+
+export interface VoteBucketAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  index: number; // u8
+  slots: (unknown)[]; // Vec<RecordSlot>
+}
+
+export const VoteBucketAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  index: 41,
+  slots: 42,
+} as const;
+
+export interface TallyShardAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  index: number; // u8
+  voters: bigint; // u64
+  updates: bigint; // u64
+  vote_chain: Uint8Array; // [u8; 32]
+  tallies: (bigint)[]; // Vec<u64>
+  tally_state: unknown; // TallyState
+}
+
+export const TallyShardAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  index: 41,
+  voters: 42,
+  updates: 50,
+  vote_chain: 58,
+  tallies: 90,
+} as const;
+
+export interface TitleIndexAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+}
+
+export const TitleIndexAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+} as const;
+
+export interface TitleReservationAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  reserver: Uint8Array; // Pubkey
+  expires_at: bigint; // u64
+}
+
+export const TitleReservationAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  reserver: 9,
+  expires_at: 41,
+} as const;
+
+export interface EnvelopeAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voter: Uint8Array; // Pubkey
+  weight: bigint; // u64
+  cast_at: bigint; // u64
+  vote_index: bigint; // u64
+  envelope: (number)[]; // Vec<u8>
+}
+
+export const EnvelopeAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voter: 9,
+  weight: 41,
+  cast_at: 49,
+  vote_index: 57,
+  envelope: 65,
+} as const;
+
+export interface FaucetClaimAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  wallet: Uint8Array; // Pubkey
+  last_claimed_at: bigint; // u64
+  claims: number; // u32
+}
+
+export const FaucetClaimAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  wallet: 9,
+  last_claimed_at: 41,
+  claims: 49,
+} as const;
+
+export interface VotingRegistryAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  votings_count: bigint; // u64
+}
+
+export const VotingRegistryAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  votings_count: 9,
+} as const;
+
+export interface VotingRegistryPageAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  page: number; // u32
+  entries: unknown; // [RegistryEntry]
+}
+
+export const VotingRegistryPageAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  page: 9,
+  entries: 13,
+} as const;
+
+export interface NotificationTargetAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  webhook_commitment: Uint8Array | null; // Option<[u8; 32]>
+  notify: Uint8Array | null; // Option<Pubkey>
+  updated_at: bigint; // u64
+}
+
+export const NotificationTargetAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  webhook_commitment: 41,
+} as const;
+
+export interface PollCardAccount {
+  discriminator: Uint8Array; // [u8; 8]
+  version: number; // u8
+  voting: Uint8Array; // Pubkey
+  status: unknown; // VotingStatus
+  ends_at: bigint; // u64
+  leading_option: number; // u8
+  total_voters: bigint; // u64
+  updated_at: bigint; // u64
+}
+
+export const PollCardAccountOffsets = {
+  discriminator: 0,
+  version: 8,
+  voting: 9,
+  status: 41,
+  ends_at: 42,
+  leading_option: 50,
+  total_voters: 51,
+  updated_at: 59,
+} as const;
+
+// Code of each program error, with its name and message
+export const ProgramErrors: Record<number, [string, string]> = {
+  0: ["InvalidStartingTime", "Starting time < Current time"],
+  1: ["InvalidEndingTime", "Ending time < Starting time"],
+  2: ["MaxVotingTimeExceeded", "Max voting time exceeded."],
+  3: ["InvalidSystemProgram", "Invalid system program account."],
+  4: ["InvalidPdaAddress", "Invalid PDA seeds."],
+  5: ["UserSigningNeeded", "User must be signer."],
+  6: ["UsersAccountMustBeMutable", "User's account must be writable."],
+  7: ["PDAsAccountMustBeMutable", "PDA's account must be writable."],
+  8: ["TitleInvalidLength", "Title must be between 10 and 50 characters"],
+  9: ["InvalidAccountOwner", "Invalid account owner."],
+  10: ["VotingNotStarted", "Voting has not started yet."],
+  11: ["VotingEnded", "Voting has been ended."],
+  12: ["NotVotingCreator", "Signer is not the voting creator."],
+  13: ["VotingAlreadyStarted", "Voting has already started."],
+  14: ["DescriptionTooLong", "Description length > 256"],
+  15: ["InvalidOptionsCount", "Options count must be at least 2"],
+  16: ["OptionInvalidLength", "Option length must be between 1 and 32"],
+  17: ["InvalidOptionIndex", "Invalid option index."],
+  18: ["InvalidOptionOrder", "Invalid option order."],
+  19: ["InstructionDisabled", "Instruction is disabled."],
+  20: ["InvalidProgramData", "Invalid program data account."],
+  21: ["NotUpgradeAuthority", "Signer is not the program upgrade authority."],
+  22: ["NotConfigAdmin", "Signer is not the config admin."],
+  23: ["NotPendingAdmin", "Signer is not the pending config admin."],
+  24: ["VotingCancelled", "Voting has been cancelled."],
+  25: ["VotingFinalized", "Voting has been finalized."],
+  26: ["SnapshotTooEarly", "Tally snapshot interval has not passed yet."],
+  27: ["VotingNotEnded", "Voting has not ended yet."],
+  28: ["CertificateAlreadyClaimed", "Certificate already claimed."],
+  29: ["InvalidTokenProgram", "Invalid token program account."],
+  30: ["NoWinningOption", "Voting has no winning option."],
+  31: ["NotWinningVoter", "Ballot is not on the winning option."],
+  32: ["RewardAlreadyClaimed", "Reward already claimed."],
+  33: ["InvalidRewardAmount", "Reward amount must be > 0"],
+  34: ["InvalidMemoProgram", "Invalid memo program account."],
+  35: ["NotRentExempt", "Account would not be rent exempt."],
+  36: ["InvalidCoCreators", "Invalid co-creator accounts."],
+  37: ["InvalidCreatorThreshold", "Creator threshold must be between 1 and the creators count"],
+  38: ["NotEnoughCreatorSignatures", "Not enough creator signatures."],
+  39: ["RealmNameInvalidLength", "Realm name length must be between 1 and 32"],
+  40: ["InvalidRealmSettings", "Invalid realm settings."],
+  41: ["NotRealmAdmin", "Signer is not the realm admin."],
+  42: ["NotRealmMember", "Signer is not a realm member."],
+  43: ["RealmSettingLocked", "Realm setting is locked."],
+  44: ["VotingTimeTooShort", "Voting time is below the minimum."],
+  45: ["VoteUpdatesDisabled", "Vote updates are disabled for this voting."],
+  46: ["InvalidBoostAmount", "Boost amount must be greater than zero."],
+  47: ["BoostAlreadyReleased", "Boost was already released."],
+  48: ["VotingNotCancelled", "Voting is not cancelled."],
+  49: ["InvalidTreasury", "Treasury must be the config admin."],
+  50: ["TooManyOptions", "Options count exceeds the maximum."],
+  51: ["TitleInvalidCharacters", "Title can't contain control characters or start or end with whitespace."],
+  52: ["AlreadyInitialized", "Account is already initialized."],
+  53: ["InvalidWeightSource", "Weight source is invalid."],
+  54: ["InvalidWeightAccount", "Weight account doesn't belong to the voter."],
+  55: ["InvalidWeightProof", "Weight proof is invalid."],
+  56: ["ZeroVoteWeight", "Vote weight must be greater than zero."],
+  57: ["InvalidTallyMode", "Tally mode is invalid."],
+  58: ["InvalidBallot", "Ballot doesn't match the tally mode."],
+  59: ["RankDepthExceeded", "Ballot ranks more options than allowed."],
+  60: ["TooManyRankings", "Voting has too many distinct rankings."],
+  61: ["InvalidEligibilityPolicy", "Eligibility policy is invalid."],
+  62: ["NotEligible", "Voter doesn't meet the eligibility policy."],
+  63: ["OptionFull", "Option reached its cap."],
+  64: ["InvalidOptionCaps", "Option caps don't match the options."],
+  65: ["SignupVoting", "Signup voting only takes slot claims."],
+  66: ["NotSignupVoting", "Voting is not a signup voting."],
+  67: ["SlotsFull", "All slots are claimed."],
+  68: ["DrawNotEnabled", "Voting has no random draw."],
+  69: ["DrawAlreadyCommitted", "Draw is already committed."],
+  70: ["DrawNotRevealable", "Draw can't be revealed yet."],
+  71: ["DrawExpired", "Committed slot hash is no longer available, commit again."],
+  72: ["NoDrawTickets", "Nobody voted, there is nothing to draw."],
+  73: ["AlreadyDrawn", "Winner was already drawn."],
+  74: ["NotDrawWinner", "Voter doesn't hold the drawn ticket."],
+  75: ["DrawAlreadyClaimed", "Draw winner is already recorded."],
+  76: ["InvalidOracleCondition", "Oracle condition is invalid."],
+  77: ["InvalidOracleFeed", "Oracle feed doesn't match the condition."],
+  78: ["StaleOracleFeed", "Oracle feed price is stale or not trading."],
+  79: ["VotingNotFinalized", "Voting must be finalized first."],
+  80: ["InvalidExecutionAllowlist", "Execution allowlist is invalid."],
+  81: ["NotRealmVoting", "Only realm votings can execute instructions."],
+  82: ["InvalidProposalInstruction", "Proposal instruction is invalid."],
+  83: ["ActionNotAllowed", "Instruction is not on the realm's execution allowlist."],
+  84: ["ProposalOptionMismatch", "Proposal instructions must be for the same option."],
+  85: ["TooManyProposalInstructions", "Proposal has too many instructions."],
+  86: ["ProposalNotPassed", "Proposal option didn't win."],
+  87: ["ProposalExecuted", "All proposal instructions are executed."],
+  88: ["InvalidInstructionAccounts", "Accounts don't match the proposal instruction."],
+  89: ["TokenBallotsUnsupported", "Token ballots can't be used with gated, signup or quadratic votings."],
+  90: ["InvalidBallotTokenAccount", "Token account isn't the option's ballot token account."],
+  91: ["RecountIncomplete", "Recount hasn't counted every voter."],
+  92: ["InvalidRecountOrder", "Recounted records must be in ascending key order."],
+  93: ["TooManyActiveVotings", "Creator has too many active votings."],
+  94: ["InvalidStartTimeTolerance", "Start time tolerance is too large."],
+  95: ["DisplayHintTooLong", "Timezone or schedule is too long."],
+  96: ["InvalidWeightMints", "Invalid weight mints."],
+  97: ["InvalidLoaderProgram", "Invalid BPF upgradeable loader program."],
+  98: ["TallyOverflow", "Tally overflow."],
+  99: ["AllowlistSealed", "Voter allowlist is sealed."],
+  100: ["AllowlistNotSealed", "Voter allowlist isn't sealed yet."],
+  101: ["InvalidAllowlistChunk", "Allowlist chunk is empty, too large or crosses a page."],
+  102: ["AllowlistInUse", "Voter allowlist already exists."],
+  103: ["InvalidRecordBuckets", "Invalid record buckets."],
+  104: ["BucketFull", "Vote bucket is full."],
+  105: ["TitleReserved", "Title is reserved by another creator."],
+  106: ["VotingIsDraft", "Voting is a draft."],
+  107: ["VotingNotDraft", "Voting is not a draft."],
+  108: ["VotingLocked", "Voting configuration is locked."],
+  109: ["InvalidRequiredStages", "Invalid required setup stages."],
+  110: ["SetupIncomplete", "Voting setup is incomplete."],
+  111: ["InvalidEnvelopeSettings", "Invalid envelope voting settings."],
+  112: ["EnvelopeVoting", "Envelope votings only take sealed ballots."],
+  113: ["NotEnvelopeVoting", "Voting is not an envelope voting."],
+  114: ["InvalidEnvelope", "Invalid envelope."],
+  115: ["InvalidEnvelopeResults", "Envelope results don't match the sealed ballots."],
+  116: ["EnvelopeResultsMissing", "Envelope results haven't been submitted."],
+  117: ["InvalidTiePolicy", "Invalid tie policy."],
+  118: ["NoTie", "Voting is not tied."],
+  119: ["InvalidDependency", "Invalid voting dependency."],
+  120: ["DependencyNotMet", "Voting dependency is not met."],
+  121: ["InvalidProposalTemplate", "Invalid proposal template."],
+  122: ["InvalidTemplateValue", "Template value is out of bounds."],
+  123: ["InvalidProgramCreator", "Creator is not a PDA of the given program."],
+  124: ["RealmVotingNotCloneable", "Realm votings can't be cloned."],
+  125: ["ArchiveTooEarly", "Voting can't be archived yet."],
+  126: ["RealmVotingNotArchivable", "Realm votings can't be archived."],
+  127: ["InvalidRecordOrder", "Vote records must be merkleized in vote_index order."],
+  128: ["RecordsMerkleized", "Vote records are already merkleized."],
+  129: ["RecordsNotMerkleized", "Vote records are not merkleized."],
+  130: ["InvalidBallotProof", "Ballot is not in the vote records."],
+  131: ["AlreadyFlagged", "Voting is already flagged by this user."],
+  132: ["NotModerator", "Only the program or realm admin can moderate the voting."],
+  133: ["InvalidIncentive", "Invalid vote incentive."],
+  134: ["InvalidTurnoutMilestones", "Invalid turnout milestones."],
+  135: ["BallotInFinalizeTransaction", "Finalization can't share a transaction with a ballot for the voting."],
+  136: ["ProposalInProgress", "Proposal is already executing an instruction."],
+  137: ["TalliesHidden", "Tallies are hidden until the voting ends."],
+  138: ["InvalidLanguage", "Invalid language tag."],
+  139: ["VoterIndexPageFull", "Voter index page is full, the next page takes the voting."],
+  140: ["InvalidCreationDeposit", "Creation deposit needs a refund window of at least a day."],
+  141: ["InvalidDepositRecipient", "Creation deposit must be refunded to the creator."],
+  142: ["InvalidPayer", "Account isn't the payer of the swept account."],
+  143: ["VotingNotExpired", "Voting can only be swept 180 days after it ended."],
+  144: ["NotVoteRecordOwner", "Vote record belongs to another voter."],
+  145: ["InvalidResultAttestors", "Invalid result attestors."],
+  146: ["NotResultAttestor", "Signer isn't an attestor of the voting result."],
+  147: ["ResultAlreadyAttested", "Result already attested by this attestor."],
+  148: ["ResultNotAttested", "Result is waiting for its attestors."],
+  149: ["InvalidPercentageDecimals", "Percentages have at most 6 decimals."],
+  150: ["InvalidTallyShards", "Invalid tally shards."],
+  151: ["ShardedVoting", "Not supported by sharded votings."],
+  152: ["ShardsNotMerged", "Tally shards are not merged yet."],
+  153: ["ShardsMerged", "Tally shards are already merged."],
+  154: ["InvalidWeightCap", "Invalid weight cap."],
+  155: ["WeightAlreadyCredited", "Weight was already credited in this slot."],
+  156: ["WeightFullyCredited", "No weight left to credit."],
+  157: ["FrozenWeightAccount", "Weight token account is frozen."],
+  158: ["InvalidProposalNote", "The creator is not the update authority of the proposal note."],
+  159: ["InvalidKioskTransfer", "Expected a transfer to the kiosk and a memo with the ballot before the claim."],
+  160: ["CloseTooEarly", "Voting can't be closed yet."],
+  161: ["FaucetCooldown", "Wallet claimed from the faucet less than a day ago."],
+  162: ["FaucetEmpty", "Faucet doesn't hold enough lamports for the claim."],
+  163: ["FaucetDisabled", "Faucet is only available in devnet builds."],
+  164: ["CommitRevealVoting", "Commit-reveal votings only take committed ballots."],
+  165: ["NotCommitRevealVoting", "Voting is not a commit-reveal voting."],
+  166: ["InvalidRevealWindow", "Invalid reveal window."],
+  167: ["RevealWindowOpen", "Committed ballots can still be revealed."],
+  168: ["RevealWindowClosed", "Reveal window is closed."],
+  169: ["BallotRevealed", "Ballot is already revealed."],
+  170: ["InvalidReveal", "Ballot and salt don't match the commitment."],
+  171: ["UnsupportedPayloadVersion", "Instruction payload version is not supported."],
+  172: ["InvalidElection", "Invalid election name or races."],
+  173: ["ElectionFinalized", "Election is already finalized."],
+  174: ["NotAllowlisted", "Voter is not on the voting's allowlist."],
+  175: ["InvalidWinnersCount", "Winners count is 0, more than the options, or more than 1 for a voting without plain counts."],
+  176: ["InvalidApportionment", "Apportionment needs 1 to 1000 seats and a voting that isn't ranked."],
+  177: ["VoterRollSealed", "Voter roll is already sealed."],
+  178: ["VoterRollClosed", "Voter roll can only be sealed before the voting's first ballot."],
+  179: ["NotOnVoterRoll", "Voter is not on the voting's sealed voter roll."],
+  180: ["VotingFrozen", "The voting is frozen."],
+  181: ["VotingNotFrozen", "The voting isn't frozen."],
+  182: ["InvalidLateRegistration", "Late registration needs an uploaded allowlist and at most 100% of the voting window."],
+  183: ["LateRegistrationClosed", "The allowlist's late registration window is closed."],
+  184: ["InvalidPassThreshold", "Pass threshold is more than 10000 basis points."],
+  185: ["TitleTooLong", "Title is longer than 50 characters or 128 bytes."],
+  186: ["UserAlreadyVoted", "User already voted."],
+  187: ["InstructionDataTooShort", "Instruction data is shorter than its discriminator."],
+  188: ["InvalidElectorate", "Electorate is empty, or the quorum is more than 10000 basis points or set without an electorate."],
+  189: ["AccountAlreadyMigrated", "Account is already in the current layout."],
+  190: ["UnknownAccountVersion", "Account data matches no known layout."],
+  191: ["InvalidPollCard", "Account is not the voting's poll card."],
+  192: ["AccountInProgress", "Account is in the middle of a transfer of another instruction."],
+};
@@ -0,0 +1,153 @@
+use pinocchio::{
+    account_info::{ AccountInfo, Ref },
+    entrypoint::{ InstructionContext, MaybeAccount }
+};
+use solana_program::{
+    entrypoint::{ deserialize, SUCCESS },
+    program_error::{ PrintProgramError, ProgramError },
+    pubkey::Pubkey
+};
+
+use crate::{
+    error::Errors,
+    instruction::{ InstructionType, VotingInstruction },
+    processor::{ get_my_vote, get_option_capacity, get_percentages, get_results, process_instruction, Sysvars }
+};
+
+// `get_my_vote` takes the most accounts of the views
+const MAX_VIEW_ACCOUNTS: usize = 3;
+
+/// Program entrypoint of the `pinocchio` build. The accounts are walked in place, without allocating
+/// `AccountInfo`s. The read-only views are answered from the raw accounts. Every other instruction
+/// is deserialized as in the default build and goes through `process_instruction`.
+///
+/// # Safety
+///
+/// `input` must be the program input serialized by the loader.
+#[no_mangle]
+pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
+    let mut context = InstructionContext::new_unchecked(input);
+
+    let accounts_len = context.remaining() as usize;
+    let mut accounts = Vec::with_capacity(MAX_VIEW_ACCOUNTS);
+    for _ in 0..accounts_len {
+        let account = match context.next_account_unchecked() {
+            MaybeAccount::Account(account) => account,
+            // Duplicates point back to an earlier account, kept if this one is
+            MaybeAccount::Duplicated(index) => match accounts.get(index as usize) {
+                Some(account) => *account,
+                None => continue
+            }
+        };
+        if accounts.len() < MAX_VIEW_ACCOUNTS {
+            accounts.push(account);
+        };
+    };
+
+    let instruction_data = context.instruction_data_unchecked();
+    let program_id = Pubkey::new_from_array(*context.program_id_unchecked());
+
+    let is_view = matches!(
+        InstructionType::from_discriminator(instruction_data),
+        Some(
+            InstructionType::GetMyVote
+            | InstructionType::GetOptionCapacity
+            | InstructionType::GetResults
+            | InstructionType::GetPercentages
+        )
+    );
+    if is_view == false {
+        let (program_id, accounts, instruction_data) = deserialize(input);
+
+        // Errors are printed by `process_instruction`
+        return match process_instruction(program_id, &accounts, instruction_data) {
+            Ok(()) => SUCCESS,
+            Err(error) => error.into()
+        };
+    };
+
+    match process_view(&program_id, &accounts, accounts_len, instruction_data) {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            error.print::<Errors>();
+            error.into()
+        }
+    }
+}
+
+// Mirrors `process_instruction` and the account parsing of each view
+fn process_view(program_id: &Pubkey, accounts: &[AccountInfo], accounts_len: usize, instruction_data: &[u8]) -> Result<(), ProgramError> {
+    #[cfg(not(feature = "skip-id-check"))]
+    if *program_id != crate::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    let sysvars = Sysvars::get()?;
+
+    match VotingInstruction::unpack(instruction_data)? {
+        VotingInstruction::GetMyVote => {
+            let [user, voting_account, user_vote_account] = parse_accounts(accounts, accounts_len)?;
+
+            assert_owned_by(voting_account, program_id)?;
+            assert_owned_by(user_vote_account, program_id)?;
+
+            let (user_vote_pda, _) = crate::find_user_vote_pda(&key(voting_account), &key(user), program_id);
+            if key(user_vote_account) != user_vote_pda {
+                return Err(Errors::InvalidPdaAddress.into());
+            };
+
+            get_my_vote(&borrow_data(voting_account)?, &borrow_data(user_vote_account)?)
+        },
+        VotingInstruction::GetOptionCapacity(ix_data) => {
+            let [voting_account] = parse_voting(program_id, accounts, accounts_len)?;
+            get_option_capacity(program_id, &key(voting_account), &borrow_data(voting_account)?, ix_data, &sysvars)
+        },
+        VotingInstruction::GetResults => {
+            let [voting_account] = parse_voting(program_id, accounts, accounts_len)?;
+            get_results(program_id, &key(voting_account), &borrow_data(voting_account)?, &sysvars)
+        },
+        VotingInstruction::GetPercentages(ix_data) => {
+            let [voting_account] = parse_voting(program_id, accounts, accounts_len)?;
+            get_percentages(program_id, &key(voting_account), &borrow_data(voting_account)?, ix_data, &sysvars)
+        },
+        _ => Err(ProgramError::InvalidInstructionData)
+    }
+}
+
+// `assert_accounts_len` over the walked accounts
+fn parse_accounts<const N: usize>(accounts: &[AccountInfo], accounts_len: usize) -> Result<&[AccountInfo; N], ProgramError> {
+    if accounts_len < N {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if accounts_len > N {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    accounts.first_chunk().ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+// The accounts of `get_option_capacity`, the voting alone
+fn parse_voting<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo], accounts_len: usize) -> Result<&'a [AccountInfo; 1], ProgramError> {
+    let accounts = parse_accounts(accounts, accounts_len)?;
+    assert_owned_by(&accounts[0], program_id)?;
+
+    Ok(accounts)
+}
+
+fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<(), ProgramError> {
+    if account.owner() != owner.as_ref() {
+        return Err(Errors::InvalidAccountOwner.into());
+    };
+
+    Ok(())
+}
+
+fn key(account: &AccountInfo) -> Pubkey {
+    Pubkey::new_from_array(*account.key())
+}
+
+// Both crates encode program errors as the same `u64`
+fn borrow_data(account: &AccountInfo) -> Result<Ref<'_, [u8]>, ProgramError> {
+    account.try_borrow_data().map_err(|error| ProgramError::from(u64::from(error)))
+}
@@ -0,0 +1,107 @@
+use solana_program::declare_id;
+
+// Account layouts with their sizes, seeds and addresses, and the layout versions, see `migrate_account`
+pub mod state;
+pub use state::*;
+
+pub mod error;
+pub use error::*;
+
+// Instruction payloads and discriminators, with builders for clients and other programs
+pub mod instruction;
+pub use instruction::*;
+
+// The handlers of every instruction, see `process_instruction`
+pub mod processor;
+pub use processor::*;
+
+mod checks;
+
+pub mod weight;
+
+pub mod tally;
+
+pub mod eligibility;
+
+pub mod draw;
+
+pub mod oracle;
+
+pub mod execution;
+
+pub mod merkle;
+
+pub mod buckets;
+
+
+// Typed decoding of account updates for indexers and Geyser plugins
+pub mod decoder;
+
+// Instruction builders for programs calling this one
+pub mod cpi;
+
+// Off-chain instruction and transaction builders
+#[cfg(feature = "client")]
+pub mod client;
+
+// Off-chain recount of a voting from its user vote accounts
+#[cfg(feature = "client")]
+pub mod audit;
+
+// Off-chain backfill of the program's accounts and transaction history
+#[cfg(feature = "client")]
+pub mod scanner;
+
+// Representative votings created on a local validator and dumped as fixtures for frontend and indexer tests
+#[cfg(feature = "client")]
+pub mod fixtures;
+
+// C ABI over the account decoders for indexers in other languages, when built as a `cdylib`. Only
+// fixed-size fields are exposed, strings and vectors are left to the Borsh layouts in LAYOUT.md.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+// Reference instances of every account and instruction layout, checked against tests/fixtures
+#[cfg(test)]
+mod golden;
+
+// Property tests of the voting window
+#[cfg(test)]
+mod time_windows;
+
+// Handlers run through `process` at fixed clock times
+#[cfg(test)]
+mod fixed_clock;
+
+// Handlers whose CPI target calls back into the program
+#[cfg(test)]
+mod reentrancy;
+
+declare_id!("81VLUAWfvwwd8MTaDeJBBmdN6q95EBYAe3gaBGDNXbu4");
+
+#[cfg(not(feature = "pinocchio"))]
+solana_program::entrypoint!(process_instruction);
+
+// Lazy entrypoint answering the read-only views from the raw input, with the heap and panic handler
+// `entrypoint!` would declare
+#[cfg(feature = "pinocchio")]
+pub mod lazy_entrypoint;
+
+#[cfg(feature = "pinocchio")]
+solana_program::custom_heap_default!();
+
+#[cfg(feature = "pinocchio")]
+solana_program::custom_panic_default!();
+
+// With the `custom-heap` feature the default 32KiB heap is replaced by a bump allocator over the
+// maximum 256KiB frame, so borsh strings and option vectors don't exhaust the heap. Transactions
+// must request the frame with `ComputeBudgetInstruction::request_heap_frame(HEAP_FRAME_BYTES)`.
+#[cfg(feature = "custom-heap")]
+pub const HEAP_FRAME_BYTES: u32 = 256 * 1024;
+
+#[cfg(all(feature = "custom-heap", target_os = "solana"))]
+#[global_allocator]
+static ALLOCATOR: solana_program::entrypoint::BumpAllocator = solana_program::entrypoint::BumpAllocator {
+    start: solana_program::entrypoint::HEAP_START_ADDRESS as usize,
+    len: HEAP_FRAME_BYTES as usize
+};
@@ -0,0 +1,81 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{ keccak::hashv, pubkey::Pubkey };
+
+// Levels of the vote record tree, enough for any `total_voters`
+pub const RECORD_TREE_DEPTH: usize = 32;
+
+// Append-only Merkle tree over a voting's vote records in `vote_index` order, so it can be built over
+// several transactions. Only the left siblings of the next leaf are kept, missing leaves hash as zero.
+// Nodes are keccak(left, right), unsorted, since a leaf's index fixes its position.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecordTree {
+    pub leaves: u64,
+    pub frontier: Vec<[u8; 32]>
+}
+
+impl Default for RecordTree {
+    fn default() -> Self {
+        Self { leaves: 0, frontier: vec![[0; 32]; RECORD_TREE_DEPTH] }
+    }
+}
+
+impl RecordTree {
+    pub fn append(&mut self, leaf: [u8; 32]) {
+        let mut node = leaf;
+        let mut index = self.leaves;
+        for level in 0..RECORD_TREE_DEPTH {
+            if index & 1 == 0 {
+                self.frontier[level] = node;
+                break;
+            };
+
+            node = hashv(&[&self.frontier[level], &node]).to_bytes();
+            index >>= 1;
+        };
+
+        self.leaves += 1;
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        let mut node = [0; 32];
+        let mut zero = [0; 32];
+        let mut index = self.leaves;
+        for level in 0..RECORD_TREE_DEPTH {
+            node = if index & 1 == 1 {
+                hashv(&[&self.frontier[level], &node]).to_bytes()
+            } else {
+                hashv(&[&node, &zero]).to_bytes()
+            };
+            zero = hashv(&[&zero, &zero]).to_bytes();
+            index >>= 1;
+        };
+
+        node
+    }
+}
+
+// Leaf of a vote record. For envelope votings `ballot` is the sealed envelope.
+pub fn record_leaf(voter: &Pubkey, vote_index: u64, weight: u64, ballot: &[u8]) -> [u8; 32] {
+    hashv(&[voter.as_ref(), &vote_index.to_le_bytes(), &weight.to_le_bytes(), ballot]).to_bytes()
+}
+
+// Checks a leaf against a root of `RecordTree`, `proof` holding one sibling per level from the leaf up
+pub fn verify_record_proof(root: &[u8; 32], leaf: [u8; 32], vote_index: u64, proof: &[[u8; 32]]) -> bool {
+    if proof.len() != RECORD_TREE_DEPTH {
+        return false;
+    };
+
+    let mut node = leaf;
+    let mut index = vote_index;
+    for sibling in proof.iter() {
+        node = if index & 1 == 1 {
+            hashv(&[sibling, &node]).to_bytes()
+        } else {
+            hashv(&[&node, sibling]).to_bytes()
+        };
+        index >>= 1;
+    };
+
+    index == 0 && node == *root
+}
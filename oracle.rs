@@ -0,0 +1,87 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey
+};
+
+use crate::Errors;
+
+// Max serialized size of an `Option<OracleCondition>`
+pub const ORACLE_CONDITION_SPACE: usize = 1 + 32 + 1 + 8 + 4 + 8;
+
+// Pyth price account layout, see `pyth_sdk_solana::state::SolanaPriceAccount`
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_PRICE_OFFSET: usize = 208;
+const PYTH_STATUS_OFFSET: usize = 224;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Comparison {
+    Above,
+    Below
+}
+
+// Outcome condition on a Pyth price feed, read when the voting is finalized. The voting only has a
+// winner if the feed price compares to `threshold` as required. The feed is picked by the creator,
+// voters judge it like the rest of the voting.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OracleCondition {
+    pub feed: Pubkey,
+    pub comparison: Comparison,
+    // In units of 10^`expo`, the feed must use the same exponent
+    pub threshold: i64,
+    pub expo: i32,
+    // Max age of the feed price at finalization, in seconds
+    pub max_staleness: u64
+}
+
+impl OracleCondition {
+    pub fn validate(&self) -> ProgramResult {
+        if self.feed == Pubkey::default() || self.max_staleness == 0 {
+            return Err(Errors::InvalidOracleCondition.into());
+        };
+
+        Ok(())
+    }
+
+    pub fn evaluate(&self, feed: &AccountInfo, current_time: u64) -> Result<bool, ProgramError> {
+        if *feed.key != self.feed {
+            return Err(Errors::InvalidOracleFeed.into());
+        };
+
+        let data = feed.data.borrow();
+        if data.len() < PYTH_STATUS_OFFSET + 4
+            || read_u32(&data, 0) != PYTH_MAGIC
+            || read_u32(&data, PYTH_EXPO_OFFSET) as i32 != self.expo
+        {
+            return Err(Errors::InvalidOracleFeed.into());
+        };
+
+        let published_at = read_u64(&data, PYTH_TIMESTAMP_OFFSET);
+        if read_u32(&data, PYTH_STATUS_OFFSET) != PYTH_STATUS_TRADING
+            || current_time.saturating_sub(published_at) > self.max_staleness
+        {
+            return Err(Errors::StaleOracleFeed.into());
+        };
+
+        let price = read_u64(&data, PYTH_PRICE_OFFSET) as i64;
+        Ok(match self.comparison {
+            Comparison::Above => price > self.threshold,
+            Comparison::Below => price < self.threshold
+        })
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,9932 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{ next_account_info, AccountInfo },
+    entrypoint::ProgramResult,
+    msg,
+    program_error::{ PrintProgramError, ProgramError },
+    pubkey::Pubkey,
+    keccak::hashv,
+    system_program::ID as system_program_address,
+    borsh0_10::try_from_slice_unchecked,
+    sysvar::{
+        Sysvar,
+        clock,
+        instructions::{ load_current_index_checked, load_instruction_at_checked, ID as instructions_sysvar_address }
+    },
+    program::{ invoke, invoke_signed, set_return_data },
+    system_instruction::{ create_account, transfer },
+    rent,
+    bpf_loader_upgradeable
+};
+use spl_token_2022::{
+    extension::ExtensionType,
+    instruction as token_instruction,
+    state::{ Account as TokenAccount, Mint }
+};
+
+use crate::{
+    buckets::{ bucket_of, probe, RecordSlot, BUCKET_SLOTS, MAX_RECORD_BUCKETS },
+    checks::{ assert_accounts_len, assert_signer, assert_writable, assert_owned_by, assert_pda, assert_program, assert_uninitialized, assert_account_type, assert_current_version },
+    eligibility::EligibilityProof,
+    error::Errors,
+    execution::{ fill_template, permits, validate_allowlist, validate_template, AllowedAction, MAX_PROPOSAL_INSTRUCTIONS },
+    instruction::*,
+    merkle::{ record_leaf, verify_record_proof, RecordTree },
+    state::*,
+    tally::{ percentages, shard_of, RankedMethod, TallyMode, TiePolicy, MAX_SEATS, MAX_TALLY_SHARDS },
+    weight::{ token_balance, WeightProof, WeightSettings, WeightSource }
+};
+
+// Sysvars are read once per instruction and handed to the handlers that need them. Handlers never
+// read the clock themselves, so `process` can be driven with a fixed `Clock` outside the runtime.
+pub(crate) struct Sysvars {
+    pub(crate) clock: clock::Clock,
+    pub(crate) rent: rent::Rent
+}
+
+impl Sysvars {
+    pub(crate) fn get() -> Result<Self, ProgramError> {
+        Ok(Self {
+            clock: clock::Clock::get()?,
+            rent: rent::Rent::get()?
+        })
+    }
+
+    fn current_time(&self) -> u64 {
+        self.clock.unix_timestamp as u64
+    }
+}
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction_data: &[u8]
+) -> ProgramResult {
+    // Local test setups deploying under a different address can opt out with `skip-id-check`
+    #[cfg(not(feature = "skip-id-check"))]
+    if *program_id != crate::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    let result = process(program_id, accounts, instruction_data, &Sysvars::get()?);
+    if let Err(error) = &result {
+        error.print::<Errors>();
+    };
+
+    result
+}
+
+pub(crate) fn process<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction_data: &[u8],
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let instruction = VotingInstruction::unpack(instruction_data)?;
+
+    // Guarded instructions take the config account first, so they can be disabled by the admin
+    let (config, accounts) = match instruction.flag() {
+        Some(flag) => {
+            let (config, accounts) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            (check_instruction_enabled(program_id, config, flag)?, accounts)
+        },
+        None => (None, accounts)
+    };
+
+    match instruction {
+        VotingInstruction::CreateVoting(ix_data) => {
+            let accounts = CreateVotingAccounts::parse(program_id, accounts, &ix_data.title, &ix_data.co_creators)?;
+            process_create_voting(program_id, accounts, ix_data, config.as_ref(), sysvars)
+        },
+        VotingInstruction::Vote(ix_data) => {
+            let accounts = VoteAccounts::parse(program_id, accounts, ix_data.memo, ix_data.voter_index_page)?;
+            process_vote(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::UpdateVote(ix_data) => {
+            let accounts = UpdateVoteAccounts::parse(program_id, accounts, ix_data.memo)?;
+            process_update_vote(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::EditVoting(ix_data) => {
+            process_edit_voting(program_id, EditVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::UpdateTitle(ix_data) => {
+            process_update_title(program_id, EditVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::AddOption(ix_data) => {
+            process_add_option(program_id, EditVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::RemoveOption(ix_data) => {
+            process_remove_option(program_id, EditVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::SetOptionOrder(ix_data) => {
+            process_set_option_order(program_id, EditVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::InitializeConfig => {
+            process_initialize_config(program_id, InitializeConfigAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::SetInstructionFlags(ix_data) => {
+            process_set_instruction_flags(program_id, ConfigAdminAccounts::parse(accounts)?, ix_data)
+        },
+        VotingInstruction::ProposeAdmin(ix_data) => {
+            process_propose_admin(program_id, ConfigAdminAccounts::parse(accounts)?, ix_data)
+        },
+        VotingInstruction::AcceptAdmin => {
+            process_accept_admin(program_id, ConfigAdminAccounts::parse(accounts)?)
+        },
+        VotingInstruction::SetMaxActiveVotings(ix_data) => {
+            process_set_max_active_votings(program_id, ConfigAdminAccounts::parse(accounts)?, ix_data)
+        },
+        VotingInstruction::SetStartTimeTolerance(ix_data) => {
+            process_set_start_time_tolerance(program_id, ConfigAdminAccounts::parse(accounts)?, ix_data)
+        },
+        VotingInstruction::GetMyVote => {
+            process_get_my_vote(GetMyVoteAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::SnapshotTally => {
+            process_snapshot_tally(program_id, SnapshotTallyAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::ClaimCertificate => {
+            process_claim_certificate(program_id, ClaimCertificateAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::FundRewards(ix_data) => {
+            process_fund_rewards(program_id, FundRewardsAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::ClaimWinnings => {
+            process_claim_winnings(program_id, ClaimWinningsAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::TopUpRent => {
+            process_top_up_rent(TopUpRentAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::CreateRealm(ix_data) => {
+            let accounts = CreateRealmAccounts::parse(program_id, accounts, &ix_data.name)?;
+            process_create_realm(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::UpdateRealm(ix_data) => {
+            process_update_realm(program_id, RealmAdminAccounts::parse(program_id, accounts)?, ix_data)
+        },
+        VotingInstruction::CreateRealmVoting(ix_data) => {
+            let accounts = CreateRealmVotingAccounts::parse(program_id, accounts, &ix_data.co_creators)?;
+            process_create_realm_voting(program_id, accounts, ix_data, config.as_ref(), sysvars)
+        },
+        VotingInstruction::JoinRealm => {
+            process_join_realm(program_id, MembershipAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::LeaveRealm => {
+            process_leave_realm(program_id, MembershipAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::Boost(ix_data) => {
+            process_boost(program_id, BoostAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::ReleaseBoost => {
+            process_release_boost(program_id, ReleaseBoostAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::RefundBoost => {
+            process_refund_boost(RefundBoostAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::GetOptionCapacity(ix_data) => {
+            process_get_option_capacity(program_id, GetOptionCapacityAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::ClaimSlot(ix_data) => {
+            process_claim_slot(program_id, ClaimSlotAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::CommitDraw => {
+            process_commit_draw(DrawAccounts::parse(program_id, accounts, false)?, sysvars)
+        },
+        VotingInstruction::RevealDraw => {
+            process_reveal_draw(DrawAccounts::parse(program_id, accounts, true)?, sysvars)
+        },
+        VotingInstruction::ClaimDraw => {
+            process_claim_draw(program_id, ClaimDrawAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::FinalizeVoting => {
+            process_finalize_voting(program_id, FinalizeVotingAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::SetExecutionAllowlist(ix_data) => {
+            let accounts = ExecutionAllowlistAccounts::parse(program_id, accounts)?;
+            process_set_execution_allowlist(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::AddProposalInstruction(ix_data) => {
+            let accounts = AddProposalInstructionAccounts::parse(program_id, accounts)?;
+            process_add_proposal_instruction(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::ExecuteNextInstruction => {
+            process_execute_next_instruction(program_id, ExecuteNextInstructionAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::EnableTokenBallots(ix_data) => {
+            process_enable_token_ballots(program_id, EnableTokenBallotsAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::CountTokenBallots => {
+            process_count_token_ballots(program_id, CountTokenBallotsAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::StartRecount => {
+            process_start_recount(program_id, RecountAccounts::parse(program_id, accounts, Some(1))?, sysvars)
+        },
+        VotingInstruction::RecountVotes => {
+            process_recount_votes(program_id, RecountAccounts::parse(program_id, accounts, None)?, sysvars)
+        },
+        VotingInstruction::CompleteRecount => {
+            process_complete_recount(program_id, RecountAccounts::parse(program_id, accounts, Some(1))?, sysvars)
+        },
+        VotingInstruction::SetVotingMetadata(ix_data) => {
+            let accounts = VotingMetadataAccounts::parse(program_id, accounts)?;
+            process_set_voting_metadata(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::UpgradeProgram => {
+            process_upgrade_program(program_id, UpgradeProgramAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::SetUpgradeAuthority => {
+            process_set_upgrade_authority(program_id, SetUpgradeAuthorityAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::GetVoteWeight(ix_data) => {
+            process_get_vote_weight(program_id, GetVoteWeightAccounts::parse(program_id, accounts)?, ix_data)
+        },
+        VotingInstruction::AppendAllowlistChunk(ix_data) => {
+            let accounts = AppendAllowlistChunkAccounts::parse(program_id, accounts)?;
+            process_append_allowlist_chunk(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::SealAllowlist => {
+            process_seal_allowlist(program_id, SealAllowlistAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::SetAllowlistRoot(ix_data) => {
+            let accounts = SetAllowlistRootAccounts::parse(program_id, accounts)?;
+            process_set_allowlist_root(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::AllocateVoteBucket(ix_data) => {
+            let accounts = AllocateVoteBucketAccounts::parse(program_id, accounts, ix_data.index)?;
+            process_allocate_vote_bucket(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::ReserveTitle(ix_data) => {
+            let accounts = ReserveTitleAccounts::parse(program_id, accounts, &ix_data.title_hash)?;
+            process_reserve_title(program_id, accounts, sysvars)
+        },
+        VotingInstruction::PublishVoting(ix_data) => {
+            let accounts = PublishVotingAccounts::parse(program_id, accounts)?;
+            process_publish_voting(program_id, accounts, ix_data, config.as_ref(), sysvars)
+        },
+        VotingInstruction::CastEnvelope(ix_data) => {
+            let accounts = VoteAccounts::parse(program_id, accounts, false, ix_data.voter_index_page)?;
+            process_cast_envelope(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::SubmitEnvelopeResults(ix_data) => {
+            let accounts = SubmitEnvelopeResultsAccounts::parse(program_id, accounts)?;
+            process_submit_envelope_results(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::CommitTieBreak => {
+            let accounts = DrawAccounts::parse(program_id, accounts, false)?;
+            process_commit_tie_break(accounts, sysvars)
+        },
+        VotingInstruction::RevealTieBreak => {
+            let accounts = DrawAccounts::parse(program_id, accounts, true)?;
+            process_reveal_tie_break(accounts, sysvars)
+        },
+        VotingInstruction::SetProposalTemplate(ix_data) => {
+            let accounts = ProposalTemplateAccounts::parse(program_id, accounts, ix_data.id)?;
+            process_set_proposal_template(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::AddTemplateInstruction(ix_data) => {
+            let accounts = AddTemplateInstructionAccounts::parse(program_id, accounts)?;
+            process_add_template_instruction(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::CloneVoting(ix_data) => {
+            let accounts = CloneVotingAccounts::parse(program_id, accounts)?;
+            process_clone_voting(program_id, accounts, ix_data, config.as_ref(), sysvars)
+        },
+        VotingInstruction::ArchiveVoting => {
+            let accounts = ArchiveVotingAccounts::parse(program_id, accounts)?;
+            process_archive_voting(program_id, accounts, sysvars)
+        },
+        VotingInstruction::MerkleizeRecords => {
+            let accounts = RecordTreeAccounts::parse(program_id, accounts)?;
+            process_merkleize_records(program_id, accounts, sysvars)
+        },
+        VotingInstruction::VerifyBallotProof(ix_data) => {
+            process_verify_ballot_proof(VerifyBallotProofAccounts::parse(program_id, accounts)?, ix_data)
+        },
+        VotingInstruction::FlagVoting => {
+            let accounts = FlagAccounts::parse(program_id, accounts)?;
+            process_flag_voting(program_id, accounts, sysvars)
+        },
+        VotingInstruction::CloseFlag => {
+            let accounts = FlagAccounts::parse(program_id, accounts)?;
+            process_close_flag(accounts, sysvars)
+        },
+        VotingInstruction::HideVoting(ix_data) => {
+            let accounts = HideVotingAccounts::parse(program_id, accounts)?;
+            process_hide_voting(program_id, accounts, ix_data)
+        },
+        VotingInstruction::FundIncentives(ix_data) => {
+            let accounts = IncentivePoolAccounts::parse(program_id, accounts)?;
+            process_fund_incentives(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::WithdrawIncentives => {
+            let accounts = IncentivePoolAccounts::parse(program_id, accounts)?;
+            process_withdraw_incentives(accounts, sysvars)
+        },
+        VotingInstruction::GetResults => {
+            process_get_results(program_id, GetOptionCapacityAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::SetLocalizedMetadata(ix_data) => {
+            let accounts = LocalizedMetadataAccounts::parse(program_id, accounts, &ix_data.language)?;
+            process_set_localized_metadata(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::SetNotificationTarget(ix_data) => {
+            let accounts = NotificationTargetAccounts::parse(program_id, accounts)?;
+            process_set_notification_target(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::SetCreationDeposit(ix_data) => {
+            process_set_creation_deposit(program_id, ConfigAdminAccounts::parse(accounts)?, ix_data)
+        },
+        VotingInstruction::GcExpired => {
+            let accounts = GcExpiredAccounts::parse(program_id, accounts)?;
+            process_gc_expired(program_id, accounts, sysvars)
+        },
+        VotingInstruction::SyncStatus => {
+            let accounts = SyncStatusAccounts::parse(program_id, accounts)?;
+            process_sync_status(program_id, accounts, sysvars)
+        },
+        VotingInstruction::AttestResult => {
+            process_attest_result(AttestResultAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::GetPercentages(ix_data) => {
+            process_get_percentages(program_id, GetOptionCapacityAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::CancelVoting => {
+            process_cancel_voting(program_id, CancelVotingAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::RestoreVoting => {
+            process_restore_voting(program_id, CancelVotingAccounts::parse(program_id, accounts)?, config.as_ref(), sysvars)
+        },
+        VotingInstruction::AllocateTallyShard(ix_data) => {
+            let accounts = AllocateTallyShardAccounts::parse(program_id, accounts, ix_data.index)?;
+            process_allocate_tally_shard(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::CastShardedVote(ix_data) => {
+            let accounts = CastShardedVoteAccounts::parse(program_id, accounts, ix_data.voter_index_page)?;
+            process_cast_sharded_vote(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::MergeTallyShards => {
+            process_merge_tally_shards(program_id, MergeTallyShardsAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::AddWeight(ix_data) => {
+            process_add_weight(program_id, AddWeightAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::LinkProposalNote(ix_data) => {
+            let accounts = LinkProposalNoteAccounts::parse(program_id, accounts, &ix_data.mint)?;
+            process_link_proposal_note(accounts, ix_data, sysvars)
+        },
+        VotingInstruction::VoteByTransferClaim(ix_data) => {
+            let accounts = VoteByTransferClaimAccounts::parse(program_id, accounts, ix_data.voter_index_page)?;
+            process_vote_by_transfer_claim(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::WithdrawKiosk => {
+            process_withdraw_kiosk(WithdrawKioskAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::CloseUserVote => {
+            process_close_user_vote(program_id, CloseUserVoteAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::CloseVoting => {
+            process_close_voting(CloseVotingAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        #[cfg(feature = "devnet")]
+        VotingInstruction::ClaimFaucet => {
+            process_claim_faucet(program_id, ClaimFaucetAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        #[cfg(not(feature = "devnet"))]
+        VotingInstruction::ClaimFaucet => Err(Errors::FaucetDisabled.into()),
+        VotingInstruction::GetCreatorSummary => {
+            process_get_creator_summary(program_id, CreatorSummaryAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::CommitVote(ix_data) => {
+            let accounts = VoteAccounts::parse(program_id, accounts, false, ix_data.voter_index_page)?;
+            process_commit_vote(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::RevealVote(ix_data) => {
+            let accounts = UpdateVoteAccounts::parse(program_id, accounts, false)?;
+            process_reveal_vote(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::CreateElection(ix_data) => {
+            let accounts = CreateElectionAccounts::parse(program_id, accounts, ix_data.id)?;
+            process_create_election(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::FinalizeElection => {
+            process_finalize_election(program_id, FinalizeElectionAccounts::parse(program_id, accounts)?, sysvars)
+        },
+        VotingInstruction::ExtendVoting(ix_data) => {
+            process_extend_voting(program_id, ExtendVotingAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::FreezeVoting => {
+            process_set_frozen(program_id, FreezeVotingAccounts::parse(program_id, accounts)?, true, sysvars)
+        },
+        VotingInstruction::UnfreezeVoting => {
+            process_set_frozen(program_id, FreezeVotingAccounts::parse(program_id, accounts)?, false, sysvars)
+        },
+        VotingInstruction::SetLateRegistration(ix_data) => {
+            let accounts = SealAllowlistAccounts::parse(program_id, accounts)?;
+            process_set_late_registration(program_id, accounts, ix_data, sysvars)
+        },
+        VotingInstruction::SealVoterRoll(ix_data) => {
+            process_seal_voter_roll(program_id, SealVoterRollAccounts::parse(program_id, accounts)?, ix_data, sysvars)
+        },
+        VotingInstruction::MigrateAccount => {
+            process_migrate_account(MigrateAccountAccounts::parse(program_id, accounts)?)
+        },
+        VotingInstruction::SyncPollCard => {
+            process_sync_poll_card(program_id, SyncPollCardAccounts::parse(program_id, accounts)?, sysvars)
+        }
+    }
+}
+
+// Account contexts validate signer/writable/owner/PDA constraints when constructed, so
+// handlers only receive accounts that already satisfy them.
+// Co-creators follow the system program, in the order of `CreateVotingInstruction::co_creators`.
+// The title index and reservation are checked by `index_voting_title` and `consume_title_reservation`.
+struct CreateVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    creator_stats: &'a AccountInfo<'a>,
+    title_index: &'a AccountInfo<'a>,
+    title_reservation: &'a AccountInfo<'a>,
+    registry: &'a AccountInfo<'a>,
+    registry_page: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    voting_bump: u8,
+    creator_stats_bump: u8,
+    // The user plus every co-creator that signed
+    creator_signatures: usize
+}
+
+impl<'a> CreateVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        title: &str,
+        co_creators: &[Pubkey]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 8 + co_creators.len())?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let creator_stats = next_account_info(accounts)?;
+        let title_index = next_account_info(accounts)?;
+        let title_reservation = next_account_info(accounts)?;
+        let registry = next_account_info(accounts)?;
+        let registry_page = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+        let creator_signatures = count_creator_signatures(accounts, user, co_creators)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(creator_stats, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(title_index, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(title_reservation, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(registry, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(registry_page, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let voting_bump = assert_pda(voting_account, find_voting_pda(title, program_id))?;
+        let creator_stats_bump = assert_pda(creator_stats, find_creator_stats_pda(user.key, program_id))?;
+
+        Ok(Self {
+            user,
+            voting_account,
+            creator_stats,
+            title_index,
+            title_reservation,
+            registry,
+            registry_page,
+            system_program,
+            voting_bump,
+            creator_stats_bump,
+            creator_signatures
+        })
+    }
+}
+
+// The source voting, then the accounts of `create_voting`. They are parsed by the handler, as the
+// co-creators come from the source voting.
+struct CloneVotingAccounts<'a> {
+    source_voting: &'a AccountInfo<'a>,
+    create_accounts: &'a [AccountInfo<'a>]
+}
+
+impl<'a> CloneVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let (source_voting, create_accounts) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        assert_owned_by(source_voting, program_id)?;
+
+        Ok(Self { source_voting, create_accounts })
+    }
+}
+
+// The voting PDA depends on its realm, so it is checked against the stored data by `check_voted_title`.
+// Members-only votings also take the voter's membership account, after the memo program.
+struct VoteAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    voter_index: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    memo_program: Option<&'a AccountInfo<'a>>,
+    // The incentive pool on votings with incentives, the voting it depends on, then the membership account
+    // on members-only votings, then the gate accounts of the eligibility clause, then the weight accounts
+    // of the weight source
+    remaining_accounts: std::slice::Iter<'a, AccountInfo<'a>>,
+    voter_index_page: u16
+}
+
+impl<'a> VoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        memo: bool,
+        voter_index_page: u16
+    ) -> Result<Self, ProgramError> {
+        // The remaining accounts depend on the voting, the handler checks none are left over.
+        let mut accounts = accounts.iter();
+        let user = next_account_info(&mut accounts)?;
+        let voting_account = next_account_info(&mut accounts)?;
+        let user_vote_account = next_account_info(&mut accounts)?;
+        let voter_index = next_account_info(&mut accounts)?;
+        let poll_card = next_account_info(&mut accounts)?;
+        let system_program = next_account_info(&mut accounts)?;
+        let memo_program = parse_memo_program(&mut accounts, memo)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(user_vote_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(voter_index, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        // The user vote account is checked by the handler, bucketed votings take the voter's bucket instead.
+        // So is the voter index, by `add_to_voter_index`, and the poll card, by `update_poll_card`.
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, user_vote_account, voter_index, poll_card, system_program, memo_program, remaining_accounts: accounts, voter_index_page })
+    }
+}
+
+struct UpdateVoteAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    memo_program: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> UpdateVoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        memo: bool
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 5 + memo as usize)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+        let memo_program = parse_memo_program(accounts, memo)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(user_vote_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        // The user vote account is checked against the voter's PDA and record by the handler, bucketed
+        // votings take the voter's bucket instead.
+        Ok(Self { user, voting_account, user_vote_account, poll_card, system_program, memo_program })
+    }
+}
+
+// Co-creator accounts must follow `co_creators` in order. Counts the user plus every co-creator that signed.
+fn count_creator_signatures<'a>(
+    accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+    user: &AccountInfo,
+    co_creators: &[Pubkey]
+) -> Result<usize, ProgramError> {
+    let mut creator_signatures = 1;
+    for (index, co_creator) in co_creators.iter().enumerate() {
+        let co_creator_account = next_account_info(accounts)?;
+        if co_creator_account.key != co_creator
+            || co_creator == user.key
+            || co_creators[..index].contains(co_creator)
+        {
+            return Err(Errors::InvalidCoCreators.into());
+        };
+
+        if co_creator_account.is_signer {
+            creator_signatures += 1;
+        };
+    };
+
+    Ok(creator_signatures)
+}
+
+fn parse_memo_program<'a>(
+    accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+    memo: bool
+) -> Result<Option<&'a AccountInfo<'a>>, ProgramError> {
+    if memo == false {
+        return Ok(None);
+    };
+
+    let memo_program = next_account_info(accounts)?;
+    assert_program(memo_program, &spl_memo::id(), Errors::InvalidMemoProgram)?;
+
+    Ok(Some(memo_program))
+}
+
+// Shared by `edit_voting`, `add_option`, `remove_option` and `set_option_order`.
+// The voting PDA is derived from the stored title, so it is checked by `load_voting_for_edit`.
+struct EditVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>
+}
+
+impl<'a> EditVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        Ok(Self { user, voting_account, system_program })
+    }
+}
+
+struct InitializeConfigAccounts<'a> {
+    authority: &'a AccountInfo<'a>,
+    config: &'a AccountInfo<'a>,
+    program_data: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    config_bump: u8
+}
+
+impl<'a> InitializeConfigAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let authority = next_account_info(accounts)?;
+        let config = next_account_info(accounts)?;
+        let program_data = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(authority)?;
+        assert_writable(authority, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(config, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let config_bump = assert_pda(config, find_config_pda(program_id))?;
+
+        assert_program_data(program_id, program_data)?;
+
+        Ok(Self { authority, config, program_data, system_program, config_bump })
+    }
+}
+
+// Shared by `set_instruction_flags`, `propose_admin` and `accept_admin`.
+// The config PDA and owner are checked by `load_config`.
+struct ConfigAdminAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    config: &'a AccountInfo<'a>
+}
+
+impl<'a> ConfigAdminAccounts<'a> {
+    fn parse(accounts: &'a [AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let config = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(config, Errors::PDAsAccountMustBeMutable)?;
+
+        Ok(Self { admin, config })
+    }
+}
+
+// Read-only, the user doesn't need to sign to look up their ballot.
+struct GetMyVoteAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>
+}
+
+impl<'a> GetMyVoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { voting_account, user_vote_account })
+    }
+}
+
+// Permissionless, the payer only funds the snapshot account on the first call.
+struct SnapshotTallyAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    snapshot_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    snapshot_bump: u8
+}
+
+impl<'a> SnapshotTallyAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let snapshot_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(snapshot_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let snapshot_bump = assert_pda(snapshot_account, find_tally_snapshot_pda(voting_account.key, program_id))?;
+
+        Ok(Self { payer, voting_account, snapshot_account, system_program, snapshot_bump })
+    }
+}
+
+struct ClaimCertificateAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    certificate_mint: &'a AccountInfo<'a>,
+    certificate_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    token_program: &'a AccountInfo<'a>,
+    certificate_mint_bump: u8,
+    certificate_bump: u8
+}
+
+impl<'a> ClaimCertificateAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 7)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+        let certificate_mint = next_account_info(accounts)?;
+        let certificate_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+        let token_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(certificate_mint, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(certificate_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+        assert_program(token_program, &spl_token_2022::id(), Errors::InvalidTokenProgram)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+        let certificate_mint_bump = assert_pda(certificate_mint, find_certificate_mint_pda(voting_account.key, program_id))?;
+        let certificate_bump = assert_pda(certificate_account, find_certificate_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self {
+            user,
+            voting_account,
+            user_vote_account,
+            certificate_mint,
+            certificate_account,
+            system_program,
+            token_program,
+            certificate_mint_bump,
+            certificate_bump
+        })
+    }
+}
+
+struct FundRewardsAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    escrow_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    escrow_bump: u8
+}
+
+impl<'a> FundRewardsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let escrow_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(escrow_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let escrow_bump = assert_pda(escrow_account, find_reward_escrow_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, escrow_account, system_program, escrow_bump })
+    }
+}
+
+// Shared by `fund_incentives` and `withdraw_incentives`.
+struct IncentivePoolAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    pool: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    pool_bump: u8
+}
+
+impl<'a> IncentivePoolAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let pool = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(pool, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let pool_bump = assert_pda(pool, find_incentive_pool_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, pool, system_program, pool_bump })
+    }
+}
+
+struct ClaimWinningsAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    escrow_account: &'a AccountInfo<'a>,
+    claim_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    claim_bump: u8
+}
+
+impl<'a> ClaimWinningsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 6)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+        let escrow_account = next_account_info(accounts)?;
+        let claim_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(escrow_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(claim_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+        assert_owned_by(escrow_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+        assert_pda(escrow_account, find_reward_escrow_pda(voting_account.key, program_id))?;
+        let claim_bump = assert_pda(claim_account, find_reward_claim_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, user_vote_account, escrow_account, claim_account, system_program, claim_bump })
+    }
+}
+
+// Permissionless, any program account can be topped up to the rent-exempt minimum of its current size.
+struct TopUpRentAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>
+}
+
+impl<'a> TopUpRentAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        Ok(Self { payer, account, system_program })
+    }
+}
+
+struct CreateRealmAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    realm_bump: u8
+}
+
+impl<'a> CreateRealmAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        name: &str
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(admin, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(realm, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        if name.is_empty() || name.len() > MAX_REALM_NAME_LENGTH {
+            return Err(Errors::RealmNameInvalidLength.into());
+        };
+
+        let realm_bump = assert_pda(realm, find_realm_pda(name, program_id))?;
+
+        Ok(Self { admin, realm, system_program, realm_bump })
+    }
+}
+
+// The realm PDA is derived from the stored name, so it is checked by `load_realm`.
+struct RealmAdminAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>
+}
+
+impl<'a> RealmAdminAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(realm, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        Ok(Self { admin, realm })
+    }
+}
+
+// Co-creators follow the system program, like in `create_voting`. The voting PDA depends on the
+// realm's voting count, so it is checked by the handler.
+struct CreateRealmVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    realm_admin: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    creator_stats: &'a AccountInfo<'a>,
+    title_index: &'a AccountInfo<'a>,
+    title_reservation: &'a AccountInfo<'a>,
+    registry: &'a AccountInfo<'a>,
+    registry_page: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    creator_stats_bump: u8,
+    creator_signatures: usize
+}
+
+impl<'a> CreateRealmVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        co_creators: &[Pubkey]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 10 + co_creators.len())?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let realm_admin = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let creator_stats = next_account_info(accounts)?;
+        let title_index = next_account_info(accounts)?;
+        let title_reservation = next_account_info(accounts)?;
+        let registry = next_account_info(accounts)?;
+        let registry_page = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+        let creator_signatures = count_creator_signatures(accounts, user, co_creators)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(realm, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(realm_admin, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(creator_stats, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(title_index, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(title_reservation, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(registry, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(registry_page, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let creator_stats_bump = assert_pda(creator_stats, find_creator_stats_pda(user.key, program_id))?;
+
+        Ok(Self {
+            user,
+            realm,
+            realm_admin,
+            voting_account,
+            creator_stats,
+            title_index,
+            title_reservation,
+            registry,
+            registry_page,
+            system_program,
+            creator_stats_bump,
+            creator_signatures
+        })
+    }
+}
+
+// Shared by `join_realm` and `leave_realm`
+struct MembershipAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    membership: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    membership_bump: u8
+}
+
+impl<'a> MembershipAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let membership = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(realm, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(membership, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let membership_bump = assert_pda(membership, find_membership_pda(realm.key, user.key, program_id))?;
+
+        Ok(Self { user, realm, membership, system_program, membership_bump })
+    }
+}
+
+struct BoostAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    boost_account: &'a AccountInfo<'a>,
+    deposit_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    boost_bump: u8,
+    deposit_bump: u8
+}
+
+impl<'a> BoostAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 5)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let boost_account = next_account_info(accounts)?;
+        let deposit_account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(boost_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(deposit_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let boost_bump = assert_pda(boost_account, find_boost_pda(voting_account.key, program_id))?;
+        let deposit_bump = assert_pda(deposit_account, find_boost_deposit_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, boost_account, deposit_account, system_program, boost_bump, deposit_bump })
+    }
+}
+
+// Permissionless, the treasury is the config admin.
+struct ReleaseBoostAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    boost_account: &'a AccountInfo<'a>,
+    config: &'a AccountInfo<'a>,
+    treasury: &'a AccountInfo<'a>
+}
+
+impl<'a> ReleaseBoostAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+        let boost_account = next_account_info(accounts)?;
+        let config = next_account_info(accounts)?;
+        let treasury = next_account_info(accounts)?;
+
+        assert_writable(boost_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(treasury, Errors::UsersAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(boost_account, program_id)?;
+
+        assert_pda(boost_account, find_boost_pda(voting_account.key, program_id))?;
+
+        Ok(Self { voting_account, boost_account, config, treasury })
+    }
+}
+
+struct RefundBoostAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    boost_account: &'a AccountInfo<'a>,
+    deposit_account: &'a AccountInfo<'a>
+}
+
+impl<'a> RefundBoostAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let boost_account = next_account_info(accounts)?;
+        let deposit_account = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(boost_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(deposit_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(boost_account, program_id)?;
+        assert_owned_by(deposit_account, program_id)?;
+
+        assert_pda(boost_account, find_boost_pda(voting_account.key, program_id))?;
+        assert_pda(deposit_account, find_boost_deposit_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, boost_account, deposit_account })
+    }
+}
+
+struct GetOptionCapacityAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>
+}
+
+impl<'a> GetOptionCapacityAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 1)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { voting_account })
+    }
+}
+
+// Read-only, the user doesn't need to sign to look up their weight.
+struct GetVoteWeightAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    // The weight accounts of the weight source, as for `vote`
+    remaining_accounts: std::slice::Iter<'a, AccountInfo<'a>>
+}
+
+impl<'a> GetVoteWeightAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let mut accounts = accounts.iter();
+        let user = next_account_info(&mut accounts)?;
+        let voting_account = next_account_info(&mut accounts)?;
+
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, remaining_accounts: accounts })
+    }
+}
+
+// The slot account is checked by the handler, its PDA depends on the voting's claimed slots.
+struct ClaimSlotAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    slot_account: &'a AccountInfo<'a>,
+    slot_claim_account: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    // The membership account on members-only votings, then the gate accounts of the eligibility clause
+    remaining_accounts: std::slice::Iter<'a, AccountInfo<'a>>,
+    slot_claim_bump: u8
+}
+
+impl<'a> ClaimSlotAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let mut accounts = accounts.iter();
+        let user = next_account_info(&mut accounts)?;
+        let voting_account = next_account_info(&mut accounts)?;
+        let poll_card = next_account_info(&mut accounts)?;
+        let slot_account = next_account_info(&mut accounts)?;
+        let slot_claim_account = next_account_info(&mut accounts)?;
+        let system_program = next_account_info(&mut accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(slot_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(slot_claim_account, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_owned_by(voting_account, program_id)?;
+        let slot_claim_bump = assert_pda(slot_claim_account, find_slot_claim_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, poll_card, slot_account, slot_claim_account, system_program, remaining_accounts: accounts, slot_claim_bump })
+    }
+}
+
+// Permissionless, anyone can commit and reveal the draw or the tie break of a closed voting.
+struct DrawAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    // Only for `reveal_draw` and `reveal_tie_break`
+    slot_hashes: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> DrawAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        reveal: bool
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 1 + reveal as usize)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+        let slot_hashes = if reveal { Some(next_account_info(accounts)?) } else { None };
+
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { voting_account, slot_hashes })
+    }
+}
+
+// Permissionless, the drawn voter's user vote account is enough to record the winner.
+struct ClaimDrawAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>
+}
+
+impl<'a> ClaimDrawAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        Ok(Self { voting_account, user_vote_account })
+    }
+}
+
+// Permissionless once the voting ended, the oracle feed follows the creator stats if the voting has a condition.
+// `deposit_recipient` gets the creation deposit, see `process_finalize_voting`.
+struct FinalizeVotingAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    creator_stats: &'a AccountInfo<'a>,
+    instructions_sysvar: &'a AccountInfo<'a>,
+    deposit_recipient: &'a AccountInfo<'a>,
+    oracle_feed: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> FinalizeVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let creator_stats = next_account_info(accounts)?;
+        let instructions_sysvar = next_account_info(accounts)?;
+        let deposit_recipient = next_account_info(accounts)?;
+        let oracle_feed = accounts.next();
+
+        if accounts.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(creator_stats, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(deposit_recipient, Errors::UsersAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(creator_stats, program_id)?;
+
+        if *instructions_sysvar.key != instructions_sysvar_address {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        Ok(Self { voting_account, poll_card, creator_stats, instructions_sysvar, deposit_recipient, oracle_feed })
+    }
+}
+
+struct ExecutionAllowlistAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    allowlist_bump: u8
+}
+
+impl<'a> ExecutionAllowlistAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(admin, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(allowlist, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let allowlist_bump = assert_pda(allowlist, find_execution_allowlist_pda(realm.key, program_id))?;
+
+        Ok(Self { admin, realm, allowlist, system_program, allowlist_bump })
+    }
+}
+
+struct ProposalTemplateAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    template: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    template_bump: u8
+}
+
+impl<'a> ProposalTemplateAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        id: u16
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let template = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(admin, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(template, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let template_bump = assert_pda(template, find_proposal_template_pda(realm.key, id, program_id))?;
+
+        Ok(Self { admin, realm, template, system_program, template_bump })
+    }
+}
+
+struct AddProposalInstructionAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    proposal: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    proposal_bump: u8
+}
+
+impl<'a> AddProposalInstructionAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 5)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let proposal = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(proposal, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let proposal_bump = assert_pda(proposal, find_proposal_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, proposal, allowlist, system_program, proposal_bump })
+    }
+}
+
+// The accounts of `add_proposal_instruction`, then the template
+struct AddTemplateInstructionAccounts<'a> {
+    proposal_accounts: AddProposalInstructionAccounts<'a>,
+    template: &'a AccountInfo<'a>
+}
+
+impl<'a> AddTemplateInstructionAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 6)?;
+
+        let (accounts, template) = accounts.split_at(5);
+        let proposal_accounts = AddProposalInstructionAccounts::parse(program_id, accounts)?;
+        let template = &template[0];
+
+        assert_owned_by(template, program_id)?;
+
+        Ok(Self { proposal_accounts, template })
+    }
+}
+
+// Permissionless. The instruction's accounts follow in the stored order, then its program.
+struct ExecuteNextInstructionAccounts<'a> {
+    proposal: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>,
+    governance: &'a AccountInfo<'a>,
+    instruction_accounts: &'a [AccountInfo<'a>]
+}
+
+impl<'a> ExecuteNextInstructionAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 5 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, instruction_accounts) = accounts.split_at(4);
+        let accounts = &mut accounts.iter();
+        let proposal = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+        let governance = next_account_info(accounts)?;
+
+        assert_writable(proposal, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(proposal, program_id)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_pda(proposal, find_proposal_pda(voting_account.key, program_id))?;
+
+        Ok(Self { proposal, voting_account, allowlist, governance, instruction_accounts })
+    }
+}
+
+struct EnableTokenBallotsAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    ballot_box: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    ballot_box_bump: u8
+}
+
+impl<'a> EnableTokenBallotsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let ballot_box = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(ballot_box, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let ballot_box_bump = assert_pda(ballot_box, find_ballot_box_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, ballot_box, system_program, ballot_box_bump })
+    }
+}
+
+// Permissionless crank, the payer covers tally state growth. The ballot token accounts follow the
+// system program, one per option in order.
+struct CountTokenBallotsAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    ballot_box: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    token_accounts: &'a [AccountInfo<'a>]
+}
+
+impl<'a> CountTokenBallotsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 5 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, token_accounts) = accounts.split_at(5);
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let ballot_box = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(ballot_box, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(ballot_box, program_id)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_pda(ballot_box, find_ballot_box_pda(voting_account.key, program_id))?;
+
+        Ok(Self { payer, ballot_box, voting_account, poll_card, system_program, token_accounts })
+    }
+}
+
+// Shared by the recount instructions, all run by the voting's creator. `start_recount` takes the
+// ballot box after the system program, `recount_votes` the user vote accounts to count and
+// `complete_recount` the voting's poll card.
+struct RecountAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    recount: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    other_accounts: &'a [AccountInfo<'a>],
+    recount_bump: u8
+}
+
+impl<'a> RecountAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        // `None` for any number of other accounts
+        other_accounts_len: Option<usize>
+    ) -> Result<Self, ProgramError> {
+        match other_accounts_len {
+            Some(len) => assert_accounts_len(accounts, 4 + len)?,
+            None if accounts.len() < 4 => return Err(ProgramError::NotEnoughAccountKeys),
+            None => ()
+        };
+
+        let (accounts, other_accounts) = accounts.split_at(4);
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let recount = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(recount, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let recount_bump = assert_pda(recount, find_recount_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, recount, system_program, other_accounts, recount_bump })
+    }
+}
+
+struct VotingMetadataAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    metadata_bump: u8
+}
+
+impl<'a> VotingMetadataAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let metadata = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(metadata, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let metadata_bump = assert_pda(metadata, find_voting_metadata_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, metadata, system_program, metadata_bump })
+    }
+}
+
+struct NotificationTargetAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    target: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    target_bump: u8
+}
+
+impl<'a> NotificationTargetAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let target = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(target, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let target_bump = assert_pda(target, find_notification_target_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, target, system_program, target_bump })
+    }
+}
+
+struct LocalizedMetadataAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    metadata_bump: u8
+}
+
+impl<'a> LocalizedMetadataAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        language: &str
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let metadata = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(metadata, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        // Checked before deriving the PDA, a seed can't be longer than 32 bytes.
+        validate_language(language)?;
+        let metadata_bump = assert_pda(metadata, find_localized_metadata_pda(voting_account.key, language, program_id))?;
+
+        Ok(Self { user, voting_account, metadata, system_program, metadata_bump })
+    }
+}
+
+// Upgrade governance: once the program's upgrade authority is a realm's governance PDA, passed proposals
+// of the realm upgrade the program by executing `upgrade_program` or `set_upgrade_authority`. Only
+// `execute_next_instruction` can sign for the governance PDA. Allowlisting these two instructions
+// instead of the loader keeps proposals from touching other programs the PDA governs.
+struct UpgradeProgramAccounts<'a> {
+    governance: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    program_data: &'a AccountInfo<'a>,
+    program: &'a AccountInfo<'a>,
+    // Its authority has to be the governance PDA too
+    buffer: &'a AccountInfo<'a>,
+    spill: &'a AccountInfo<'a>,
+    rent_sysvar: &'a AccountInfo<'a>,
+    clock_sysvar: &'a AccountInfo<'a>,
+    loader: &'a AccountInfo<'a>
+}
+
+impl<'a> UpgradeProgramAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 9)?;
+
+        let accounts = &mut accounts.iter();
+        let governance = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let program_data = next_account_info(accounts)?;
+        let program = next_account_info(accounts)?;
+        let buffer = next_account_info(accounts)?;
+        let spill = next_account_info(accounts)?;
+        let rent_sysvar = next_account_info(accounts)?;
+        let clock_sysvar = next_account_info(accounts)?;
+        let loader = next_account_info(accounts)?;
+
+        assert_signer(governance)?;
+        assert_writable(program_data, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(program, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(buffer, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(spill, Errors::UsersAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(loader, &bpf_loader_upgradeable::id(), Errors::InvalidLoaderProgram)?;
+        assert_program(program, program_id, Errors::InvalidProgramData)?;
+        assert_program_data(program_id, program_data)?;
+
+        Ok(Self { governance, realm, program_data, program, buffer, spill, rent_sysvar, clock_sysvar, loader })
+    }
+}
+
+// The new authority follows the loader, without it the program becomes immutable.
+struct SetUpgradeAuthorityAccounts<'a> {
+    governance: &'a AccountInfo<'a>,
+    realm: &'a AccountInfo<'a>,
+    program_data: &'a AccountInfo<'a>,
+    loader: &'a AccountInfo<'a>,
+    new_authority: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> SetUpgradeAuthorityAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let accounts = &mut accounts.iter();
+        let governance = next_account_info(accounts)?;
+        let realm = next_account_info(accounts)?;
+        let program_data = next_account_info(accounts)?;
+        let loader = next_account_info(accounts)?;
+        let new_authority = accounts.next();
+
+        if accounts.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        assert_signer(governance)?;
+        assert_writable(program_data, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(realm, program_id)?;
+
+        assert_program(loader, &bpf_loader_upgradeable::id(), Errors::InvalidLoaderProgram)?;
+        assert_program_data(program_id, program_data)?;
+
+        Ok(Self { governance, realm, program_data, loader, new_authority })
+    }
+}
+
+// The page account is checked by the handler, its PDA depends on the voters uploaded so far.
+struct AppendAllowlistChunkAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>,
+    page: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    allowlist_bump: u8
+}
+
+impl<'a> AppendAllowlistChunkAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 5)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+        let page = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(allowlist, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(page, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let allowlist_bump = assert_pda(allowlist, find_voter_allowlist_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, allowlist, page, system_program, allowlist_bump })
+    }
+}
+
+struct SealAllowlistAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>
+}
+
+impl<'a> SealAllowlistAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(allowlist, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(allowlist, program_id)?;
+
+        assert_pda(allowlist, find_voter_allowlist_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, allowlist })
+    }
+}
+
+struct SealVoterRollAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>
+}
+
+impl<'a> SealVoterRollAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account })
+    }
+}
+
+struct SetAllowlistRootAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    allowlist: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    allowlist_bump: u8
+}
+
+impl<'a> SetAllowlistRootAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let allowlist = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(allowlist, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let allowlist_bump = assert_pda(allowlist, find_voter_allowlist_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, allowlist, system_program, allowlist_bump })
+    }
+}
+
+// Permissionless, usually the creator allocates every bucket before the voting starts.
+struct AllocateVoteBucketAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    bucket: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    bucket_bump: u8
+}
+
+impl<'a> AllocateVoteBucketAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        index: u8
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let bucket = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(bucket, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let bucket_bump = assert_pda(bucket, find_vote_bucket_pda(voting_account.key, index, program_id))?;
+
+        Ok(Self { payer, voting_account, bucket, system_program, bucket_bump })
+    }
+}
+
+struct ReserveTitleAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    reservation: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    title_hash: [u8; 32],
+    reservation_bump: u8
+}
+
+impl<'a> ReserveTitleAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        title_hash: &[u8; 32]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let reservation = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(reservation, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let reservation_bump = assert_pda(reservation, find_title_reservation_pda(title_hash, program_id))?;
+
+        Ok(Self { user, reservation, system_program, title_hash: *title_hash, reservation_bump })
+    }
+}
+
+// The voting PDA is derived from the stored title, so it is checked by `load_voting_for_edit`.
+struct PublishVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>
+}
+
+impl<'a> PublishVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, poll_card })
+    }
+}
+
+// `realm` is required for realm votings, whose voting time the realm bounds
+struct ExtendVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    realm: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> ExtendVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let realm = accounts.next();
+
+        if accounts.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, poll_card, realm })
+    }
+}
+
+struct SubmitEnvelopeResultsAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>
+}
+
+impl<'a> SubmitEnvelopeResultsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, poll_card })
+    }
+}
+
+struct ArchiveVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    archive: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    archive_bump: u8
+}
+
+impl<'a> ArchiveVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let archive = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(archive, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let archive_bump = assert_pda(archive, find_voting_archive_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, archive, system_program, archive_bump })
+    }
+}
+
+// The vote records follow the system program, in `vote_index` order. Records in a bucket are found by
+// their index, so a bucket is passed once per record it holds.
+struct RecordTreeAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    record_tree: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    records: &'a [AccountInfo<'a>],
+    record_tree_bump: u8
+}
+
+impl<'a> RecordTreeAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 4 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, records) = accounts.split_at(4);
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let record_tree = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(record_tree, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let record_tree_bump = assert_pda(record_tree, find_record_tree_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, record_tree, system_program, records, record_tree_bump })
+    }
+}
+
+// Read-only, takes the voting or its archive.
+struct VerifyBallotProofAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>
+}
+
+impl<'a> VerifyBallotProofAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 1)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { voting_account })
+    }
+}
+
+// Shared by `flag_voting` and `close_flag`.
+struct FlagAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    flag: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    flag_bump: u8
+}
+
+impl<'a> FlagAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let flag = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(flag, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let flag_bump = assert_pda(flag, find_flag_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, flag, system_program, flag_bump })
+    }
+}
+
+// `authority` is the config PDA for the program admin, or the voting's realm for its admin.
+struct HideVotingAccounts<'a> {
+    admin: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    authority: &'a AccountInfo<'a>
+}
+
+impl<'a> HideVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let admin = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let authority = next_account_info(accounts)?;
+
+        assert_signer(admin)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(authority, program_id)?;
+
+        Ok(Self { admin, voting_account, authority })
+    }
+}
+
+// Permissionless. The voting's payer and creator stats are only used while the voting isn't a tombstone
+// yet. User vote records follow in (record, voter) pairs.
+struct GcExpiredAccounts<'a> {
+    caller: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    voting_payer: &'a AccountInfo<'a>,
+    creator_stats: &'a AccountInfo<'a>,
+    records: &'a [AccountInfo<'a>]
+}
+
+impl<'a> GcExpiredAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 4 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, records) = accounts.split_at(4);
+        // A record without its voter
+        if records.len() & 1 == 1 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let accounts = &mut accounts.iter();
+        let caller = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let voting_payer = next_account_info(accounts)?;
+        let creator_stats = next_account_info(accounts)?;
+
+        assert_signer(caller)?;
+        assert_writable(caller, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(voting_payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(creator_stats, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        for record in records.chunks(2) {
+            assert_writable(&record[0], Errors::PDAsAccountMustBeMutable)?;
+            assert_writable(&record[1], Errors::UsersAccountMustBeMutable)?;
+            assert_owned_by(&record[0], program_id)?;
+        };
+
+        Ok(Self { caller, voting_account, voting_payer, creator_stats, records })
+    }
+}
+
+// Permissionless
+struct SyncStatusAccounts<'a> {
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>
+}
+
+impl<'a> SyncStatusAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { voting_account, poll_card })
+    }
+}
+
+struct AttestResultAccounts<'a> {
+    attestor: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>
+}
+
+impl<'a> AttestResultAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 2)?;
+
+        let accounts = &mut accounts.iter();
+        let attestor = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+
+        assert_signer(attestor)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { attestor, voting_account })
+    }
+}
+
+// `restore_voting` takes the same accounts
+struct CancelVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    creator_stats: &'a AccountInfo<'a>
+}
+
+impl<'a> CancelVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let creator_stats = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(creator_stats, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(creator_stats, program_id)?;
+        assert_pda(creator_stats, find_creator_stats_pda(user.key, program_id))?;
+
+        Ok(Self { user, voting_account, poll_card, creator_stats })
+    }
+}
+
+struct AllocateTallyShardAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    shard: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    shard_bump: u8
+}
+
+impl<'a> AllocateTallyShardAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        index: u8
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let shard = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(shard, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let shard_bump = assert_pda(shard, find_tally_shard_pda(voting_account.key, index, program_id))?;
+
+        Ok(Self { payer, voting_account, shard, system_program, shard_bump })
+    }
+}
+
+// The voting is read-only, only the voter's shard is written
+struct CastShardedVoteAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    voter_index: &'a AccountInfo<'a>,
+    shard: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    // The voting it depends on, then the membership account on members-only votings, then the gate
+    // accounts of the eligibility clause, then the weight accounts of the weight source
+    remaining_accounts: std::slice::Iter<'a, AccountInfo<'a>>,
+    voter_index_page: u16
+}
+
+impl<'a> CastShardedVoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        voter_index_page: u16
+    ) -> Result<Self, ProgramError> {
+        let mut accounts = accounts.iter();
+        let user = next_account_info(&mut accounts)?;
+        let voting_account = next_account_info(&mut accounts)?;
+        let user_vote_account = next_account_info(&mut accounts)?;
+        let voter_index = next_account_info(&mut accounts)?;
+        let shard = next_account_info(&mut accounts)?;
+        let system_program = next_account_info(&mut accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(user_vote_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(voter_index, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(shard, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        // The user vote account and the shard are checked by the handler, which knows the voter's shard
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(shard, program_id)?;
+
+        Ok(Self { user, voting_account, user_vote_account, voter_index, shard, system_program, remaining_accounts: accounts, voter_index_page })
+    }
+}
+
+// The shards follow the system program in index order, every one of the voting's
+struct MergeTallyShardsAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    shards: &'a [AccountInfo<'a>]
+}
+
+impl<'a> MergeTallyShardsAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 4 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, shards) = accounts.split_at(4);
+        let payer = &accounts[0];
+        let voting_account = &accounts[1];
+        let poll_card = &accounts[2];
+        let system_program = &accounts[3];
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        Ok(Self { payer, voting_account, poll_card, system_program, shards })
+    }
+}
+
+struct AddWeightAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    // The weight accounts of the weight source, as for `vote`
+    remaining_accounts: std::slice::Iter<'a, AccountInfo<'a>>
+}
+
+impl<'a> AddWeightAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let mut accounts = accounts.iter();
+        let user = next_account_info(&mut accounts)?;
+        let voting_account = next_account_info(&mut accounts)?;
+        let user_vote_account = next_account_info(&mut accounts)?;
+        let poll_card = next_account_info(&mut accounts)?;
+        let system_program = next_account_info(&mut accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(user_vote_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, user_vote_account, poll_card, system_program, remaining_accounts: accounts })
+    }
+}
+
+struct LinkProposalNoteAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>
+}
+
+impl<'a> LinkProposalNoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        mint: &Pubkey
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let metadata = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(metadata, &TOKEN_METADATA_PROGRAM_ID)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_pda(metadata, find_metadata_pda(mint))?;
+
+        Ok(Self { user, voting_account, metadata, system_program })
+    }
+}
+
+// The kiosk and the instructions sysvar, then the accounts of `vote` without the memo program
+struct VoteByTransferClaimAccounts<'a> {
+    kiosk: &'a AccountInfo<'a>,
+    instructions_sysvar: &'a AccountInfo<'a>,
+    vote_accounts: VoteAccounts<'a>
+}
+
+impl<'a> VoteByTransferClaimAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        voter_index_page: u16
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 2 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, vote_accounts) = accounts.split_at(2);
+        let kiosk = &accounts[0];
+        let instructions_sysvar = &accounts[1];
+
+        if *instructions_sysvar.key != instructions_sysvar_address {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        let vote_accounts = VoteAccounts::parse(program_id, vote_accounts, false, voter_index_page)?;
+        assert_pda(kiosk, find_kiosk_pda(vote_accounts.voting_account.key, program_id))?;
+
+        Ok(Self { kiosk, instructions_sysvar, vote_accounts })
+    }
+}
+
+struct WithdrawKioskAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    kiosk: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    kiosk_bump: u8
+}
+
+impl<'a> WithdrawKioskAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let kiosk = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(kiosk, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let kiosk_bump = assert_pda(kiosk, find_kiosk_pda(voting_account.key, program_id))?;
+
+        Ok(Self { user, voting_account, kiosk, system_program, kiosk_bump })
+    }
+}
+
+struct CloseUserVoteAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>
+}
+
+impl<'a> CloseUserVoteAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(user_vote_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+        assert_owned_by(user_vote_account, program_id)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+        Ok(Self { user, voting_account, user_vote_account })
+    }
+}
+
+struct CloseVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    voting_payer: &'a AccountInfo<'a>
+}
+
+impl<'a> CloseVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 3)?;
+
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let voting_payer = next_account_info(accounts)?;
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(voting_payer, Errors::UsersAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        Ok(Self { user, voting_account, voting_payer })
+    }
+}
+
+// The wallet signs for itself, anyone can pay the fee of the transaction
+#[cfg(feature = "devnet")]
+struct ClaimFaucetAccounts<'a> {
+    wallet: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    user_vote_account: &'a AccountInfo<'a>,
+    faucet: &'a AccountInfo<'a>,
+    faucet_claim: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    faucet_bump: u8,
+    faucet_claim_bump: u8
+}
+
+#[cfg(feature = "devnet")]
+impl<'a> ClaimFaucetAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 6)?;
+
+        let accounts = &mut accounts.iter();
+        let wallet = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let user_vote_account = next_account_info(accounts)?;
+        let faucet = next_account_info(accounts)?;
+        let faucet_claim = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(wallet)?;
+        assert_writable(wallet, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(faucet, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(faucet_claim, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, wallet.key, program_id))?;
+        let faucet_bump = assert_pda(faucet, find_faucet_pda(program_id))?;
+        let faucet_claim_bump = assert_pda(faucet_claim, find_faucet_claim_pda(wallet.key, program_id))?;
+
+        Ok(Self { wallet, voting_account, user_vote_account, faucet, faucet_claim, system_program, faucet_bump, faucet_claim_bump })
+    }
+}
+
+// Read-only, anyone can look a creator up
+struct CreatorSummaryAccounts<'a> {
+    creator_stats: &'a AccountInfo<'a>
+}
+
+impl<'a> CreatorSummaryAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 1)?;
+
+        let accounts = &mut accounts.iter();
+        let creator_stats = next_account_info(accounts)?;
+
+        assert_owned_by(creator_stats, program_id)?;
+
+        Ok(Self { creator_stats })
+    }
+}
+
+// The races follow the system program, in election order
+struct CreateElectionAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    election: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    votings: &'a [AccountInfo<'a>],
+    election_bump: u8
+}
+
+impl<'a> CreateElectionAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+        id: u64
+    ) -> Result<Self, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (accounts, votings) = accounts.split_at(3);
+        let user = &accounts[0];
+        let election = &accounts[1];
+        let system_program = &accounts[2];
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(election, Errors::PDAsAccountMustBeMutable)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let election_bump = assert_pda(election, find_election_pda(user.key, id, program_id))?;
+
+        Ok(Self { user, election, system_program, votings, election_bump })
+    }
+}
+
+// Permissionless, the races follow the election in election order
+struct FinalizeElectionAccounts<'a> {
+    election: &'a AccountInfo<'a>,
+    votings: &'a [AccountInfo<'a>]
+}
+
+impl<'a> FinalizeElectionAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let (election, votings) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        assert_writable(election, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(election, program_id)?;
+
+        Ok(Self { election, votings })
+    }
+}
+
+// `unfreeze_voting` takes the same accounts. The creator leaves `authority` out, moderators pass it as
+// for `hide_voting`.
+struct FreezeVotingAccounts<'a> {
+    user: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    authority: Option<&'a AccountInfo<'a>>
+}
+
+impl<'a> FreezeVotingAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let authority = accounts.next();
+
+        if accounts.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        assert_signer(user)?;
+        assert_writable(voting_account, Errors::PDAsAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        if let Some(authority) = authority {
+            assert_owned_by(authority, program_id)?;
+        };
+
+        Ok(Self { user, voting_account, poll_card, authority })
+    }
+}
+
+// The signer pays for the room an upgrade adds and the system program moves the rent. Both are checked
+// now so clients pass the same accounts once an upgrade needs them.
+struct MigrateAccountAccounts<'a> {
+    account: &'a AccountInfo<'a>
+}
+
+impl<'a> MigrateAccountAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        let accounts = &mut accounts.iter();
+        let user = next_account_info(accounts)?;
+        let account = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        if accounts.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        assert_signer(user)?;
+        assert_writable(user, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(account, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        Ok(Self { account })
+    }
+}
+
+// Permissionless, the payer covers the rent of a new card
+struct SyncPollCardAccounts<'a> {
+    payer: &'a AccountInfo<'a>,
+    voting_account: &'a AccountInfo<'a>,
+    poll_card: &'a AccountInfo<'a>,
+    system_program: &'a AccountInfo<'a>,
+    poll_card_bump: u8
+}
+
+impl<'a> SyncPollCardAccounts<'a> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>]
+    ) -> Result<Self, ProgramError> {
+        assert_accounts_len(accounts, 4)?;
+
+        let accounts = &mut accounts.iter();
+        let payer = next_account_info(accounts)?;
+        let voting_account = next_account_info(accounts)?;
+        let poll_card = next_account_info(accounts)?;
+        let system_program = next_account_info(accounts)?;
+
+        assert_signer(payer)?;
+        assert_writable(payer, Errors::UsersAccountMustBeMutable)?;
+        assert_writable(poll_card, Errors::PDAsAccountMustBeMutable)?;
+        assert_owned_by(voting_account, program_id)?;
+
+        assert_program(system_program, &system_program_address, Errors::InvalidSystemProgram)?;
+
+        let poll_card_bump = assert_pda(poll_card, find_poll_card_pda(voting_account.key, program_id))?;
+
+        Ok(Self { payer, voting_account, poll_card, system_program, poll_card_bump })
+    }
+}
+
+fn process_create_voting(
+    program_id: &Pubkey,
+    accounts: CreateVotingAccounts,
+    mut ix_data: CreateVotingInstruction,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CreateVotingAccounts {
+        user,
+        voting_account,
+        creator_stats,
+        title_index,
+        title_reservation,
+        registry,
+        registry_page,
+        system_program,
+        voting_bump,
+        creator_stats_bump,
+        creator_signatures
+    } = accounts;
+
+    ix_data.resolve_times(sysvars.current_time())?;
+    validate_new_voting(&ix_data, creator_signatures, &DEFAULT_VOTING_SETTINGS, sysvars.current_time(), start_time_tolerance(config))?;
+    if let Some(program_creator) = ix_data.program_creator.as_ref() {
+        program_creator.check(program_id, user.key)?;
+    };
+    count_active_voting(program_id, user, creator_stats, system_program, creator_stats_bump, config, sysvars)?;
+    consume_title_reservation(program_id, user, title_reservation, &ix_data.title, sysvars.current_time())?;
+    index_voting_title(program_id, user, title_index, system_program, &ix_data.title, voting_account.key, sysvars)?;
+
+    let title_seed = voting_title_seed(&ix_data.title);
+    let entry = RegistryEntry { voting: *voting_account.key, title_hash: title_seed, ends_at: ix_data.ends_at };
+    register_voting(program_id, user, registry, registry_page, system_program, &entry, sysvars)?;
+
+    let mut vote_account = VoteMainAccount::new(*user.key, ix_data, Pubkey::default(), 0, &DEFAULT_VOTING_SETTINGS)?;
+    vote_account.take_creation_deposit(config);
+    vote_account.sync_status(sysvars.current_time());
+    create_voting_account(
+        program_id,
+        user,
+        voting_account,
+        system_program,
+        &[
+            VOTING_SEED,
+            title_seed.as_ref(),
+            &[ voting_bump ]
+        ],
+        &vote_account,
+        sysvars
+    )?;
+
+    msg!("New voting account has been created.");
+
+    Ok(())
+}
+
+// A new voting with the options, gating, weighting, tallying and thresholds of one of the creator's
+// votings, and new dates. Uploaded allowlists and proposal instructions belong to the source voting and
+// aren't copied.
+fn process_clone_voting(
+    program_id: &Pubkey,
+    accounts: CloneVotingAccounts,
+    ix_data: CloneVotingInstruction,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CloneVotingAccounts { source_voting, create_accounts } = accounts;
+
+    let source = {
+        let data = source_voting.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(source_voting, source.find_pda(program_id))?;
+
+    // Members-only gating and the settings come from the realm, which only `create_realm_voting` applies.
+    if source.realm != Pubkey::default() {
+        return Err(Errors::RealmVotingNotCloneable.into());
+    };
+
+    let accounts = CreateVotingAccounts::parse(program_id, create_accounts, &ix_data.title, &source.co_creators)?;
+    if source.creator != *accounts.user.key {
+        return Err(Errors::NotVotingCreator.into());
+    };
+
+    let create_ix_data = CreateVotingInstruction {
+        starts_at: ix_data.starts_at,
+        ends_at: ix_data.ends_at,
+        title: ix_data.title,
+        description: source.description,
+        options: source.options,
+        co_creators: source.co_creators,
+        creator_threshold: source.creator_threshold,
+        weight_source: source.weight_source,
+        tally_mode: source.tally_mode,
+        eligibility: source.eligibility,
+        // Uncapped votings keep a 0 cap per option, a new voting only takes those as no caps
+        option_caps: if source.option_caps.iter().all(|cap| *cap == 0) { Vec::new() } else { source.option_caps },
+        signup_slots: source.signup_slots,
+        random_draw: source.random_draw.is_some(),
+        oracle_condition: source.oracle_condition,
+        relative_times: ix_data.relative_times,
+        weight_mints: source.weight_mints,
+        weight_precision: source.weight_precision,
+        large_supply: source.tally_state.large_supply,
+        record_buckets: source.record_buckets,
+        draft: false,
+        required_stages: 0,
+        envelope_key: source.envelope_key,
+        tie_policy: source.tie_policy,
+        dependency: source.dependency,
+        // Program creators sign with seeds the voting doesn't keep, they clone through `cpi::create_voting`
+        program_creator: None,
+        turnout_milestones: source.turnout_milestones,
+        hide_tallies: source.hide_tallies,
+        result_attestors: source.result_attestors.map(|result_attestors| ResultAttestors { attested: 0, ..result_attestors }),
+        tally_shards: source.tally_shards,
+        weight_cap_per_slot: source.weight_cap_per_slot,
+        reject_encumbered_tokens: source.reject_encumbered_tokens,
+        reveal_window: source.reveal_window,
+        winners_count: source.winners_count,
+        apportionment: source.apportionment,
+        min_participation: source.min_participation,
+        pass_threshold_bps: source.pass_threshold_bps,
+        electorate: source.electorate,
+        quorum_bps: source.quorum_bps
+    };
+
+    process_create_voting(program_id, accounts, create_ix_data, config, sysvars)
+}
+
+// Checks shared by `create_voting` and `create_realm_voting`.
+pub(crate) fn validate_new_voting(
+    ix_data: &CreateVotingInstruction,
+    creator_signatures: usize,
+    settings: &VotingSettings,
+    current_time: u64,
+    start_time_tolerance: u64
+) -> ProgramResult {
+    if ix_data.starts_at.saturating_add(start_time_tolerance) < current_time {
+        return Err(Errors::InvalidStartingTime.into());
+    };
+
+    if ix_data.ends_at <= ix_data.starts_at {
+        return Err(Errors::InvalidEndingTime.into());
+    };
+
+    validate_title(&ix_data.title)?;
+
+    if (ix_data.ends_at - ix_data.starts_at) > settings.max_voting_time {
+        return Err(Errors::MaxVotingTimeExceeded.into());
+    };
+
+    if (ix_data.ends_at - ix_data.starts_at) < settings.min_voting_time {
+        return Err(Errors::VotingTimeTooShort.into());
+    };
+
+    if ix_data.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(Errors::DescriptionTooLong.into());
+    };
+
+    if ix_data.options.len() < MIN_OPTIONS {
+        return Err(Errors::InvalidOptionsCount.into());
+    };
+
+    if ix_data.options.len() > MAX_OPTIONS {
+        return Err(Errors::TooManyOptions.into());
+    };
+
+    for option in ix_data.options.iter() {
+        validate_option(option)?;
+    };
+
+    if ix_data.option_caps.is_empty() == false && (ix_data.option_caps.len() != ix_data.options.len() || ix_data.hide_tallies) {
+        return Err(Errors::InvalidOptionCaps.into());
+    };
+
+    if ix_data.co_creators.len() >= MAX_CREATORS {
+        return Err(Errors::InvalidCoCreators.into());
+    };
+
+    let creators_count = 1 + ix_data.co_creators.len();
+    if ix_data.creator_threshold == 0 || ix_data.creator_threshold as usize > creators_count {
+        return Err(Errors::InvalidCreatorThreshold.into());
+    };
+
+    if creator_signatures < ix_data.creator_threshold as usize {
+        return Err(Errors::NotEnoughCreatorSignatures.into());
+    };
+
+    ix_data.weight_source.validate(&WeightSettings {
+        weight_mints: &ix_data.weight_mints,
+        precision: ix_data.weight_precision,
+        reject_encumbered: ix_data.reject_encumbered_tokens
+    })?;
+
+    if ix_data.required_stages & ALL_STAGES != ix_data.required_stages || (ix_data.required_stages != 0 && ix_data.draft == false) {
+        return Err(Errors::InvalidRequiredStages.into());
+    };
+
+    // Sealed ballots are only counted as per-option tallies, and they're kept in their own records.
+    if ix_data.envelope_key.is_some() && (
+        matches!(ix_data.tally_mode, TallyMode::Simple | TallyMode::Threshold(_) | TallyMode::Approval) == false
+        || ix_data.option_caps.is_empty() == false
+        || ix_data.large_supply
+        || ix_data.record_buckets > 0
+        || ix_data.random_draw
+        || ix_data.signup_slots > 0
+    ) {
+        return Err(Errors::InvalidEnvelopeSettings.into());
+    };
+
+    // Random draws number the tickets in the user vote accounts.
+    if ix_data.record_buckets > MAX_RECORD_BUCKETS || (ix_data.record_buckets > 0 && ix_data.random_draw) {
+        return Err(Errors::InvalidRecordBuckets.into());
+    };
+    ix_data.tally_mode.validate(ix_data.large_supply)?;
+    ix_data.tally_mode.validate_tie_policy(ix_data.tie_policy)?;
+
+    // Sharded ballots only write their shard, so nothing may depend on the running count of all ballots.
+    // Instant-runoff rankings of several shards could outgrow `MAX_RANKINGS` once merged.
+    if ix_data.tally_shards == 1 || ix_data.tally_shards > MAX_TALLY_SHARDS || (ix_data.tally_shards > 0 && (
+        ix_data.option_caps.iter().any(|cap| *cap > 0)
+        || ix_data.random_draw
+        || ix_data.turnout_milestones.is_some()
+        || ix_data.tie_policy == TiePolicy::EarliestLeader
+        || ix_data.tally_mode == TallyMode::Ranked(RankedMethod::InstantRunoff)
+        || ix_data.envelope_key.is_some()
+        || ix_data.signup_slots > 0
+        || ix_data.record_buckets > 0
+    )) {
+        return Err(Errors::InvalidTallyShards.into());
+    };
+
+    // Crediting weight later swaps the voter's ballot in the voting's tallies, so the ballot has to be in
+    // a user vote account and counted in the voting. Draw tickets are handed out in ballot order.
+    if ix_data.weight_cap_per_slot > 0 && (
+        matches!(ix_data.weight_source, WeightSource::TokenBalance(_) | WeightSource::MultiToken | WeightSource::StakedSol) == false
+        || ix_data.record_buckets > 0
+        || ix_data.tally_shards > 0
+        || ix_data.envelope_key.is_some()
+        || ix_data.signup_slots > 0
+        || ix_data.random_draw
+    ) {
+        return Err(Errors::InvalidWeightCap.into());
+    };
+
+    // Committed ballots are only counted after `ends_at`, so no cap or draw ticket can depend on them while
+    // the voting runs. Each ballot is kept in a user vote account until it's revealed.
+    if ix_data.reveal_window > MAX_REVEAL_WINDOW || (ix_data.reveal_window > 0 && (
+        ix_data.option_caps.iter().any(|cap| *cap > 0)
+        || ix_data.random_draw
+        || ix_data.envelope_key.is_some()
+        || ix_data.tally_shards > 0
+        || ix_data.record_buckets > 0
+        || ix_data.signup_slots > 0
+        || ix_data.weight_cap_per_slot > 0
+    )) {
+        return Err(Errors::InvalidRevealWindow.into());
+    };
+
+    if ix_data.pass_threshold_bps > 10_000 {
+        return Err(Errors::InvalidPassThreshold.into());
+    };
+    Electorate::validate(ix_data.electorate, ix_data.quorum_bps)?;
+
+    // Only plain counts rank every option, and the runners-up have no tie policy of their own
+    let multiple_winners = matches!(ix_data.tally_mode, TallyMode::Simple | TallyMode::Approval | TallyMode::Quadratic)
+        && ix_data.tie_policy == TiePolicy::LowestIndex;
+    if ix_data.winners_count == 0
+        || ix_data.winners_count as usize > ix_data.options.len()
+        || (ix_data.winners_count > 1 && multiple_winners == false)
+    {
+        return Err(Errors::InvalidWinnersCount.into());
+    };
+
+    // Ranked tallies are points or pairwise wins, not shares of the votes
+    if let Some(apportionment) = ix_data.apportionment {
+        if apportionment.seats == 0 || apportionment.seats > MAX_SEATS || matches!(ix_data.tally_mode, TallyMode::Ranked(_)) {
+            return Err(Errors::InvalidApportionment.into());
+        };
+    };
+
+    // Sealed ballots don't tell which options changed.
+    if ix_data.envelope_key.is_some() && ix_data.tie_policy == TiePolicy::EarliestLeader {
+        return Err(Errors::InvalidTiePolicy.into());
+    };
+    ix_data.eligibility.validate()?;
+
+    if let Some(oracle_condition) = ix_data.oracle_condition.as_ref() {
+        oracle_condition.validate()?;
+    };
+
+    if let Some(turnout_milestones) = ix_data.turnout_milestones.as_ref() {
+        turnout_milestones.validate()?;
+    };
+
+    if let Some(result_attestors) = ix_data.result_attestors.as_ref() {
+        result_attestors.validate()?;
+    };
+
+    Ok(())
+}
+
+// Counts a new voting of the creator against the config's cap, the stats account is created on the first one
+fn count_active_voting<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    creator_stats: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    creator_stats_bump: u8,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let mut creator_stats_data = if creator_stats.data_is_empty() {
+        let space = CREATOR_STATS_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                creator_stats.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                creator_stats.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    CREATOR_STATS_SEED,
+                    user.key.as_ref(),
+                    &[ creator_stats_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(creator_stats)?;
+
+        CreatorStatsAccount {
+            discriminator: AccountType::CreatorStats.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            creator: *user.key,
+            active_votings: 0,
+            created_votings: 0,
+            finalized_votings: 0,
+            cancelled_votings: 0,
+            total_voters: 0,
+            flags: 0
+        }
+    } else {
+        assert_owned_by(creator_stats, program_id)?;
+
+        load_creator_stats(creator_stats)?
+    };
+
+    let max_active_votings = config.map_or(0, |config| config.max_active_votings);
+    if max_active_votings > 0 && creator_stats_data.active_votings >= max_active_votings {
+        return Err(Errors::TooManyActiveVotings.into());
+    };
+
+    creator_stats_data.active_votings += 1;
+    creator_stats_data.created_votings = creator_stats_data.created_votings.saturating_add(1);
+    creator_stats_data.serialize(&mut &mut creator_stats.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Only the reserver can create a voting with a reserved title until the reservation expires. The
+// reserver's own reservation is closed, its rent goes back to them.
+fn consume_title_reservation(
+    program_id: &Pubkey,
+    user: &AccountInfo,
+    title_reservation: &AccountInfo,
+    title: &str,
+    current_time: u64
+) -> ProgramResult {
+    assert_pda(title_reservation, find_title_reservation_pda(&voting_title_seed(title), program_id))?;
+    if title_reservation.data_is_empty() {
+        return Ok(());
+    };
+
+    let reservation_data = load_title_reservation(program_id, title_reservation)?;
+    if reservation_data.reserver != *user.key {
+        if reservation_data.expires_at > current_time {
+            return Err(Errors::TitleReserved.into());
+        };
+
+        return Ok(());
+    };
+
+    let lamports = title_reservation.lamports();
+    **title_reservation.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    title_reservation.realloc(0, false)?;
+    title_reservation.assign(&system_program_address);
+
+    Ok(())
+}
+
+// Points the title index at the voting, unless an earlier voting already has the title.
+fn index_voting_title<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    title_index: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    title: &str,
+    voting: &Pubkey,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let title_index_bump = assert_pda(title_index, find_title_index_pda(title, program_id))?;
+    if title_index.data_is_empty() == false {
+        return Ok(());
+    };
+
+    let space = TITLE_INDEX_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            title_index.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            title_index.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                TITLE_INDEX_SEED,
+                voting_title_seed(title).as_ref(),
+                &[ title_index_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(title_index)?;
+
+    let title_index_data = TitleIndexAccount {
+        discriminator: AccountType::TitleIndex.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting
+    };
+    title_index_data.serialize(&mut &mut title_index.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Appends the voting to the registry page taking the next voting, the root and the page are created on
+// their first voting.
+fn register_voting<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    registry: &AccountInfo<'a>,
+    registry_page: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    entry: &RegistryEntry,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let registry_bump = assert_pda(registry, find_voting_registry_pda(program_id))?;
+    let mut registry_data = if registry.data_is_empty() {
+        let space = VOTING_REGISTRY_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                registry.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                registry.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    VOTING_REGISTRY_SEED,
+                    &[ registry_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(registry)?;
+
+        VotingRegistryAccount {
+            discriminator: AccountType::VotingRegistry.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            votings_count: 0
+        }
+    } else {
+        assert_owned_by(registry, program_id)?;
+
+        let data = registry.data.borrow();
+        assert_account_type(&data, AccountType::VotingRegistry)?;
+
+        try_from_slice_unchecked::<VotingRegistryAccount>(&data)?
+    };
+
+    // Only the page taking the next voting is accepted
+    let page = u32::try_from(registry_data.votings_count / MAX_REGISTRY_PAGE_ENTRIES).map_err(|_| ProgramError::ArithmeticOverflow)?;
+    let registry_page_bump = assert_pda(registry_page, find_voting_registry_page_pda(page, program_id))?;
+
+    if registry_page.data_is_empty() {
+        let space = registry_page_account_space(0);
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                registry_page.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                registry_page.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    VOTING_REGISTRY_PAGE_SEED,
+                    &page.to_le_bytes(),
+                    &[ registry_page_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(registry_page)?;
+
+        let header = VotingRegistryPageAccount {
+            discriminator: AccountType::VotingRegistryPage.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            page
+        };
+        header.serialize(&mut &mut registry_page.data.borrow_mut()[..])?;
+    } else {
+        assert_owned_by(registry_page, program_id)?;
+
+        assert_account_type(&registry_page.data.borrow(), AccountType::VotingRegistryPage)?;
+    };
+
+    let entries = (registry_page.data_len() - REGISTRY_PAGE_HEADER_SPACE) / REGISTRY_ENTRY_SPACE + 1;
+    let space = registry_page_account_space(entries);
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    let current_lamports = registry_page.lamports();
+    if rent_exempt > current_lamports {
+        invoke(
+            &transfer(
+                user.key,
+                registry_page.key,
+                rent_exempt - current_lamports
+            ),
+            &[
+                user.clone(),
+                registry_page.clone(),
+                system_program.clone()
+            ]
+        )?;
+    };
+
+    registry_page.realloc(space, false)?;
+    entry.serialize(&mut &mut registry_page.data.borrow_mut()[space - REGISTRY_ENTRY_SPACE..])?;
+
+    registry_data.votings_count += 1;
+    registry_data.serialize(&mut &mut registry.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn start_time_tolerance(config: Option<&ConfigAccount>) -> u64 {
+    config.map_or(DEFAULT_START_TIME_TOLERANCE, |config| config.start_time_tolerance)
+}
+
+// Room for the voting with every optional setting and result set, see `voting_account_space`
+fn voting_space(vote_account: &VoteMainAccount) -> Option<usize> {
+    let options_len: usize = vote_account.options.iter().map(|option| option.len()).sum();
+    voting_account_space(vote_account.description.len(), options_len, vote_account.options.len(), vote_account.co_creators.len())
+}
+
+fn create_voting_account<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    voting_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    vote_account: &VoteMainAccount,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let space = voting_space(vote_account).ok_or(ProgramError::InvalidInstructionData)?;
+    let lamports = sysvars.rent.minimum_balance(space).checked_add(vote_account.creation_deposit)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    invoke_signed(
+        &create_account(
+            user.key,
+            voting_account.key,
+            lamports,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            voting_account.clone(),
+            system_program.clone()
+        ],
+        &[ signer_seeds ]
+    )?;
+
+    assert_uninitialized(voting_account)?;
+
+    vote_account.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    let event = VotingCreatedEvent {
+        voting: *voting_account.key,
+        creator: vote_account.creator,
+        starts_at: vote_account.starts_at,
+        ends_at: vote_account.ends_at,
+        timestamp: sysvars.current_time()
+    };
+    log_event(VOTING_CREATED_EVENT_TAG, VOTING_CREATED_EVENT_VERSION, &event)
+}
+
+fn process_vote(
+    program_id: &Pubkey,
+    accounts: VoteAccounts,
+    ix_data: VoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let VoteAccounts { user, voting_account, user_vote_account, voter_index, poll_card, system_program, memo_program, mut remaining_accounts, voter_index_page } = accounts;
+
+    validate_title(&ix_data.vote_title)?;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    check_voted_title(program_id, voting_account, &voting_account_data, &ix_data.vote_title)?;
+
+    if voting_account_data.signup_slots > 0 {
+        return Err(Errors::SignupVoting.into());
+    };
+
+    // Sharded votings take ballots through `cast_sharded_vote`.
+    if voting_account_data.tally_shards > 0 {
+        return Err(Errors::ShardedVoting.into());
+    };
+
+    // Envelope votings take sealed ballots, see `cast_envelope`.
+    if voting_account_data.envelope_key.is_some() {
+        return Err(Errors::EnvelopeVoting.into());
+    };
+
+    if voting_account_data.reveal_window > 0 {
+        return Err(Errors::CommitRevealVoting.into());
+    };
+
+    let incentive_pool = next_incentive_pool(&voting_account_data, &mut remaining_accounts)?;
+
+    // Membership, eligibility and weight are checked when the ballot is cast, updating it later doesn't need them.
+    let weight = voting_account_data.capped_weight(check_voter(
+        program_id,
+        voting_account,
+        &voting_account_data,
+        user,
+        &mut remaining_accounts,
+        ix_data.eligibility_proof.as_ref(),
+        ix_data.weight_proof.as_ref()
+    )?);
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    voting_account_data.tally_mode.validate_ballot(&ix_data.ballot, voting_account_data.options.len())?;
+
+    let record = load_vote_record(program_id, voting_account, &voting_account_data, user, user_vote_account)?;
+    let already_voted = match &record {
+        VoteRecord::Account(_) => user_vote_account.data_is_empty() == false,
+        VoteRecord::Bucket(bucket_data, slot) => bucket_data.slots[*slot].is_empty() == false
+    };
+
+    if ix_data.dry_run {
+        if already_voted {
+            return Err(Errors::UserAlreadyVoted.into());
+        };
+
+        voting_account_data.tally_mode.accumulate(
+            &mut voting_account_data.tallies,
+            &mut voting_account_data.tally_state,
+            &ix_data.ballot,
+            weight,
+            true
+        )?;
+        voting_account_data.check_option_caps(&ix_data.ballot)?;
+
+        return return_vote_preview(weight, preview_tallies(&voting_account_data));
+    };
+
+    match record {
+        VoteRecord::Account(user_vote_bump) => {
+            create_user_vote_account(program_id, user, voting_account.key, user_vote_account, system_program, user_vote_bump, sysvars)?;
+
+            let mut draw_offset = 0;
+            if let Some(random_draw) = voting_account_data.random_draw.as_mut() {
+                draw_offset = random_draw.total_weight;
+                random_draw.total_weight = random_draw.total_weight.checked_add(weight).ok_or(ProgramError::ArithmeticOverflow)?;
+            };
+
+            let user_account = UserVotingAccount {
+                discriminator: AccountType::UserVoting.discriminator(),
+                version: StateVersion::CURRENT as u8,
+                payer: *user.key,
+                first_voted_at: current_time,
+                last_time_voted: current_time,
+                times_updated: 0,
+                choice: ballot_choice(&ix_data.ballot),
+                weight,
+                credited_slot: sysvars.clock.slot,
+                draw_offset,
+                vote_index: voting_account_data.total_voters,
+                commitment: None,
+                ballot: ix_data.ballot.clone(),
+                voted_to: VotingTitle::new(&ix_data.vote_title)?
+            };
+            user_account.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+        },
+        VoteRecord::Bucket(mut bucket_data, slot) => {
+            if already_voted {
+                return Err(Errors::UserAlreadyVoted.into());
+            };
+
+            bucket_data.slots[slot] = RecordSlot::new(*user.key, weight, voting_account_data.total_voters, &ix_data.ballot);
+            bucket_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+        }
+    };
+
+    // The tally state can grow with the ballot, the voter pays for it.
+    voting_account_data.tally_mode.accumulate(
+        &mut voting_account_data.tallies,
+        &mut voting_account_data.tally_state,
+        &ix_data.ballot,
+        weight,
+        true
+    )?;
+    voting_account_data.check_option_caps(&ix_data.ballot)?;
+    voting_account_data.mark_changed(&ix_data.ballot, current_time);
+    voting_account_data.total_voters += 1;
+    voting_account_data.vote_chain = chain_vote_hash(&voting_account_data.vote_chain, user.key, &ix_data.ballot, weight, current_time);
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+    voting_account_data.log_turnout_milestones(voting_account.key)?;
+    log_vote_cast(voting_account.key, user.key, voting_account_data.visible_choice(&ix_data.ballot, current_time), weight, current_time)?;
+
+    add_to_voter_index(program_id, user, voter_index, system_program, voter_index_page, voting_account.key, sysvars)?;
+
+    // Logged choices would add up to the running tallies.
+    if let Some(memo_program) = memo_program.filter(|_| voting_account_data.hide_tallies == false) {
+        log_vote_memo(memo_program, voting_account.key, ballot_choice(&ix_data.ballot))?;
+    };
+
+    if let Some(incentive_pool) = incentive_pool {
+        pay_incentive(program_id, voting_account.key, voting_account_data.incentive_per_vote, incentive_pool, user, sysvars)?;
+    };
+
+    msg!("Voted successfully.");
+    msg!("Voted to - {}", ix_data.vote_title);
+    if voting_account_data.hide_tallies == false {
+        match ix_data.ballot.first() {
+            Some(option) => msg!("Voted option - {}", voting_account_data.options[*option as usize]),
+            None => msg!("Ballot spoiled.")
+        };
+    };
+
+    Ok(())
+}
+
+// Created empty on the voter's first ballot. The voter pays for its user vote account, so the payer is the voter.
+fn create_user_vote_account<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    voting: &Pubkey,
+    user_vote_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    user_vote_bump: u8,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    if user_vote_account.data_is_empty() == false {
+        return Err(Errors::UserAlreadyVoted.into());
+    };
+
+    let space = USER_VOTE_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            user_vote_account.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            user_vote_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                USER_VOTE_SEED,
+                voting.as_ref(),
+                user.key.as_ref(),
+                &[ user_vote_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(user_vote_account)
+}
+
+// Votings with incentives take their pool first among the remaining accounts of a new ballot.
+fn next_incentive_pool<'a>(
+    voting_account_data: &VoteMainAccount,
+    remaining_accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>
+) -> Result<Option<&'a AccountInfo<'a>>, ProgramError> {
+    if voting_account_data.incentive_per_vote == 0 {
+        return Ok(None);
+    };
+
+    next_account_info(remaining_accounts).map(Some).or(Err(Errors::InvalidIncentive.into()))
+}
+
+// Pays the voter from the pool, an exhausted pool pays nothing without failing the ballot.
+fn pay_incentive(
+    program_id: &Pubkey,
+    voting: &Pubkey,
+    incentive_per_vote: u64,
+    pool: &AccountInfo,
+    user: &AccountInfo,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    assert_pda(pool, find_incentive_pool_pda(voting, program_id))?;
+    assert_owned_by(pool, program_id)?;
+    assert_writable(pool, Errors::PDAsAccountMustBeMutable)?;
+
+    let mut pool_data = {
+        let data = pool.data.borrow();
+        assert_account_type(&data, AccountType::IncentivePool)?;
+
+        try_from_slice_unchecked::<IncentivePoolAccount>(&data)?
+    };
+    pool_data.check_not_in_progress()?;
+
+    let available = pool.lamports().saturating_sub(sysvars.rent.minimum_balance(INCENTIVE_POOL_ACCOUNT_SPACE));
+    if available < incentive_per_vote {
+        msg!("Incentive pool exhausted.");
+
+        return Ok(());
+    };
+
+    **pool.try_borrow_mut_lamports()? -= incentive_per_vote;
+    **user.try_borrow_mut_lamports()? += incentive_per_vote;
+
+    pool_data.total_paid = pool_data.total_paid.checked_add(incentive_per_vote).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    msg!("Incentive paid - {}", incentive_per_vote);
+
+    Ok(())
+}
+
+// Dependency, membership, eligibility and weight of a new ballot, returns the voter's weight. No remaining
+// account may be left over.
+fn check_voter<'a>(
+    program_id: &Pubkey,
+    voting_account: &AccountInfo,
+    voting_account_data: &VoteMainAccount,
+    user: &AccountInfo,
+    remaining_accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+    eligibility_proof: Option<&EligibilityProof>,
+    weight_proof: Option<&WeightProof>
+) -> Result<u64, ProgramError> {
+    if let Some(dependency) = voting_account_data.dependency.as_ref() {
+        let dependency_account = next_account_info(remaining_accounts).or(Err(Errors::InvalidDependency))?;
+        dependency.check(program_id, dependency_account)?;
+    };
+
+    if voting_account_data.members_only {
+        let membership = next_account_info(remaining_accounts).or(Err(Errors::NotRealmMember))?;
+        check_membership(program_id, membership, &voting_account_data.realm, user.key)?;
+    };
+
+    // A voting without a policy takes the proof for its roll only
+    if let Some(voter_roll) = voting_account_data.voter_roll.as_ref() {
+        voter_roll.check(user.key, eligibility_proof.map(|proof| proof.roll_proof.as_slice()).unwrap_or_default())?;
+    };
+
+    let eligibility_proof = eligibility_proof.filter(|_| voting_account_data.voter_roll.is_none() || voting_account_data.eligibility.clauses.is_empty() == false);
+    voting_account_data.eligibility.check(program_id, voting_account.key, user.key, remaining_accounts, eligibility_proof)?;
+
+    let weight = voting_account_data.weight_source.resolve(
+        voting_account.key,
+        user.key,
+        remaining_accounts,
+        weight_proof,
+        &voting_account_data.weight_settings()
+    )?;
+
+    if remaining_accounts.next().is_some() {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    Ok(weight)
+}
+
+// Appends the voting to the voter's index page, a page is created on the first ballot it takes. Only the
+// voter's own ballots write their pages, so a client skipping a page only hides part of its own history.
+fn add_to_voter_index<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    voter_index: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    voter_index_page: u16,
+    voting: &Pubkey,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let voter_index_bump = assert_pda(voter_index, find_voter_index_pda(user.key, voter_index_page, program_id))?;
+
+    let mut header = if voter_index.data_is_empty() {
+        let space = voter_index_account_space(0);
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                voter_index.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                voter_index.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    VOTER_INDEX_SEED,
+                    user.key.as_ref(),
+                    &voter_index_page.to_le_bytes(),
+                    &[ voter_index_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(voter_index)?;
+
+        VoterIndexAccount {
+            discriminator: AccountType::VoterIndex.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voter: *user.key,
+            page: voter_index_page,
+            next: Pubkey::default()
+        }
+    } else {
+        assert_owned_by(voter_index, program_id)?;
+
+        let data = voter_index.data.borrow();
+        assert_account_type(&data, AccountType::VoterIndex)?;
+
+        try_from_slice_unchecked::<VoterIndexAccount>(&data)?
+    };
+
+    if header.next != Pubkey::default() {
+        return Err(Errors::VoterIndexPageFull.into());
+    };
+
+    let entries = (voter_index.data_len() - VOTER_INDEX_HEADER_SPACE) / 32 + 1;
+    if entries == MAX_VOTER_INDEX_PAGE_ENTRIES {
+        let next_page = voter_index_page.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        header.next = find_voter_index_pda(user.key, next_page, program_id).0;
+    };
+
+    let space = voter_index_account_space(entries);
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    let current_lamports = voter_index.lamports();
+    if rent_exempt > current_lamports {
+        invoke(
+            &transfer(
+                user.key,
+                voter_index.key,
+                rent_exempt - current_lamports
+            ),
+            &[
+                user.clone(),
+                voter_index.clone(),
+                system_program.clone()
+            ]
+        )?;
+    };
+
+    voter_index.realloc(space, false)?;
+    let mut data = voter_index.data.borrow_mut();
+    header.serialize(&mut &mut data[..VOTER_INDEX_HEADER_SPACE])?;
+    data[space - 32..].copy_from_slice(voting.as_ref());
+
+    Ok(())
+}
+
+fn process_update_vote(
+    program_id: &Pubkey,
+    accounts: UpdateVoteAccounts,
+    ix_data: UpdateVoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let UpdateVoteAccounts { user, voting_account, user_vote_account, poll_card, system_program, memo_program } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if voting_account_data.allow_updates == false {
+        return Err(Errors::VoteUpdatesDisabled.into());
+    };
+
+    if voting_account_data.tally_shards > 0 {
+        return Err(Errors::ShardedVoting.into());
+    };
+
+    // A committed ballot is only known once revealed, after the voting ended.
+    if voting_account_data.reveal_window > 0 {
+        return Err(Errors::CommitRevealVoting.into());
+    };
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    voting_account_data.tally_mode.validate_ballot(&ix_data.ballot, voting_account_data.options.len())?;
+
+    // Bucketed ballots are kept in a slot, the rest in the user vote account.
+    let mut record = load_vote_record(program_id, voting_account, &voting_account_data, user, user_vote_account)?;
+    let mut user_vote_account_data = match &record {
+        VoteRecord::Account(_) => {
+            let data = user_vote_account.data.borrow();
+            assert_account_type(&data, AccountType::UserVoting)?;
+
+            // The PDA is derived from the signer, the record must also have been created by them.
+            let user_vote_account_data = try_from_slice_unchecked::<UserVotingAccount>(&data)?;
+            if user_vote_account_data.payer != *user.key {
+                return Err(Errors::NotVoteRecordOwner.into());
+            };
+
+            Some(user_vote_account_data)
+        },
+        VoteRecord::Bucket(bucket_data, slot) if bucket_data.slots[*slot].is_empty() => {
+            return Err(ProgramError::InvalidAccountData);
+        },
+        VoteRecord::Bucket(..) => None
+    };
+    let (previous_ballot, weight) = match (&user_vote_account_data, &record) {
+        (Some(user_vote_account_data), _) => (user_vote_account_data.ballot.clone(), user_vote_account_data.weight),
+        (None, VoteRecord::Bucket(bucket_data, slot)) => (bucket_data.slots[*slot].ballot().to_vec(), bucket_data.slots[*slot].weight),
+        (None, VoteRecord::Account(_)) => return Err(ProgramError::InvalidAccountData)
+    };
+
+    // Swap the ballots in the tallies, the weight is only read once.
+    let VoteMainAccount { tally_mode, tallies, tally_state, .. } = &mut voting_account_data;
+    tally_mode.accumulate(tallies, tally_state, &previous_ballot, weight, false)?;
+    tally_mode.accumulate(tallies, tally_state, &ix_data.ballot, weight, true)?;
+    voting_account_data.check_option_caps(&ix_data.ballot)?;
+
+    if ix_data.dry_run {
+        return return_vote_preview(weight, preview_tallies(&voting_account_data));
+    };
+
+    voting_account_data.mark_changed(&previous_ballot, current_time);
+    voting_account_data.mark_changed(&ix_data.ballot, current_time);
+    voting_account_data.vote_chain = chain_vote_hash(&voting_account_data.vote_chain, user.key, &ix_data.ballot, weight, current_time);
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    if let Some(user_vote_account_data) = user_vote_account_data.as_mut() {
+        user_vote_account_data.choice = ballot_choice(&ix_data.ballot);
+        user_vote_account_data.ballot = ix_data.ballot.clone();
+        user_vote_account_data.last_time_voted = current_time;
+        user_vote_account_data.times_updated = user_vote_account_data.times_updated.saturating_add(1);
+        user_vote_account_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+    } else if let VoteRecord::Bucket(bucket_data, slot) = &mut record {
+        bucket_data.slots[*slot] = RecordSlot::new(*user.key, weight, bucket_data.slots[*slot].vote_index, &ix_data.ballot);
+        bucket_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+    };
+
+    log_vote_updated(voting_account.key, user.key, voting_account_data.visible_choice(&ix_data.ballot, current_time), current_time)?;
+
+    if let Some(memo_program) = memo_program.filter(|_| voting_account_data.hide_tallies == false) {
+        log_vote_memo(memo_program, voting_account.key, ballot_choice(&ix_data.ballot))?;
+    };
+
+    msg!("Vote updated.");
+
+    Ok(())
+}
+
+// Ballots are only previewed while the voting takes them, so hidden tallies are always left out.
+fn preview_tallies(voting_account_data: &VoteMainAccount) -> Vec<u128> {
+    if voting_account_data.hide_tallies {
+        Vec::new()
+    } else {
+        voting_account_data.tally_state.totals(&voting_account_data.tallies)
+    }
+}
+
+fn return_vote_preview(weight: u64, tallies: Vec<u128>) -> ProgramResult {
+    set_return_data(&VotePreviewReturnData { weight, tallies }.try_to_vec()?);
+
+    msg!("Dry run, nothing written.");
+
+    Ok(())
+}
+
+fn process_edit_voting(
+    program_id: &Pubkey,
+    accounts: EditVotingAccounts,
+    ix_data: EditVotingInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EditVotingAccounts { user, voting_account, system_program } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    if ix_data.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(Errors::DescriptionTooLong.into());
+    };
+
+    // The title is part of the PDA seeds, so only the description can be edited.
+    voting_account_data.description = ix_data.description;
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Voting updated.");
+
+    Ok(())
+}
+
+// The title is part of the PDA seeds and of the title index, so only its case and spacing can change.
+// Written with `write_resized` like the other edits, which grows the account when it lacks the room.
+fn process_update_title(
+    program_id: &Pubkey,
+    accounts: EditVotingAccounts,
+    ix_data: UpdateTitleInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EditVotingAccounts { user, voting_account, system_program } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    validate_title(&ix_data.title)?;
+    if voting_title_seed(&ix_data.title) != voting_title_seed(voting_account_data.title.as_str()) {
+        return Err(Errors::InvalidPdaAddress.into());
+    };
+
+    voting_account_data.title = VotingTitle::new(&ix_data.title)?;
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Title updated - {}", voting_account_data.title);
+
+    Ok(())
+}
+
+fn process_add_option(
+    program_id: &Pubkey,
+    accounts: EditVotingAccounts,
+    ix_data: AddOptionInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EditVotingAccounts { user, voting_account, system_program } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    validate_option(&ix_data.option)?;
+
+    if voting_account_data.options.len() >= MAX_OPTIONS {
+        return Err(Errors::TooManyOptions.into());
+    };
+
+    if ix_data.cap > 0 && voting_account_data.hide_tallies {
+        return Err(Errors::InvalidOptionCaps.into());
+    };
+
+    voting_account_data.options.push(ix_data.option);
+    voting_account_data.tallies.push(0);
+    voting_account_data.option_caps.push(ix_data.cap);
+    if voting_account_data.apportionment.is_some() {
+        voting_account_data.seats.push(0);
+    };
+    voting_account_data.init_stage |= STAGE_OPTIONS;
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Option added.");
+
+    Ok(())
+}
+
+fn process_remove_option(
+    program_id: &Pubkey,
+    accounts: EditVotingAccounts,
+    ix_data: RemoveOptionInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EditVotingAccounts { user, voting_account, system_program } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    if ix_data.index as usize >= voting_account_data.options.len() {
+        return Err(Errors::InvalidOptionIndex.into());
+    };
+
+    if voting_account_data.options.len() <= MIN_OPTIONS {
+        return Err(Errors::InvalidOptionsCount.into());
+    };
+
+    voting_account_data.options.remove(ix_data.index as usize);
+    voting_account_data.tallies.remove(ix_data.index as usize);
+    voting_account_data.option_caps.remove(ix_data.index as usize);
+    // All 0 until finalization, only the length follows the options
+    voting_account_data.seats.pop();
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Option removed.");
+
+    Ok(())
+}
+
+fn process_set_option_order(
+    program_id: &Pubkey,
+    accounts: EditVotingAccounts,
+    ix_data: SetOptionOrderInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EditVotingAccounts { user, voting_account, system_program } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    // The new order must be a permutation of the current option indexes.
+    let options_count = voting_account_data.options.len();
+    if ix_data.order.len() != options_count {
+        return Err(Errors::InvalidOptionOrder.into());
+    };
+
+    let mut seen = [false; MAX_OPTIONS];
+    for index in ix_data.order.iter() {
+        let index = *index as usize;
+        if index >= options_count || seen[index] {
+            return Err(Errors::InvalidOptionOrder.into());
+        };
+        seen[index] = true;
+    };
+
+    voting_account_data.options = ix_data.order
+        .iter()
+        .map(|index| voting_account_data.options[*index as usize].clone())
+        .collect();
+    voting_account_data.tallies = ix_data.order
+        .iter()
+        .map(|index| voting_account_data.tallies[*index as usize])
+        .collect();
+    voting_account_data.option_caps = ix_data.order
+        .iter()
+        .map(|index| voting_account_data.option_caps[*index as usize])
+        .collect();
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Options reordered.");
+
+    Ok(())
+}
+
+fn process_get_my_vote(accounts: GetMyVoteAccounts) -> ProgramResult {
+    let GetMyVoteAccounts { voting_account, user_vote_account } = accounts;
+
+    get_my_vote(&voting_account.data.borrow(), &user_vote_account.data.borrow())
+}
+
+// The views below take the raw account data, the lazy entrypoint of the `pinocchio` build answers them
+// without building `AccountInfo`s.
+pub(crate) fn get_my_vote(voting_account_data: &[u8], user_vote_account_data: &[u8]) -> ProgramResult {
+    if voting_account_data.get(..8) != Some(&AccountType::Vote.discriminator()[..]) {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    assert_account_type(user_vote_account_data, AccountType::UserVoting)?;
+    let user_vote_account_data = try_from_slice_unchecked::<UserVotingAccount>(user_vote_account_data)?;
+
+    let my_vote = MyVoteReturnData {
+        choice: user_vote_account_data.choice,
+        weight: user_vote_account_data.weight,
+        first_voted_at: user_vote_account_data.first_voted_at,
+        last_time_voted: user_vote_account_data.last_time_voted,
+        times_updated: user_vote_account_data.times_updated
+    };
+    set_return_data(&my_vote.try_to_vec()?);
+
+    Ok(())
+}
+
+// The voting behind a view, at its PDA and with visible tallies
+fn load_voting_view(program_id: &Pubkey, voting_account: &Pubkey, data: &[u8], sysvars: &Sysvars) -> Result<VoteMainAccount, ProgramError> {
+    assert_account_type(data, AccountType::Vote)?;
+    let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(data)?;
+
+    if *voting_account != voting_account_data.find_pda(program_id).0 {
+        return Err(Errors::InvalidPdaAddress.into());
+    };
+
+    voting_account_data.check_tallies_visible(sysvars.current_time())?;
+
+    Ok(voting_account_data)
+}
+
+fn process_get_option_capacity(program_id: &Pubkey, accounts: GetOptionCapacityAccounts, ix_data: GetOptionCapacityInstruction, sysvars: &Sysvars) -> ProgramResult {
+    let GetOptionCapacityAccounts { voting_account } = accounts;
+
+    get_option_capacity(program_id, voting_account.key, &voting_account.data.borrow(), ix_data, sysvars)
+}
+
+pub(crate) fn get_option_capacity(program_id: &Pubkey, voting_account: &Pubkey, data: &[u8], ix_data: GetOptionCapacityInstruction, sysvars: &Sysvars) -> ProgramResult {
+    let voting_account_data = load_voting_view(program_id, voting_account, data, sysvars)?;
+
+    if ix_data.index as usize >= voting_account_data.options.len() {
+        return Err(Errors::InvalidOptionIndex.into());
+    };
+
+    let capacity = OptionCapacityReturnData {
+        cap: voting_account_data.option_caps[ix_data.index as usize],
+        tally: voting_account_data.tally_state.total(&voting_account_data.tallies, ix_data.index),
+        remaining: voting_account_data.remaining_capacity(ix_data.index)
+    };
+    set_return_data(&capacity.try_to_vec()?);
+
+    Ok(())
+}
+
+// Uses the accounts of `get_option_capacity`, the voting alone.
+fn process_get_results(program_id: &Pubkey, accounts: GetOptionCapacityAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let GetOptionCapacityAccounts { voting_account } = accounts;
+
+    get_results(program_id, voting_account.key, &voting_account.data.borrow(), sysvars)
+}
+
+pub(crate) fn get_results(program_id: &Pubkey, voting_account: &Pubkey, data: &[u8], sysvars: &Sysvars) -> ProgramResult {
+    let voting_account_data = load_voting_view(program_id, voting_account, data, sysvars)?;
+    let results = ResultsReturnData {
+        tallies: voting_account_data.tally_state.totals(&voting_account_data.tallies),
+        total_voters: voting_account_data.total_voters,
+        winning_option: voting_account_data.winning_option(),
+        winners: match voting_account_data.status {
+            VotingStatus::Finalized => voting_account_data.winners.clone(),
+            _ => voting_account_data.winners()
+        },
+        seats: match voting_account_data.status {
+            VotingStatus::Finalized => voting_account_data.seats.clone(),
+            _ => voting_account_data.apportioned_seats()?
+        },
+        outcome: voting_account_data.outcome.unwrap_or_else(|| voting_account_data.decide_outcome()),
+        spoiled_ballots: voting_account_data.tally_state.spoiled,
+        spoiled_weight: voting_account_data.tally_state.spoiled_weight,
+        turnout_bps: voting_account_data.turnout_bps()
+    };
+    set_return_data(&results.try_to_vec()?);
+
+    Ok(())
+}
+
+// Tallies stay raw on-chain, this only derives the shares. Uses the accounts of `get_option_capacity`.
+fn process_get_percentages(program_id: &Pubkey, accounts: GetOptionCapacityAccounts, ix_data: GetPercentagesInstruction, sysvars: &Sysvars) -> ProgramResult {
+    let GetOptionCapacityAccounts { voting_account } = accounts;
+
+    get_percentages(program_id, voting_account.key, &voting_account.data.borrow(), ix_data, sysvars)
+}
+
+pub(crate) fn get_percentages(program_id: &Pubkey, voting_account: &Pubkey, data: &[u8], ix_data: GetPercentagesInstruction, sysvars: &Sysvars) -> ProgramResult {
+    let voting_account_data = load_voting_view(program_id, voting_account, data, sysvars)?;
+
+    let tallies = voting_account_data.tally_state.totals(&voting_account_data.tallies);
+    let results = PercentagesReturnData {
+        decimals: ix_data.decimals,
+        percentages: percentages(&tallies, ix_data.decimals)?
+    };
+    set_return_data(&results.try_to_vec()?);
+
+    Ok(())
+}
+
+// Weight the user's ballot would have, membership and eligibility aren't checked
+fn process_get_vote_weight(program_id: &Pubkey, accounts: GetVoteWeightAccounts, ix_data: GetVoteWeightInstruction) -> ProgramResult {
+    let GetVoteWeightAccounts { user, voting_account, mut remaining_accounts } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    let weight = voting_account_data.weight_source.resolve(
+        voting_account.key,
+        user.key,
+        &mut remaining_accounts,
+        ix_data.weight_proof.as_ref(),
+        &voting_account_data.weight_settings()
+    )?;
+
+    if remaining_accounts.next().is_some() {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    set_return_data(&VoteWeightReturnData { weight }.try_to_vec()?);
+
+    Ok(())
+}
+
+fn process_snapshot_tally(
+    program_id: &Pubkey,
+    accounts: SnapshotTallyAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let SnapshotTallyAccounts { payer, voting_account, snapshot_account, system_program, snapshot_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_active()?;
+    voting_account_data.check_tallies_visible(current_time)?;
+
+    if voting_account_data.starts_at > current_time {
+        return Err(Errors::VotingNotStarted.into());
+    };
+
+    // Options are locked once voting starts, so the ring is allocated at its full size up front.
+    let mut snapshot_account_data = if snapshot_account.data_is_empty() {
+        let space = tally_snapshot_account_space(voting_account_data.tallies.len())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                payer.key,
+                snapshot_account.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                payer.clone(),
+                snapshot_account.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    TALLY_SNAPSHOT_SEED,
+                    voting_account.key.as_ref(),
+                    &[ snapshot_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(snapshot_account)?;
+
+        TallySnapshotAccount {
+            discriminator: AccountType::TallySnapshot.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            last_taken_at: 0,
+            next_index: 0,
+            snapshots: Vec::with_capacity(MAX_TALLY_SNAPSHOTS)
+        }
+    } else {
+        let data = snapshot_account.data.borrow();
+        assert_account_type(&data, AccountType::TallySnapshot)?;
+
+        let snapshot_account_data = try_from_slice_unchecked::<TallySnapshotAccount>(&data)?;
+        if current_time < snapshot_account_data.last_taken_at + TALLY_SNAPSHOT_INTERVAL {
+            return Err(Errors::SnapshotTooEarly.into());
+        };
+
+        snapshot_account_data
+    };
+
+    let snapshot = TallySnapshot {
+        taken_at: current_time,
+        tallies: voting_account_data.tallies,
+        high: voting_account_data.tally_state.high
+    };
+
+    let index = snapshot_account_data.next_index as usize;
+    if index < snapshot_account_data.snapshots.len() {
+        snapshot_account_data.snapshots[index] = snapshot;
+    } else {
+        snapshot_account_data.snapshots.push(snapshot);
+    };
+    snapshot_account_data.next_index = ((index + 1) % MAX_TALLY_SNAPSHOTS) as u8;
+    snapshot_account_data.last_taken_at = current_time;
+    snapshot_account_data.serialize(&mut &mut snapshot_account.data.borrow_mut()[..])?;
+
+    msg!("Tally snapshot taken.");
+
+    Ok(())
+}
+
+fn process_claim_certificate(
+    _program_id: &Pubkey,
+    accounts: ClaimCertificateAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ClaimCertificateAccounts {
+        user,
+        voting_account,
+        user_vote_account,
+        certificate_mint,
+        certificate_account,
+        system_program,
+        token_program,
+        certificate_mint_bump,
+        certificate_bump
+    } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    // Only users with a ballot on this voting took part in it.
+    if user_vote_account.data.borrow().get(..8) != Some(&AccountType::UserVoting.discriminator()[..]) {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    // The certificate account is created on claim, so an existing one means it was already claimed.
+    if certificate_account.data_is_empty() == false {
+        return Err(Errors::CertificateAlreadyClaimed.into());
+    };
+
+    let mint_seeds: &[&[u8]] = &[
+        CERTIFICATE_MINT_SEED,
+        voting_account.key.as_ref(),
+        &[ certificate_mint_bump ]
+    ];
+
+    // The first claim of a voting creates its mint.
+    if certificate_mint.data_is_empty() {
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[ ExtensionType::NonTransferable ])?;
+        invoke_signed(
+            &create_account(
+                user.key,
+                certificate_mint.key,
+                sysvars.rent.minimum_balance(space),
+                space as u64,
+                token_program.key
+            ),
+            &[
+                user.clone(),
+                certificate_mint.clone(),
+                system_program.clone()
+            ],
+            &[ mint_seeds ]
+        )?;
+
+        invoke(
+            &token_instruction::initialize_non_transferable_mint(token_program.key, certificate_mint.key)?,
+            &[
+                certificate_mint.clone(),
+                token_program.clone()
+            ]
+        )?;
+
+        invoke(
+            &token_instruction::initialize_mint2(token_program.key, certificate_mint.key, certificate_mint.key, None, 0)?,
+            &[
+                certificate_mint.clone(),
+                token_program.clone()
+            ]
+        )?;
+    };
+
+    // Accounts of a non-transferable mint are initialized with these extensions.
+    let space = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+        ExtensionType::ImmutableOwner,
+        ExtensionType::NonTransferableAccount
+    ])?;
+    invoke_signed(
+        &create_account(
+            user.key,
+            certificate_account.key,
+            sysvars.rent.minimum_balance(space),
+            space as u64,
+            token_program.key
+        ),
+        &[
+            user.clone(),
+            certificate_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                CERTIFICATE_SEED,
+                voting_account.key.as_ref(),
+                user.key.as_ref(),
+                &[ certificate_bump ]
+            ]
+        ]
+    )?;
+
+    invoke(
+        &token_instruction::initialize_account3(token_program.key, certificate_account.key, certificate_mint.key, user.key)?,
+        &[
+            certificate_account.clone(),
+            certificate_mint.clone(),
+            token_program.clone()
+        ]
+    )?;
+
+    invoke_signed(
+        &token_instruction::mint_to(token_program.key, certificate_mint.key, certificate_account.key, certificate_mint.key, &[], 1)?,
+        &[
+            certificate_mint.clone(),
+            certificate_account.clone(),
+            token_program.clone()
+        ],
+        &[ mint_seeds ]
+    )?;
+
+    msg!("Certificate claimed - {}", voting_account_data.title);
+
+    Ok(())
+}
+
+fn process_fund_rewards(
+    program_id: &Pubkey,
+    accounts: FundRewardsAccounts,
+    ix_data: FundRewardsInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let FundRewardsAccounts { user, voting_account, escrow_account, system_program, escrow_bump } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.creator != *user.key {
+        return Err(Errors::NotVotingCreator.into());
+    };
+
+    // Drafts can be funded as one of their setup steps.
+    if voting_account_data.status != VotingStatus::Draft {
+        voting_account_data.check_active()?;
+    };
+
+    // Rewards can't change once the outcome is known.
+    if voting_account_data.ends_at <= sysvars.current_time() {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    if ix_data.amount == 0 {
+        return Err(Errors::InvalidRewardAmount.into());
+    };
+
+    let mut escrow_account_data = if escrow_account.data_is_empty() {
+        let space = REWARD_ESCROW_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                escrow_account.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                escrow_account.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    REWARD_ESCROW_SEED,
+                    voting_account.key.as_ref(),
+                    &[ escrow_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(escrow_account)?;
+
+        RewardEscrowAccount {
+            discriminator: AccountType::RewardEscrow.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            total_funded: 0,
+            total_claimed: 0,
+            in_progress: false
+        }
+    } else {
+        assert_owned_by(escrow_account, program_id)?;
+
+        let data = escrow_account.data.borrow();
+        assert_account_type(&data, AccountType::RewardEscrow)?;
+
+        try_from_slice_unchecked::<RewardEscrowAccount>(&data)?
+    };
+
+    escrow_account_data.check_not_in_progress()?;
+
+    // State is written before the transfer, like before every CPI moving funds.
+    escrow_account_data.total_funded = escrow_account_data.total_funded
+        .checked_add(ix_data.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    voting_account_data.init_stage |= STAGE_ESCROW;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    invoke_latched(escrow_account, &mut escrow_account_data, || invoke(
+        &transfer(
+            user.key,
+            escrow_account.key,
+            ix_data.amount
+        ),
+        &[
+            user.clone(),
+            escrow_account.clone(),
+            system_program.clone()
+        ]
+    ))?;
+
+    msg!("Rewards funded - {}", escrow_account_data.total_funded);
+
+    Ok(())
+}
+
+fn process_claim_winnings(
+    program_id: &Pubkey,
+    accounts: ClaimWinningsAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ClaimWinningsAccounts { user, voting_account, user_vote_account, escrow_account, claim_account, system_program, claim_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    let winner = voting_account_data.winning_option().ok_or(Errors::NoWinningOption)?;
+
+    let user_vote_account_data = {
+        let data = user_vote_account.data.borrow();
+        assert_account_type(&data, AccountType::UserVoting)?;
+
+        try_from_slice_unchecked::<UserVotingAccount>(&data)?
+    };
+
+    if voting_account_data.tally_mode.supports(&user_vote_account_data.ballot, winner) == false {
+        return Err(Errors::NotWinningVoter.into());
+    };
+
+    let mut escrow_account_data = {
+        let data = escrow_account.data.borrow();
+        assert_account_type(&data, AccountType::RewardEscrow)?;
+
+        try_from_slice_unchecked::<RewardEscrowAccount>(&data)?
+    };
+    escrow_account_data.check_not_in_progress()?;
+
+    if claim_account.data_is_empty() == false {
+        return Err(Errors::RewardAlreadyClaimed.into());
+    };
+
+    // Shares are pro-rata to the ballot weight, rounded down. The remainder stays in the escrow.
+    let amount = (escrow_account_data.total_funded as u128 * user_vote_account_data.weight as u128
+        / voting_account_data.tally_state.total(&voting_account_data.tallies, winner)) as u64;
+
+    let space = REWARD_CLAIM_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            claim_account.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            claim_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                REWARD_CLAIM_SEED,
+                voting_account.key.as_ref(),
+                user.key.as_ref(),
+                &[ claim_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(claim_account)?;
+
+    let claim_account_data = RewardClaimAccount {
+        discriminator: AccountType::RewardClaim.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        amount
+    };
+    claim_account_data.serialize(&mut &mut claim_account.data.borrow_mut()[..])?;
+
+    escrow_account_data.total_claimed += amount;
+    escrow_account_data.serialize(&mut &mut escrow_account.data.borrow_mut()[..])?;
+
+    **escrow_account.try_borrow_mut_lamports()? -= amount;
+    **user.try_borrow_mut_lamports()? += amount;
+
+    msg!("Winnings claimed - {}", amount);
+
+    Ok(())
+}
+
+fn process_top_up_rent(
+    accounts: TopUpRentAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let TopUpRentAccounts { payer, account, system_program } = accounts;
+
+    let rent_exempt = sysvars.rent.minimum_balance(account.data_len());
+    let current_lamports = account.lamports();
+    if rent_exempt <= current_lamports {
+        msg!("Account is already rent exempt.");
+
+        return Ok(());
+    };
+
+    invoke(
+        &transfer(
+            payer.key,
+            account.key,
+            rent_exempt - current_lamports
+        ),
+        &[
+            payer.clone(),
+            account.clone(),
+            system_program.clone()
+        ]
+    )?;
+
+    msg!("Rent topped up - {}", rent_exempt - current_lamports);
+
+    Ok(())
+}
+
+fn process_create_realm(
+    program_id: &Pubkey,
+    accounts: CreateRealmAccounts,
+    ix_data: CreateRealmInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CreateRealmAccounts { admin, realm, system_program, realm_bump } = accounts;
+
+    ix_data.defaults.validate()?;
+
+    let space = REALM_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            admin.key,
+            realm.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            admin.clone(),
+            realm.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                REALM_SEED,
+                ix_data.name.as_bytes(),
+                &[ realm_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(realm)?;
+
+    let realm_account = RealmAccount {
+        discriminator: AccountType::Realm.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        admin: *admin.key,
+        defaults: ix_data.defaults,
+        locked_settings: ix_data.locked_settings,
+        creation_fee: ix_data.creation_fee,
+        creators_gated: ix_data.creators_gated,
+        votings_count: 0,
+        members_count: 0,
+        name: ix_data.name
+    };
+    realm_account.serialize(&mut &mut realm.data.borrow_mut()[..])?;
+
+    msg!("Realm created - {}", realm_account.name);
+
+    Ok(())
+}
+
+fn process_update_realm(
+    program_id: &Pubkey,
+    accounts: RealmAdminAccounts,
+    ix_data: UpdateRealmInstruction
+) -> ProgramResult {
+    let RealmAdminAccounts { admin, realm } = accounts;
+
+    let mut realm_account = load_realm(program_id, realm)?;
+    if realm_account.admin != *admin.key {
+        return Err(Errors::NotRealmAdmin.into());
+    };
+
+    ix_data.defaults.validate()?;
+
+    // Existing votings keep the settings they were created with.
+    realm_account.defaults = ix_data.defaults;
+    realm_account.locked_settings = ix_data.locked_settings;
+    realm_account.creation_fee = ix_data.creation_fee;
+    realm_account.creators_gated = ix_data.creators_gated;
+    realm_account.serialize(&mut &mut realm.data.borrow_mut()[..])?;
+
+    msg!("Realm updated.");
+
+    Ok(())
+}
+
+fn process_set_execution_allowlist(
+    program_id: &Pubkey,
+    accounts: ExecutionAllowlistAccounts,
+    ix_data: SetExecutionAllowlistInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ExecutionAllowlistAccounts { admin, realm, allowlist, system_program, allowlist_bump } = accounts;
+
+    let realm_account = load_realm(program_id, realm)?;
+    if realm_account.admin != *admin.key {
+        return Err(Errors::NotRealmAdmin.into());
+    };
+
+    validate_allowlist(&ix_data.actions)?;
+
+    // Created empty on first use, `write_resized` then sizes it to the new entries.
+    if allowlist.data_is_empty() {
+        let space = ExecutionAllowlistAccount {
+            discriminator: AccountType::ExecutionAllowlist.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            realm: *realm.key,
+            actions: Vec::new()
+        }.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                admin.key,
+                allowlist.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                admin.clone(),
+                allowlist.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    EXECUTION_ALLOWLIST_SEED,
+                    realm.key.as_ref(),
+                    &[ allowlist_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(allowlist)?;
+    } else {
+        assert_owned_by(allowlist, program_id)?;
+
+        assert_account_type(&allowlist.data.borrow(), AccountType::ExecutionAllowlist)?;
+    };
+
+    let allowlist_account = ExecutionAllowlistAccount {
+        discriminator: AccountType::ExecutionAllowlist.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        realm: *realm.key,
+        actions: ix_data.actions
+    };
+    write_resized(&allowlist_account, allowlist, admin, system_program, sysvars)?;
+
+    msg!("Execution allowlist updated, {} actions.", allowlist_account.actions.len());
+
+    Ok(())
+}
+
+fn process_create_realm_voting(
+    program_id: &Pubkey,
+    accounts: CreateRealmVotingAccounts,
+    ix_data: CreateRealmVotingInstruction,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CreateRealmVotingAccounts {
+        user,
+        realm,
+        realm_admin,
+        voting_account,
+        creator_stats,
+        title_index,
+        title_reservation,
+        registry,
+        registry_page,
+        system_program,
+        creator_stats_bump,
+        creator_signatures
+    } = accounts;
+
+    let mut realm_account = load_realm(program_id, realm)?;
+    if realm_account.admin != *realm_admin.key {
+        return Err(Errors::NotRealmAdmin.into());
+    };
+
+    if realm_account.creators_gated && realm_account.admin != *user.key {
+        return Err(Errors::NotRealmAdmin.into());
+    };
+
+    let settings = realm_account.defaults.resolve(&ix_data.overrides, realm_account.locked_settings)?;
+    let mut ix_data = CreateVotingInstruction {
+        starts_at: ix_data.starts_at,
+        ends_at: ix_data.ends_at,
+        title: ix_data.title,
+        description: ix_data.description,
+        options: ix_data.options,
+        co_creators: ix_data.co_creators,
+        creator_threshold: settings.creator_threshold,
+        weight_source: ix_data.weight_source,
+        tally_mode: ix_data.tally_mode,
+        eligibility: ix_data.eligibility,
+        option_caps: ix_data.option_caps,
+        signup_slots: ix_data.signup_slots,
+        random_draw: ix_data.random_draw,
+        oracle_condition: ix_data.oracle_condition,
+        relative_times: ix_data.relative_times,
+        weight_mints: ix_data.weight_mints,
+        weight_precision: ix_data.weight_precision,
+        large_supply: ix_data.large_supply,
+        record_buckets: ix_data.record_buckets,
+        draft: ix_data.draft,
+        required_stages: ix_data.required_stages,
+        envelope_key: ix_data.envelope_key,
+        tie_policy: ix_data.tie_policy,
+        dependency: ix_data.dependency,
+        program_creator: ix_data.program_creator,
+        turnout_milestones: ix_data.turnout_milestones,
+        hide_tallies: ix_data.hide_tallies,
+        result_attestors: ix_data.result_attestors,
+        tally_shards: ix_data.tally_shards,
+        weight_cap_per_slot: ix_data.weight_cap_per_slot,
+        reject_encumbered_tokens: ix_data.reject_encumbered_tokens,
+        reveal_window: ix_data.reveal_window,
+        winners_count: ix_data.winners_count,
+        apportionment: ix_data.apportionment,
+        min_participation: ix_data.min_participation,
+        pass_threshold_bps: ix_data.pass_threshold_bps,
+        electorate: ix_data.electorate,
+        quorum_bps: ix_data.quorum_bps
+    };
+
+    // Realm votings measure their turnout against the members at creation
+    if let Some(turnout_milestones) = ix_data.turnout_milestones.as_mut() {
+        turnout_milestones.electorate = realm_account.members_count;
+    };
+
+    ix_data.resolve_times(sysvars.current_time())?;
+    validate_new_voting(&ix_data, creator_signatures, &settings, sysvars.current_time(), start_time_tolerance(config))?;
+    if let Some(program_creator) = ix_data.program_creator.as_ref() {
+        program_creator.check(program_id, user.key)?;
+    };
+    count_active_voting(program_id, user, creator_stats, system_program, creator_stats_bump, config, sysvars)?;
+
+    let realm_voting_id = realm_account.votings_count;
+    let voting_bump = assert_pda(voting_account, find_realm_voting_pda(realm.key, realm_voting_id, program_id))?;
+    consume_title_reservation(program_id, user, title_reservation, &ix_data.title, sysvars.current_time())?;
+    index_voting_title(program_id, user, title_index, system_program, &ix_data.title, voting_account.key, sysvars)?;
+
+    let entry = RegistryEntry { voting: *voting_account.key, title_hash: voting_title_seed(&ix_data.title), ends_at: ix_data.ends_at };
+    register_voting(program_id, user, registry, registry_page, system_program, &entry, sysvars)?;
+
+    // The admin doesn't pay itself.
+    if realm_account.creation_fee > 0 && realm_account.admin != *user.key {
+        invoke(
+            &transfer(
+                user.key,
+                realm_admin.key,
+                realm_account.creation_fee
+            ),
+            &[
+                user.clone(),
+                realm_admin.clone(),
+                system_program.clone()
+            ]
+        )?;
+    };
+
+    let mut vote_account = VoteMainAccount::new(*user.key, ix_data, *realm.key, realm_voting_id, &settings)?;
+    vote_account.take_creation_deposit(config);
+    vote_account.sync_status(sysvars.current_time());
+    create_voting_account(
+        program_id,
+        user,
+        voting_account,
+        system_program,
+        &[
+            VOTING_SEED,
+            realm.key.as_ref(),
+            &realm_voting_id.to_le_bytes(),
+            &[ voting_bump ]
+        ],
+        &vote_account,
+        sysvars
+    )?;
+
+    realm_account.votings_count += 1;
+    realm_account.serialize(&mut &mut realm.data.borrow_mut()[..])?;
+
+    msg!("New realm voting account has been created - {}", realm_voting_id);
+
+    Ok(())
+}
+
+fn process_join_realm(
+    program_id: &Pubkey,
+    accounts: MembershipAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let MembershipAccounts { user, realm, membership, system_program, membership_bump } = accounts;
+
+    let mut realm_account = load_realm(program_id, realm)?;
+
+    let space = MEMBERSHIP_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            membership.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            membership.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                MEMBERSHIP_SEED,
+                realm.key.as_ref(),
+                user.key.as_ref(),
+                &[ membership_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(membership)?;
+
+    let membership_account = MembershipAccount {
+        discriminator: AccountType::Membership.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        realm: *realm.key,
+        member: *user.key,
+        joined_at: sysvars.current_time()
+    };
+    membership_account.serialize(&mut &mut membership.data.borrow_mut()[..])?;
+
+    realm_account.members_count += 1;
+    realm_account.serialize(&mut &mut realm.data.borrow_mut()[..])?;
+
+    msg!("Joined realm - {}", realm_account.name);
+
+    Ok(())
+}
+
+fn process_leave_realm(
+    program_id: &Pubkey,
+    accounts: MembershipAccounts
+) -> ProgramResult {
+    let MembershipAccounts { user, realm, membership, .. } = accounts;
+
+    let mut realm_account = load_realm(program_id, realm)?;
+
+    check_membership(program_id, membership, realm.key, user.key)?;
+
+    // Close the membership, its rent goes back to the member.
+    let lamports = membership.lamports();
+    **membership.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    membership.realloc(0, false)?;
+    membership.assign(&system_program_address);
+
+    realm_account.members_count = realm_account.members_count.saturating_sub(1);
+    realm_account.serialize(&mut &mut realm.data.borrow_mut()[..])?;
+
+    msg!("Left realm - {}", realm_account.name);
+
+    Ok(())
+}
+
+fn process_boost(
+    program_id: &Pubkey,
+    accounts: BoostAccounts,
+    ix_data: BoostInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let BoostAccounts { user, voting_account, boost_account, deposit_account, system_program, boost_bump, deposit_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_active()?;
+
+    if voting_account_data.ends_at <= sysvars.current_time() {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    if ix_data.amount == 0 {
+        return Err(Errors::InvalidBoostAmount.into());
+    };
+
+    let mut boost_account_data = if boost_account.data_is_empty() {
+        let space = BOOST_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                boost_account.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                boost_account.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    BOOST_SEED,
+                    voting_account.key.as_ref(),
+                    &[ boost_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(boost_account)?;
+
+        BoostAccount {
+            discriminator: AccountType::Boost.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            amount: 0,
+            released: false,
+            in_progress: false
+        }
+    } else {
+        assert_owned_by(boost_account, program_id)?;
+
+        let data = boost_account.data.borrow();
+        assert_account_type(&data, AccountType::Boost)?;
+
+        try_from_slice_unchecked::<BoostAccount>(&data)?
+    };
+
+    let mut deposit_account_data = if deposit_account.data_is_empty() {
+        let space = BOOST_DEPOSIT_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                deposit_account.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                deposit_account.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    BOOST_DEPOSIT_SEED,
+                    voting_account.key.as_ref(),
+                    user.key.as_ref(),
+                    &[ deposit_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(deposit_account)?;
+
+        BoostDepositAccount {
+            discriminator: AccountType::BoostDeposit.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            amount: 0
+        }
+    } else {
+        assert_owned_by(deposit_account, program_id)?;
+
+        let data = deposit_account.data.borrow();
+        assert_account_type(&data, AccountType::BoostDeposit)?;
+
+        try_from_slice_unchecked::<BoostDepositAccount>(&data)?
+    };
+
+    boost_account_data.check_not_in_progress()?;
+
+    boost_account_data.amount = boost_account_data.amount
+        .checked_add(ix_data.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    deposit_account_data.amount += ix_data.amount;
+    deposit_account_data.serialize(&mut &mut deposit_account.data.borrow_mut()[..])?;
+
+    invoke_latched(boost_account, &mut boost_account_data, || invoke(
+        &transfer(
+            user.key,
+            boost_account.key,
+            ix_data.amount
+        ),
+        &[
+            user.clone(),
+            boost_account.clone(),
+            system_program.clone()
+        ]
+    ))?;
+
+    msg!("Voting boosted - {}", boost_account_data.amount);
+
+    Ok(())
+}
+
+fn process_release_boost(
+    program_id: &Pubkey,
+    accounts: ReleaseBoostAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ReleaseBoostAccounts { voting_account, boost_account, config, treasury } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    let config_account = load_config(program_id, config)?;
+    if config_account.admin != *treasury.key {
+        return Err(Errors::InvalidTreasury.into());
+    };
+
+    let mut boost_account_data = {
+        let data = boost_account.data.borrow();
+        assert_account_type(&data, AccountType::Boost)?;
+
+        try_from_slice_unchecked::<BoostAccount>(&data)?
+    };
+    boost_account_data.check_not_in_progress()?;
+
+    if boost_account_data.released {
+        return Err(Errors::BoostAlreadyReleased.into());
+    };
+
+    // `amount` stays as is, so closed votings keep their rank.
+    boost_account_data.released = true;
+    boost_account_data.serialize(&mut &mut boost_account.data.borrow_mut()[..])?;
+
+    **boost_account.try_borrow_mut_lamports()? -= boost_account_data.amount;
+    **treasury.try_borrow_mut_lamports()? += boost_account_data.amount;
+
+    msg!("Boost released - {}", boost_account_data.amount);
+
+    Ok(())
+}
+
+fn process_refund_boost(accounts: RefundBoostAccounts) -> ProgramResult {
+    let RefundBoostAccounts { user, voting_account, boost_account, deposit_account } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.status != VotingStatus::Cancelled {
+        return Err(Errors::VotingNotCancelled.into());
+    };
+
+    let mut boost_account_data = {
+        let data = boost_account.data.borrow();
+        assert_account_type(&data, AccountType::Boost)?;
+
+        try_from_slice_unchecked::<BoostAccount>(&data)?
+    };
+    boost_account_data.check_not_in_progress()?;
+
+    let deposit_account_data = {
+        let data = deposit_account.data.borrow();
+        assert_account_type(&data, AccountType::BoostDeposit)?;
+
+        try_from_slice_unchecked::<BoostDepositAccount>(&data)?
+    };
+
+    boost_account_data.amount -= deposit_account_data.amount;
+    boost_account_data.serialize(&mut &mut boost_account.data.borrow_mut()[..])?;
+
+    **boost_account.try_borrow_mut_lamports()? -= deposit_account_data.amount;
+    **user.try_borrow_mut_lamports()? += deposit_account_data.amount;
+
+    // Close the deposit, its rent goes back to the booster.
+    let lamports = deposit_account.lamports();
+    **deposit_account.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    deposit_account.realloc(0, false)?;
+    deposit_account.assign(&system_program_address);
+
+    msg!("Boost refunded - {}", deposit_account_data.amount);
+
+    Ok(())
+}
+
+fn process_claim_slot(
+    program_id: &Pubkey,
+    accounts: ClaimSlotAccounts,
+    ix_data: ClaimSlotInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ClaimSlotAccounts { user, voting_account, poll_card, slot_account, slot_claim_account, system_program, mut remaining_accounts, slot_claim_bump } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.signup_slots == 0 {
+        return Err(Errors::NotSignupVoting.into());
+    };
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    if voting_account_data.claimed_slots >= voting_account_data.signup_slots {
+        return Err(Errors::SlotsFull.into());
+    };
+
+    if voting_account_data.members_only {
+        let membership = next_account_info(&mut remaining_accounts).or(Err(Errors::NotRealmMember))?;
+        check_membership(program_id, membership, &voting_account_data.realm, user.key)?;
+    };
+
+    voting_account_data.eligibility.check(program_id, voting_account.key, user.key, &mut remaining_accounts, ix_data.eligibility_proof.as_ref())?;
+
+    if remaining_accounts.next().is_some() {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    let slot = voting_account_data.claimed_slots;
+    let slot_bump = assert_pda(slot_account, find_slot_pda(voting_account.key, slot, program_id))?;
+
+    // A slot counts once and leaves the tallies as they are
+    if ix_data.dry_run {
+        if slot_claim_account.data_is_empty() == false {
+            return Err(Errors::UserAlreadyVoted.into());
+        };
+
+        return return_vote_preview(1, preview_tallies(&voting_account_data));
+    };
+
+    let space = SLOT_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            slot_account.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            slot_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                SLOT_SEED,
+                voting_account.key.as_ref(),
+                &slot.to_le_bytes(),
+                &[ slot_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(slot_account)?;
+
+    let slot_account_data = SlotAccount {
+        discriminator: AccountType::Slot.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        claimant: *user.key,
+        slot,
+        claimed_at: current_time
+    };
+    slot_account_data.serialize(&mut &mut slot_account.data.borrow_mut()[..])?;
+
+    // Fails if the user already claimed a slot of this voting.
+    let space = SLOT_CLAIM_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            slot_claim_account.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            slot_claim_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                SLOT_CLAIM_SEED,
+                voting_account.key.as_ref(),
+                user.key.as_ref(),
+                &[ slot_claim_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(slot_claim_account)?;
+
+    let slot_claim_account_data = SlotClaimAccount {
+        discriminator: AccountType::SlotClaim.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        slot
+    };
+    slot_claim_account_data.serialize(&mut &mut slot_claim_account.data.borrow_mut()[..])?;
+
+    voting_account_data.claimed_slots += 1;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    msg!("Claimed slot - {}", slot);
+
+    Ok(())
+}
+
+fn process_commit_draw(accounts: DrawAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let DrawAccounts { voting_account, .. } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    let random_draw = voting_account_data.random_draw.as_mut().ok_or(Errors::DrawNotEnabled)?;
+    let reveal_slot = random_draw.commit(sysvars.clock.slot)?;
+
+    // The draw has a fixed size once enabled, so the account keeps its size.
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Draw committed, reveal after slot - {}", reveal_slot);
+
+    Ok(())
+}
+
+fn process_reveal_draw(accounts: DrawAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let DrawAccounts { voting_account, slot_hashes } = accounts;
+    let slot_hashes = slot_hashes.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let random_draw = voting_account_data.random_draw.as_mut().ok_or(Errors::DrawNotEnabled)?;
+    let ticket = random_draw.reveal(voting_account.key, slot_hashes, sysvars.clock.slot)?;
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Drawn ticket - {}", ticket);
+
+    Ok(())
+}
+
+fn process_claim_draw(program_id: &Pubkey, accounts: ClaimDrawAccounts) -> ProgramResult {
+    let ClaimDrawAccounts { voting_account, user_vote_account } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let user_vote_account_data = {
+        let data = user_vote_account.data.borrow();
+        assert_account_type(&data, AccountType::UserVoting)?;
+
+        try_from_slice_unchecked::<UserVotingAccount>(&data)?
+    };
+
+    assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, &user_vote_account_data.payer, program_id))?;
+
+    let random_draw = voting_account_data.random_draw.as_mut().ok_or(Errors::DrawNotEnabled)?;
+    if random_draw.winner != Pubkey::default() {
+        return Err(Errors::DrawAlreadyClaimed.into());
+    };
+
+    if random_draw.holds_ticket(user_vote_account_data.draw_offset, user_vote_account_data.weight) == false {
+        return Err(Errors::NotDrawWinner.into());
+    };
+
+    random_draw.winner = user_vote_account_data.payer;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Draw winner - {}", user_vote_account_data.payer);
+
+    Ok(())
+}
+
+fn process_finalize_voting(program_id: &Pubkey, accounts: FinalizeVotingAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let FinalizeVotingAccounts { voting_account, poll_card, creator_stats, instructions_sysvar, deposit_recipient, oracle_feed } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_active()?;
+
+    let current_time = sysvars.current_time();
+    if voting_account_data.ends_at > current_time {
+        return Err(Errors::VotingNotEnded.into());
+    };
+
+    if voting_account_data.reveal_ends_at() > current_time {
+        return Err(Errors::RevealWindowOpen.into());
+    };
+
+    voting_account_data.check_not_frozen()?;
+
+    check_no_ballots_in_transaction(program_id, instructions_sysvar, voting_account.key)?;
+
+    voting_account_data.check_shards_merged()?;
+
+    if voting_account_data.envelope_key.is_some() && voting_account_data.envelope_commitment.is_none() {
+        return Err(Errors::EnvelopeResultsMissing.into());
+    };
+
+    if let Some(oracle_condition) = voting_account_data.oracle_condition.as_ref() {
+        let oracle_feed = oracle_feed.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        voting_account_data.condition_met = oracle_condition.evaluate(oracle_feed, current_time)?;
+    };
+
+    // The deposit goes back to the creator while the refund window is open. Past it, whoever cleans the
+    // voting up keeps it.
+    let deposit = voting_account_data.creation_deposit;
+    let refund_deadline = voting_account_data.ends_at.saturating_add(voting_account_data.deposit_refund_window);
+    if deposit > 0 && current_time <= refund_deadline && *deposit_recipient.key != voting_account_data.creator {
+        return Err(Errors::InvalidDepositRecipient.into());
+    };
+
+    voting_account_data.status = VotingStatus::Finalized;
+    voting_account_data.outcome = Some(voting_account_data.decide_outcome());
+    voting_account_data.result = voting_account_data.winning_option();
+    voting_account_data.winners = voting_account_data.winners();
+    voting_account_data.seats = voting_account_data.apportioned_seats()?;
+    voting_account_data.creation_deposit = 0;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    if deposit > 0 {
+        **voting_account.try_borrow_mut_lamports()? -= deposit;
+        **deposit_recipient.try_borrow_mut_lamports()? += deposit;
+
+        if current_time <= refund_deadline {
+            msg!("Creation deposit refunded - {} lamports", deposit);
+        } else {
+            msg!("Creation deposit forfeited - {} lamports", deposit);
+        };
+    };
+
+    assert_pda(creator_stats, find_creator_stats_pda(&voting_account_data.creator, program_id))?;
+    let mut creator_stats_data = load_creator_stats(creator_stats)?;
+    creator_stats_data.active_votings = creator_stats_data.active_votings.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    creator_stats_data.finalized_votings = creator_stats_data.finalized_votings.saturating_add(1);
+    creator_stats_data.total_voters = creator_stats_data.total_voters.saturating_add(voting_account_data.total_voters);
+    creator_stats_data.flags = creator_stats_data.flags.saturating_add(voting_account_data.flag_count);
+    creator_stats_data.serialize(&mut &mut creator_stats.data.borrow_mut()[..])?;
+
+    let event = VotingFinalizedEvent {
+        voting: *voting_account.key,
+        winning_option: voting_account_data.result,
+        total_voters: voting_account_data.total_voters,
+        timestamp: current_time
+    };
+    log_event(VOTING_FINALIZED_EVENT_TAG, VOTING_FINALIZED_EVENT_VERSION, &event)?;
+
+    match (event.winning_option, voting_account_data.outcome) {
+        (Some(winner), _) => msg!("Voting finalized, winning option - {}", voting_account_data.options[winner as usize]),
+        (None, Some(VotingOutcome::QuorumNotMet)) => msg!("Voting finalized without a quorum."),
+        (None, _) => msg!("Voting finalized without a winner.")
+    };
+
+    let tied = voting_account_data.tally_mode.tied(&voting_account_data.tallies, &voting_account_data.tally_state);
+    if voting_account_data.tie_policy == TiePolicy::Runoff && tied.len() > 1 {
+        msg!("Runoff between options - {:?}", tied);
+    };
+
+    Ok(())
+}
+
+// A finalization can't share its transaction with a ballot for the same voting, so a ballot cast at the
+// boundary can't be finalized atomically with it. The sysvar only lists top-level instructions, ballots
+// cast through CPI go unnoticed.
+fn check_no_ballots_in_transaction(program_id: &Pubkey, instructions_sysvar: &AccountInfo, voting: &Pubkey) -> ProgramResult {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id != *program_id {
+            continue;
+        };
+
+        let ballot = InstructionType::from_discriminator(&instruction.data).is_some_and(InstructionType::is_ballot);
+        if ballot && instruction.accounts.iter().any(|account| account.pubkey == *voting) {
+            return Err(Errors::BallotInFinalizeTransaction.into());
+        };
+    };
+
+    Ok(())
+}
+
+fn process_add_proposal_instruction(
+    program_id: &Pubkey,
+    accounts: AddProposalInstructionAccounts,
+    ix_data: AddProposalInstructionInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let AddProposalInstructionAccounts { user, voting_account, proposal, allowlist, system_program, proposal_bump } = accounts;
+
+    // Like options, instructions are locked once voting opens.
+    let voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    if voting_account_data.realm == Pubkey::default() {
+        return Err(Errors::NotRealmVoting.into());
+    };
+
+    if ix_data.option as usize >= voting_account_data.options.len() {
+        return Err(Errors::InvalidOptionIndex.into());
+    };
+
+    let (governance, _) = find_governance_pda(&voting_account_data.realm, program_id);
+    ix_data.instruction.validate(&governance)?;
+
+    let actions = load_execution_allowlist(program_id, allowlist, &voting_account_data.realm)?;
+    if permits(&actions, &ix_data.instruction.program_id, &ix_data.instruction.data) == false {
+        return Err(Errors::ActionNotAllowed.into());
+    };
+
+    let mut proposal_account = if proposal.data_is_empty() {
+        let proposal_account = ProposalAccount {
+            discriminator: AccountType::Proposal.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            option: ix_data.option,
+            next_instruction: 0,
+            in_progress: false,
+            instructions: Vec::new()
+        };
+
+        let space = proposal_account.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                proposal.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                proposal.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    PROPOSAL_SEED,
+                    voting_account.key.as_ref(),
+                    &[ proposal_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(proposal)?;
+
+        proposal_account
+    } else {
+        assert_owned_by(proposal, program_id)?;
+
+        let data = proposal.data.borrow();
+        assert_account_type(&data, AccountType::Proposal)?;
+
+        try_from_slice_unchecked::<ProposalAccount>(&data)?
+    };
+    proposal_account.check_not_in_progress()?;
+
+    if proposal_account.option != ix_data.option {
+        return Err(Errors::ProposalOptionMismatch.into());
+    };
+
+    if proposal_account.instructions.len() >= MAX_PROPOSAL_INSTRUCTIONS {
+        return Err(Errors::TooManyProposalInstructions.into());
+    };
+
+    proposal_account.instructions.push(ix_data.instruction);
+    write_resized(&proposal_account, proposal, user, system_program, sysvars)?;
+
+    msg!("Proposal instruction added, {} in total.", proposal_account.instructions.len());
+
+    Ok(())
+}
+
+fn process_set_proposal_template(
+    program_id: &Pubkey,
+    accounts: ProposalTemplateAccounts,
+    ix_data: SetProposalTemplateInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ProposalTemplateAccounts { admin, realm, template, system_program, template_bump } = accounts;
+
+    let realm_account = load_realm(program_id, realm)?;
+    if realm_account.admin != *admin.key {
+        return Err(Errors::NotRealmAdmin.into());
+    };
+
+    let (governance, _) = find_governance_pda(realm.key, program_id);
+    ix_data.instruction.validate(&governance)?;
+    validate_template(&ix_data.instruction, &ix_data.params)?;
+
+    let template_account = ProposalTemplateAccount {
+        discriminator: AccountType::ProposalTemplate.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        realm: *realm.key,
+        id: ix_data.id,
+        instruction: ix_data.instruction,
+        params: ix_data.params
+    };
+
+    // Created at its first size, `write_resized` resizes it on replacement.
+    if template.data_is_empty() {
+        let space = template_account.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                admin.key,
+                template.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                admin.clone(),
+                template.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    PROPOSAL_TEMPLATE_SEED,
+                    realm.key.as_ref(),
+                    &ix_data.id.to_le_bytes(),
+                    &[ template_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(template)?;
+    } else {
+        assert_owned_by(template, program_id)?;
+
+        assert_account_type(&template.data.borrow(), AccountType::ProposalTemplate)?;
+    };
+
+    write_resized(&template_account, template, admin, system_program, sysvars)?;
+
+    msg!("Proposal template #{} set with {} placeholders.", template_account.id, template_account.params.len());
+
+    Ok(())
+}
+
+// Fills the template's placeholders and adds the result like `add_proposal_instruction`, so it still has
+// to pass the realm's execution allowlist.
+fn process_add_template_instruction(
+    program_id: &Pubkey,
+    accounts: AddTemplateInstructionAccounts,
+    ix_data: AddTemplateInstructionInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let AddTemplateInstructionAccounts { proposal_accounts, template } = accounts;
+
+    let realm = load_creator_voting(proposal_accounts.user, proposal_accounts.voting_account)?.realm;
+    assert_pda(template, find_proposal_template_pda(&realm, ix_data.template_id, program_id))?;
+
+    let template_account = {
+        let data = template.data.borrow();
+        assert_account_type(&data, AccountType::ProposalTemplate)?;
+
+        try_from_slice_unchecked::<ProposalTemplateAccount>(&data)?
+    };
+
+    let instruction = fill_template(&template_account.instruction, &template_account.params, &ix_data.values)?;
+
+    process_add_proposal_instruction(
+        program_id,
+        proposal_accounts,
+        AddProposalInstructionInstruction { option: ix_data.option, instruction },
+        sysvars
+    )
+}
+
+fn process_execute_next_instruction(
+    program_id: &Pubkey,
+    accounts: ExecuteNextInstructionAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ExecuteNextInstructionAccounts { proposal, voting_account, allowlist, governance, instruction_accounts } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let mut proposal_account = {
+        let data = proposal.data.borrow();
+        assert_account_type(&data, AccountType::Proposal)?;
+
+        try_from_slice_unchecked::<ProposalAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+    proposal_account.check_not_in_progress()?;
+
+    if voting_account_data.winning_option() != Some(proposal_account.option) {
+        return Err(Errors::ProposalNotPassed.into());
+    };
+
+    let instruction = proposal_account.instructions
+        .get(proposal_account.next_instruction as usize)
+        .ok_or(Errors::ProposalExecuted)?
+        .to_instruction();
+
+    // The allowlist may have shrunk since the instruction was added.
+    let actions = load_execution_allowlist(program_id, allowlist, &voting_account_data.realm)?;
+    if permits(&actions, &instruction.program_id, &instruction.data) == false {
+        return Err(Errors::ActionNotAllowed.into());
+    };
+
+    let governance_bump = assert_pda(governance, find_governance_pda(&voting_account_data.realm, program_id))?;
+
+    if instruction_accounts.len() != instruction.accounts.len() + 1
+        || instruction_accounts.iter().zip(instruction.accounts.iter()).any(|(account, meta)| *account.key != meta.pubkey)
+        || *instruction_accounts[instruction.accounts.len()].key != instruction.program_id
+    {
+        return Err(Errors::InvalidInstructionAccounts.into());
+    };
+
+    // Advance the cursor before the CPI, so the instruction can't be executed twice.
+    proposal_account.next_instruction += 1;
+
+    let mut account_infos = instruction_accounts.to_vec();
+    account_infos.push(governance.clone());
+    invoke_latched(proposal, &mut proposal_account, || invoke_signed(
+        &instruction,
+        &account_infos,
+        &[
+            &[
+                GOVERNANCE_SEED,
+                voting_account_data.realm.as_ref(),
+                &[ governance_bump ]
+            ]
+        ]
+    ))?;
+
+    msg!("Executed proposal instruction {} of {}.", proposal_account.next_instruction, proposal_account.instructions.len());
+
+    Ok(())
+}
+
+fn process_enable_token_ballots(
+    program_id: &Pubkey,
+    accounts: EnableTokenBallotsAccounts,
+    ix_data: EnableTokenBallotsInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let EnableTokenBallotsAccounts { user, voting_account, ballot_box, system_program, ballot_box_bump } = accounts;
+
+    // Like options, token ballots are set up before voting opens.
+    let voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    // A token transfer can't prove membership, eligibility or a signup, and quadratic weights need
+    // to be taken per voter, not per counted batch.
+    if voting_account_data.members_only
+        || voting_account_data.eligibility.clauses.is_empty() == false
+        || voting_account_data.signup_slots > 0
+        || voting_account_data.tally_mode == TallyMode::Quadratic
+    {
+        return Err(Errors::TokenBallotsUnsupported.into());
+    };
+
+    let ballot_box_account = BallotBoxAccount {
+        discriminator: AccountType::BallotBox.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        mint: ix_data.mint,
+        counted: vec![0; MAX_OPTIONS]
+    };
+
+    let space = ballot_box_account.try_to_vec()?.len();
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            ballot_box.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            ballot_box.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                BALLOT_BOX_SEED,
+                voting_account.key.as_ref(),
+                &[ ballot_box_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(ballot_box)?;
+
+    ballot_box_account.serialize(&mut &mut ballot_box.data.borrow_mut()[..])?;
+
+    msg!("Token ballots enabled, mint - {}", ix_data.mint);
+
+    Ok(())
+}
+
+fn process_count_token_ballots(
+    program_id: &Pubkey,
+    accounts: CountTokenBallotsAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CountTokenBallotsAccounts { payer, ballot_box, voting_account, poll_card, system_program, token_accounts } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let mut ballot_box_account = {
+        let data = ballot_box.data.borrow();
+        assert_account_type(&data, AccountType::BallotBox)?;
+
+        try_from_slice_unchecked::<BallotBoxAccount>(&data)?
+    };
+
+    // Tokens arriving after the end are never counted.
+    voting_account_data.check_accepts_votes(sysvars.current_time())?;
+
+    if token_accounts.len() != voting_account_data.options.len() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut counted = 0;
+    for (option, token_account) in token_accounts.iter().enumerate() {
+        let option = option as u8;
+        let (ballot_address, _) = find_ballot_address(voting_account.key, option, program_id);
+        let balance = token_balance(token_account, &ballot_box_account.mint, &ballot_address, Errors::InvalidBallotTokenAccount)?;
+
+        // Ballot tokens can't leave the ballot address, so the balance only grows.
+        let new_ballots = balance.saturating_sub(ballot_box_account.counted[option as usize]);
+        if new_ballots == 0 {
+            continue;
+        };
+
+        let VoteMainAccount { tally_mode, tallies, tally_state, .. } = &mut voting_account_data;
+        tally_mode.accumulate(tallies, tally_state, &[ option ], new_ballots, true)?;
+        voting_account_data.check_option_caps(&[ option ])?;
+        voting_account_data.mark_changed(&[ option ], sysvars.current_time());
+
+        ballot_box_account.counted[option as usize] = balance;
+        counted += new_ballots;
+    };
+
+    write_resized_voting(&voting_account_data, voting_account, payer, system_program, sysvars)?;
+    ballot_box_account.serialize(&mut &mut ballot_box.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Counted {} new token ballots.", counted);
+
+    Ok(())
+}
+
+fn process_start_recount(
+    program_id: &Pubkey,
+    accounts: RecountAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let RecountAccounts { user, voting_account, recount, system_program, other_accounts, recount_bump } = accounts;
+    let ballot_box = &other_accounts[0];
+
+    assert_pda(ballot_box, find_ballot_box_pda(voting_account.key, program_id))?;
+
+    let voting_account_data = load_creator_voting(user, voting_account)?;
+    voting_account_data.check_active()?;
+    voting_account_data.check_shards_merged()?;
+
+    let mut recount_account = RecountAccount {
+        discriminator: AccountType::Recount.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        last_record: Pubkey::default(),
+        records_counted: 0,
+        tallies: vec![0; voting_account_data.options.len()],
+        tally_state: voting_account_data.tally_state.restarted()
+    };
+
+    // Token ballots have no user vote accounts, the shadow tallies start from the ballot box.
+    if ballot_box.data_is_empty() == false {
+        assert_owned_by(ballot_box, program_id)?;
+
+        let ballot_box_account = {
+            let data = ballot_box.data.borrow();
+            assert_account_type(&data, AccountType::BallotBox)?;
+
+            try_from_slice_unchecked::<BallotBoxAccount>(&data)?
+        };
+
+        let RecountAccount { tallies, tally_state, .. } = &mut recount_account;
+        for option in 0..voting_account_data.options.len() {
+            if ballot_box_account.counted[option] > 0 {
+                voting_account_data.tally_mode.accumulate(tallies, tally_state, &[ option as u8 ], ballot_box_account.counted[option], true)?;
+            };
+        };
+    };
+
+    // Starting again discards a recount in progress.
+    if recount.data_is_empty() {
+        let space = recount_account.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                recount.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                recount.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    RECOUNT_SEED,
+                    voting_account.key.as_ref(),
+                    &[ recount_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(recount)?;
+    } else {
+        assert_owned_by(recount, program_id)?;
+
+        assert_account_type(&recount.data.borrow(), AccountType::Recount)?;
+    };
+
+    write_resized(&recount_account, recount, user, system_program, sysvars)?;
+
+    msg!("Recount started, {} voters to count.", voting_account_data.total_voters);
+
+    Ok(())
+}
+
+fn process_recount_votes(
+    program_id: &Pubkey,
+    accounts: RecountAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let RecountAccounts { user, voting_account, recount, system_program, other_accounts, .. } = accounts;
+
+    let voting_account_data = load_creator_voting(user, voting_account)?;
+    let mut recount_account = load_recount(recount)?;
+
+    for record in other_accounts.iter() {
+        assert_owned_by(record, program_id)?;
+
+        let user_vote_account_data = {
+            let data = record.data.borrow();
+            assert_account_type(&data, AccountType::UserVoting)?;
+
+            try_from_slice_unchecked::<UserVotingAccount>(&data)?
+        };
+
+        assert_pda(record, find_user_vote_pda(voting_account.key, &user_vote_account_data.payer, program_id))?;
+
+        if *record.key <= recount_account.last_record {
+            return Err(Errors::InvalidRecountOrder.into());
+        };
+
+        // Ballots left unrevealed were never counted.
+        recount_account.last_record = *record.key;
+        if user_vote_account_data.commitment.is_some() {
+            continue;
+        };
+
+        let RecountAccount { tallies, tally_state, .. } = &mut recount_account;
+        voting_account_data.tally_mode.accumulate(tallies, tally_state, &user_vote_account_data.ballot, user_vote_account_data.weight, true)?;
+
+        recount_account.records_counted += 1;
+    };
+
+    // The instant-runoff rankings can grow, the creator pays for them.
+    write_resized(&recount_account, recount, user, system_program, sysvars)?;
+
+    msg!("Recounted {} of {} voters.", recount_account.records_counted, voting_account_data.total_voters);
+
+    Ok(())
+}
+
+fn process_complete_recount(program_id: &Pubkey, accounts: RecountAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let RecountAccounts { user, voting_account, recount, system_program, other_accounts, .. } = accounts;
+    let poll_card = &other_accounts[0];
+
+    // A finalized voting's outcome is settled, payouts and executions already went by it.
+    let mut voting_account_data = load_creator_voting(user, voting_account)?;
+    voting_account_data.check_active()?;
+    let recount_account = load_recount(recount)?;
+
+    if recount_account.records_counted != voting_account_data.total_voters {
+        return Err(Errors::RecountIncomplete.into());
+    };
+
+    voting_account_data.tallies = recount_account.tallies;
+    voting_account_data.tally_state = recount_account.tally_state;
+    voting_account_data.reset_tie_draw();
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    // Close the recount, its rent goes back to the creator.
+    let lamports = recount.lamports();
+    **recount.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    recount.realloc(0, false)?;
+    recount.assign(&system_program_address);
+
+    msg!("Recount completed, tallies replaced.");
+
+    Ok(())
+}
+
+fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: InitializeConfigAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let InitializeConfigAccounts { authority, config, program_data, system_program, config_bump } = accounts;
+
+    // Only the upgrade authority may initialize the config, so it can't be front-run after deployment.
+    {
+        // UpgradeableLoaderState::ProgramData => tag (u32), slot (u64), Option<Pubkey>
+        let program_data_data = program_data.data.borrow();
+        if program_data_data.get(..4) != Some(&[3, 0, 0, 0][..]) {
+            return Err(Errors::InvalidProgramData.into());
+        };
+
+        if program_data_data.get(12) != Some(&1) || program_data_data.get(13..45) != Some(authority.key.as_ref()) {
+            return Err(Errors::NotUpgradeAuthority.into());
+        };
+    }
+
+    let space = CONFIG_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            authority.key,
+            config.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            authority.clone(),
+            config.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                CONFIG_SEED,
+                &[ config_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(config)?;
+
+    let config_account = ConfigAccount {
+        discriminator: AccountType::Config.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        admin: *authority.key,
+        disabled_instructions: 0,
+        max_active_votings: 0,
+        start_time_tolerance: DEFAULT_START_TIME_TOLERANCE,
+        pending_admin: None,
+        creation_deposit: 0,
+        deposit_refund_window: 0
+    };
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Config initialized.");
+
+    Ok(())
+}
+
+fn process_set_instruction_flags(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts,
+    ix_data: SetInstructionFlagsInstruction
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.admin != *admin.key {
+        return Err(Errors::NotConfigAdmin.into());
+    };
+
+    config_account.disabled_instructions = ix_data.disabled_instructions;
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Instruction flags updated - {:#b}", config_account.disabled_instructions);
+
+    Ok(())
+}
+
+fn process_set_max_active_votings(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts,
+    ix_data: SetMaxActiveVotingsInstruction
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.admin != *admin.key {
+        return Err(Errors::NotConfigAdmin.into());
+    };
+
+    config_account.max_active_votings = ix_data.max_active_votings;
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Max active votings per creator - {}", config_account.max_active_votings);
+
+    Ok(())
+}
+
+fn process_set_start_time_tolerance(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts,
+    ix_data: SetStartTimeToleranceInstruction
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.admin != *admin.key {
+        return Err(Errors::NotConfigAdmin.into());
+    };
+
+    if ix_data.start_time_tolerance > MAX_START_TIME_TOLERANCE {
+        return Err(Errors::InvalidStartTimeTolerance.into());
+    };
+
+    config_account.start_time_tolerance = ix_data.start_time_tolerance;
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Start time tolerance - {}s", config_account.start_time_tolerance);
+
+    Ok(())
+}
+
+fn process_set_creation_deposit(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts,
+    ix_data: SetCreationDepositInstruction
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.admin != *admin.key {
+        return Err(Errors::NotConfigAdmin.into());
+    };
+
+    if ix_data.creation_deposit > 0 && ix_data.deposit_refund_window < MIN_DEPOSIT_REFUND_WINDOW {
+        return Err(Errors::InvalidCreationDeposit.into());
+    };
+
+    config_account.creation_deposit = ix_data.creation_deposit;
+    config_account.deposit_refund_window = ix_data.deposit_refund_window;
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Creation deposit - {} lamports, refunded within {}s", config_account.creation_deposit, config_account.deposit_refund_window);
+
+    Ok(())
+}
+
+fn process_propose_admin(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts,
+    ix_data: ProposeAdminInstruction
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.admin != *admin.key {
+        return Err(Errors::NotConfigAdmin.into());
+    };
+
+    // The new admin only takes over once it signs `accept_admin`.
+    config_account.pending_admin = Some(ix_data.new_admin);
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Admin proposed - {}", ix_data.new_admin);
+
+    Ok(())
+}
+
+fn process_accept_admin(
+    program_id: &Pubkey,
+    accounts: ConfigAdminAccounts
+) -> ProgramResult {
+    let ConfigAdminAccounts { admin: new_admin, config } = accounts;
+
+    let mut config_account = load_config(program_id, config)?;
+    if config_account.pending_admin != Some(*new_admin.key) {
+        return Err(Errors::NotPendingAdmin.into());
+    };
+
+    config_account.admin = *new_admin.key;
+    config_account.pending_admin = None;
+    config_account.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    msg!("Admin updated - {}", config_account.admin);
+
+    Ok(())
+}
+
+fn process_set_voting_metadata(
+    program_id: &Pubkey,
+    accounts: VotingMetadataAccounts,
+    ix_data: SetVotingMetadataInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let VotingMetadataAccounts { user, voting_account, metadata, system_program, metadata_bump } = accounts;
+
+    load_creator_voting(user, voting_account)?;
+
+    if ix_data.timezone.len() > MAX_TIMEZONE_LENGTH || ix_data.schedule.len() > MAX_SCHEDULE_LENGTH {
+        return Err(Errors::DisplayHintTooLong.into());
+    };
+
+    // Created empty on first use, `write_resized` then sizes it to the hints.
+    if metadata.data_is_empty() {
+        let space = VotingMetadataAccount {
+            discriminator: AccountType::VotingMetadata.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            timezone: String::new(),
+            schedule: String::new()
+        }.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                metadata.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                metadata.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    VOTING_METADATA_SEED,
+                    voting_account.key.as_ref(),
+                    &[ metadata_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(metadata)?;
+    } else {
+        assert_owned_by(metadata, program_id)?;
+
+        assert_account_type(&metadata.data.borrow(), AccountType::VotingMetadata)?;
+    };
+
+    let metadata_account = VotingMetadataAccount {
+        discriminator: AccountType::VotingMetadata.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        timezone: ix_data.timezone,
+        schedule: ix_data.schedule
+    };
+    write_resized(&metadata_account, metadata, user, system_program, sysvars)?;
+
+    msg!("Voting metadata updated.");
+
+    Ok(())
+}
+
+fn process_set_localized_metadata(
+    program_id: &Pubkey,
+    accounts: LocalizedMetadataAccounts,
+    ix_data: SetLocalizedMetadataInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let LocalizedMetadataAccounts { user, voting_account, metadata, system_program, metadata_bump } = accounts;
+
+    load_creator_voting(user, voting_account)?;
+
+    if ix_data.title.is_empty() && ix_data.description.is_empty() {
+        if metadata.data_is_empty() {
+            return Err(ProgramError::UninitializedAccount);
+        };
+        assert_owned_by(metadata, program_id)?;
+
+        // The rent goes back to the creator.
+        let lamports = metadata.lamports();
+        **metadata.try_borrow_mut_lamports()? -= lamports;
+        **user.try_borrow_mut_lamports()? += lamports;
+        metadata.realloc(0, false)?;
+        metadata.assign(&system_program_address);
+
+        msg!("Localized metadata removed - {}", ix_data.language);
+
+        return Ok(());
+    };
+
+    // Translations can be shorter than the canonical title's minimum, e.g. in scripts without spaces.
+    if ix_data.title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(Errors::TitleTooLong.into());
+    };
+
+    if ix_data.title.is_empty() {
+        return Err(Errors::TitleInvalidLength.into());
+    };
+
+    if ix_data.title.chars().any(char::is_control) || ix_data.title.trim() != ix_data.title {
+        return Err(Errors::TitleInvalidCharacters.into());
+    };
+
+    if ix_data.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(Errors::DescriptionTooLong.into());
+    };
+
+    let metadata_account = LocalizedMetadataAccount {
+        discriminator: AccountType::LocalizedMetadata.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        language: ix_data.language,
+        title: ix_data.title,
+        description: ix_data.description
+    };
+
+    // Created at its first size, `write_resized` follows later edits.
+    if metadata.data_is_empty() {
+        let space = metadata_account.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                metadata.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                metadata.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    LOCALIZED_METADATA_SEED,
+                    voting_account.key.as_ref(),
+                    metadata_account.language.as_bytes(),
+                    &[ metadata_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(metadata)?;
+    } else {
+        assert_owned_by(metadata, program_id)?;
+
+        assert_account_type(&metadata.data.borrow(), AccountType::LocalizedMetadata)?;
+    };
+    write_resized(&metadata_account, metadata, user, system_program, sysvars)?;
+
+    msg!("Localized metadata updated - {}", metadata_account.language);
+
+    Ok(())
+}
+
+// The creator points off-chain notifiers at the voting, every change is logged as a
+// `NotificationTargetEvent`
+fn process_set_notification_target(
+    program_id: &Pubkey,
+    accounts: NotificationTargetAccounts,
+    ix_data: SetNotificationTargetInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let NotificationTargetAccounts { user, voting_account, target, system_program, target_bump } = accounts;
+
+    load_creator_voting(user, voting_account)?;
+
+    let event = NotificationTargetEvent {
+        voting: *voting_account.key,
+        creator: *user.key,
+        webhook_commitment: ix_data.webhook_commitment,
+        notify: ix_data.notify,
+        timestamp: sysvars.current_time()
+    };
+
+    if ix_data.webhook_commitment.is_none() && ix_data.notify.is_none() {
+        if target.data_is_empty() {
+            return Err(ProgramError::UninitializedAccount);
+        };
+        assert_owned_by(target, program_id)?;
+
+        // The rent goes back to the creator.
+        let lamports = target.lamports();
+        **target.try_borrow_mut_lamports()? -= lamports;
+        **user.try_borrow_mut_lamports()? += lamports;
+        target.realloc(0, false)?;
+        target.assign(&system_program_address);
+
+        log_event(NOTIFICATION_TARGET_EVENT_TAG, NOTIFICATION_TARGET_EVENT_VERSION, &event)?;
+        msg!("Notification target removed.");
+
+        return Ok(());
+    };
+
+    // Sized for both fields, so changing them never reallocates.
+    if target.data_is_empty() {
+        let rent_exempt = sysvars.rent.minimum_balance(NOTIFICATION_TARGET_ACCOUNT_SPACE);
+        invoke_signed(
+            &create_account(
+                user.key,
+                target.key,
+                rent_exempt,
+                NOTIFICATION_TARGET_ACCOUNT_SPACE as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                target.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    NOTIFICATION_TARGET_SEED,
+                    voting_account.key.as_ref(),
+                    &[ target_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(target)?;
+    } else {
+        assert_owned_by(target, program_id)?;
+
+        assert_account_type(&target.data.borrow(), AccountType::NotificationTarget)?;
+    };
+
+    let target_account = NotificationTargetAccount {
+        discriminator: AccountType::NotificationTarget.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        webhook_commitment: ix_data.webhook_commitment,
+        notify: ix_data.notify,
+        updated_at: event.timestamp
+    };
+    {
+        let mut data = target.data.borrow_mut();
+        // A shorter encoding leaves no bytes of the previous one behind
+        data.fill(0);
+        target_account.serialize(&mut &mut data[..])?;
+    };
+
+    log_event(NOTIFICATION_TARGET_EVENT_TAG, NOTIFICATION_TARGET_EVENT_VERSION, &event)?;
+    msg!("Notification target updated.");
+
+    Ok(())
+}
+
+fn process_upgrade_program(program_id: &Pubkey, accounts: UpgradeProgramAccounts) -> ProgramResult {
+    let UpgradeProgramAccounts { governance, realm, program_data, program, buffer, spill, rent_sysvar, clock_sysvar, loader } = accounts;
+
+    let governance_bump = assert_pda(governance, find_governance_pda(realm.key, program_id))?;
+
+    invoke_signed(
+        &bpf_loader_upgradeable::upgrade(
+            program_id,
+            buffer.key,
+            governance.key,
+            spill.key
+        ),
+        &[
+            program_data.clone(),
+            program.clone(),
+            buffer.clone(),
+            spill.clone(),
+            rent_sysvar.clone(),
+            clock_sysvar.clone(),
+            governance.clone(),
+            loader.clone()
+        ],
+        &[
+            &[
+                GOVERNANCE_SEED,
+                realm.key.as_ref(),
+                &[ governance_bump ]
+            ]
+        ]
+    )?;
+
+    msg!("Program upgraded from buffer - {}", buffer.key);
+
+    Ok(())
+}
+
+fn process_set_upgrade_authority(program_id: &Pubkey, accounts: SetUpgradeAuthorityAccounts) -> ProgramResult {
+    let SetUpgradeAuthorityAccounts { governance, realm, program_data, loader, new_authority } = accounts;
+
+    let governance_bump = assert_pda(governance, find_governance_pda(realm.key, program_id))?;
+
+    let mut account_infos = vec![program_data.clone(), governance.clone(), loader.clone()];
+    account_infos.extend(new_authority.cloned());
+    invoke_signed(
+        &bpf_loader_upgradeable::set_upgrade_authority(
+            program_id,
+            governance.key,
+            new_authority.map(|new_authority| new_authority.key)
+        ),
+        &account_infos,
+        &[
+            &[
+                GOVERNANCE_SEED,
+                realm.key.as_ref(),
+                &[ governance_bump ]
+            ]
+        ]
+    )?;
+
+    match new_authority {
+        Some(new_authority) => msg!("Upgrade authority set - {}", new_authority.key),
+        None => msg!("Program made immutable.")
+    };
+
+    Ok(())
+}
+
+fn process_append_allowlist_chunk(
+    program_id: &Pubkey,
+    accounts: AppendAllowlistChunkAccounts,
+    ix_data: AppendAllowlistChunkInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let AppendAllowlistChunkAccounts { user, voting_account, allowlist, page, system_program, allowlist_bump } = accounts;
+
+    // Once the voting started only a sealed allowlist with a late registration window takes chunks
+    let voting_account_data = load_voting_for_status_change(program_id, user, voting_account)?;
+    let current_time = sysvars.current_time();
+    let late = voting_account_data.status != VotingStatus::Draft && voting_account_data.starts_at <= current_time;
+    if late == false {
+        load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+    };
+
+    let mut allowlist_data = if allowlist.data_is_empty() {
+        if late {
+            return Err(Errors::AllowlistNotSealed.into());
+        };
+
+        create_voter_allowlist(program_id, user, voting_account, allowlist, system_program, allowlist_bump, sysvars)?;
+
+        VoterAllowlistAccount {
+            discriminator: AccountType::VoterAllowlist.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            voters_count: 0,
+            sealed: false,
+            root: None,
+            late_registration: 0,
+            late_chunks: Vec::new()
+        }
+    } else {
+        load_voter_allowlist(program_id, allowlist)?
+    };
+
+    if late {
+        voting_account_data.check_accepts_votes(current_time)?;
+
+        if allowlist_data.sealed == false {
+            return Err(Errors::AllowlistNotSealed.into());
+        };
+
+        if voting_account_data.late_registration_ends_at(allowlist_data.late_registration) <= current_time {
+            return Err(Errors::LateRegistrationClosed.into());
+        };
+
+        allowlist_data.late_chunks.push(LateAllowlistChunk { first_voter: allowlist_data.voters_count, added_at: current_time });
+    } else if allowlist_data.sealed {
+        return Err(Errors::AllowlistSealed.into());
+    };
+
+    // Chunks can't cross a page boundary, clients cut them there.
+    let page_offset = allowlist_data.voters_count as usize % MAX_ALLOWLIST_PAGE_VOTERS;
+    if ix_data.voters.is_empty()
+        || ix_data.voters.len() > MAX_ALLOWLIST_CHUNK
+        || page_offset + ix_data.voters.len() > MAX_ALLOWLIST_PAGE_VOTERS
+    {
+        return Err(Errors::InvalidAllowlistChunk.into());
+    };
+
+    let page_index = u16::try_from(allowlist_data.voters_count as usize / MAX_ALLOWLIST_PAGE_VOTERS)
+        .or(Err(Errors::InvalidAllowlistChunk))?;
+    let page_bump = assert_pda(page, find_voter_allowlist_page_pda(voting_account.key, page_index, program_id))?;
+
+    let mut page_data = if page_offset == 0 {
+        let page_data = VoterAllowlistPageAccount {
+            discriminator: AccountType::VoterAllowlistPage.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            page: page_index,
+            voters: Vec::new()
+        };
+        let space = page_data.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                page.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                page.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    VOTER_ALLOWLIST_PAGE_SEED,
+                    voting_account.key.as_ref(),
+                    &page_index.to_le_bytes(),
+                    &[ page_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(page)?;
+
+        page_data
+    } else {
+        assert_owned_by(page, program_id)?;
+
+        let data = page.data.borrow();
+        assert_account_type(&data, AccountType::VoterAllowlistPage)?;
+
+        try_from_slice_unchecked::<VoterAllowlistPageAccount>(&data)?
+    };
+
+    allowlist_data.voters_count += ix_data.voters.len() as u32;
+    page_data.voters.extend(ix_data.voters);
+    write_resized(&page_data, page, user, system_program, sysvars)?;
+
+    write_resized(&allowlist_data, allowlist, user, system_program, sysvars)?;
+
+    if late {
+        msg!("Allowlist chunk appended late, {} voters.", allowlist_data.voters_count);
+    } else {
+        msg!("Allowlist chunk appended.");
+    };
+
+    Ok(())
+}
+
+// Seals the allowlist right away, the root can't be changed or mixed with uploaded pages
+fn process_set_allowlist_root(
+    program_id: &Pubkey,
+    accounts: SetAllowlistRootAccounts,
+    ix_data: SetAllowlistRootInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let SetAllowlistRootAccounts { user, voting_account, allowlist, system_program, allowlist_bump } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    if allowlist.data_is_empty() == false {
+        return Err(Errors::AllowlistInUse.into());
+    };
+
+    create_voter_allowlist(program_id, user, voting_account, allowlist, system_program, allowlist_bump, sysvars)?;
+
+    let allowlist_data = VoterAllowlistAccount {
+        discriminator: AccountType::VoterAllowlist.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        voters_count: ix_data.voters_count,
+        sealed: true,
+        root: Some(ix_data.root),
+        late_registration: 0,
+        late_chunks: Vec::new()
+    };
+    allowlist_data.serialize(&mut &mut allowlist.data.borrow_mut()[..])?;
+
+    voting_account_data.init_stage |= STAGE_GATING;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Allowlist root set.");
+
+    Ok(())
+}
+
+fn process_seal_allowlist(program_id: &Pubkey, accounts: SealAllowlistAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let SealAllowlistAccounts { user, voting_account, allowlist } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    let mut allowlist_data = load_voter_allowlist(program_id, allowlist)?;
+    if allowlist_data.sealed {
+        return Err(Errors::AllowlistSealed.into());
+    };
+
+    allowlist_data.sealed = true;
+    allowlist_data.serialize(&mut &mut allowlist.data.borrow_mut()[..])?;
+
+    voting_account_data.init_stage |= STAGE_GATING;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Allowlist sealed with {} voters.", allowlist_data.voters_count);
+
+    Ok(())
+}
+
+// Sealed at most once, from `starts_at` until the first ballot, so no one voted under another roll
+fn process_seal_voter_roll(
+    program_id: &Pubkey,
+    accounts: SealVoterRollAccounts,
+    ix_data: SealVoterRollInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let SealVoterRollAccounts { user, voting_account } = accounts;
+
+    let mut voting_account_data = load_voting_for_status_change(program_id, user, voting_account)?;
+    voting_account_data.check_active()?;
+
+    let current_time = sysvars.current_time();
+    if voting_account_data.starts_at > current_time {
+        return Err(Errors::VotingNotStarted.into());
+    };
+
+    if voting_account_data.ends_at <= current_time {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    if voting_account_data.voter_roll.is_some() {
+        return Err(Errors::VoterRollSealed.into());
+    };
+
+    if voting_account_data.total_voters > 0 {
+        return Err(Errors::VoterRollClosed.into());
+    };
+
+    voting_account_data.voter_roll = Some(VoterRoll { root: ix_data.root, voters_count: ix_data.voters_count, sealed_at: current_time });
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Voter roll sealed with {} voters.", ix_data.voters_count);
+
+    Ok(())
+}
+
+fn create_voter_allowlist<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    voting_account: &AccountInfo<'a>,
+    allowlist: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    allowlist_bump: u8,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let space = VOTER_ALLOWLIST_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            allowlist.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            allowlist.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                VOTER_ALLOWLIST_SEED,
+                voting_account.key.as_ref(),
+                &[ allowlist_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(allowlist)
+}
+
+fn load_voter_allowlist(program_id: &Pubkey, allowlist: &AccountInfo) -> Result<VoterAllowlistAccount, ProgramError> {
+    assert_owned_by(allowlist, program_id)?;
+
+    let data = allowlist.data.borrow();
+    assert_account_type(&data, AccountType::VoterAllowlist)?;
+
+    Ok(try_from_slice_unchecked::<VoterAllowlistAccount>(&data)?)
+}
+
+fn process_allocate_vote_bucket(
+    program_id: &Pubkey,
+    accounts: AllocateVoteBucketAccounts,
+    ix_data: AllocateVoteBucketInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let AllocateVoteBucketAccounts { payer, voting_account, bucket, system_program, bucket_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_active()?;
+
+    if ix_data.index >= voting_account_data.record_buckets {
+        return Err(Errors::InvalidRecordBuckets.into());
+    };
+
+    let space = VOTE_BUCKET_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            payer.key,
+            bucket.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            payer.clone(),
+            bucket.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                VOTE_BUCKET_SEED,
+                voting_account.key.as_ref(),
+                &[ ix_data.index ],
+                &[ bucket_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(bucket)?;
+
+    let bucket_account = VoteBucketAccount {
+        discriminator: AccountType::VoteBucket.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        index: ix_data.index,
+        slots: vec![RecordSlot::default(); BUCKET_SLOTS]
+    };
+    bucket_account.serialize(&mut &mut bucket.data.borrow_mut()[..])?;
+
+    msg!("Vote bucket {} allocated.", ix_data.index);
+
+    Ok(())
+}
+
+// Where a voter's ballot is kept, see `VoteMainAccount::record_buckets`
+enum VoteRecord {
+    // The user vote account, with its bump
+    Account(u8),
+    // The voter's bucket, with the voter's slot
+    Bucket(VoteBucketAccount, usize)
+}
+
+// Bucketed votings take the voter's bucket in place of the user vote account
+fn load_vote_record(
+    program_id: &Pubkey,
+    voting_account: &AccountInfo,
+    voting_account_data: &VoteMainAccount,
+    user: &AccountInfo,
+    record: &AccountInfo
+) -> Result<VoteRecord, ProgramError> {
+    if voting_account_data.record_buckets == 0 {
+        let bump = assert_pda(record, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+        return Ok(VoteRecord::Account(bump));
+    };
+
+    let index = bucket_of(user.key, voting_account_data.record_buckets);
+    assert_pda(record, find_vote_bucket_pda(voting_account.key, index, program_id))?;
+    assert_owned_by(record, program_id)?;
+
+    let bucket_data = {
+        let data = record.data.borrow();
+        assert_account_type(&data, AccountType::VoteBucket)?;
+
+        try_from_slice_unchecked::<VoteBucketAccount>(&data)?
+    };
+
+    let slot = probe(&bucket_data.slots, user.key)?;
+
+    Ok(VoteRecord::Bucket(bucket_data, slot))
+}
+
+fn process_reserve_title(program_id: &Pubkey, accounts: ReserveTitleAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let ReserveTitleAccounts { user, reservation, system_program, title_hash, reservation_bump } = accounts;
+
+    let current_time = sysvars.current_time();
+
+    if reservation.data_is_empty() {
+        let space = TITLE_RESERVATION_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                reservation.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                reservation.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    TITLE_RESERVATION_SEED,
+                    title_hash.as_ref(),
+                    &[ reservation_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(reservation)?;
+    } else {
+        // The title is taken over once the previous reservation expired, its rent goes to the new reserver.
+        let reservation_data = load_title_reservation(program_id, reservation)?;
+        if reservation_data.reserver != *user.key && reservation_data.expires_at > current_time {
+            return Err(Errors::TitleReserved.into());
+        };
+    };
+
+    let reservation_data = TitleReservationAccount {
+        discriminator: AccountType::TitleReservation.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        reserver: *user.key,
+        expires_at: current_time + TITLE_RESERVATION_TIME
+    };
+    reservation_data.serialize(&mut &mut reservation.data.borrow_mut()[..])?;
+
+    msg!("Title reserved until {}", reservation_data.expires_at);
+
+    Ok(())
+}
+
+fn process_publish_voting(
+    program_id: &Pubkey,
+    accounts: PublishVotingAccounts,
+    ix_data: PublishVotingInstruction,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let PublishVotingAccounts { user, voting_account, poll_card } = accounts;
+
+    let mut voting_account_data = load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+    if voting_account_data.status != VotingStatus::Draft {
+        return Err(Errors::VotingNotDraft.into());
+    };
+
+    if voting_account_data.init_stage & voting_account_data.required_stages != voting_account_data.required_stages {
+        return Err(Errors::SetupIncomplete.into());
+    };
+
+    if ix_data.starts_at.saturating_add(start_time_tolerance(config)) < sysvars.current_time() {
+        return Err(Errors::InvalidStartingTime.into());
+    };
+
+    // The duration was validated against the voting settings on creation.
+    let duration = voting_account_data.ends_at - voting_account_data.starts_at;
+    voting_account_data.starts_at = ix_data.starts_at;
+    voting_account_data.ends_at = ix_data.starts_at.checked_add(duration).ok_or(ProgramError::ArithmeticOverflow)?;
+    voting_account_data.status = VotingStatus::Active;
+    voting_account_data.sync_status(sysvars.current_time());
+    voting_account_data.locked = true;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Voting published, starts at {}", voting_account_data.starts_at);
+
+    Ok(())
+}
+
+// Same accounts as `vote`, without the memo program. The envelope takes the place of the user vote account.
+fn process_cast_envelope(
+    program_id: &Pubkey,
+    accounts: VoteAccounts,
+    ix_data: CastEnvelopeInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let VoteAccounts { user, voting_account, user_vote_account, voter_index, poll_card, system_program, mut remaining_accounts, voter_index_page, .. } = accounts;
+
+    validate_title(&ix_data.vote_title)?;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    check_voted_title(program_id, voting_account, &voting_account_data, &ix_data.vote_title)?;
+
+    if voting_account_data.envelope_key.is_none() {
+        return Err(Errors::NotEnvelopeVoting.into());
+    };
+
+    let incentive_pool = next_incentive_pool(&voting_account_data, &mut remaining_accounts)?;
+
+    let weight = check_voter(
+        program_id,
+        voting_account,
+        &voting_account_data,
+        user,
+        &mut remaining_accounts,
+        ix_data.eligibility_proof.as_ref(),
+        ix_data.weight_proof.as_ref()
+    )?;
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    if ix_data.envelope.len() <= ENVELOPE_OVERHEAD || ix_data.envelope.len() > MAX_ENVELOPE_LENGTH {
+        return Err(Errors::InvalidEnvelope.into());
+    };
+
+    let envelope_bump = assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+    // Sealed ballots are only counted once the results are submitted, the preview has no tallies
+    if ix_data.dry_run {
+        if user_vote_account.data_is_empty() == false {
+            return Err(Errors::UserAlreadyVoted.into());
+        };
+
+        return return_vote_preview(weight, Vec::new());
+    };
+
+    let space = ENVELOPE_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            user_vote_account.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            user_vote_account.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                USER_VOTE_SEED,
+                voting_account.key.as_ref(),
+                user.key.as_ref(),
+                &[ envelope_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(user_vote_account)?;
+
+    let envelope_account = EnvelopeAccount {
+        discriminator: AccountType::Envelope.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voter: *user.key,
+        weight,
+        cast_at: current_time,
+        vote_index: voting_account_data.total_voters,
+        envelope: ix_data.envelope
+    };
+
+    voting_account_data.envelope_weight = voting_account_data.envelope_weight.checked_add(weight).ok_or(Errors::TallyOverflow)?;
+    voting_account_data.total_voters += 1;
+    voting_account_data.vote_chain = chain_vote_hash(&voting_account_data.vote_chain, user.key, &envelope_account.envelope, weight, current_time);
+
+    envelope_account.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+    voting_account_data.log_turnout_milestones(voting_account.key)?;
+    log_vote_cast(voting_account.key, user.key, None, weight, current_time)?;
+
+    add_to_voter_index(program_id, user, voter_index, system_program, voter_index_page, voting_account.key, sysvars)?;
+
+    if let Some(incentive_pool) = incentive_pool {
+        pay_incentive(program_id, voting_account.key, voting_account_data.incentive_per_vote, incentive_pool, user, sysvars)?;
+    };
+
+    msg!("Envelope cast.");
+    msg!("Voted to - {}", ix_data.vote_title);
+
+    Ok(())
+}
+
+// The program can't decrypt the ballots, it only checks the results add up to the sealed weight. Single
+// choice ballots count towards one option each, approval ballots at most once towards every option.
+fn process_submit_envelope_results(
+    program_id: &Pubkey,
+    accounts: SubmitEnvelopeResultsAccounts,
+    ix_data: SubmitEnvelopeResultsInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let SubmitEnvelopeResultsAccounts { user, voting_account, poll_card } = accounts;
+
+    let mut voting_account_data = load_creator_voting(user, voting_account)?;
+
+    if voting_account_data.envelope_key.is_none() {
+        return Err(Errors::NotEnvelopeVoting.into());
+    };
+
+    voting_account_data.check_active()?;
+
+    if voting_account_data.ends_at > sysvars.current_time() {
+        return Err(Errors::VotingNotEnded.into());
+    };
+
+    if voting_account_data.envelope_commitment.is_some() || ix_data.tallies.len() != voting_account_data.options.len() {
+        return Err(Errors::InvalidEnvelopeResults.into());
+    };
+
+    let consistent = match voting_account_data.tally_mode {
+        TallyMode::Approval => ix_data.tallies.iter().all(|tally| *tally <= voting_account_data.envelope_weight),
+        _ => ix_data.tallies.iter().try_fold(0u64, |total, tally| total.checked_add(*tally)) == Some(voting_account_data.envelope_weight)
+    };
+    if consistent == false {
+        return Err(Errors::InvalidEnvelopeResults.into());
+    };
+
+    voting_account_data.tallies = ix_data.tallies;
+    voting_account_data.envelope_commitment = Some(ix_data.commitment);
+    voting_account_data.reset_tie_draw();
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Envelope results submitted.");
+
+    Ok(())
+}
+
+// For `TiePolicy::Random` votings closed with a tie
+fn process_commit_tie_break(accounts: DrawAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let DrawAccounts { voting_account, .. } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    if voting_account_data.tie_policy != TiePolicy::Random {
+        return Err(Errors::InvalidTiePolicy.into());
+    };
+
+    // Tallies replaced after the close reset the draw, so the tied options don't change under it.
+    let tied = voting_account_data.tally_mode.tied(&voting_account_data.tallies, &voting_account_data.tally_state);
+    if tied.len() < 2 {
+        return Err(Errors::NoTie.into());
+    };
+
+    voting_account_data.tie_draw.total_weight = tied.len() as u64;
+    let reveal_slot = voting_account_data.tie_draw.commit(sysvars.clock.slot)?;
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Tie break committed, reveal after slot - {}", reveal_slot);
+
+    Ok(())
+}
+
+fn process_reveal_tie_break(accounts: DrawAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let DrawAccounts { voting_account, slot_hashes } = accounts;
+    let slot_hashes = slot_hashes.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    let tied = voting_account_data.tally_mode.tied(&voting_account_data.tallies, &voting_account_data.tally_state);
+    let ticket = voting_account_data.tie_draw.reveal(voting_account.key, slot_hashes, sysvars.clock.slot)?;
+    let option = tied.get(ticket as usize).copied().ok_or(Errors::NoTie)?;
+    voting_account_data.tie_draw.ticket = option as u64;
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Tie broken, winning option - {}", voting_account_data.options[option as usize]);
+
+    Ok(())
+}
+
+// Keeps a finalized voting's outcome in a small archive account and gives the rest of the voting's rent
+// back to the creator. Voters get `ARCHIVE_DELAY` after the end to claim certificates, winnings and boosts
+// first, these need the full voting.
+fn process_archive_voting(
+    program_id: &Pubkey,
+    accounts: ArchiveVotingAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ArchiveVotingAccounts { user, voting_account, archive, system_program, archive_bump } = accounts;
+
+    let voting_account_data = load_creator_voting(user, voting_account)?;
+
+    // Passed proposals of a realm still execute from the voting.
+    if voting_account_data.realm != Pubkey::default() {
+        return Err(Errors::RealmVotingNotArchivable.into());
+    };
+
+    let current_time = sysvars.current_time();
+    if voting_account_data.status != VotingStatus::Finalized
+        || voting_account_data.ends_at.saturating_add(ARCHIVE_DELAY) > current_time
+    {
+        return Err(Errors::ArchiveTooEarly.into());
+    };
+
+    voting_account_data.check_result_attested()?;
+
+    let archive_account = VotingArchiveAccount {
+        discriminator: AccountType::VotingArchive.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        creator: voting_account_data.creator,
+        winning_option: voting_account_data.winning_option(),
+        tallies: voting_account_data.tally_state.totals(&voting_account_data.tallies),
+        total_voters: voting_account_data.total_voters,
+        vote_chain: voting_account_data.vote_chain,
+        records_root: voting_account_data.records_root,
+        archived_at: current_time,
+        title: voting_account_data.title.to_string()
+    };
+
+    let space = archive_account.try_to_vec()?.len();
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            archive.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            archive.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                VOTING_ARCHIVE_SEED,
+                voting_account.key.as_ref(),
+                &[ archive_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(archive)?;
+    archive_account.serialize(&mut &mut archive.data.borrow_mut()[..])?;
+
+    // Only the discriminator stays, so the voting's PDA and title can't be taken again.
+    let tombstone = AccountType::ArchivedVoting.discriminator();
+    voting_account.realloc(tombstone.len(), false)?;
+    voting_account.data.borrow_mut().copy_from_slice(&tombstone);
+
+    let refund = voting_account.lamports().saturating_sub(sysvars.rent.minimum_balance(tombstone.len()));
+    **voting_account.try_borrow_mut_lamports()? -= refund;
+    **user.try_borrow_mut_lamports()? += refund;
+
+    msg!("Voting archived, {} lamports refunded.", refund);
+
+    Ok(())
+}
+
+// Each attestor co-signs the finalized result once, in any order. The result counts from the attestation
+// reaching the threshold, later ones are still recorded.
+fn process_attest_result(accounts: AttestResultAccounts) -> ProgramResult {
+    let AttestResultAccounts { attestor, voting_account } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.status != VotingStatus::Finalized {
+        return Err(Errors::VotingNotFinalized.into());
+    };
+
+    let result_attestors = voting_account_data.result_attestors.as_mut()
+        .ok_or(Errors::NotResultAttestor)?;
+    let index = result_attestors.attestors.iter().position(|key| key == attestor.key)
+        .ok_or(Errors::NotResultAttestor)?;
+
+    if result_attestors.attested & (1 << index) != 0 {
+        return Err(Errors::ResultAlreadyAttested.into());
+    };
+
+    result_attestors.attested |= 1 << index;
+    let (attested, threshold) = (result_attestors.attested_count(), result_attestors.threshold);
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Result attested, {} of {} attestations.", attested, threshold);
+
+    if attested == threshold {
+        msg!("Result is canonical.");
+    };
+
+    Ok(())
+}
+
+// Crank for indexers reading the status byte, flips open votings between `Pending`, `Active` and `Ended`
+// as the clock goes. Running it again is a no-op.
+fn process_sync_status(program_id: &Pubkey, accounts: SyncStatusAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let SyncStatusAccounts { voting_account, poll_card } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    // The leading option shows up on the card once hidden tallies are revealed, even if the status
+    // was already up to date.
+    let current_time = sysvars.current_time();
+    let changed = voting_account_data.sync_status(current_time);
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    if changed == false {
+        msg!("Voting status is up to date - {:?}", voting_account_data.status);
+
+        return Ok(());
+    };
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Voting status - {:?}", voting_account_data.status);
+
+    Ok(())
+}
+
+// Cleans up after votings nobody archived. `GC_DELAY` after its end, a voting is shrunk to its tombstone
+// like `archive_voting`, without keeping an archive, and its user vote records can be closed, those of an
+// archived voting right away. Every closed account pays `GC_BOUNTY_BPS` of its lamports to the caller and
+// the rest to whoever funded it. Realm votings stay for their proposals, and ballots kept in buckets or
+// envelopes aren't swept.
+fn process_gc_expired(
+    program_id: &Pubkey,
+    accounts: GcExpiredAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let GcExpiredAccounts { caller, voting_account, voting_payer, creator_stats, records } = accounts;
+
+    assert_current_version(&voting_account.data.borrow())?;
+    let account_type = AccountType::from_discriminator(&voting_account.data.borrow());
+    match account_type {
+        Some(AccountType::Vote) => gc_voting(program_id, caller, voting_account, voting_payer, creator_stats, sysvars)?,
+        Some(AccountType::ArchivedVoting) => (),
+        _ => return Err(ProgramError::InvalidAccountData)
+    };
+
+    for record in records.chunks(2) {
+        let (user_vote, voter) = (&record[0], &record[1]);
+        assert_pda(user_vote, find_user_vote_pda(voting_account.key, voter.key, program_id))?;
+
+        let user_vote_data = {
+            let data = user_vote.data.borrow();
+            assert_account_type(&data, AccountType::UserVoting)?;
+
+            try_from_slice_unchecked::<UserVotingAccount>(&data)?
+        };
+
+        if user_vote_data.payer != *voter.key {
+            return Err(Errors::InvalidPayer.into());
+        };
+
+        let lamports = user_vote.lamports();
+        user_vote.realloc(0, false)?;
+        user_vote.assign(&system_program_address);
+        pay_out_sweep(user_vote, caller, voter, lamports, 0)?;
+    };
+
+    msg!("Swept {} vote records.", records.len() / 2);
+
+    Ok(())
+}
+
+fn gc_voting<'a>(
+    program_id: &Pubkey,
+    caller: &AccountInfo<'a>,
+    voting_account: &AccountInfo<'a>,
+    voting_payer: &AccountInfo<'a>,
+    creator_stats: &AccountInfo<'a>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(&voting_account.data.borrow())?;
+
+    if voting_account_data.realm != Pubkey::default() {
+        return Err(Errors::RealmVotingNotArchivable.into());
+    };
+
+    let current_time = sysvars.current_time();
+    if voting_account_data.ends_at.saturating_add(GC_DELAY) > current_time {
+        return Err(Errors::VotingNotExpired.into());
+    };
+
+    if voting_account_data.payer != *voting_payer.key {
+        return Err(Errors::InvalidPayer.into());
+    };
+
+    // Never finalized, so it still counts towards the creator's open votings.
+    if matches!(voting_account_data.status, VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended | VotingStatus::Draft) {
+        assert_pda(creator_stats, find_creator_stats_pda(&voting_account_data.creator, program_id))?;
+        assert_owned_by(creator_stats, program_id)?;
+        let mut creator_stats_data = load_creator_stats(creator_stats)?;
+        creator_stats_data.active_votings = creator_stats_data.active_votings.saturating_sub(1);
+        creator_stats_data.serialize(&mut &mut creator_stats.data.borrow_mut()[..])?;
+    };
+
+    let tombstone = AccountType::ArchivedVoting.discriminator();
+    voting_account.realloc(tombstone.len(), false)?;
+    voting_account.data.borrow_mut().copy_from_slice(&tombstone);
+
+    // An unsettled creation deposit past its refund window goes to the caller, like on a late finalization.
+    let refund_deadline = voting_account_data.ends_at.saturating_add(voting_account_data.deposit_refund_window);
+    let forfeited = if current_time > refund_deadline { voting_account_data.creation_deposit } else { 0 };
+
+    let lamports = voting_account.lamports().saturating_sub(sysvars.rent.minimum_balance(tombstone.len()));
+    pay_out_sweep(voting_account, caller, voting_payer, lamports, forfeited)?;
+
+    msg!("Expired voting swept - {}", voting_account_data.title);
+
+    Ok(())
+}
+
+// Moves `lamports` out of a swept account, `forfeited` of them and the bounty on the rest to the caller
+fn pay_out_sweep(account: &AccountInfo, caller: &AccountInfo, payer: &AccountInfo, lamports: u64, forfeited: u64) -> ProgramResult {
+    let forfeited = forfeited.min(lamports);
+    let bounty = forfeited + ((lamports - forfeited) as u128 * GC_BOUNTY_BPS as u128 / 10_000) as u64;
+
+    **account.try_borrow_mut_lamports()? -= lamports;
+    **caller.try_borrow_mut_lamports()? += bounty;
+    **payer.try_borrow_mut_lamports()? += lamports - bounty;
+
+    Ok(())
+}
+
+// Run by the creator once the voting is finalized, over as many transactions as the records need. The
+// last batch stores the root in the voting and closes the tree, its rent goes back to the creator.
+fn process_merkleize_records(
+    program_id: &Pubkey,
+    accounts: RecordTreeAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let RecordTreeAccounts { user, voting_account, record_tree, system_program, records, record_tree_bump } = accounts;
+
+    let mut voting_account_data = load_creator_voting(user, voting_account)?;
+
+    if voting_account_data.status != VotingStatus::Finalized {
+        return Err(Errors::VotingNotFinalized.into());
+    };
+
+    if voting_account_data.records_root.is_some() {
+        return Err(Errors::RecordsMerkleized.into());
+    };
+
+    // The tree is built in `vote_index` order, which sharded ballots don't number sequentially.
+    if voting_account_data.tally_shards > 0 {
+        return Err(Errors::ShardedVoting.into());
+    };
+
+    let mut record_tree_account = if record_tree.data_is_empty() {
+        let record_tree_account = RecordTreeAccount {
+            discriminator: AccountType::RecordTree.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            tree: RecordTree::default()
+        };
+
+        let space = record_tree_account.try_to_vec()?.len();
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                record_tree.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                record_tree.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    RECORD_TREE_SEED,
+                    voting_account.key.as_ref(),
+                    &[ record_tree_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(record_tree)?;
+
+        record_tree_account
+    } else {
+        assert_owned_by(record_tree, program_id)?;
+
+        let data = record_tree.data.borrow();
+        assert_account_type(&data, AccountType::RecordTree)?;
+
+        try_from_slice_unchecked::<RecordTreeAccount>(&data)?
+    };
+
+    for record in records.iter() {
+        let vote_index = record_tree_account.tree.leaves;
+        let leaf = record_leaf_at(program_id, voting_account.key, record, vote_index)?;
+        record_tree_account.tree.append(leaf);
+    };
+
+    if record_tree_account.tree.leaves < voting_account_data.total_voters {
+        record_tree_account.serialize(&mut &mut record_tree.data.borrow_mut()[..])?;
+
+        msg!("Merkleized {} of {} vote records.", record_tree_account.tree.leaves, voting_account_data.total_voters);
+
+        return Ok(());
+    };
+
+    if record_tree_account.tree.leaves > voting_account_data.total_voters {
+        return Err(Errors::InvalidRecordOrder.into());
+    };
+
+    voting_account_data.records_root = Some(record_tree_account.tree.root());
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    let lamports = record_tree.lamports();
+    **record_tree.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    record_tree.realloc(0, false)?;
+    record_tree.assign(&system_program_address);
+
+    msg!("Vote records merkleized, {} in total.", record_tree_account.tree.leaves);
+
+    Ok(())
+}
+
+// Leaf of the record with `vote_index`, from a user vote account, an envelope or a vote bucket
+fn record_leaf_at(
+    program_id: &Pubkey,
+    voting: &Pubkey,
+    record: &AccountInfo,
+    vote_index: u64
+) -> Result<[u8; 32], ProgramError> {
+    assert_owned_by(record, program_id)?;
+
+    let data = record.data.borrow();
+    assert_current_version(&data)?;
+    let (voter, weight, ballot, record_index) = match AccountType::from_discriminator(&data) {
+        Some(AccountType::UserVoting) => {
+            let user_vote_account_data = try_from_slice_unchecked::<UserVotingAccount>(&data)?;
+            assert_pda(record, find_user_vote_pda(voting, &user_vote_account_data.payer, program_id))?;
+
+            (user_vote_account_data.payer, user_vote_account_data.weight, user_vote_account_data.ballot, user_vote_account_data.vote_index)
+        },
+        Some(AccountType::Envelope) => {
+            let envelope_account = try_from_slice_unchecked::<EnvelopeAccount>(&data)?;
+            assert_pda(record, find_user_vote_pda(voting, &envelope_account.voter, program_id))?;
+
+            (envelope_account.voter, envelope_account.weight, envelope_account.envelope, envelope_account.vote_index)
+        },
+        Some(AccountType::VoteBucket) => {
+            let bucket_account = try_from_slice_unchecked::<VoteBucketAccount>(&data)?;
+            assert_pda(record, find_vote_bucket_pda(voting, bucket_account.index, program_id))?;
+
+            let slot = bucket_account.slots.iter()
+                .find(|slot| slot.is_empty() == false && slot.vote_index == vote_index)
+                .ok_or(Errors::InvalidRecordOrder)?;
+
+            (slot.voter, slot.weight, slot.ballot().to_vec(), slot.vote_index)
+        },
+        _ => return Err(ProgramError::InvalidAccountData)
+    };
+
+    if record_index != vote_index {
+        return Err(Errors::InvalidRecordOrder.into());
+    };
+
+    Ok(record_leaf(&voter, vote_index, weight, &ballot))
+}
+
+// Proves a ballot against the records root, e.g. for a program granting a role to everyone who voted yes.
+// Keeps working after the voting is archived, the archive keeps the root.
+fn process_verify_ballot_proof(accounts: VerifyBallotProofAccounts, ix_data: VerifyBallotProofInstruction) -> ProgramResult {
+    let VerifyBallotProofAccounts { voting_account } = accounts;
+
+    let records_root = {
+        let data = voting_account.data.borrow();
+        assert_current_version(&data)?;
+        match AccountType::from_discriminator(&data) {
+            Some(AccountType::Vote) => try_from_slice_unchecked::<VoteMainAccount>(&data)?.records_root,
+            Some(AccountType::VotingArchive) => try_from_slice_unchecked::<VotingArchiveAccount>(&data)?.records_root,
+            _ => return Err(ProgramError::InvalidAccountData)
+        }
+    };
+    let records_root = records_root.ok_or(Errors::RecordsNotMerkleized)?;
+
+    let leaf = record_leaf(&ix_data.voter, ix_data.vote_index, ix_data.weight, &ix_data.ballot);
+    if verify_record_proof(&records_root, leaf, ix_data.vote_index, &ix_data.proof) == false {
+        return Err(Errors::InvalidBallotProof.into());
+    };
+
+    msg!("Ballot #{} of {} verified.", ix_data.vote_index, ix_data.voter);
+
+    Ok(())
+}
+
+// Permissionless, the flagger locks `FLAG_DEPOSIT` in the flag account until the voting is closed.
+fn process_flag_voting(
+    program_id: &Pubkey,
+    accounts: FlagAccounts,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let FlagAccounts { user, voting_account, flag, system_program, flag_bump } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if flag.data_is_empty() == false {
+        return Err(Errors::AlreadyFlagged.into());
+    };
+
+    let flag_account = FlagAccount {
+        discriminator: AccountType::Flag.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        flagger: *user.key,
+        flagged_at: sysvars.current_time()
+    };
+
+    let space = flag_account.try_to_vec()?.len();
+    let lamports = sysvars.rent.minimum_balance(space).checked_add(FLAG_DEPOSIT).ok_or(ProgramError::ArithmeticOverflow)?;
+    invoke_signed(
+        &create_account(
+            user.key,
+            flag.key,
+            lamports,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            flag.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                FLAG_SEED,
+                voting_account.key.as_ref(),
+                user.key.as_ref(),
+                &[ flag_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(flag)?;
+    flag_account.serialize(&mut &mut flag.data.borrow_mut()[..])?;
+
+    voting_account_data.flag_count = voting_account_data.flag_count.saturating_add(1);
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Voting flagged, {} flags in total.", voting_account_data.flag_count);
+
+    Ok(())
+}
+
+// Gives the deposit and rent back once the voting is closed. The flag stays counted.
+fn process_close_flag(accounts: FlagAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let FlagAccounts { user, voting_account, flag, .. } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    assert_account_type(&flag.data.borrow(), AccountType::Flag)?;
+
+    let lamports = flag.lamports();
+    **flag.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    flag.realloc(0, false)?;
+    flag.assign(&system_program_address);
+
+    msg!("Flag closed, {} lamports returned.", lamports);
+
+    Ok(())
+}
+
+fn process_hide_voting(
+    program_id: &Pubkey,
+    accounts: HideVotingAccounts,
+    ix_data: HideVotingInstruction
+) -> ProgramResult {
+    let HideVotingAccounts { admin, voting_account, authority } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    check_moderator(program_id, admin, authority, &voting_account_data)?;
+
+    voting_account_data.moderation = if ix_data.hidden { ModerationStatus::Hidden } else { ModerationStatus::Visible };
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    msg!("Voting moderation set to {:?}.", voting_account_data.moderation);
+
+    Ok(())
+}
+
+// `authority` is the config PDA for the program admin, or the voting's realm for its admin
+fn check_moderator(
+    program_id: &Pubkey,
+    admin: &AccountInfo,
+    authority: &AccountInfo,
+    voting_account_data: &VoteMainAccount
+) -> ProgramResult {
+    let moderator = if *authority.key == find_config_pda(program_id).0 {
+        load_config(program_id, authority)?.admin
+    } else if *authority.key == voting_account_data.realm {
+        load_realm(program_id, authority)?.admin
+    } else {
+        return Err(Errors::NotModerator.into());
+    };
+
+    if moderator != *admin.key {
+        return Err(Errors::NotModerator.into());
+    };
+
+    Ok(())
+}
+
+fn process_fund_incentives(
+    program_id: &Pubkey,
+    accounts: IncentivePoolAccounts,
+    ix_data: FundIncentivesInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let IncentivePoolAccounts { user, voting_account, pool, system_program, pool_bump } = accounts;
+
+    let mut voting_account_data = load_creator_voting(user, voting_account)?;
+
+    if voting_account_data.status != VotingStatus::Draft {
+        voting_account_data.check_active()?;
+    };
+
+    if voting_account_data.ends_at <= sysvars.current_time() {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    // Signup votings take slot claims, which aren't paid, and sharded ballots don't take the pool.
+    if ix_data.amount == 0
+        || ix_data.lamports_per_vote == 0
+        || voting_account_data.signup_slots > 0
+        || voting_account_data.tally_shards > 0
+        || (voting_account_data.incentive_per_vote != 0 && voting_account_data.incentive_per_vote != ix_data.lamports_per_vote)
+    {
+        return Err(Errors::InvalidIncentive.into());
+    };
+
+    let mut pool_data = if pool.data_is_empty() {
+        let space = INCENTIVE_POOL_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                user.key,
+                pool.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                user.clone(),
+                pool.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    INCENTIVE_POOL_SEED,
+                    voting_account.key.as_ref(),
+                    &[ pool_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(pool)?;
+
+        IncentivePoolAccount {
+            discriminator: AccountType::IncentivePool.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting_account.key,
+            total_funded: 0,
+            total_paid: 0,
+            in_progress: false
+        }
+    } else {
+        assert_owned_by(pool, program_id)?;
+
+        let data = pool.data.borrow();
+        assert_account_type(&data, AccountType::IncentivePool)?;
+
+        try_from_slice_unchecked::<IncentivePoolAccount>(&data)?
+    };
+
+    pool_data.check_not_in_progress()?;
+
+    pool_data.total_funded = pool_data.total_funded.checked_add(ix_data.amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    voting_account_data.incentive_per_vote = ix_data.lamports_per_vote;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    invoke_latched(pool, &mut pool_data, || invoke(
+        &transfer(
+            user.key,
+            pool.key,
+            ix_data.amount
+        ),
+        &[
+            user.clone(),
+            pool.clone(),
+            system_program.clone()
+        ]
+    ))?;
+
+    msg!("Incentives funded - {}", pool_data.total_funded);
+
+    Ok(())
+}
+
+// Whatever wasn't paid out goes back to the creator once the voting is closed.
+fn process_withdraw_incentives(accounts: IncentivePoolAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let IncentivePoolAccounts { user, voting_account, pool, .. } = accounts;
+
+    let voting_account_data = load_creator_voting(user, voting_account)?;
+    voting_account_data.check_closed(sysvars.current_time())?;
+
+    let pool_data = {
+        let data = pool.data.borrow();
+        assert_account_type(&data, AccountType::IncentivePool)?;
+
+        try_from_slice_unchecked::<IncentivePoolAccount>(&data)?
+    };
+    pool_data.check_not_in_progress()?;
+
+    let lamports = pool.lamports();
+    **pool.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+    pool.realloc(0, false)?;
+    pool.assign(&system_program_address);
+
+    msg!("Incentive pool closed, {} lamports returned.", lamports);
+
+    Ok(())
+}
+
+// Cancelling is only a status change, it stops the voting from taking ballots until `ends_at`. It keeps
+// its accounts and can be restored with `restore_voting` as long as it hasn't reached `starts_at`.
+// Boosters can take their deposits back while it stays cancelled. The voting stops counting towards the
+// creator's open votings.
+fn process_cancel_voting(program_id: &Pubkey, accounts: CancelVotingAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let CancelVotingAccounts { user, voting_account, poll_card, creator_stats } = accounts;
+
+    let mut voting_account_data = load_voting_for_status_change(program_id, user, voting_account)?;
+    voting_account_data.check_active()?;
+
+    let current_time = sysvars.current_time();
+    if voting_account_data.ends_at <= current_time {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    voting_account_data.status = VotingStatus::Cancelled;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    let mut creator_stats_data = load_creator_stats(creator_stats)?;
+    creator_stats_data.active_votings = creator_stats_data.active_votings.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    creator_stats_data.cancelled_votings = creator_stats_data.cancelled_votings.saturating_add(1);
+    creator_stats_data.serialize(&mut &mut creator_stats.data.borrow_mut()[..])?;
+
+    if voting_account_data.starts_at > current_time {
+        msg!("Voting cancelled, it can be restored until {}", voting_account_data.starts_at);
+    } else {
+        msg!("Voting cancelled.");
+    };
+
+    Ok(())
+}
+
+// Undoes `cancel_voting` before `starts_at`. The voting counts towards the config's cap again.
+fn process_restore_voting(
+    program_id: &Pubkey,
+    accounts: CancelVotingAccounts,
+    config: Option<&ConfigAccount>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CancelVotingAccounts { user, voting_account, poll_card, creator_stats } = accounts;
+
+    let mut voting_account_data = load_voting_for_status_change(program_id, user, voting_account)?;
+    if voting_account_data.status != VotingStatus::Cancelled {
+        return Err(Errors::VotingNotCancelled.into());
+    };
+
+    if voting_account_data.starts_at <= sysvars.current_time() {
+        return Err(Errors::VotingAlreadyStarted.into());
+    };
+
+    let mut creator_stats_data = load_creator_stats(creator_stats)?;
+    let max_active_votings = config.map_or(0, |config| config.max_active_votings);
+    if max_active_votings > 0 && creator_stats_data.active_votings >= max_active_votings {
+        return Err(Errors::TooManyActiveVotings.into());
+    };
+
+    creator_stats_data.active_votings += 1;
+    creator_stats_data.cancelled_votings = creator_stats_data.cancelled_votings.saturating_sub(1);
+    creator_stats_data.serialize(&mut &mut creator_stats.data.borrow_mut()[..])?;
+
+    voting_account_data.status = VotingStatus::Active;
+    voting_account_data.sync_status(sysvars.current_time());
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Voting restored - {:?}", voting_account_data.status);
+
+    Ok(())
+}
+
+// The creator's voting, the callers check when its status may change
+fn load_voting_for_status_change(
+    program_id: &Pubkey,
+    user: &AccountInfo,
+    voting_account: &AccountInfo,
+) -> Result<VoteMainAccount, ProgramError> {
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if voting_account_data.creator != *user.key {
+        return Err(Errors::NotVotingCreator.into());
+    };
+
+    Ok(voting_account_data)
+}
+
+// Anyone can allocate the shards of a sharded voting, until it's closed
+fn process_allocate_tally_shard(
+    program_id: &Pubkey,
+    accounts: AllocateTallyShardAccounts,
+    ix_data: AllocateTallyShardInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let AllocateTallyShardAccounts { payer, voting_account, shard, system_program, shard_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.status != VotingStatus::Draft {
+        voting_account_data.check_active()?;
+    };
+
+    if ix_data.index >= voting_account_data.tally_shards {
+        return Err(Errors::InvalidTallyShards.into());
+    };
+
+    let space = TALLY_SHARD_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            payer.key,
+            shard.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            payer.clone(),
+            shard.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                TALLY_SHARD_SEED,
+                voting_account.key.as_ref(),
+                &[ ix_data.index ],
+                &[ shard_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(shard)?;
+
+    let shard_account = TallyShardAccount {
+        discriminator: AccountType::TallyShard.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting_account.key,
+        index: ix_data.index,
+        voters: 0,
+        updates: 0,
+        vote_chain: [0; 32],
+        tallies: Vec::new(),
+        tally_state: voting_account_data.tally_state.restarted()
+    };
+    shard_account.serialize(&mut &mut shard.data.borrow_mut()[..])?;
+
+    msg!("Tally shard {} allocated.", ix_data.index);
+
+    Ok(())
+}
+
+// Ballots of sharded votings. The voting is only read and the ballot is counted in the voter's shard, so
+// ballots in different shards can land in the same slot. Voters already on record swap their ballot
+// when the voting allows updates, the gate and weight accounts are then left out. Shard-local counters
+// number the records, `vote_index` is unique but not sequential.
+fn process_cast_sharded_vote(
+    program_id: &Pubkey,
+    accounts: CastShardedVoteAccounts,
+    ix_data: CastShardedVoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CastShardedVoteAccounts { user, voting_account, user_vote_account, voter_index, shard, system_program, mut remaining_accounts, voter_index_page } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if voting_account_data.tally_shards == 0 {
+        return Err(Errors::InvalidTallyShards.into());
+    };
+
+    let index = shard_of(user.key, voting_account_data.tally_shards);
+    assert_pda(shard, find_tally_shard_pda(voting_account.key, index, program_id))?;
+    let mut shard_data = load_tally_shard(shard)?;
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    voting_account_data.tally_mode.validate_ballot(&ix_data.ballot, voting_account_data.options.len())?;
+
+    let user_vote_bump = assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+    if user_vote_account.data_is_empty() == false {
+        if voting_account_data.allow_updates == false {
+            return Err(Errors::VoteUpdatesDisabled.into());
+        };
+
+        assert_owned_by(user_vote_account, program_id)?;
+        let mut user_vote_account_data = {
+            let data = user_vote_account.data.borrow();
+            assert_account_type(&data, AccountType::UserVoting)?;
+
+            try_from_slice_unchecked::<UserVotingAccount>(&data)?
+        };
+
+        if user_vote_account_data.payer != *user.key {
+            return Err(Errors::NotVoteRecordOwner.into());
+        };
+
+        let weight = user_vote_account_data.weight;
+        let TallyShardAccount { tallies, tally_state, .. } = &mut shard_data;
+        voting_account_data.tally_mode.accumulate(tallies, tally_state, &user_vote_account_data.ballot, weight, false)?;
+        voting_account_data.tally_mode.accumulate(tallies, tally_state, &ix_data.ballot, weight, true)?;
+
+        if ix_data.dry_run {
+            return return_vote_preview(weight, Vec::new());
+        };
+
+        shard_data.updates = shard_data.updates.saturating_add(1);
+        shard_data.vote_chain = chain_vote_hash(&shard_data.vote_chain, user.key, &ix_data.ballot, weight, current_time);
+        write_resized(&shard_data, shard, user, system_program, sysvars)?;
+        log_shard_vote(voting_account.key, &shard_data, voting_account_data.tally_shards, true)?;
+        log_vote_updated(voting_account.key, user.key, voting_account_data.visible_choice(&ix_data.ballot, current_time), current_time)?;
+
+        user_vote_account_data.choice = ballot_choice(&ix_data.ballot);
+        user_vote_account_data.ballot = ix_data.ballot;
+        user_vote_account_data.last_time_voted = current_time;
+        user_vote_account_data.times_updated = user_vote_account_data.times_updated.saturating_add(1);
+        user_vote_account_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+        msg!("Vote updated.");
+
+        return Ok(());
+    };
+
+    let weight = check_voter(
+        program_id,
+        voting_account,
+        &voting_account_data,
+        user,
+        &mut remaining_accounts,
+        ix_data.eligibility_proof.as_ref(),
+        ix_data.weight_proof.as_ref()
+    )?;
+
+    // The shards are only added up once the voting ends, the preview has no tallies
+    if ix_data.dry_run {
+        if shard_data.tallies.is_empty() {
+            shard_data.tallies = vec![0; voting_account_data.options.len()];
+        };
+
+        let TallyShardAccount { tallies, tally_state, .. } = &mut shard_data;
+        voting_account_data.tally_mode.accumulate(tallies, tally_state, &ix_data.ballot, weight, true)?;
+
+        return return_vote_preview(weight, Vec::new());
+    };
+
+    create_user_vote_account(program_id, user, voting_account.key, user_vote_account, system_program, user_vote_bump, sysvars)?;
+
+    let vote_index = shard_data.voters
+        .checked_mul(voting_account_data.tally_shards as u64)
+        .and_then(|vote_index| vote_index.checked_add(index as u64))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let user_account = UserVotingAccount {
+        discriminator: AccountType::UserVoting.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        payer: *user.key,
+        first_voted_at: current_time,
+        last_time_voted: current_time,
+        times_updated: 0,
+        choice: ballot_choice(&ix_data.ballot),
+        weight,
+        credited_slot: sysvars.clock.slot,
+        draw_offset: 0,
+        vote_index,
+        commitment: None,
+        ballot: ix_data.ballot.clone(),
+        voted_to: voting_account_data.title
+    };
+    user_account.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+    // Options are fixed once the voting opens
+    if shard_data.tallies.is_empty() {
+        shard_data.tallies = vec![0; voting_account_data.options.len()];
+    };
+
+    let TallyShardAccount { tallies, tally_state, .. } = &mut shard_data;
+    voting_account_data.tally_mode.accumulate(tallies, tally_state, &ix_data.ballot, weight, true)?;
+    shard_data.voters += 1;
+    shard_data.vote_chain = chain_vote_hash(&shard_data.vote_chain, user.key, &ix_data.ballot, weight, current_time);
+    write_resized(&shard_data, shard, user, system_program, sysvars)?;
+    log_shard_vote(voting_account.key, &shard_data, voting_account_data.tally_shards, false)?;
+    log_vote_cast(voting_account.key, user.key, voting_account_data.visible_choice(&ix_data.ballot, current_time), weight, current_time)?;
+
+    add_to_voter_index(program_id, user, voter_index, system_program, voter_index_page, voting_account.key, sysvars)?;
+
+    msg!("Voted successfully.");
+    msg!("Voted to - {}", voting_account_data.title);
+
+    Ok(())
+}
+
+// Adds every shard to the voting once it stopped taking ballots. Permissionless, the caller pays for the
+// tally state growing. Results, finalization and recounts wait for it.
+fn process_merge_tally_shards(program_id: &Pubkey, accounts: MergeTallyShardsAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let MergeTallyShardsAccounts { payer, voting_account, poll_card, system_program, shards } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    voting_account_data.check_active()?;
+
+    if voting_account_data.ends_at > sysvars.current_time() {
+        return Err(Errors::VotingNotEnded.into());
+    };
+
+    if voting_account_data.tally_shards == 0 || shards.len() != voting_account_data.tally_shards as usize {
+        return Err(Errors::InvalidTallyShards.into());
+    };
+
+    if voting_account_data.shards_merged {
+        return Err(Errors::ShardsMerged.into());
+    };
+
+    for (index, shard) in shards.iter().enumerate() {
+        assert_pda(shard, find_tally_shard_pda(voting_account.key, index as u8, program_id))?;
+
+        // Never allocated, so it took no ballots
+        if shard.data_is_empty() {
+            continue;
+        };
+
+        assert_owned_by(shard, program_id)?;
+        let shard_data = load_tally_shard(shard)?;
+
+        let VoteMainAccount { tallies, tally_state, .. } = &mut voting_account_data;
+        tally_state.merge(tallies, &shard_data.tally_state, &shard_data.tallies)?;
+        voting_account_data.total_voters = voting_account_data.total_voters.checked_add(shard_data.voters).ok_or(ProgramError::ArithmeticOverflow)?;
+        voting_account_data.vote_chain = hashv(&[&voting_account_data.vote_chain, &shard_data.vote_chain]).to_bytes();
+    };
+
+    voting_account_data.shards_merged = true;
+    voting_account_data.reset_tie_draw();
+    write_resized_voting(&voting_account_data, voting_account, payer, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Tally shards merged, {} voters.", voting_account_data.total_voters);
+
+    Ok(())
+}
+
+fn log_shard_vote(voting: &Pubkey, shard: &TallyShardAccount, tally_shards: u8, update: bool) -> ProgramResult {
+    let event = ShardVoteEvent {
+        voting: *voting,
+        shard: shard.index,
+        tally_shards,
+        shard_voters: shard.voters,
+        shard_updates: shard.updates,
+        update
+    };
+    log_event(SHARD_VOTE_EVENT_TAG, SHARD_VOTE_EVENT_VERSION, &event)
+}
+
+fn log_vote_cast(voting: &Pubkey, voter: &Pubkey, choice: Option<u8>, weight: u64, timestamp: u64) -> ProgramResult {
+    let event = VoteCastEvent {
+        voting: *voting,
+        voter: *voter,
+        choice,
+        weight,
+        timestamp
+    };
+
+    log_event(VOTE_CAST_EVENT_TAG, VOTE_CAST_EVENT_VERSION, &event)
+}
+
+fn log_vote_updated(voting: &Pubkey, voter: &Pubkey, choice: Option<u8>, timestamp: u64) -> ProgramResult {
+    let event = VoteUpdatedEvent {
+        voting: *voting,
+        voter: *voter,
+        choice,
+        timestamp
+    };
+
+    log_event(VOTE_UPDATED_EVENT_TAG, VOTE_UPDATED_EVENT_VERSION, &event)
+}
+
+fn load_tally_shard(shard: &AccountInfo) -> Result<TallyShardAccount, ProgramError> {
+    let data = shard.data.borrow();
+    assert_account_type(&data, AccountType::TallyShard)?;
+
+    Ok(try_from_slice_unchecked::<TallyShardAccount>(&data)?)
+}
+
+// Credits more of the voter's current balance to a ballot of a capped voting, up to the cap once per
+// slot, until the ballot carries the whole balance. A balance that dropped since is left as credited.
+fn process_add_weight(program_id: &Pubkey, accounts: AddWeightAccounts, ix_data: AddWeightInstruction, sysvars: &Sysvars) -> ProgramResult {
+    let AddWeightAccounts { user, voting_account, user_vote_account, poll_card, system_program, mut remaining_accounts } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    if voting_account_data.weight_cap_per_slot == 0 {
+        return Err(Errors::InvalidWeightCap.into());
+    };
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    let mut user_vote_account_data = {
+        let data = user_vote_account.data.borrow();
+        assert_account_type(&data, AccountType::UserVoting)?;
+
+        try_from_slice_unchecked::<UserVotingAccount>(&data)?
+    };
+
+    if user_vote_account_data.payer != *user.key {
+        return Err(Errors::NotVoteRecordOwner.into());
+    };
+
+    if user_vote_account_data.credited_slot >= sysvars.clock.slot {
+        return Err(Errors::WeightAlreadyCredited.into());
+    };
+
+    let balance = voting_account_data.weight_source.resolve(
+        voting_account.key,
+        user.key,
+        &mut remaining_accounts,
+        None,
+        &voting_account_data.weight_settings()
+    )?;
+
+    if remaining_accounts.next().is_some() {
+        return Err(ProgramError::InvalidArgument);
+    };
+
+    let previous_weight = user_vote_account_data.weight;
+    let credited = voting_account_data.capped_weight(balance.saturating_sub(previous_weight));
+    if credited == 0 {
+        return Err(Errors::WeightFullyCredited.into());
+    };
+    let weight = previous_weight + credited;
+
+    // Taken back out and counted again, quadratic and ranked counts aren't linear in the weight
+    let ballot = &user_vote_account_data.ballot;
+    let VoteMainAccount { tally_mode, tallies, tally_state, .. } = &mut voting_account_data;
+    tally_mode.accumulate(tallies, tally_state, ballot, previous_weight, false)?;
+    tally_mode.accumulate(tallies, tally_state, ballot, weight, true)?;
+    voting_account_data.check_option_caps(ballot)?;
+
+    if ix_data.dry_run {
+        return return_vote_preview(weight, preview_tallies(&voting_account_data));
+    };
+
+    voting_account_data.mark_changed(ballot, current_time);
+    voting_account_data.vote_chain = chain_vote_hash(&voting_account_data.vote_chain, user.key, ballot, weight, current_time);
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    user_vote_account_data.weight = weight;
+    user_vote_account_data.credited_slot = sysvars.clock.slot;
+    user_vote_account_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+    msg!("Weight added - {}", credited);
+    msg!("Weight credited - {} of {}", weight, balance);
+
+    Ok(())
+}
+
+// Links the NFT holding the proposal document, replacing an earlier one. The creator must be the NFT's
+// update authority, so nobody else can point frontends at a document. Compressed NFTs have no metadata
+// account to check and aren't supported.
+fn process_link_proposal_note(
+    accounts: LinkProposalNoteAccounts,
+    ix_data: LinkProposalNoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let LinkProposalNoteAccounts { user, voting_account, metadata, system_program } = accounts;
+
+    let mut voting_account_data = load_creator_voting(user, voting_account)?;
+
+    if voting_account_data.status != VotingStatus::Draft {
+        voting_account_data.check_active()?;
+    };
+
+    {
+        let data = metadata.data.borrow();
+        if data.len() < METADATA_MINT_OFFSET + 32 || data[0] != METADATA_KEY {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        if data[METADATA_UPDATE_AUTHORITY_OFFSET..METADATA_UPDATE_AUTHORITY_OFFSET + 32] != *user.key.as_ref()
+            || data[METADATA_MINT_OFFSET..METADATA_MINT_OFFSET + 32] != *ix_data.mint.as_ref()
+        {
+            return Err(Errors::InvalidProposalNote.into());
+        };
+    };
+
+    voting_account_data.proposal_note = Some(ix_data.mint);
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+
+    msg!("Proposal note linked - {}", ix_data.mint);
+
+    Ok(())
+}
+
+// Records the ballot of a wallet that scanned a voting's QR code. The transaction sends any amount from
+// the voter to the kiosk with a system transfer, then a memo with the ballot's option indices separated
+// by commas, e.g. `2` or `0,3`, right before this instruction. Everything else works as for `vote`.
+fn process_vote_by_transfer_claim(
+    program_id: &Pubkey,
+    accounts: VoteByTransferClaimAccounts,
+    ix_data: VoteByTransferClaimInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let VoteByTransferClaimAccounts { kiosk, instructions_sysvar, vote_accounts } = accounts;
+
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    if current_index < 2 {
+        return Err(Errors::InvalidKioskTransfer.into());
+    };
+
+    let transfer_instruction = load_instruction_at_checked(current_index - 2, instructions_sysvar)?;
+    // Bincode encoded `SystemInstruction::Transfer`, the variant as a u32 and the lamports
+    let is_transfer = transfer_instruction.program_id == system_program_address
+        && transfer_instruction.data.len() == 4 + 8
+        && transfer_instruction.data[..4] == 2u32.to_le_bytes()
+        && transfer_instruction.accounts.len() == 2
+        && transfer_instruction.accounts[0].pubkey == *vote_accounts.user.key
+        && transfer_instruction.accounts[1].pubkey == *kiosk.key;
+    if is_transfer == false {
+        return Err(Errors::InvalidKioskTransfer.into());
+    };
+
+    let memo_instruction = load_instruction_at_checked(current_index - 1, instructions_sysvar)?;
+    if memo_instruction.program_id != spl_memo::id() {
+        return Err(Errors::InvalidKioskTransfer.into());
+    };
+
+    let ballot = std::str::from_utf8(&memo_instruction.data)
+        .ok()
+        .and_then(|memo| memo.split(',').map(|option| option.trim().parse::<u8>().ok()).collect::<Option<Vec<u8>>>())
+        .ok_or(Errors::InvalidKioskTransfer)?;
+
+    let VoteByTransferClaimInstruction { vote_title, weight_proof, eligibility_proof, voter_index_page, dry_run } = ix_data;
+    process_vote(
+        program_id,
+        vote_accounts,
+        VoteInstruction { ballot, vote_title, memo: false, weight_proof, eligibility_proof, dry_run, voter_index_page },
+        sysvars
+    )
+}
+
+// Transfers to a kiosk only mark ballots, the creator can take them out at any time
+fn process_withdraw_kiosk(accounts: WithdrawKioskAccounts) -> ProgramResult {
+    let WithdrawKioskAccounts { user, voting_account, kiosk, system_program, kiosk_bump } = accounts;
+
+    load_creator_voting(user, voting_account)?;
+
+    let lamports = kiosk.lamports();
+    if lamports > 0 {
+        invoke_signed(
+            &transfer(kiosk.key, user.key, lamports),
+            &[
+                kiosk.clone(),
+                user.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    KIOSK_SEED,
+                    voting_account.key.as_ref(),
+                    &[ kiosk_bump ]
+                ]
+            ]
+        )?;
+    };
+
+    msg!("Kiosk withdrawn - {} lamports", lamports);
+
+    Ok(())
+}
+
+// Gives the voter the rent of their vote record back once the voting is over: closed, cancelled or
+// archived. Certificates, winnings and recounts need the record, closing it gives them up.
+fn process_close_user_vote(program_id: &Pubkey, accounts: CloseUserVoteAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let CloseUserVoteAccounts { user, voting_account, user_vote_account } = accounts;
+
+    assert_current_version(&voting_account.data.borrow())?;
+    let account_type = AccountType::from_discriminator(&voting_account.data.borrow());
+    match account_type {
+        Some(AccountType::Vote) => {
+            let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(&voting_account.data.borrow())?;
+            assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+            if voting_account_data.status != VotingStatus::Cancelled {
+                voting_account_data.check_closed(sysvars.current_time())?;
+            };
+        },
+        Some(AccountType::ArchivedVoting) => (),
+        _ => return Err(ProgramError::InvalidAccountData)
+    };
+
+    let user_vote_account_data = {
+        let data = user_vote_account.data.borrow();
+        assert_account_type(&data, AccountType::UserVoting)?;
+
+        try_from_slice_unchecked::<UserVotingAccount>(&data)?
+    };
+
+    if user_vote_account_data.payer != *user.key {
+        return Err(Errors::NotVoteRecordOwner.into());
+    };
+
+    let lamports = user_vote_account.lamports();
+    user_vote_account.realloc(0, false)?;
+    user_vote_account.assign(&system_program_address);
+    **user_vote_account.try_borrow_mut_lamports()? -= lamports;
+    **user.try_borrow_mut_lamports()? += lamports;
+
+    msg!("Vote record closed, {} lamports refunded.", lamports);
+
+    Ok(())
+}
+
+// Like `archive_voting` without keeping an archive, the rent goes back to whoever paid for the voting.
+// Finalized and cancelled votings can be closed `ARCHIVE_DELAY` after their end, so voters and boosters
+// still get to claim first. Only the discriminator stays, so the voting's PDA and title can't be taken again.
+fn process_close_voting(accounts: CloseVotingAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let CloseVotingAccounts { user, voting_account, voting_payer } = accounts;
+
+    let voting_account_data = load_creator_voting(user, voting_account)?;
+
+    // Passed proposals of a realm still execute from the voting.
+    if voting_account_data.realm != Pubkey::default() {
+        return Err(Errors::RealmVotingNotArchivable.into());
+    };
+
+    if voting_account_data.payer != *voting_payer.key {
+        return Err(Errors::InvalidPayer.into());
+    };
+
+    if matches!(voting_account_data.status, VotingStatus::Finalized | VotingStatus::Cancelled) == false
+        || voting_account_data.ends_at.saturating_add(ARCHIVE_DELAY) > sysvars.current_time()
+    {
+        return Err(Errors::CloseTooEarly.into());
+    };
+
+    if voting_account_data.status == VotingStatus::Finalized {
+        voting_account_data.check_result_attested()?;
+    };
+
+    let tombstone = AccountType::ArchivedVoting.discriminator();
+    voting_account.realloc(tombstone.len(), false)?;
+    voting_account.data.borrow_mut().copy_from_slice(&tombstone);
+
+    let refund = voting_account.lamports().saturating_sub(sysvars.rent.minimum_balance(tombstone.len()));
+    **voting_account.try_borrow_mut_lamports()? -= refund;
+    **voting_payer.try_borrow_mut_lamports()? += refund;
+
+    msg!("Voting closed, {} lamports refunded.", refund);
+
+    Ok(())
+}
+
+// Pays a wallet that hasn't voted on the voting yet the rent its first vote needs, see `faucet_drip`, so
+// demo wallets vote without an airdrop. The faucet's own balance pays for the wallet's claim record.
+#[cfg(feature = "devnet")]
+fn process_claim_faucet(program_id: &Pubkey, accounts: ClaimFaucetAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let ClaimFaucetAccounts { wallet, voting_account, user_vote_account, faucet, faucet_claim, system_program, faucet_bump, faucet_claim_bump } = accounts;
+
+    assert_account_type(&voting_account.data.borrow(), AccountType::Vote)?;
+    if user_vote_account.data_is_empty() == false {
+        return Err(Errors::UserAlreadyVoted.into());
+    };
+
+    let current_time = sysvars.current_time();
+    let previous_claim = if faucet_claim.data_is_empty() {
+        None
+    } else {
+        assert_owned_by(faucet_claim, program_id)?;
+
+        let data = faucet_claim.data.borrow();
+        assert_account_type(&data, AccountType::FaucetClaim)?;
+
+        Some(try_from_slice_unchecked::<FaucetClaimAccount>(&data)?)
+    };
+
+    if let Some(previous_claim) = &previous_claim {
+        if current_time < previous_claim.last_claimed_at.saturating_add(FAUCET_COOLDOWN) {
+            return Err(Errors::FaucetCooldown.into());
+        };
+    };
+
+    // The faucet is a system account and has to stay rent exempt
+    let drip = faucet_drip(&sysvars.rent);
+    let claim_rent = if previous_claim.is_none() { sysvars.rent.minimum_balance(FAUCET_CLAIM_ACCOUNT_SPACE) } else { 0 };
+    if faucet.lamports() < drip + claim_rent + sysvars.rent.minimum_balance(0) {
+        return Err(Errors::FaucetEmpty.into());
+    };
+
+    let faucet_seeds: &[&[u8]] = &[ FAUCET_SEED, &[ faucet_bump ] ];
+    let mut faucet_claim_data = match previous_claim {
+        Some(previous_claim) => previous_claim,
+        None => {
+            invoke_signed(
+                &create_account(
+                    faucet.key,
+                    faucet_claim.key,
+                    claim_rent,
+                    FAUCET_CLAIM_ACCOUNT_SPACE as u64,
+                    program_id
+                ),
+                &[
+                    faucet.clone(),
+                    faucet_claim.clone(),
+                    system_program.clone()
+                ],
+                &[
+                    faucet_seeds,
+                    &[
+                        FAUCET_CLAIM_SEED,
+                        wallet.key.as_ref(),
+                        &[ faucet_claim_bump ]
+                    ]
+                ]
+            )?;
+
+            assert_uninitialized(faucet_claim)?;
+
+            FaucetClaimAccount {
+                discriminator: AccountType::FaucetClaim.discriminator(),
+                version: StateVersion::CURRENT as u8,
+                wallet: *wallet.key,
+                last_claimed_at: 0,
+                claims: 0
+            }
+        }
+    };
+
+    invoke_signed(
+        &transfer(faucet.key, wallet.key, drip),
+        &[
+            faucet.clone(),
+            wallet.clone(),
+            system_program.clone()
+        ],
+        &[ faucet_seeds ]
+    )?;
+
+    faucet_claim_data.last_claimed_at = current_time;
+    faucet_claim_data.claims += 1;
+    faucet_claim_data.serialize(&mut &mut faucet_claim.data.borrow_mut()[..])?;
+
+    msg!("Faucet claimed - {} lamports", drip);
+
+    Ok(())
+}
+
+// Dashboard numbers in one simulation, instead of fetching every voting of the creator.
+fn process_get_creator_summary(program_id: &Pubkey, accounts: CreatorSummaryAccounts) -> ProgramResult {
+    let CreatorSummaryAccounts { creator_stats } = accounts;
+
+    let creator_stats_data = load_creator_stats(creator_stats)?;
+    assert_pda(creator_stats, find_creator_stats_pda(&creator_stats_data.creator, program_id))?;
+
+    let summary = CreatorSummaryReturnData {
+        creator: creator_stats_data.creator,
+        active_votings: creator_stats_data.active_votings,
+        ended_votings: creator_stats_data.finalized_votings.saturating_add(creator_stats_data.cancelled_votings),
+        finalized_votings: creator_stats_data.finalized_votings,
+        cancelled_votings: creator_stats_data.cancelled_votings,
+        created_votings: creator_stats_data.created_votings,
+        total_voters: creator_stats_data.total_voters,
+        flags: creator_stats_data.flags
+    };
+    set_return_data(&summary.try_to_vec()?);
+
+    Ok(())
+}
+
+// Keeps only the hash of the ballot while the voting runs, so nobody can follow the others' choices. The
+// voter is checked and weighed now, the ballot is counted when `reveal_vote` opens it after `ends_at`.
+fn process_commit_vote(
+    program_id: &Pubkey,
+    accounts: VoteAccounts,
+    ix_data: CommitVoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let VoteAccounts { user, voting_account, user_vote_account, voter_index, system_program, mut remaining_accounts, voter_index_page, .. } = accounts;
+
+    validate_title(&ix_data.vote_title)?;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    check_voted_title(program_id, voting_account, &voting_account_data, &ix_data.vote_title)?;
+
+    if voting_account_data.reveal_window == 0 {
+        return Err(Errors::NotCommitRevealVoting.into());
+    };
+
+    let incentive_pool = next_incentive_pool(&voting_account_data, &mut remaining_accounts)?;
+
+    let weight = check_voter(
+        program_id,
+        voting_account,
+        &voting_account_data,
+        user,
+        &mut remaining_accounts,
+        ix_data.eligibility_proof.as_ref(),
+        ix_data.weight_proof.as_ref()
+    )?;
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_accepts_votes(current_time)?;
+
+    let user_vote_bump = assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+    // Committed ballots are only counted once revealed, the preview has no tallies
+    if ix_data.dry_run {
+        if user_vote_account.data_is_empty() == false {
+            return Err(Errors::UserAlreadyVoted.into());
+        };
+
+        return return_vote_preview(weight, Vec::new());
+    };
+
+    create_user_vote_account(program_id, user, voting_account.key, user_vote_account, system_program, user_vote_bump, sysvars)?;
+
+    let user_account = UserVotingAccount {
+        discriminator: AccountType::UserVoting.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        payer: *user.key,
+        first_voted_at: current_time,
+        last_time_voted: current_time,
+        times_updated: 0,
+        choice: 0,
+        weight,
+        credited_slot: sysvars.clock.slot,
+        draw_offset: 0,
+        vote_index: u64::MAX,
+        commitment: Some(ix_data.commitment),
+        ballot: Vec::new(),
+        voted_to: VotingTitle::new(&ix_data.vote_title)?
+    };
+    user_account.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+    voting_account_data.commitments += 1;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+
+    add_to_voter_index(program_id, user, voter_index, system_program, voter_index_page, voting_account.key, sysvars)?;
+
+    if let Some(incentive_pool) = incentive_pool {
+        pay_incentive(program_id, voting_account.key, voting_account_data.incentive_per_vote, incentive_pool, user, sysvars)?;
+    };
+
+    msg!("Vote committed, reveal it before {}", voting_account_data.reveal_ends_at());
+    msg!("Voted to - {}", ix_data.vote_title);
+
+    Ok(())
+}
+
+// Counts a committed ballot with the weight resolved at commit time. Ballots left unrevealed once the
+// window closes are never counted.
+fn process_reveal_vote(
+    program_id: &Pubkey,
+    accounts: UpdateVoteAccounts,
+    ix_data: RevealVoteInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let UpdateVoteAccounts { user, voting_account, user_vote_account, poll_card, system_program, .. } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if voting_account_data.reveal_window == 0 {
+        return Err(Errors::NotCommitRevealVoting.into());
+    };
+
+    let current_time = sysvars.current_time();
+
+    voting_account_data.check_active()?;
+    voting_account_data.check_reveal_open(current_time)?;
+
+    assert_pda(user_vote_account, find_user_vote_pda(voting_account.key, user.key, program_id))?;
+
+    let mut user_vote_account_data = {
+        let data = user_vote_account.data.borrow();
+        assert_account_type(&data, AccountType::UserVoting)?;
+
+        try_from_slice_unchecked::<UserVotingAccount>(&data)?
+    };
+
+    let commitment = user_vote_account_data.commitment.ok_or(Errors::BallotRevealed)?;
+    if vote_commitment(&ix_data.ballot, &ix_data.salt, user.key) != commitment {
+        return Err(Errors::InvalidReveal.into());
+    };
+
+    voting_account_data.tally_mode.validate_ballot(&ix_data.ballot, voting_account_data.options.len())?;
+
+    // The tally state can grow with the ballot, the voter pays for it.
+    let weight = user_vote_account_data.weight;
+    voting_account_data.tally_mode.accumulate(
+        &mut voting_account_data.tallies,
+        &mut voting_account_data.tally_state,
+        &ix_data.ballot,
+        weight,
+        true
+    )?;
+    voting_account_data.mark_changed(&ix_data.ballot, current_time);
+    voting_account_data.vote_chain = chain_vote_hash(&voting_account_data.vote_chain, user.key, &ix_data.ballot, weight, current_time);
+
+    user_vote_account_data.choice = ballot_choice(&ix_data.ballot);
+    user_vote_account_data.vote_index = voting_account_data.total_voters;
+    user_vote_account_data.commitment = None;
+    user_vote_account_data.ballot = ix_data.ballot;
+    user_vote_account_data.serialize(&mut &mut user_vote_account.data.borrow_mut()[..])?;
+
+    voting_account_data.total_voters += 1;
+    write_resized_voting(&voting_account_data, voting_account, user, system_program, sysvars)?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+    voting_account_data.log_turnout_milestones(voting_account.key)?;
+    log_vote_cast(voting_account.key, user.key, voting_account_data.visible_choice(&user_vote_account_data.ballot, current_time), weight, current_time)?;
+
+    msg!("Vote revealed.");
+    if voting_account_data.hide_tallies == false {
+        match user_vote_account_data.ballot.first() {
+            Some(option) => msg!("Voted option - {}", voting_account_data.options[*option as usize]),
+            None => msg!("Ballot spoiled.")
+        };
+    };
+
+    Ok(())
+}
+
+// Groups votings of the creator into an election. Races can't be added later, so the outcome covers the
+// same votings the voters were told about.
+fn process_create_election(
+    program_id: &Pubkey,
+    accounts: CreateElectionAccounts,
+    ix_data: CreateElectionInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let CreateElectionAccounts { user, election, system_program, votings, election_bump } = accounts;
+
+    if ix_data.name.is_empty() || ix_data.name.len() > MAX_ELECTION_NAME_LENGTH || votings.is_empty() || votings.len() > MAX_ELECTION_RACES {
+        return Err(Errors::InvalidElection.into());
+    };
+
+    let mut races: Vec<ElectionRace> = Vec::with_capacity(votings.len());
+    for voting_account in votings.iter() {
+        assert_owned_by(voting_account, program_id)?;
+
+        let voting_account_data = {
+            let data = voting_account.data.borrow();
+            assert_account_type(&data, AccountType::Vote)?;
+
+            try_from_slice_unchecked::<VoteMainAccount>(&data)?
+        };
+
+        if voting_account_data.creator != *user.key {
+            return Err(Errors::NotVotingCreator.into());
+        };
+
+        if races.iter().any(|race| race.voting == *voting_account.key) {
+            return Err(Errors::InvalidElection.into());
+        };
+
+        races.push(ElectionRace {
+            voting: *voting_account.key,
+            winning_option: None,
+            winner: String::new(),
+            total_voters: 0
+        });
+    };
+
+    let space = ELECTION_ACCOUNT_SPACE;
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    invoke_signed(
+        &create_account(
+            user.key,
+            election.key,
+            rent_exempt,
+            space as u64,
+            program_id
+        ),
+        &[
+            user.clone(),
+            election.clone(),
+            system_program.clone()
+        ],
+        &[
+            &[
+                ELECTION_SEED,
+                user.key.as_ref(),
+                &ix_data.id.to_le_bytes(),
+                &[ election_bump ]
+            ]
+        ]
+    )?;
+
+    assert_uninitialized(election)?;
+
+    let election_account = ElectionAccount {
+        discriminator: AccountType::Election.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        creator: *user.key,
+        id: ix_data.id,
+        name: ix_data.name,
+        finalized_at: 0,
+        races
+    };
+    election_account.serialize(&mut &mut election.data.borrow_mut()[..])?;
+
+    msg!("Election created - {}, {} races.", election_account.name, election_account.races.len());
+
+    Ok(())
+}
+
+// Records the winner of every race once all of them are closed, see `VoteMainAccount::check_closed`.
+// Cancelled races count as decided without a winner. Archived votings are no longer read, the election
+// has to be finalized before its races are archived.
+fn process_finalize_election(program_id: &Pubkey, accounts: FinalizeElectionAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let FinalizeElectionAccounts { election, votings } = accounts;
+
+    let mut election_account = {
+        let data = election.data.borrow();
+        assert_account_type(&data, AccountType::Election)?;
+
+        try_from_slice_unchecked::<ElectionAccount>(&data)?
+    };
+
+    assert_pda(election, find_election_pda(&election_account.creator, election_account.id, program_id))?;
+
+    if election_account.finalized_at > 0 {
+        return Err(Errors::ElectionFinalized.into());
+    };
+
+    if votings.len() != election_account.races.len() {
+        return Err(Errors::InvalidElection.into());
+    };
+
+    let current_time = sysvars.current_time();
+    for (race, voting_account) in election_account.races.iter_mut().zip(votings.iter()) {
+        if *voting_account.key != race.voting {
+            return Err(Errors::InvalidElection.into());
+        };
+
+        assert_owned_by(voting_account, program_id)?;
+
+        let voting_account_data = {
+            let data = voting_account.data.borrow();
+            assert_account_type(&data, AccountType::Vote)?;
+
+            try_from_slice_unchecked::<VoteMainAccount>(&data)?
+        };
+
+        if voting_account_data.status != VotingStatus::Cancelled {
+            voting_account_data.check_closed(current_time)?;
+
+            race.winning_option = voting_account_data.winning_option();
+        };
+
+        race.winner = race.winning_option.map_or(String::new(), |winner| voting_account_data.options[winner as usize].clone());
+        race.total_voters = voting_account_data.total_voters;
+    };
+
+    election_account.finalized_at = current_time;
+    election_account.serialize(&mut &mut election.data.borrow_mut()[..])?;
+
+    msg!("Election finalized - {}", election_account.name);
+    for race in election_account.races.iter() {
+        match race.winning_option {
+            Some(_) => msg!("Race {} won by - {}", race.voting, race.winner),
+            None => msg!("Race {} without a winner.", race.voting)
+        };
+    };
+
+    Ok(())
+}
+
+// Pushes `ends_at` of a running or pending voting back, e.g. after an outage kept voters away. Never
+// shortens it, ballots cast so far keep counting.
+fn process_extend_voting(
+    program_id: &Pubkey,
+    accounts: ExtendVotingAccounts,
+    ix_data: ExtendVotingInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let ExtendVotingAccounts { user, voting_account, poll_card, realm } = accounts;
+
+    let mut voting_account_data = load_voting_for_status_change(program_id, user, voting_account)?;
+    voting_account_data.check_active()?;
+
+    if voting_account_data.ends_at <= sysvars.current_time() {
+        return Err(Errors::VotingEnded.into());
+    };
+
+    if ix_data.ends_at <= voting_account_data.ends_at {
+        return Err(Errors::InvalidEndingTime.into());
+    };
+
+    // A realm voting stays within the realm's maximum, and keeps its schedule when the realm locks it
+    let max_voting_time = if voting_account_data.realm == Pubkey::default() {
+        MAX_VOTING_TIME as u64
+    } else {
+        let realm = realm.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if *realm.key != voting_account_data.realm {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        let realm_account = load_realm(program_id, realm)?;
+        if realm_account.locked_settings & LOCK_VOTING_TIME != 0 {
+            return Err(Errors::RealmSettingLocked.into());
+        };
+
+        realm_account.defaults.max_voting_time
+    };
+
+    if ix_data.ends_at - voting_account_data.starts_at > max_voting_time {
+        return Err(Errors::MaxVotingTimeExceeded.into());
+    };
+
+    voting_account_data.ends_at = ix_data.ends_at;
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Voting extended until {}", voting_account_data.ends_at);
+
+    Ok(())
+}
+
+// Emergency stop for a live voting, e.g. after an eligibility bug was found. While frozen it takes no
+// ballots, updates or reveals and can't be finalized. The schedule is left alone, the frozen time counts
+// towards the voting period, `extend_voting` gives it back. The creator or a moderator freezes and
+// unfreezes it, every change is logged as a `VotingFreezeEvent`.
+fn process_set_frozen(
+    program_id: &Pubkey,
+    accounts: FreezeVotingAccounts,
+    frozen: bool,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let FreezeVotingAccounts { user, voting_account, poll_card, authority } = accounts;
+
+    let mut voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    match authority {
+        Some(authority) => check_moderator(program_id, user, authority, &voting_account_data)?,
+        None if voting_account_data.creator != *user.key => {
+            return Err(Errors::NotVotingCreator.into());
+        },
+        None => ()
+    };
+
+    let current_time = sysvars.current_time();
+    if frozen {
+        voting_account_data.check_active()?;
+        voting_account_data.check_not_frozen()?;
+
+        if voting_account_data.reveal_ends_at() <= current_time {
+            return Err(Errors::VotingEnded.into());
+        };
+
+        voting_account_data.frozen_at = Some(current_time);
+    } else {
+        if voting_account_data.frozen_at.is_none() {
+            return Err(Errors::VotingNotFrozen.into());
+        };
+
+        voting_account_data.frozen_at = None;
+    };
+
+    voting_account_data.serialize(&mut &mut voting_account.data.borrow_mut()[..])?;
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, current_time)?;
+
+    let event = VotingFreezeEvent {
+        voting: *voting_account.key,
+        authority: *user.key,
+        frozen,
+        timestamp: current_time
+    };
+    log_event(VOTING_FREEZE_EVENT_TAG, VOTING_FREEZE_EVENT_VERSION, &event)?;
+
+    if frozen {
+        msg!("Voting frozen by {}", user.key);
+    } else {
+        msg!("Voting unfrozen by {}", user.key);
+    };
+
+    Ok(())
+}
+
+// Lets the uploaded allowlist take chunks during the first `percent` of the voting window, for voters
+// registering late. Set before the voting starts, so voters know the window up front. The late chunks
+// are recorded with their time, and voters are never removed. Merkle root allowlists can't be appended
+// to. Uses the accounts of `seal_allowlist`.
+fn process_set_late_registration(
+    program_id: &Pubkey,
+    accounts: SealAllowlistAccounts,
+    ix_data: SetLateRegistrationInstruction,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let SealAllowlistAccounts { user, voting_account, allowlist } = accounts;
+
+    load_voting_for_edit(program_id, user, voting_account, sysvars)?;
+
+    let mut allowlist_data = load_voter_allowlist(program_id, allowlist)?;
+    if ix_data.percent > 100 || allowlist_data.root.is_some() {
+        return Err(Errors::InvalidLateRegistration.into());
+    };
+
+    allowlist_data.late_registration = ix_data.percent;
+    allowlist_data.serialize(&mut &mut allowlist.data.borrow_mut()[..])?;
+
+    msg!("Late registration set to {}% of the voting window.", ix_data.percent);
+
+    Ok(())
+}
+
+// Moves an account to the `StateVersion::CURRENT` layout. Every layout is still the first one, so there is
+// nothing to upgrade yet: the next version adds its upgrade here and reallocs the account for it.
+fn process_migrate_account(accounts: MigrateAccountAccounts) -> ProgramResult {
+    let MigrateAccountAccounts { account } = accounts;
+
+    let data = account.data.borrow();
+    if AccountType::from_discriminator(&data).is_none() {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    match data.get(8).copied().and_then(StateVersion::from_byte) {
+        Some(StateVersion::V1) => Err(Errors::AccountAlreadyMigrated.into()),
+        None => Err(Errors::UnknownAccountVersion.into())
+    }
+}
+
+// Creates the voting's poll card on the first call, later calls refresh it
+fn process_sync_poll_card(program_id: &Pubkey, accounts: SyncPollCardAccounts, sysvars: &Sysvars) -> ProgramResult {
+    let SyncPollCardAccounts { payer, voting_account, poll_card, system_program, poll_card_bump } = accounts;
+
+    let voting_account_data = {
+        let data = voting_account.data.borrow();
+        assert_account_type(&data, AccountType::Vote)?;
+
+        try_from_slice_unchecked::<VoteMainAccount>(&data)?
+    };
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if poll_card.data_is_empty() {
+        let space = POLL_CARD_ACCOUNT_SPACE;
+        let rent_exempt = sysvars.rent.minimum_balance(space);
+        invoke_signed(
+            &create_account(
+                payer.key,
+                poll_card.key,
+                rent_exempt,
+                space as u64,
+                program_id
+            ),
+            &[
+                payer.clone(),
+                poll_card.clone(),
+                system_program.clone()
+            ],
+            &[
+                &[
+                    POLL_CARD_SEED,
+                    voting_account.key.as_ref(),
+                    &[ poll_card_bump ]
+                ]
+            ]
+        )?;
+
+        assert_uninitialized(poll_card)?;
+
+        PollCardAccount::new(voting_account.key, &voting_account_data, sysvars.current_time())
+            .serialize(&mut &mut poll_card.data.borrow_mut()[..])?;
+
+        msg!("Poll card created.");
+
+        return Ok(());
+    };
+
+    update_poll_card(program_id, poll_card, voting_account.key, &voting_account_data, sysvars.current_time())?;
+
+    msg!("Poll card synced.");
+
+    Ok(())
+}
+
+// Rewrites the voting's poll card. Instructions changing what it shows take the card's PDA whether
+// `sync_poll_card` created it or not, there's nothing to write until it did.
+fn update_poll_card(
+    program_id: &Pubkey,
+    poll_card: &AccountInfo,
+    voting: &Pubkey,
+    voting_account_data: &VoteMainAccount,
+    current_time: u64
+) -> ProgramResult {
+    // Any other empty account would leave an existing card behind.
+    assert_pda(poll_card, find_poll_card_pda(voting, program_id))?;
+    if poll_card.data_is_empty() {
+        return Ok(());
+    };
+
+    assert_owned_by(poll_card, program_id)?;
+
+    {
+        let data = poll_card.data.borrow();
+        assert_account_type(&data, AccountType::PollCard)?;
+        if try_from_slice_unchecked::<PollCardAccount>(&data)?.voting != *voting {
+            return Err(Errors::InvalidPollCard.into());
+        };
+    };
+
+    PollCardAccount::new(voting, voting_account_data, current_time).serialize(&mut &mut poll_card.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn load_config(
+    program_id: &Pubkey,
+    config: &AccountInfo
+) -> Result<ConfigAccount, ProgramError> {
+    assert_pda(config, find_config_pda(program_id))?;
+
+    assert_owned_by(config, program_id)?;
+
+    let data = config.data.borrow();
+    assert_account_type(&data, AccountType::Config)?;
+
+    Ok(try_from_slice_unchecked::<ConfigAccount>(&data)?)
+}
+
+fn load_title_reservation(program_id: &Pubkey, reservation: &AccountInfo) -> Result<TitleReservationAccount, ProgramError> {
+    assert_owned_by(reservation, program_id)?;
+
+    let data = reservation.data.borrow();
+    assert_account_type(&data, AccountType::TitleReservation)?;
+
+    Ok(try_from_slice_unchecked::<TitleReservationAccount>(&data)?)
+}
+
+fn load_creator_stats(creator_stats: &AccountInfo) -> Result<CreatorStatsAccount, ProgramError> {
+    let data = creator_stats.data.borrow();
+    assert_account_type(&data, AccountType::CreatorStats)?;
+
+    Ok(try_from_slice_unchecked::<CreatorStatsAccount>(&data)?)
+}
+
+fn load_realm(
+    program_id: &Pubkey,
+    realm: &AccountInfo
+) -> Result<RealmAccount, ProgramError> {
+    let data = realm.data.borrow();
+    assert_account_type(&data, AccountType::Realm)?;
+
+    let realm_account = try_from_slice_unchecked::<RealmAccount>(&data)?;
+
+    assert_pda(realm, find_realm_pda(&realm_account.name, program_id))?;
+
+    Ok(realm_account)
+}
+
+// The realm's allowlisted actions, none until the admin sets the allowlist
+fn load_execution_allowlist(
+    program_id: &Pubkey,
+    allowlist: &AccountInfo,
+    realm: &Pubkey
+) -> Result<Vec<AllowedAction>, ProgramError> {
+    assert_pda(allowlist, find_execution_allowlist_pda(realm, program_id))?;
+
+    if allowlist.data_is_empty() {
+        return Ok(Vec::new());
+    };
+
+    assert_owned_by(allowlist, program_id)?;
+
+    let data = allowlist.data.borrow();
+    assert_account_type(&data, AccountType::ExecutionAllowlist)?;
+
+    Ok(try_from_slice_unchecked::<ExecutionAllowlistAccount>(&data)?.actions)
+}
+
+// Recounts are run by the voting's creator, at any time. Votes cast meanwhile only count if they sort
+// after the last recounted record, otherwise the recount can't complete and has to start again.
+fn load_creator_voting(user: &AccountInfo, voting_account: &AccountInfo) -> Result<VoteMainAccount, ProgramError> {
+    let data = voting_account.data.borrow();
+    assert_account_type(&data, AccountType::Vote)?;
+
+    let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(&data)?;
+
+    if voting_account_data.creator != *user.key {
+        return Err(Errors::NotVotingCreator.into());
+    };
+
+    Ok(voting_account_data)
+}
+
+fn load_recount(recount: &AccountInfo) -> Result<RecountAccount, ProgramError> {
+    let data = recount.data.borrow();
+    assert_account_type(&data, AccountType::Recount)?;
+
+    Ok(try_from_slice_unchecked::<RecountAccount>(&data)?)
+}
+
+fn check_membership(
+    program_id: &Pubkey,
+    membership: &AccountInfo,
+    realm: &Pubkey,
+    member: &Pubkey
+) -> ProgramResult {
+    if membership.owner != program_id
+        || *membership.key != find_membership_pda(realm, member, program_id).0
+        || membership.data.borrow().get(..8) != Some(&AccountType::Membership.discriminator()[..])
+    {
+        return Err(Errors::NotRealmMember.into());
+    };
+
+    Ok(())
+}
+
+// The loader's program data account of this program
+fn assert_program_data(program_id: &Pubkey, program_data: &AccountInfo) -> ProgramResult {
+    let (program_data_addr, _) = Pubkey::find_program_address(
+        &[
+            program_id.as_ref()
+        ],
+        &bpf_loader_upgradeable::id()
+    );
+    if *program_data.key != program_data_addr || *program_data.owner != bpf_loader_upgradeable::id() {
+        return Err(Errors::InvalidProgramData.into());
+    };
+
+    Ok(())
+}
+
+// An uninitialized config leaves every instruction enabled.
+fn check_instruction_enabled(
+    program_id: &Pubkey,
+    config: &AccountInfo,
+    flag: u128
+) -> Result<Option<ConfigAccount>, ProgramError> {
+    if config.data_is_empty() {
+        assert_pda(config, find_config_pda(program_id))?;
+
+        return Ok(None);
+    };
+
+    let config_account = load_config(program_id, config)?;
+    if config_account.disabled_instructions & flag != 0 {
+        return Err(Errors::InstructionDisabled.into());
+    };
+
+    Ok(Some(config_account))
+}
+
+// The voted-to title is stored in a `VotingTitle` like the voting title.
+fn validate_title(title: &str) -> ProgramResult {
+    let length = title.chars().count();
+    if length > MAX_TITLE_LENGTH || title.len() > MAX_TITLE_BYTES {
+        return Err(Errors::TitleTooLong.into());
+    };
+
+    if length < MIN_TITLE_LENGTH {
+        return Err(Errors::TitleInvalidLength.into());
+    };
+
+    if title.chars().any(char::is_control) || title.trim() != title {
+        return Err(Errors::TitleInvalidCharacters.into());
+    };
+
+    Ok(())
+}
+
+fn validate_language(language: &str) -> ProgramResult {
+    if language.len() < 2
+        || language.len() > MAX_LANGUAGE_LENGTH
+        || language.bytes().any(|byte| (byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-') == false)
+    {
+        return Err(Errors::InvalidLanguage.into());
+    };
+
+    Ok(())
+}
+
+fn validate_option(option: &str) -> ProgramResult {
+    if option.is_empty() || option.len() > MAX_OPTION_LENGTH {
+        return Err(Errors::OptionInvalidLength.into());
+    };
+
+    Ok(())
+}
+
+// Canonical `vote:<voting>:<choice>` memo, so explorers show votes without a custom decoder.
+// `SPOILED_CHOICE` for a spoiled ballot
+fn ballot_choice(ballot: &[u8]) -> u8 {
+    ballot.first().copied().unwrap_or(SPOILED_CHOICE)
+}
+
+fn log_vote_memo(
+    memo_program: &AccountInfo,
+    voting: &Pubkey,
+    choice: u8
+) -> ProgramResult {
+    let memo = format!("vote:{}:{}", voting, choice);
+    invoke(
+        &spl_memo::build_memo(memo.as_bytes(), &[]),
+        std::slice::from_ref(memo_program)
+    )
+}
+
+// Voters name the voting they vote on by title, which must match the voting account. The account is
+// checked against the PDA of its own title, the instruction's title is never used as a seed.
+fn check_voted_title(
+    program_id: &Pubkey,
+    voting_account: &AccountInfo,
+    voting_account_data: &VoteMainAccount,
+    vote_title: &str
+) -> ProgramResult {
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if normalize_title(voting_account_data.title.as_str()) != normalize_title(vote_title) {
+        return Err(Errors::InvalidPdaAddress.into());
+    };
+
+    Ok(())
+}
+
+// Shared checks for the creator-only instructions which edit a voting before it starts.
+fn load_voting_for_edit(
+    program_id: &Pubkey,
+    user: &AccountInfo,
+    voting_account: &AccountInfo,
+    sysvars: &Sysvars
+) -> Result<VoteMainAccount, ProgramError> {
+    let data = voting_account.data.borrow();
+    assert_account_type(&data, AccountType::Vote)?;
+
+    let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(&data)?;
+
+    assert_pda(voting_account, voting_account_data.find_pda(program_id))?;
+
+    if voting_account_data.creator != *user.key {
+        return Err(Errors::NotVotingCreator.into());
+    };
+
+    if voting_account_data.locked {
+        return Err(Errors::VotingLocked.into());
+    };
+
+    // Drafts have no schedule yet, they stay editable until published.
+    if voting_account_data.status == VotingStatus::Draft {
+        return Ok(voting_account_data);
+    };
+
+    voting_account_data.check_active()?;
+
+    // Options and metadata are locked once voting opens.
+    if voting_account_data.starts_at <= sysvars.current_time() {
+        return Err(Errors::VotingAlreadyStarted.into());
+    };
+
+    Ok(voting_account_data)
+}
+
+// Grows the account to fit its new contents, topping up rent from the user. Accounts never shrink: the
+// handlers writing in place count on the room reserved on creation, e.g. for `frozen_at`, and moving the
+// surplus lamports by hand here would unbalance the CPIs that follow in the same instruction. The bytes
+// past the contents are zeroed, readers ignore them.
+fn write_resized<'a>(
+    account_data: &impl BorshSerialize,
+    account: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    write_resized_with(account_data, 0, 0, account, user, system_program, sysvars)
+}
+
+// Grown votings keep the room `voting_space` reserves, finalizing writes the result in place. Their creation
+// deposit sits on top of the rent and finalizing pays all of it out, so it doesn't count towards the rent.
+fn write_resized_voting<'a>(
+    voting_account_data: &VoteMainAccount,
+    voting_account: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let room = voting_space(voting_account_data).ok_or(ProgramError::InvalidAccountData)?;
+    write_resized_with(voting_account_data, room, voting_account_data.creation_deposit, voting_account, user, system_program, sysvars)
+}
+
+// `write_resized` to at least `room` bytes, for an account of which `held` lamports aren't rent
+fn write_resized_with<'a>(
+    account_data: &impl BorshSerialize,
+    room: usize,
+    held: u64,
+    account: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    sysvars: &Sysvars
+) -> ProgramResult {
+    let serialized = account_data.try_to_vec()?;
+    let space = serialized.len().max(room).max(account.data_len());
+
+    let rent_exempt = sysvars.rent.minimum_balance(space);
+    let current_lamports = account.lamports().saturating_sub(held);
+    if rent_exempt > current_lamports {
+        invoke(
+            &transfer(
+                user.key,
+                account.key,
+                rent_exempt - current_lamports
+            ),
+            &[
+                user.clone(),
+                account.clone(),
+                system_program.clone()
+            ]
+        )?;
+    };
+
+    if sysvars.rent.is_exempt(account.lamports().saturating_sub(held), space) == false {
+        return Err(Errors::NotRentExempt.into());
+    };
+
+    account.realloc(space, false)?;
+    let mut data = account.data.borrow_mut();
+    data[..serialized.len()].copy_from_slice(&serialized);
+    data[serialized.len()..].fill(0);
+
+    Ok(())
+}
+
+// Writes `state` to `account` with the latch set, runs `cpi` and writes it again with the latch cleared
+fn invoke_latched<T: Latched>(account: &AccountInfo, state: &mut T, cpi: impl FnOnce() -> ProgramResult) -> ProgramResult {
+    state.set_in_progress(true);
+    state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    cpi()?;
+
+    state.set_in_progress(false);
+    state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+// CPIs whose target calls back into the program before returning. Outside the runtime every CPI goes
+// through the syscall stubs, which hand it to the callback the test installed. A callback is what the
+// runtime allows a target to do: run an instruction of this program, with accounts of the outer call.
+use std::{ cell::RefCell, sync::Once };
+
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    account_info::AccountInfo,
+    borsh0_10::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    instruction::{ AccountMeta, Instruction },
+    program_stubs::{ set_syscall_stubs, SyscallStubs },
+    pubkey::Pubkey,
+    system_program::ID as system_program_address
+};
+
+use crate::{
+    execution::{ AllowedAction, ProposalAccountMeta, ProposalInstruction },
+    fixed_clock::{ account, program, sysvars },
+    time_windows::create_instruction,
+    *
+};
+
+const STARTS_AT: u64 = 1_000;
+const ENDS_AT: u64 = 2_000;
+
+type Callback = Box<dyn Fn(&Instruction) -> ProgramResult>;
+
+thread_local! {
+    // Run in place of every CPI made on this thread, the CPI does nothing without one
+    static CALLBACK: RefCell<Option<Callback>> = RefCell::new(None);
+}
+
+struct CallbackStubs;
+
+impl SyscallStubs for CallbackStubs {
+    fn sol_invoke_signed(&self, instruction: &Instruction, _account_infos: &[AccountInfo], _signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+        CALLBACK.with(|callback| callback.borrow().as_ref().map_or(Ok(()), |callback| callback(instruction)))
+    }
+}
+
+fn set_callback(callback: impl Fn(&Instruction) -> ProgramResult + 'static) {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(CallbackStubs));
+    });
+
+    CALLBACK.with(|slot| *slot.borrow_mut() = Some(Box::new(callback)));
+}
+
+// Runs `instruction` over the accounts with the same keys, as the runtime does for a CPI into the program
+fn run(instruction: &Instruction, accounts: &[AccountInfo<'static>], current_time: u64) -> ProgramResult {
+    let accounts = instruction.accounts.iter()
+        .map(|meta| accounts.iter().find(|account| *account.key == meta.pubkey).unwrap().clone())
+        .collect::<Vec<_>>();
+
+    process(&ID, Box::leak(accounts.into_boxed_slice()), &instruction.data, &sysvars(current_time))
+}
+
+fn instruction(instruction_type: InstructionType, args: impl BorshSerialize, accounts: &[&AccountInfo]) -> Instruction {
+    let mut data = instruction_type.header();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id: ID,
+        accounts: accounts.iter().map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable
+        }).collect(),
+        data
+    }
+}
+
+fn voting_account(voting: &VoteMainAccount) -> AccountInfo<'static> {
+    account(find_voting_pda(voting.title.as_str(), &ID).0, ID, borsh::to_vec(voting).unwrap(), false, true)
+}
+
+fn config() -> AccountInfo<'static> {
+    account(find_config_pda(&ID).0, system_program_address, Vec::new(), false, false)
+}
+
+fn read<T: BorshDeserialize>(account: &AccountInfo) -> T {
+    try_from_slice_unchecked(&account.data.borrow()).unwrap()
+}
+
+// A running voting of `creator` and its reward escrow, funded once already
+fn rewards(creator: &AccountInfo<'static>) -> Vec<AccountInfo<'static>> {
+    let voting_data = VoteMainAccount::new(*creator.key, create_instruction(STARTS_AT, ENDS_AT), Pubkey::default(), 0, &DEFAULT_VOTING_SETTINGS).unwrap();
+    let voting = voting_account(&voting_data);
+    let escrow = RewardEscrowAccount {
+        discriminator: AccountType::RewardEscrow.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting.key,
+        total_funded: 100,
+        total_claimed: 0,
+        in_progress: false
+    };
+    let escrow = account(find_reward_escrow_pda(voting.key, &ID).0, ID, borsh::to_vec(&escrow).unwrap(), false, true);
+
+    vec![ config(), creator.clone(), voting, escrow, program(system_program_address) ]
+}
+
+fn fund_rewards(accounts: &[AccountInfo<'static>], amount: u64) -> Instruction {
+    instruction(InstructionType::FundRewards, FundRewardsInstruction { amount }, &accounts.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn escrow_is_written_and_latched_during_the_transfer() {
+    let creator = account(Pubkey::new_unique(), system_program_address, Vec::new(), true, true);
+    let accounts = rewards(&creator);
+    let escrow = accounts[3].clone();
+
+    // A target that only looks: the funding is already recorded when it runs
+    set_callback(move |_| {
+        let escrow_data = read::<RewardEscrowAccount>(&escrow);
+        assert_eq!(escrow_data.total_funded, 150);
+        assert!(escrow_data.in_progress);
+
+        Ok(())
+    });
+    run(&fund_rewards(&accounts, 50), &accounts, STARTS_AT).unwrap();
+
+    let escrow_data = read::<RewardEscrowAccount>(&accounts[3]);
+    assert_eq!(escrow_data.total_funded, 150);
+    assert!(escrow_data.in_progress == false);
+}
+
+#[test]
+fn escrow_rejects_a_callback_into_its_funding() {
+    let creator = account(Pubkey::new_unique(), system_program_address, Vec::new(), true, true);
+    let accounts = rewards(&creator);
+
+    // The target funds the escrow again instead of taking the transfer
+    let callback_accounts = accounts.clone();
+    set_callback(move |_| run(&fund_rewards(&callback_accounts, 1), &callback_accounts, STARTS_AT));
+    assert_eq!(run(&fund_rewards(&accounts, 50), &accounts, STARTS_AT), Err(Errors::AccountInProgress.into()));
+}
+
+// An ended voting won by its first option, with a proposal executing `proposal_instruction` for it. The
+// allowlist lets the proposal call the program itself.
+fn proposal(proposal_instruction: impl Fn(&[&AccountInfo]) -> Instruction) -> Vec<AccountInfo<'static>> {
+    let creator = Pubkey::new_unique();
+    let mut voting_data = VoteMainAccount::new(creator, create_instruction(STARTS_AT, ENDS_AT), Pubkey::default(), 0, &DEFAULT_VOTING_SETTINGS).unwrap();
+    voting_data.tallies = vec![ 2, 1 ];
+    let voting = voting_account(&voting_data);
+
+    let allowlist = ExecutionAllowlistAccount {
+        discriminator: AccountType::ExecutionAllowlist.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        realm: Pubkey::default(),
+        actions: vec![ AllowedAction { program_id: ID, data_prefix: Vec::new() } ]
+    };
+    let allowlist = account(find_execution_allowlist_pda(&Pubkey::default(), &ID).0, ID, borsh::to_vec(&allowlist).unwrap(), false, false);
+    let governance = account(find_governance_pda(&Pubkey::default(), &ID).0, system_program_address, Vec::new(), false, false);
+    let proposal_key = find_proposal_pda(voting.key, &ID).0;
+    let config = config();
+
+    // The proposal's own address is known before its data
+    let placeholder = account(proposal_key, ID, Vec::new(), false, true);
+    let executed = proposal_instruction(&[ &config, &placeholder, &voting, &allowlist, &governance ]);
+    let proposal = ProposalAccount {
+        discriminator: AccountType::Proposal.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        voting: *voting.key,
+        option: 0,
+        next_instruction: 0,
+        in_progress: false,
+        instructions: vec![ ProposalInstruction {
+            program_id: executed.program_id,
+            accounts: executed.accounts.iter().map(|meta| ProposalAccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable
+            }).collect(),
+            data: executed.data
+        } ]
+    };
+    let proposal = account(proposal_key, ID, borsh::to_vec(&proposal).unwrap(), false, true);
+
+    vec![ config, proposal, voting, allowlist, governance, program(ID) ]
+}
+
+fn execute_next_instruction(accounts: &[AccountInfo<'static>]) -> Instruction {
+    let mut instruction = instruction(InstructionType::ExecuteNextInstruction, (), &accounts[..5].iter().collect::<Vec<_>>());
+    let executed = read::<ProposalAccount>(&accounts[1]).instructions[0].to_instruction();
+    instruction.accounts.extend(executed.accounts);
+    instruction.accounts.push(AccountMeta::new_readonly(executed.program_id, false));
+
+    instruction
+}
+
+#[test]
+fn proposal_cannot_execute_itself() {
+    // The proposal's instruction executes the proposal
+    let accounts = proposal(|accounts| {
+        let mut instruction = instruction(InstructionType::ExecuteNextInstruction, (), accounts);
+        instruction.accounts.push(AccountMeta::new_readonly(ID, false));
+
+        instruction
+    });
+
+    let callback_accounts = accounts.clone();
+    set_callback(move |instruction| run(instruction, &callback_accounts, ENDS_AT));
+    assert_eq!(run(&execute_next_instruction(&accounts), &accounts, ENDS_AT), Err(Errors::ProposalInProgress.into()));
+}
+
+#[test]
+fn proposal_is_released_after_its_instruction() {
+    let accounts = proposal(|accounts| instruction(InstructionType::SyncStatus, (), &[ accounts[2] ]));
+
+    let callback_accounts = accounts.clone();
+    set_callback(move |_| {
+        assert!(read::<ProposalAccount>(&callback_accounts[1]).in_progress);
+
+        Ok(())
+    });
+    run(&execute_next_instruction(&accounts), &accounts, ENDS_AT).unwrap();
+
+    let proposal_data = read::<ProposalAccount>(&accounts[1]);
+    assert_eq!(proposal_data.next_instruction, 1);
+    assert!(proposal_data.in_progress == false);
+}
@@ -0,0 +1,356 @@
+// Anchor twin of the native voting lifecycle: creating a voting, voting, updating the vote, extending,
+// freezing, unfreezing, cancelling and syncing the status, for the votings `client::create_voting` builds
+// (one option per ballot, every ballot weighing 1, starting at creation). Written from the native rules,
+// not from its code: each handler checks in the same order and fails with an error of the same name, so
+// `tests/differential.rs` can run both programs on the same actions and compare outcomes and state.
+use anchor_lang::prelude::*;
+
+declare_id!("77wNFRpsqRVMgRwJ37vfD9WptkzWq2ugZg3jXEU2CfUk");
+
+pub const MIN_TITLE_LENGTH: usize = 10;
+pub const MAX_TITLE_LENGTH: usize = 50;
+pub const MAX_TITLE_BYTES: usize = 128;
+pub const MIN_OPTIONS: usize = 2;
+pub const MAX_OPTIONS: usize = 10;
+pub const MAX_OPTION_LENGTH: usize = 32;
+pub const MAX_VOTING_TIME: u64 = 1_209_600;
+
+pub const BALLOT_SEED: &[u8] = b"ballot";
+
+#[program]
+pub mod voting_reference {
+    use super::*;
+
+    // The voting runs from now for `duration` seconds
+    pub fn create_voting(ctx: Context<CreateVoting>, title: String, options: Vec<String>, duration: u64) -> Result<()> {
+        let current_time = current_time()?;
+        let ends_at = current_time.checked_add(duration).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        require!(ends_at > current_time, ReferenceError::InvalidEndingTime);
+        validate_title(&title)?;
+        require!(ends_at - current_time <= MAX_VOTING_TIME, ReferenceError::MaxVotingTimeExceeded);
+        require!(options.len() >= MIN_OPTIONS, ReferenceError::InvalidOptionsCount);
+        require!(options.len() <= MAX_OPTIONS, ReferenceError::TooManyOptions);
+        for option in options.iter() {
+            require!(option.is_empty() == false && option.len() <= MAX_OPTION_LENGTH, ReferenceError::OptionInvalidLength);
+        };
+
+        let voting = &mut ctx.accounts.voting;
+        voting.set_inner(Voting {
+            creator: ctx.accounts.creator.key(),
+            starts_at: current_time,
+            ends_at,
+            status: VotingStatus::Pending,
+            frozen_at: None,
+            tallies: vec![ 0; options.len() ],
+            total_voters: 0,
+            spoiled: 0,
+            title,
+            options
+        });
+        voting.sync_status(current_time);
+
+        Ok(())
+    }
+
+    // An empty ballot is a spoiled one
+    pub fn vote(ctx: Context<Vote>, ballot: Vec<u8>) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+        voting.check_accepts_votes(current_time()?)?;
+        validate_ballot(&ballot, voting.options.len())?;
+
+        // Created empty by this instruction on the first ballot
+        let record = &mut ctx.accounts.ballot;
+        require!(record.voter == Pubkey::default(), ReferenceError::UserAlreadyVoted);
+
+        voting.count(&ballot, true)?;
+        voting.total_voters += 1;
+        record.voter = ctx.accounts.voter.key();
+        record.ballot = ballot;
+
+        Ok(())
+    }
+
+    pub fn update_vote(ctx: Context<UpdateVote>, ballot: Vec<u8>) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+        voting.check_accepts_votes(current_time()?)?;
+        validate_ballot(&ballot, voting.options.len())?;
+
+        // Only read once the voting takes ballots, its owner was checked with the accounts
+        let record_info = ctx.accounts.ballot.to_account_info();
+        let mut record = Ballot::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+
+        voting.count(&record.ballot, false)?;
+        voting.count(&ballot, true)?;
+        record.ballot = ballot;
+        record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+
+    // Later than the current end, at most `MAX_VOTING_TIME` after the start
+    pub fn extend_voting(ctx: Context<ManageVoting>, ends_at: u64) -> Result<()> {
+        let current_time = current_time()?;
+        let voting = &mut ctx.accounts.voting;
+        voting.check_creator(&ctx.accounts.creator)?;
+        voting.check_active()?;
+
+        require!(voting.ends_at > current_time, ReferenceError::VotingEnded);
+        require!(ends_at > voting.ends_at, ReferenceError::InvalidEndingTime);
+        require!(ends_at - voting.starts_at <= MAX_VOTING_TIME, ReferenceError::MaxVotingTimeExceeded);
+
+        voting.ends_at = ends_at;
+
+        Ok(())
+    }
+
+    pub fn freeze_voting(ctx: Context<ManageVoting>) -> Result<()> {
+        let current_time = current_time()?;
+        let voting = &mut ctx.accounts.voting;
+        voting.check_creator(&ctx.accounts.creator)?;
+        voting.check_active()?;
+
+        require!(voting.frozen_at.is_none(), ReferenceError::VotingFrozen);
+        require!(voting.ends_at > current_time, ReferenceError::VotingEnded);
+
+        voting.frozen_at = Some(current_time);
+
+        Ok(())
+    }
+
+    // Also for a voting that ended or was cancelled while frozen
+    pub fn unfreeze_voting(ctx: Context<ManageVoting>) -> Result<()> {
+        let voting = &mut ctx.accounts.voting;
+        voting.check_creator(&ctx.accounts.creator)?;
+
+        require!(voting.frozen_at.is_some(), ReferenceError::VotingNotFrozen);
+
+        voting.frozen_at = None;
+
+        Ok(())
+    }
+
+    pub fn cancel_voting(ctx: Context<ManageVoting>) -> Result<()> {
+        let current_time = current_time()?;
+        let voting = &mut ctx.accounts.voting;
+        voting.check_creator(&ctx.accounts.creator)?;
+        voting.check_active()?;
+
+        require!(voting.ends_at > current_time, ReferenceError::VotingEnded);
+
+        voting.status = VotingStatus::Cancelled;
+
+        Ok(())
+    }
+
+    pub fn sync_status(ctx: Context<SyncStatus>) -> Result<()> {
+        ctx.accounts.voting.sync_status(current_time()?);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateVoting<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(init, payer = creator, space = 8 + Voting::INIT_SPACE)]
+    pub voting: Account<'info, Voting>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(mut)]
+    pub voting: Account<'info, Voting>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + Ballot::INIT_SPACE,
+        seeds = [ BALLOT_SEED, voting.key().as_ref(), voter.key().as_ref() ],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct UpdateVote<'info> {
+    pub voter: Signer<'info>,
+    #[account(mut)]
+    pub voting: Account<'info, Voting>,
+    /// CHECK: the voter's ballot, read by `update_vote` after the voting's checks
+    #[account(
+        mut,
+        owner = crate::ID @ ReferenceError::InvalidAccountOwner,
+        seeds = [ BALLOT_SEED, voting.key().as_ref(), voter.key().as_ref() ],
+        bump
+    )]
+    pub ballot: UncheckedAccount<'info>
+}
+
+// The signer has to be the creator, checked by each handler where the native one checks it
+#[derive(Accounts)]
+pub struct ManageVoting<'info> {
+    pub creator: Signer<'info>,
+    #[account(mut)]
+    pub voting: Account<'info, Voting>
+}
+
+#[derive(Accounts)]
+pub struct SyncStatus<'info> {
+    #[account(mut)]
+    pub voting: Account<'info, Voting>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingStatus {
+    Pending,
+    Active,
+    Ended,
+    Cancelled
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Voting {
+    pub creator: Pubkey,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub status: VotingStatus,
+    pub frozen_at: Option<u64>,
+    #[max_len(MAX_OPTIONS)]
+    pub tallies: Vec<u64>,
+    pub total_voters: u64,
+    pub spoiled: u64,
+    #[max_len(MAX_TITLE_BYTES)]
+    pub title: String,
+    #[max_len(MAX_OPTIONS, MAX_OPTION_LENGTH)]
+    pub options: Vec<String>
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Ballot {
+    pub voter: Pubkey,
+    #[max_len(1)]
+    pub ballot: Vec<u8>
+}
+
+impl Voting {
+    fn check_creator(&self, signer: &Signer) -> Result<()> {
+        require_keys_eq!(self.creator, signer.key(), ReferenceError::NotVotingCreator);
+
+        Ok(())
+    }
+
+    fn check_active(&self) -> Result<()> {
+        require!(self.status != VotingStatus::Cancelled, ReferenceError::VotingCancelled);
+
+        Ok(())
+    }
+
+    fn check_accepts_votes(&self, current_time: u64) -> Result<()> {
+        self.check_active()?;
+        require!(self.frozen_at.is_none(), ReferenceError::VotingFrozen);
+        require!(self.starts_at <= current_time, ReferenceError::VotingNotStarted);
+        require!(self.ends_at > current_time, ReferenceError::VotingEnded);
+
+        Ok(())
+    }
+
+    // Cancelled votings keep their status
+    fn sync_status(&mut self, current_time: u64) {
+        if self.status == VotingStatus::Cancelled {
+            return;
+        };
+
+        self.status = if self.starts_at > current_time {
+            VotingStatus::Pending
+        } else if self.ends_at <= current_time {
+            VotingStatus::Ended
+        } else {
+            VotingStatus::Active
+        };
+    }
+
+    // Adds or removes a validated ballot, a spoiled one only counts as spoiled
+    fn count(&mut self, ballot: &[u8], add: bool) -> Result<()> {
+        let counter = match ballot.first() {
+            Some(option) => &mut self.tallies[*option as usize],
+            None => &mut self.spoiled
+        };
+
+        *counter = if add { counter.checked_add(1) } else { counter.checked_sub(1) }.ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
+
+fn current_time() -> Result<u64> {
+    Ok(Clock::get()?.unix_timestamp as u64)
+}
+
+fn validate_title(title: &str) -> Result<()> {
+    let length = title.chars().count();
+    require!(length <= MAX_TITLE_LENGTH && title.len() <= MAX_TITLE_BYTES, ReferenceError::TitleTooLong);
+    require!(length >= MIN_TITLE_LENGTH, ReferenceError::TitleInvalidLength);
+    require!(title.chars().any(char::is_control) == false && title.trim() == title, ReferenceError::TitleInvalidCharacters);
+
+    Ok(())
+}
+
+fn validate_ballot(ballot: &[u8], options_count: usize) -> Result<()> {
+    require!(ballot.len() <= 1, ReferenceError::InvalidBallot);
+    require!(ballot.iter().all(|option| (*option as usize) < options_count), ReferenceError::InvalidOptionIndex);
+
+    Ok(())
+}
+
+// Named after the native `Errors` variants the checks stand for
+#[error_code]
+pub enum ReferenceError {
+    InvalidEndingTime,
+    TitleTooLong,
+    TitleInvalidLength,
+    TitleInvalidCharacters,
+    MaxVotingTimeExceeded,
+    InvalidOptionsCount,
+    TooManyOptions,
+    OptionInvalidLength,
+    VotingCancelled,
+    VotingFrozen,
+    VotingNotStarted,
+    VotingEnded,
+    InvalidBallot,
+    InvalidOptionIndex,
+    UserAlreadyVoted,
+    NotVotingCreator,
+    VotingNotFrozen,
+    InvalidAccountOwner
+}
+
+impl ReferenceError {
+    pub const ALL: [Self; 18] = [
+        Self::InvalidEndingTime,
+        Self::TitleTooLong,
+        Self::TitleInvalidLength,
+        Self::TitleInvalidCharacters,
+        Self::MaxVotingTimeExceeded,
+        Self::InvalidOptionsCount,
+        Self::TooManyOptions,
+        Self::OptionInvalidLength,
+        Self::VotingCancelled,
+        Self::VotingFrozen,
+        Self::VotingNotStarted,
+        Self::VotingEnded,
+        Self::InvalidBallot,
+        Self::InvalidOptionIndex,
+        Self::UserAlreadyVoted,
+        Self::NotVotingCreator,
+        Self::VotingNotFrozen,
+        Self::InvalidAccountOwner
+    ];
+}
@@ -0,0 +1,465 @@
+// Random action sequences run against the native program and its Anchor twin in one test validator,
+// under one clock. After every action both have to agree on the outcome: both went through, both failed
+// with a program error of the same name, or both failed otherwise. The votings and ballots of both then
+// have to hold the same state. A divergence is a check one of them lacks or makes in another order.
+use anchor_lang::{ AccountDeserialize, InstructionData, ToAccountMetas };
+use num_traits::FromPrimitive;
+use proptest::{ prelude::*, test_runner::RngSeed };
+use serde::{ de::DeserializeOwned, Deserialize };
+use solana_program_test::{ processor, BanksClientError, ProgramTest, ProgramTestContext };
+use solana_sdk::{
+    account_info::AccountInfo,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    entrypoint::ProgramResult,
+    instruction::{ Instruction, InstructionError },
+    pubkey::Pubkey,
+    signature::{ Keypair, Signer },
+    system_instruction,
+    system_program,
+    transaction::{ Transaction, TransactionError }
+};
+
+use solana_voting_program::{ client, decoder::decode_account, find_user_vote_pda, find_voting_pda, Errors };
+use voting_reference::{ ReferenceError, BALLOT_SEED, MAX_OPTION_LENGTH, MAX_OPTIONS, MAX_VOTING_TIME, MIN_OPTIONS };
+
+const TITLES: [&str; 2] = [ "Lunch on Fridays", "Dinner on Wednesdays" ];
+// The creator, then the voters
+const ACTORS: usize = 4;
+const START: u64 = 1_700_000_000;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Create { voting: usize, options: Vec<String>, duration: u64 },
+    Vote { voter: usize, voting: usize, ballot: Vec<u8> },
+    UpdateVote { voter: usize, voting: usize, ballot: Vec<u8> },
+    // Moves the end by `by` seconds from where it is
+    Extend { signer: usize, voting: usize, by: i64 },
+    Freeze { signer: usize, voting: usize },
+    Unfreeze { signer: usize, voting: usize },
+    // Only the creator, a voter would be turned away on its missing creator stats first
+    Cancel { voting: usize },
+    SyncStatus { voting: usize },
+    Warp { seconds: u64 }
+}
+
+// Mostly options a voting takes, so that most sequences get to vote
+fn options() -> impl Strategy<Value = Vec<String>> {
+    let option = prop_oneof![
+        8 => "[A-Za-z]{1,32}",
+        1 => Just(String::new()),
+        1 => Just("o".repeat(MAX_OPTION_LENGTH + 1))
+    ];
+
+    prop_oneof![
+        4 => prop::collection::vec("[A-Za-z]{1,32}", MIN_OPTIONS..=MAX_OPTIONS),
+        1 => prop::collection::vec(option, 0..=(MAX_OPTIONS + 1))
+    ]
+}
+
+fn duration() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        4 => 60..=3_600u64,
+        1 => prop_oneof![ Just(0), Just(MAX_VOTING_TIME), Just(MAX_VOTING_TIME + 1), Just(u64::MAX) ]
+    ]
+}
+
+fn ballot() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(Vec::new()),
+        (0..3u8).prop_map(|option| vec![ option ]),
+        prop::collection::vec(0..(MAX_OPTIONS as u8 + 2), 0..=2)
+    ]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let voting = 0..TITLES.len();
+    let actor = 0..ACTORS;
+
+    prop_oneof![
+        3 => (voting.clone(), options(), duration()).prop_map(|(voting, options, duration)| Action::Create { voting, options, duration }),
+        4 => (actor.clone(), voting.clone(), ballot()).prop_map(|(voter, voting, ballot)| Action::Vote { voter, voting, ballot }),
+        3 => (actor.clone(), voting.clone(), ballot()).prop_map(|(voter, voting, ballot)| Action::UpdateVote { voter, voting, ballot }),
+        2 => (actor.clone(), voting.clone(), prop_oneof![ 4 => -5..=600i64, 1 => Just(MAX_VOTING_TIME as i64) ])
+            .prop_map(|(signer, voting, by)| Action::Extend { signer, voting, by }),
+        1 => (actor.clone(), voting.clone()).prop_map(|(signer, voting)| Action::Freeze { signer, voting }),
+        1 => (actor, voting.clone()).prop_map(|(signer, voting)| Action::Unfreeze { signer, voting }),
+        1 => voting.clone().prop_map(|voting| Action::Cancel { voting }),
+        1 => voting.prop_map(|voting| Action::SyncStatus { voting }),
+        2 => prop_oneof![ 8 => 1..=600u64, 1 => Just(MAX_VOTING_TIME) ].prop_map(|seconds| Action::Warp { seconds })
+    ]
+}
+
+// Both programs take the account slice with the lifetime of the accounts, see `tests/common`
+fn process_native<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+
+    solana_voting_program::process_instruction(program_id, accounts, data)
+}
+
+fn process_reference<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+
+    voting_reference::entry(program_id, accounts, data)
+}
+
+// What is compared of a transaction's outcome
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Ok,
+    // A program error, by its name
+    Error(String),
+    Failed
+}
+
+// Errors of the test validator itself, e.g. a timed out request, say nothing about the programs. The
+// case can't be compared and fails as a harness failure.
+fn outcome(result: Result<(), BanksClientError>, name: impl Fn(u32) -> Option<String>) -> Outcome {
+    let error = match result {
+        Ok(()) => return Outcome::Ok,
+        Err(BanksClientError::TransactionError(error) | BanksClientError::SimulationError { err: error, .. }) => error,
+        Err(error) => panic!("the test validator failed, not a program: {}", error)
+    };
+
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => name(code).map_or(Outcome::Failed, Outcome::Error),
+        _ => Outcome::Failed
+    }
+}
+
+fn native_error(code: u32) -> Option<String> {
+    Errors::from_u32(code).map(|error| format!("{:?}", error))
+}
+
+fn reference_error(code: u32) -> Option<String> {
+    ReferenceError::ALL.iter().find(|error| u32::from(**error) == code).map(ReferenceError::name)
+}
+
+// What is compared of a voting, with the ballot of each actor
+#[derive(Debug, PartialEq)]
+struct VotingState {
+    status: String,
+    ends_at: u64,
+    frozen_at: Option<u64>,
+    tallies: Vec<u64>,
+    total_voters: u64,
+    spoiled: u64,
+    ballots: Vec<Option<Vec<u8>>>
+}
+
+// The fields of the decoded native accounts, read through their serde form
+#[derive(Deserialize)]
+struct NativeVoting {
+    status: String,
+    ends_at: u64,
+    frozen_at: Option<u64>,
+    tallies: Vec<u64>,
+    total_voters: u64,
+    tally_state: NativeTallyState
+}
+
+#[derive(Deserialize)]
+struct NativeTallyState {
+    spoiled: u64
+}
+
+#[derive(Deserialize)]
+struct NativeUserVote {
+    ballot: Vec<u8>
+}
+
+// The signers of the actions, kept apart from the validator so the instructions can borrow them while
+// they are sent
+struct Keys {
+    actors: Vec<Keypair>,
+    // The twin's votings are plain accounts, created with these keypairs
+    reference_votings: Vec<Keypair>
+}
+
+impl Keys {
+    fn native_voting(&self, voting: usize) -> Pubkey {
+        find_voting_pda(TITLES[voting], &solana_voting_program::id()).0
+    }
+
+    fn reference_voting(&self, voting: usize) -> Pubkey {
+        self.reference_votings[voting].pubkey()
+    }
+
+    fn reference_ballot(&self, voting: usize, voter: usize) -> Pubkey {
+        let (voting, voter) = (self.reference_voting(voting), self.actors[voter].pubkey());
+
+        Pubkey::find_program_address(&[ BALLOT_SEED, voting.as_ref(), voter.as_ref() ], &voting_reference::ID).0
+    }
+
+    // The action as an instruction of each program, with their signers. `ends_at` is the current end of
+    // the voting the action is for, both programs agreed on it so far.
+    fn instructions(&self, action: &Action, ends_at: u64) -> (Instruction, Instruction, Vec<&Keypair>) {
+        let native_id = solana_voting_program::id();
+        let creator = &self.actors[0];
+
+        match *action {
+            Action::Create { voting, ref options, duration } => (
+                client::create_voting(&native_id, &creator.pubkey(), TITLES[voting].to_string(), String::new(), options.clone(), duration),
+                reference(
+                    voting_reference::accounts::CreateVoting {
+                        creator: creator.pubkey(),
+                        voting: self.reference_voting(voting),
+                        system_program: system_program::ID
+                    },
+                    voting_reference::instruction::CreateVoting { title: TITLES[voting].to_string(), options: options.clone(), duration }
+                ),
+                vec![ creator, &self.reference_votings[voting] ]
+            ),
+            Action::Vote { voter, voting, ref ballot } => (
+                client::vote(
+                    &native_id,
+                    &self.actors[voter].pubkey(),
+                    &self.native_voting(voting),
+                    ballot.clone(),
+                    TITLES[voting].to_string(),
+                    false,
+                    client::VoteProofs::default()
+                ),
+                reference(
+                    voting_reference::accounts::Vote {
+                        voter: self.actors[voter].pubkey(),
+                        voting: self.reference_voting(voting),
+                        ballot: self.reference_ballot(voting, voter),
+                        system_program: system_program::ID
+                    },
+                    voting_reference::instruction::Vote { ballot: ballot.clone() }
+                ),
+                vec![ &self.actors[voter] ]
+            ),
+            Action::UpdateVote { voter, voting, ref ballot } => (
+                client::update_vote(&native_id, &self.actors[voter].pubkey(), &self.native_voting(voting), ballot.clone(), false),
+                reference(
+                    voting_reference::accounts::UpdateVote {
+                        voter: self.actors[voter].pubkey(),
+                        voting: self.reference_voting(voting),
+                        ballot: self.reference_ballot(voting, voter)
+                    },
+                    voting_reference::instruction::UpdateVote { ballot: ballot.clone() }
+                ),
+                vec![ &self.actors[voter] ]
+            ),
+            Action::Extend { signer, voting, by } => {
+                let ends_at = ends_at.saturating_add_signed(by);
+                (
+                    client::extend_voting(&native_id, &self.actors[signer].pubkey(), &self.native_voting(voting), None, ends_at),
+                    reference(
+                        voting_reference::accounts::ManageVoting { creator: self.actors[signer].pubkey(), voting: self.reference_voting(voting) },
+                        voting_reference::instruction::ExtendVoting { ends_at }
+                    ),
+                    vec![ &self.actors[signer] ]
+                )
+            },
+            Action::Freeze { signer, voting } => (
+                client::freeze_voting(&native_id, &self.actors[signer].pubkey(), &self.native_voting(voting), None),
+                reference(
+                    voting_reference::accounts::ManageVoting { creator: self.actors[signer].pubkey(), voting: self.reference_voting(voting) },
+                    voting_reference::instruction::FreezeVoting {}
+                ),
+                vec![ &self.actors[signer] ]
+            ),
+            Action::Unfreeze { signer, voting } => (
+                client::unfreeze_voting(&native_id, &self.actors[signer].pubkey(), &self.native_voting(voting), None),
+                reference(
+                    voting_reference::accounts::ManageVoting { creator: self.actors[signer].pubkey(), voting: self.reference_voting(voting) },
+                    voting_reference::instruction::UnfreezeVoting {}
+                ),
+                vec![ &self.actors[signer] ]
+            ),
+            Action::Cancel { voting } => (
+                client::cancel_voting(&native_id, &creator.pubkey(), &self.native_voting(voting)),
+                reference(
+                    voting_reference::accounts::ManageVoting { creator: creator.pubkey(), voting: self.reference_voting(voting) },
+                    voting_reference::instruction::CancelVoting {}
+                ),
+                vec![ creator ]
+            ),
+            Action::SyncStatus { voting } => (
+                client::sync_status(&native_id, &self.native_voting(voting)),
+                reference(voting_reference::accounts::SyncStatus { voting: self.reference_voting(voting) }, voting_reference::instruction::SyncStatus {}),
+                Vec::new()
+            ),
+            Action::Warp { .. } => unreachable!("warps are no instructions")
+        }
+    }
+}
+
+struct Differential {
+    context: ProgramTestContext,
+    keys: Keys,
+    created: [bool; TITLES.len()],
+    now: u64,
+    // Sets each transaction apart, the same action can come twice under one blockhash
+    sent: u32
+}
+
+impl Differential {
+    async fn new() -> Self {
+        let mut program_test = ProgramTest::new("solana_voting_program", solana_voting_program::id(), processor!(process_native));
+        program_test.add_program("voting_reference", voting_reference::ID, processor!(process_reference));
+        program_test.prefer_bpf(false);
+
+        let mut differential = Self {
+            context: program_test.start_with_context().await,
+            keys: Keys {
+                actors: (0..ACTORS).map(|_| Keypair::new()).collect(),
+                reference_votings: TITLES.iter().map(|_| Keypair::new()).collect()
+            },
+            created: [ false; TITLES.len() ],
+            now: START,
+            sent: 0
+        };
+        differential.warp(0).await;
+
+        let payer = differential.context.payer.pubkey();
+        let fundings = differential.keys.actors.iter()
+            .map(|actor| system_instruction::transfer(&payer, &actor.pubkey(), 1_000_000_000))
+            .collect::<Vec<_>>();
+        let result = send(&mut differential.context, &mut differential.sent, &fundings, &[]).await;
+        result.unwrap();
+
+        differential
+    }
+
+    async fn warp(&mut self, seconds: u64) {
+        self.now += seconds;
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = self.now as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    // Runs the action on both programs and compares them. Actions on a voting that wasn't created, and
+    // creating one again, are left out: each program would only fail on the account, in its own way.
+    async fn step(&mut self, action: &Action) {
+        let voting = match *action {
+            Action::Warp { seconds } => return self.warp(seconds).await,
+            Action::Create { voting, .. } if self.created[voting] => return,
+            Action::Create { voting, .. } => voting,
+            Action::Vote { voting, .. }
+            | Action::UpdateVote { voting, .. }
+            | Action::Extend { voting, .. }
+            | Action::Freeze { voting, .. }
+            | Action::Unfreeze { voting, .. }
+            | Action::Cancel { voting }
+            | Action::SyncStatus { voting } if self.created[voting] => voting,
+            _ => return
+        };
+
+        let ends_at = match self.created[voting] {
+            true => self.reference_state(voting).await.ends_at,
+            false => 0
+        };
+        let (native, reference, signers) = self.keys.instructions(action, ends_at);
+        let native = outcome(send(&mut self.context, &mut self.sent, &[ native ], &signers).await, native_error);
+        let reference = outcome(send(&mut self.context, &mut self.sent, &[ reference ], &signers).await, reference_error);
+        assert_eq!(native, reference, "outcome of {:?}", action);
+
+        if let (Action::Create { .. }, Outcome::Ok) = (action, &native) {
+            self.created[voting] = true;
+        };
+
+        for voting in 0..TITLES.len() {
+            if self.created[voting] {
+                assert_eq!(self.native_state(voting).await, self.reference_state(voting).await, "voting {} after {:?}", voting, action);
+            };
+        };
+    }
+
+    async fn native_state(&mut self, voting: usize) -> VotingState {
+        let program_id = solana_voting_program::id();
+        let voting_key = self.keys.native_voting(voting);
+        let user_votes = self.keys.actors.iter()
+            .map(|actor| find_user_vote_pda(&voting_key, &actor.pubkey(), &program_id).0)
+            .collect::<Vec<_>>();
+
+        let voting_data = self.native_account::<NativeVoting>(voting_key, "Voting").await.unwrap();
+        let mut ballots = Vec::new();
+        for user_vote in user_votes {
+            ballots.push(self.native_account::<NativeUserVote>(user_vote, "UserVote").await.map(|user_vote| user_vote.ballot));
+        };
+
+        VotingState {
+            status: voting_data.status,
+            ends_at: voting_data.ends_at,
+            frozen_at: voting_data.frozen_at,
+            tallies: voting_data.tallies,
+            total_voters: voting_data.total_voters,
+            spoiled: voting_data.tally_state.spoiled,
+            ballots
+        }
+    }
+
+    async fn reference_state(&mut self, voting: usize) -> VotingState {
+        let voting_key = self.keys.reference_voting(voting);
+        let ballot_keys = (0..ACTORS).map(|voter| self.keys.reference_ballot(voting, voter)).collect::<Vec<_>>();
+
+        let voting_data = self.reference_account::<voting_reference::Voting>(voting_key).await.unwrap();
+        let mut ballots = Vec::new();
+        for ballot in ballot_keys {
+            ballots.push(self.reference_account::<voting_reference::Ballot>(ballot).await.map(|ballot| ballot.ballot));
+        };
+
+        VotingState {
+            status: format!("{:?}", voting_data.status),
+            ends_at: voting_data.ends_at,
+            frozen_at: voting_data.frozen_at,
+            tallies: voting_data.tallies,
+            total_voters: voting_data.total_voters,
+            spoiled: voting_data.spoiled,
+            ballots
+        }
+    }
+
+    // The native account at `address` through its serde form, `kind` names its `DecodedAccount` variant
+    async fn native_account<T: DeserializeOwned>(&mut self, address: Pubkey, kind: &str) -> Option<T> {
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        let decoded = decode_account(&solana_voting_program::id(), &account.owner, &account.data).unwrap().unwrap();
+
+        Some(serde_json::from_value(serde_json::to_value(decoded).unwrap()[kind].clone()).unwrap())
+    }
+
+    async fn reference_account<T: AccountDeserialize>(&mut self, address: Pubkey) -> Option<T> {
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+
+        Some(T::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+}
+
+fn reference(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: voting_reference::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+async fn send(context: &mut ProgramTestContext, sent: &mut u32, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    *sent += 1;
+    let mut all_instructions = vec![ ComputeBudgetInstruction::set_compute_unit_limit(1_000_000 + *sent) ];
+    all_instructions.extend_from_slice(instructions);
+
+    // Each program's instruction needs its own part of the action's signers
+    let mut all_signers = vec![ &context.payer ];
+    all_signers.extend(signers.iter().filter(|signer| {
+        instructions.iter().flat_map(|instruction| instruction.accounts.iter()).any(|meta| meta.is_signer && meta.pubkey == signer.pubkey())
+    }));
+    let transaction = Transaction::new_signed_with_payer(&all_instructions, Some(&context.payer.pubkey()), &all_signers, context.last_blockhash);
+
+    context.banks_client.process_transaction(transaction).await
+}
+
+proptest! {
+    // Each case starts its own validator, so few cases from a fixed seed keep the run short and the same
+    // on every run
+    #![proptest_config(ProptestConfig { cases: 8, rng_seed: RngSeed::Fixed(0), ..ProptestConfig::default() })]
+
+    #[test]
+    fn both_programs_agree_on_every_action(actions in prop::collection::vec(action(), 1..=32)) {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let mut differential = Differential::new().await;
+            for action in actions.iter() {
+                differential.step(action).await;
+            };
+        });
+    }
+}
@@ -0,0 +1,158 @@
+// Returns the RPC client's `ClientResult` like the builders in `client`
+#![allow(clippy::result_large_err)]
+
+use solana_client::{
+    client_error::{ ClientErrorKind, Result as ClientResult },
+    rpc_client::{ GetConfirmedSignaturesForAddress2Config, RpcClient },
+    rpc_config::{ RpcProgramAccountsConfig, RpcTransactionConfig },
+    rpc_filter::{ Memcmp, RpcFilterType }
+};
+use solana_program::{ instruction::CompiledInstruction, pubkey::Pubkey };
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{ option_serializer::OptionSerializer, UiTransactionEncoding };
+
+use crate::{
+    decoder::{ decode_account, DecodedAccount },
+    AccountType,
+    VotingInstruction
+};
+
+// Max signatures `getSignaturesForAddress` returns per call
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum HistoryEventKind {
+    VotingCreated { voting: Pubkey, creator: Pubkey },
+    Voted { voting: Pubkey, voter: Pubkey },
+    VoteUpdated { voting: Pubkey, voter: Pubkey },
+    // A cancellation can be undone before the voting starts, the last of the two wins
+    VotingCancelled { voting: Pubkey, creator: Pubkey },
+    VotingRestored { voting: Pubkey, creator: Pubkey },
+    // Memberships left, refunded boost deposits and completed recounts. Votings and user vote accounts
+    // are never closed.
+    AccountClosed { account: Pubkey }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HistoryEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub kind: HistoryEventKind
+}
+
+// Current accounts of the program of the given types, one `getProgramAccounts` call per type so a
+// single response doesn't hold every account of the program.
+pub fn scan_accounts(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    account_types: &[AccountType]
+) -> ClientResult<Vec<(Pubkey, DecodedAccount)>> {
+    let mut decoded = Vec::new();
+    for account_type in account_types {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, account_type.discriminator().to_vec()))]),
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        for (address, account) in rpc.get_program_accounts_with_config(program_id, config)? {
+            let account_data = decode_account(program_id, &account.owner, &account.data)
+                .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+            if let Some(account_data) = account_data {
+                decoded.push((address, account_data));
+            };
+        };
+    };
+
+    Ok(decoded)
+}
+
+// Events of every successful transaction calling the program after `until`, oldest first. Pass the
+// signature of the last event of a previous scan to resume from it. Only top-level instructions are
+// decoded, calls through other programs are missed.
+pub fn scan_history(rpc: &RpcClient, program_id: &Pubkey, until: Option<Signature>) -> ClientResult<Vec<HistoryEvent>> {
+    let mut statuses = Vec::new();
+    let mut before = None;
+    loop {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit: Some(SIGNATURES_PAGE_SIZE),
+            ..GetConfirmedSignaturesForAddress2Config::default()
+        };
+        let page = rpc.get_signatures_for_address_with_config(program_id, config)?;
+        let Some(last) = page.last() else {
+            break;
+        };
+
+        before = Some(parse_signature(&last.signature)?);
+        statuses.extend(page.into_iter().filter(|status| status.err.is_none()));
+    };
+
+    let mut events = Vec::new();
+    for status in statuses.into_iter().rev() {
+        let signature = parse_signature(&status.signature)?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: Some(0),
+            ..RpcTransactionConfig::default()
+        };
+        let confirmed = rpc.get_transaction_with_config(&signature, config)?;
+        let transaction = confirmed.transaction.transaction.decode()
+            .ok_or(ClientErrorKind::Custom("Transaction can't be decoded.".to_string()))?;
+
+        // Addresses loaded from lookup tables follow the static keys, writable ones first
+        let mut keys = transaction.message.static_account_keys().to_vec();
+        let loaded = confirmed.transaction.meta.map(|meta| meta.loaded_addresses);
+        if let Some(OptionSerializer::Some(loaded)) = loaded {
+            for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                keys.push(address.parse().map_err(|_| ClientErrorKind::Custom("Invalid loaded address.".to_string()))?);
+            };
+        };
+
+        for instruction in transaction.message.instructions() {
+            if keys.get(instruction.program_id_index as usize) != Some(program_id) {
+                continue;
+            };
+
+            if let Some(kind) = instruction_event(instruction, &keys) {
+                events.push(HistoryEvent {
+                    signature: status.signature.clone(),
+                    slot: confirmed.slot,
+                    block_time: confirmed.block_time,
+                    kind
+                });
+            };
+        };
+    };
+
+    Ok(events)
+}
+
+fn instruction_event(instruction: &CompiledInstruction, keys: &[Pubkey]) -> Option<HistoryEventKind> {
+    let voting_instruction = VotingInstruction::unpack(&instruction.data).ok()?;
+    // Guarded instructions take the config account first
+    let offset = voting_instruction.flag().is_some() as usize;
+    let account = |index: usize| -> Option<Pubkey> {
+        keys.get(*instruction.accounts.get(offset + index)? as usize).copied()
+    };
+
+    match voting_instruction {
+        VotingInstruction::CreateVoting(_) => Some(HistoryEventKind::VotingCreated { voting: account(1)?, creator: account(0)? }),
+        VotingInstruction::CreateRealmVoting(_) => Some(HistoryEventKind::VotingCreated { voting: account(3)?, creator: account(0)? }),
+        VotingInstruction::Vote(_) => Some(HistoryEventKind::Voted { voting: account(1)?, voter: account(0)? }),
+        VotingInstruction::UpdateVote(_) => Some(HistoryEventKind::VoteUpdated { voting: account(1)?, voter: account(0)? }),
+        VotingInstruction::CancelVoting => Some(HistoryEventKind::VotingCancelled { voting: account(1)?, creator: account(0)? }),
+        VotingInstruction::RestoreVoting => Some(HistoryEventKind::VotingRestored { voting: account(1)?, creator: account(0)? }),
+        VotingInstruction::LeaveRealm => Some(HistoryEventKind::AccountClosed { account: account(2)? }),
+        VotingInstruction::RefundBoost => Some(HistoryEventKind::AccountClosed { account: account(3)? }),
+        VotingInstruction::CompleteRecount => Some(HistoryEventKind::AccountClosed { account: account(2)? }),
+        _ => None
+    }
+}
+
+fn parse_signature(signature: &str) -> ClientResult<Signature> {
+    signature.parse().map_err(|_| ClientErrorKind::Custom("Invalid signature.".to_string()).into())
+}
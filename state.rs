@@ -0,0 +1,2418 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use unicode_normalization::UnicodeNormalization;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    keccak::{ hash, hashv },
+    borsh0_10::try_from_slice_unchecked,
+    rent
+};
+
+use crate::{
+    buckets::{ RecordSlot, BUCKET_SLOTS, RECORD_SLOT_SPACE },
+    checks::assert_current_version,
+    draw::{ RandomDraw, RANDOM_DRAW_SPACE },
+    eligibility::{ EligibilityPolicy, ELIGIBILITY_POLICY_SPACE },
+    error::Errors,
+    execution::{ AllowedAction, ProposalInstruction, TemplateParam },
+    instruction::*,
+    merkle::RecordTree,
+    oracle::{ OracleCondition, ORACLE_CONDITION_SPACE },
+    tally::{ apportion, top_options, Apportionment, TallyMode, TallyState, TiePolicy, APPORTIONMENT_SPACE, TALLY_MODE_SPACE },
+    weight::{ verify_merkle_proof, MintRatio, WeightSettings, WeightSource, MAX_WEIGHT_MINTS, MINT_RATIO_SPACE, WEIGHT_SOURCE_SPACE }
+};
+
+// Layouts of the program's accounts. Every account keeps its `StateVersion` in the byte after its
+// discriminator and is written with `StateVersion::CURRENT`, loaders reject any other version.
+// `migrate_account` is where an account moves to the current layout once one changes.
+// `VoterWeightRecord`s are written by weight plugins and have no version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StateVersion {
+    // `version` between the discriminator and the fields
+    V1 = 1
+}
+
+impl StateVersion {
+    pub const CURRENT: Self = Self::V1;
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::V1),
+            _ => None
+        }
+    }
+}
+
+// Settings a realm provides defaults for, creators override them with `VotingSettingsOverrides`
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingSettings {
+    pub min_voting_time: u64,
+    pub max_voting_time: u64,
+    pub creator_threshold: u8,
+    pub allow_updates: bool,
+    pub members_only: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConfigAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub admin: Pubkey,
+    pub disabled_instructions: u128,
+    // Votings a creator can have open at once, 0 for no cap
+    pub max_active_votings: u32,
+    // How far `starts_at` may lie in the past on creation, absorbs clock drift between clients and the cluster
+    pub start_time_tolerance: u64,
+    pub pending_admin: Option<Pubkey>,
+    // Lamports a creator locks in each new voting on top of its rent, 0 for none. They're refunded on a
+    // finalization within `deposit_refund_window` seconds of the end.
+    pub creation_deposit: u64,
+    pub deposit_refund_window: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoteMainAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub creator: Pubkey,
+    pub payer: Pubkey,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub status: VotingStatus,
+    // `Pubkey::default()` for votings outside a realm
+    pub realm: Pubkey,
+    pub realm_voting_id: u64,
+    // Resolved from the realm settings on creation
+    pub members_only: bool,
+    pub allow_updates: bool,
+    pub title: VotingTitle,
+    pub description: String,
+    pub options: Vec<String>,
+    // Summed weights of the ballots supporting each option, see `TallyMode::supports`, kept in the same
+    // order as `options`
+    pub tallies: Vec<u64>,
+    // Voters with a user vote account, a recount has to find all of them. Also the `vote_index` of the
+    // next ballot, so ballots are numbered 0, 1, 2... without gaps.
+    pub total_voters: u64,
+    // Rolling hash over every accepted vote and update, see `chain_vote_hash`
+    pub vote_chain: [u8; 32],
+    // Max tally per option, 0 for uncapped
+    pub option_caps: Vec<u64>,
+    pub co_creators: Vec<Pubkey>,
+    pub creator_threshold: u8,
+    pub weight_source: WeightSource,
+    pub weight_mints: Vec<MintRatio>,
+    pub weight_precision: Option<u8>,
+    pub tally_mode: TallyMode,
+    pub eligibility: EligibilityPolicy,
+    // Signup votings take slot claims instead of ballots, first come, first served
+    pub signup_slots: u32,
+    pub claimed_slots: u32,
+    pub random_draw: Option<RandomDraw>,
+    pub oracle_condition: Option<OracleCondition>,
+    // Set on finalization for votings with an oracle condition, always true for the others
+    pub condition_met: bool,
+    pub record_buckets: u8,
+    // Set once a draft is published, its configuration can't be edited anymore
+    pub locked: bool,
+    // `STAGE_*` bits of the setup steps done so far, and those `publish_voting` requires
+    pub init_stage: u8,
+    pub required_stages: u8,
+    // Envelope votings keep their tallies at 0 until the creator submits the decrypted results, along
+    // with their commitment. `envelope_weight` sums the weights of the sealed ballots.
+    pub envelope_key: Option<[u8; 32]>,
+    pub envelope_weight: u64,
+    pub envelope_commitment: Option<[u8; 32]>,
+    pub tie_policy: TiePolicy,
+    // Last time each option's count changed, only kept for `TiePolicy::EarliestLeader`
+    pub changed_at: Vec<u64>,
+    // Draws among the tied options for `TiePolicy::Random`, with one ticket per tied option. Once drawn,
+    // `ticket` holds the drawn option itself. Replacing the tallies resets the draw, see `reset_tie_draw`.
+    pub tie_draw: RandomDraw,
+    pub dependency: Option<VotingDependency>,
+    // Program the creator is a PDA of, which signs for it by CPI. `None` for wallet creators.
+    pub creator_program: Option<Pubkey>,
+    // Root of the `RecordTree` over the vote records, once `merkleize_records` went through all of them
+    pub records_root: Option<[u8; 32]>,
+    // Set by `flag_voting` and `hide_voting`, frontends decide what to do with them
+    pub flag_count: u32,
+    pub moderation: ModerationStatus,
+    // Lamports each new voter gets from the incentive pool while it lasts, 0 without a pool
+    pub incentive_per_vote: u64,
+    pub turnout_milestones: Option<TurnoutMilestones>,
+    pub hide_tallies: bool,
+    // Creation deposit held in the account's lamports, snapshotted from the config along with its
+    // window. Settled by `finalize_voting`, 0 afterwards.
+    pub creation_deposit: u64,
+    pub deposit_refund_window: u64,
+    pub result_attestors: Option<ResultAttestors>,
+    // Shards counting the ballots, 0 when they're counted here. See `TallyShardAccount`.
+    pub tally_shards: u8,
+    // Set by `merge_tally_shards`, the tallies of a sharded voting only count its ballots afterwards
+    pub shards_merged: bool,
+    // 0 for no cap, see `CreateVotingInstruction::weight_cap_per_slot`
+    pub weight_cap_per_slot: u64,
+    pub reject_encumbered_tokens: bool,
+    // Mint of the NFT holding the canonical proposal document, see `link_proposal_note`
+    pub proposal_note: Option<Pubkey>,
+    // 0 for plain ballots, see `CreateVotingInstruction::reveal_window`
+    pub reveal_window: u64,
+    // Ballots committed so far, `total_voters` counts the revealed ones
+    pub commitments: u64,
+    pub winners_count: u8,
+    // Recorded by `finalize_voting`, best first
+    pub winners: Vec<u8>,
+    pub apportionment: Option<Apportionment>,
+    // Seats of each option with an apportionment, all 0 until `finalize_voting`. Empty without one.
+    pub seats: Vec<u16>,
+    // Sealed once by `seal_voter_roll`, ballots then need a proof against it
+    pub voter_roll: Option<VoterRoll>,
+    // Set by `freeze_voting`, see `process_set_frozen`
+    pub frozen_at: Option<u64>,
+    // See `CreateVotingInstruction::min_participation` and `pass_threshold_bps`
+    pub min_participation: u32,
+    pub pass_threshold_bps: u16,
+    // See `CreateVotingInstruction::electorate` and `quorum_bps`
+    pub electorate: Option<Electorate>,
+    pub quorum_bps: u16,
+    // Recorded by `finalize_voting`, `result` is the winning option of a passed voting
+    pub outcome: Option<VotingOutcome>,
+    pub result: Option<u8>,
+    pub tally_state: TallyState
+}
+
+impl VotingSettings {
+    // Applies the creator's overrides, a locked setting can't be overridden
+    pub(crate) fn resolve(&self, overrides: &VotingSettingsOverrides, locked_settings: u8) -> Result<Self, ProgramError> {
+        let locked = |lock: u8, overridden: bool| overridden && locked_settings & lock != 0;
+        if locked(LOCK_VOTING_TIME, overrides.min_voting_time.is_some() || overrides.max_voting_time.is_some())
+            || locked(LOCK_CREATOR_THRESHOLD, overrides.creator_threshold.is_some())
+            || locked(LOCK_ALLOW_UPDATES, overrides.allow_updates.is_some())
+            || locked(LOCK_MEMBERS_ONLY, overrides.members_only.is_some())
+        {
+            return Err(Errors::RealmSettingLocked.into());
+        };
+
+        let settings = Self {
+            min_voting_time: overrides.min_voting_time.unwrap_or(self.min_voting_time),
+            max_voting_time: overrides.max_voting_time.unwrap_or(self.max_voting_time),
+            creator_threshold: overrides.creator_threshold.unwrap_or(self.creator_threshold),
+            allow_updates: overrides.allow_updates.unwrap_or(self.allow_updates),
+            members_only: overrides.members_only.unwrap_or(self.members_only)
+        };
+        settings.validate()?;
+
+        Ok(settings)
+    }
+
+    pub(crate) fn validate(&self) -> ProgramResult {
+        if self.max_voting_time == 0
+            || self.max_voting_time > MAX_VOTING_TIME.into()
+            || self.min_voting_time > self.max_voting_time
+            || self.creator_threshold == 0
+            || self.creator_threshold as usize > MAX_CREATORS
+        {
+            return Err(Errors::InvalidRealmSettings.into());
+        };
+
+        Ok(())
+    }
+}
+
+impl VoteMainAccount {
+    pub(crate) fn new(
+        creator: Pubkey,
+        ix_data: CreateVotingInstruction,
+        realm: Pubkey,
+        realm_voting_id: u64,
+        settings: &VotingSettings
+    ) -> Result<Self, ProgramError> {
+        Ok(Self {
+            discriminator: AccountType::Vote.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            creator,
+            payer: creator,
+            starts_at: ix_data.starts_at,
+            ends_at: ix_data.ends_at,
+            status: if ix_data.draft { VotingStatus::Draft } else { VotingStatus::Active },
+            realm,
+            realm_voting_id,
+            members_only: settings.members_only,
+            allow_updates: settings.allow_updates,
+            title: VotingTitle::new(&ix_data.title)?,
+            description: ix_data.description,
+            tallies: vec![0; ix_data.options.len()],
+            seats: if ix_data.apportionment.is_some() { vec![0; ix_data.options.len()] } else { Vec::new() },
+            total_voters: 0,
+            vote_chain: [0; 32],
+            option_caps: if ix_data.option_caps.is_empty() { vec![0; ix_data.options.len()] } else { ix_data.option_caps },
+            options: ix_data.options,
+            co_creators: ix_data.co_creators,
+            creator_threshold: ix_data.creator_threshold,
+            weight_source: ix_data.weight_source,
+            weight_mints: ix_data.weight_mints,
+            weight_precision: ix_data.weight_precision,
+            tally_mode: ix_data.tally_mode,
+            eligibility: ix_data.eligibility,
+            signup_slots: ix_data.signup_slots,
+            claimed_slots: 0,
+            random_draw: ix_data.random_draw.then(RandomDraw::default),
+            condition_met: ix_data.oracle_condition.is_none(),
+            oracle_condition: ix_data.oracle_condition,
+            record_buckets: ix_data.record_buckets,
+            locked: false,
+            init_stage: 0,
+            required_stages: ix_data.required_stages,
+            envelope_key: ix_data.envelope_key,
+            envelope_weight: 0,
+            envelope_commitment: None,
+            tie_policy: ix_data.tie_policy,
+            changed_at: Vec::new(),
+            tie_draw: RandomDraw::default(),
+            dependency: ix_data.dependency,
+            creator_program: ix_data.program_creator.map(|program_creator| program_creator.program_id),
+            records_root: None,
+            flag_count: 0,
+            moderation: ModerationStatus::Visible,
+            incentive_per_vote: 0,
+            turnout_milestones: ix_data.turnout_milestones,
+            hide_tallies: ix_data.hide_tallies,
+            creation_deposit: 0,
+            deposit_refund_window: 0,
+            result_attestors: ix_data.result_attestors,
+            tally_shards: ix_data.tally_shards,
+            shards_merged: false,
+            weight_cap_per_slot: ix_data.weight_cap_per_slot,
+            reject_encumbered_tokens: ix_data.reject_encumbered_tokens,
+            proposal_note: None,
+            reveal_window: ix_data.reveal_window,
+            commitments: 0,
+            winners_count: ix_data.winners_count,
+            winners: Vec::new(),
+            apportionment: ix_data.apportionment,
+            voter_roll: None,
+            frozen_at: None,
+            min_participation: ix_data.min_participation,
+            pass_threshold_bps: ix_data.pass_threshold_bps,
+            electorate: ix_data.electorate,
+            quorum_bps: ix_data.quorum_bps,
+            outcome: None,
+            result: None,
+            tally_state: TallyState { large_supply: ix_data.large_supply, ..TallyState::default() }
+        })
+    }
+
+    // `create_voting_account` funds the deposit on top of the rent
+    pub(crate) fn take_creation_deposit(&mut self, config: Option<&ConfigAccount>) {
+        if let Some(config) = config {
+            self.creation_deposit = config.creation_deposit;
+            self.deposit_refund_window = config.deposit_refund_window;
+        };
+    }
+
+    // Votings outside a realm are keyed by title, realm votings by (realm, id)
+    pub(crate) fn find_pda(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        if self.realm == Pubkey::default() {
+            find_voting_pda(self.title.as_str(), program_id)
+        } else {
+            find_realm_voting_pda(&self.realm, self.realm_voting_id, program_id)
+        }
+    }
+
+    pub(crate) fn check_active(&self) -> ProgramResult {
+        match self.status {
+            VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended => Ok(()),
+            VotingStatus::Cancelled => Err(Errors::VotingCancelled.into()),
+            VotingStatus::Finalized => Err(Errors::VotingFinalized.into()),
+            VotingStatus::Draft => Err(Errors::VotingIsDraft.into())
+        }
+    }
+
+    pub(crate) fn check_not_frozen(&self) -> ProgramResult {
+        if self.frozen_at.is_some() {
+            return Err(Errors::VotingFrozen.into());
+        };
+
+        Ok(())
+    }
+
+    // Every path that records or changes a ballot goes through this check
+    pub(crate) fn check_accepts_votes(&self, current_time: u64) -> ProgramResult {
+        self.check_active()?;
+        self.check_not_frozen()?;
+
+        if self.starts_at > current_time {
+            return Err(Errors::VotingNotStarted.into());
+        };
+
+        if self.ends_at <= current_time {
+            return Err(Errors::VotingEnded.into());
+        };
+
+        Ok(())
+    }
+
+    // Every output of the running tallies goes through this check. It only keeps them out of the
+    // program's return data and logs: the account data and the ballots in the vote transactions stay
+    // public, envelope votings are the way to seal ballots.
+    pub(crate) fn check_tallies_visible(&self, current_time: u64) -> ProgramResult {
+        if self.hide_tallies && self.ends_at > current_time {
+            return Err(Errors::TalliesHidden.into());
+        };
+
+        Ok(())
+    }
+
+    // End of the first `percent` of the voting window, see `VoterAllowlistAccount::late_registration`
+    pub(crate) fn late_registration_ends_at(&self, percent: u8) -> u64 {
+        self.starts_at + (self.ends_at - self.starts_at) * percent as u64 / 100
+    }
+
+    pub(crate) fn reveal_ends_at(&self) -> u64 {
+        self.ends_at.saturating_add(self.reveal_window)
+    }
+
+    // Committed ballots are revealed after `ends_at`, within the reveal window
+    pub(crate) fn check_reveal_open(&self, current_time: u64) -> ProgramResult {
+        self.check_not_frozen()?;
+
+        if self.ends_at > current_time {
+            return Err(Errors::VotingNotEnded.into());
+        };
+
+        if self.reveal_ends_at() <= current_time {
+            return Err(Errors::RevealWindowClosed.into());
+        };
+
+        Ok(())
+    }
+
+    // A voting is closed once it is finalized, or once it ended without being cancelled. Votings with an
+    // oracle condition have to be finalized, the outcome isn't known before, and so do votings with result
+    // attestors, which must have co-signed it.
+    pub(crate) fn check_closed(&self, current_time: u64) -> ProgramResult {
+        match self.status {
+            VotingStatus::Finalized => self.check_result_attested(),
+            VotingStatus::Cancelled => Err(Errors::VotingCancelled.into()),
+            VotingStatus::Draft => Err(Errors::VotingIsDraft.into()),
+            VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended if self.ends_at > current_time => {
+                Err(Errors::VotingNotEnded.into())
+            },
+            VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended if self.reveal_ends_at() > current_time => {
+                Err(Errors::RevealWindowOpen.into())
+            },
+            VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended if self.oracle_condition.is_some() || self.result_attestors.is_some() => {
+                Err(Errors::VotingNotFinalized.into())
+            },
+            VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended => self.check_shards_merged()
+        }
+    }
+
+    // The choice events may carry, withheld while the running tallies are hidden
+    pub(crate) fn visible_choice(&self, ballot: &[u8], current_time: u64) -> Option<u8> {
+        self.check_tallies_visible(current_time).ok().and(ballot.first().copied())
+    }
+
+    pub(crate) fn check_result_attested(&self) -> ProgramResult {
+        if self.result_attestors.as_ref().is_some_and(|result_attestors| result_attestors.is_met() == false) {
+            return Err(Errors::ResultNotAttested.into());
+        };
+
+        Ok(())
+    }
+
+    pub(crate) fn weight_settings(&self) -> WeightSettings<'_> {
+        WeightSettings {
+            weight_mints: &self.weight_mints,
+            precision: self.weight_precision,
+            reject_encumbered: self.reject_encumbered_tokens
+        }
+    }
+
+    // Part of a voter's weight credited in a single slot
+    pub(crate) fn capped_weight(&self, weight: u64) -> u64 {
+        match self.weight_cap_per_slot {
+            0 => weight,
+            cap => weight.min(cap)
+        }
+    }
+
+    // The tallies of a sharded voting are incomplete until `merge_tally_shards`
+    pub(crate) fn check_shards_merged(&self) -> ProgramResult {
+        if self.tally_shards > 0 && self.shards_merged == false {
+            return Err(Errors::ShardsNotMerged.into());
+        };
+
+        Ok(())
+    }
+
+    // Moves an open voting to the status the clock puts it in, returns whether it changed
+    pub(crate) fn sync_status(&mut self, current_time: u64) -> bool {
+        if matches!(self.status, VotingStatus::Pending | VotingStatus::Active | VotingStatus::Ended) == false {
+            return false;
+        };
+
+        let status = if self.starts_at > current_time {
+            VotingStatus::Pending
+        } else if self.ends_at <= current_time {
+            VotingStatus::Ended
+        } else {
+            VotingStatus::Active
+        };
+
+        let changed = self.status != status;
+        self.status = status;
+
+        changed
+    }
+
+    // Rejects a ballot that took a capped option over its cap, checked after the ballot is counted
+    pub(crate) fn check_option_caps(&self, ballot: &[u8]) -> ProgramResult {
+        for option in ballot.iter() {
+            let cap = self.option_caps[*option as usize];
+            if cap > 0 && self.tally_mode.supports(ballot, *option) && self.tally_state.total(&self.tallies, *option) > cap as u128 {
+                return Err(Errors::OptionFull.into());
+            };
+        };
+
+        Ok(())
+    }
+
+    // `None` for an uncapped option
+    pub(crate) fn remaining_capacity(&self, option: u8) -> Option<u64> {
+        match self.option_caps[option as usize] {
+            0 => None,
+            cap => Some((cap as u128).saturating_sub(self.tally_state.total(&self.tallies, option)) as u64)
+        }
+    }
+
+    // Decided by the tally mode and the tie policy, `None` when nobody voted, the mode found no winner,
+    // the oracle condition wasn't met, a tie left it open or the voting didn't pass, see `decide_outcome`
+    pub(crate) fn winning_option(&self) -> Option<u8> {
+        self.top_option().filter(|_| self.decide_outcome() == VotingOutcome::Passed)
+    }
+
+    // Turnout in basis points of the electorate, `None` without one. Spoiled ballots count.
+    pub(crate) fn turnout_bps(&self) -> Option<u16> {
+        let (turnout, electorate) = match self.electorate? {
+            Electorate::Voters(voters) => (self.total_voters as u128, voters as u128),
+            Electorate::Weight(weight) => (self.tally_state.cast_weight, weight)
+        };
+
+        // Turnout can pass a stale snapshot of the electorate
+        Some((turnout.saturating_mul(10_000) / electorate.max(1)).min(10_000) as u16)
+    }
+
+    // The quorums are checked first, then the top option's share of all option tallies
+    pub(crate) fn decide_outcome(&self) -> VotingOutcome {
+        if self.total_voters < self.min_participation as u64
+            || self.turnout_bps().is_some_and(|turnout_bps| turnout_bps < self.quorum_bps)
+        {
+            return VotingOutcome::QuorumNotMet;
+        };
+
+        let totals = self.tally_state.totals(&self.tallies);
+        let total: u128 = totals.iter().sum();
+        match self.top_option() {
+            Some(option) if totals[option as usize] * 10_000 >= total * self.pass_threshold_bps as u128 => VotingOutcome::Passed,
+            _ => VotingOutcome::Rejected
+        }
+    }
+
+    pub(crate) fn top_option(&self) -> Option<u8> {
+        if self.condition_met == false {
+            return None;
+        };
+
+        let winner = self.tally_mode.winner(&self.tallies, &self.tally_state)?;
+        let tied = self.tally_mode.tied(&self.tallies, &self.tally_state);
+        if tied.len() < 2 {
+            return Some(winner);
+        };
+
+        match self.tie_policy {
+            TiePolicy::LowestIndex => Some(winner),
+            TiePolicy::NoWinner | TiePolicy::Runoff => None,
+            TiePolicy::EarliestLeader => tied.into_iter().min_by_key(|option| (self.changed_at.get(*option as usize).copied().unwrap_or(0), *option)),
+            TiePolicy::Random => self.tie_draw.drawn.then_some(self.tie_draw.ticket as u8).filter(|option| tied.contains(option))
+        }
+    }
+
+    // Tallies replaced after the close can tie other options, the draw starts over among those
+    pub(crate) fn reset_tie_draw(&mut self) {
+        self.tie_draw = RandomDraw::default();
+    }
+
+    // The option `winning_option` would pick if the voting closed now, `None` while the tallies are hidden
+    pub(crate) fn leading_option(&self, current_time: u64) -> Option<u8> {
+        self.check_tallies_visible(current_time).ok()?;
+        self.winning_option()
+    }
+
+    // The winning option, or the `winners_count` options with the highest counts, of a passed voting
+    pub(crate) fn winners(&self) -> Vec<u8> {
+        if self.winners_count <= 1 {
+            return self.winning_option().into_iter().collect();
+        };
+
+        if self.decide_outcome() != VotingOutcome::Passed {
+            return Vec::new();
+        };
+
+        top_options(&self.tally_state.totals(&self.tallies), self.winners_count as usize)
+    }
+
+    // Seats of each option by the voting's apportionment, none without a quorum
+    pub(crate) fn apportioned_seats(&self) -> Result<Vec<u16>, ProgramError> {
+        let Some(apportionment) = self.apportionment else {
+            return Ok(Vec::new());
+        };
+
+        if self.decide_outcome() == VotingOutcome::QuorumNotMet {
+            return Ok(vec![0; self.options.len()]);
+        };
+
+        apportion(&self.tally_state.totals(&self.tallies), apportionment)
+    }
+
+    // Logs a `TurnoutMilestoneEvent` for every milestone the latest ballot reached. Ballots are never
+    // removed, so each milestone is reached once.
+    pub(crate) fn log_turnout_milestones(&self, voting: &Pubkey) -> ProgramResult {
+        let turnout_milestones = match self.turnout_milestones.as_ref() {
+            Some(turnout_milestones) => turnout_milestones,
+            None => return Ok(())
+        };
+
+        let mut milestones: Vec<TurnoutMilestone> = turnout_milestones.percents.iter()
+            .filter(|percent| turnout_milestones.voters_at(**percent) == self.total_voters)
+            .map(|percent| TurnoutMilestone::Percent(*percent))
+            .collect();
+        if turnout_milestones.quorum > 0 && turnout_milestones.quorum == self.total_voters {
+            milestones.push(TurnoutMilestone::Quorum);
+        };
+
+        for milestone in milestones {
+            match milestone {
+                TurnoutMilestone::Percent(percent) => msg!("Turnout milestone reached - {}%", percent),
+                TurnoutMilestone::Quorum => msg!("Turnout milestone reached - quorum")
+            };
+
+            let event = TurnoutMilestoneEvent {
+                voting: *voting,
+                milestone,
+                total_voters: self.total_voters,
+                electorate: turnout_milestones.electorate
+            };
+            log_event(TURNOUT_EVENT_TAG, TURNOUT_EVENT_VERSION, &event)?;
+        };
+
+        Ok(())
+    }
+
+    // Records when the counts of the ballot's options changed, for `TiePolicy::EarliestLeader`
+    pub(crate) fn mark_changed(&mut self, ballot: &[u8], current_time: u64) {
+        if self.tie_policy != TiePolicy::EarliestLeader {
+            return;
+        };
+
+        self.changed_at.resize(self.options.len(), 0);
+        for option in ballot.iter() {
+            self.changed_at[*option as usize] = current_time;
+        };
+    }
+}
+
+// A launchpad or DAO framework opening votings for its users signs with one of its PDAs. The seeds let
+// the voting record which program the creator belongs to, the PDA itself can only sign through it.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct ProgramCreator {
+    pub program_id: Pubkey,
+    // Seeds of the creator PDA, bump included
+    pub seeds: Vec<Vec<u8>>
+}
+
+impl ProgramCreator {
+    pub(crate) fn check(&self, program_id: &Pubkey, creator: &Pubkey) -> ProgramResult {
+        let seeds: Vec<&[u8]> = self.seeds.iter().map(Vec::as_slice).collect();
+        let derived = Pubkey::create_program_address(&seeds, &self.program_id)
+            .or(Err(Errors::InvalidProgramCreator))?;
+
+        if self.program_id == *program_id || derived != *creator {
+            return Err(Errors::InvalidProgramCreator.into());
+        };
+
+        Ok(())
+    }
+}
+
+// E.g. "only opens if proposal #12 passed", with `option` the passing option of proposal #12
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingDependency {
+    pub voting: Pubkey,
+    // Winning option the voting must have been finalized with
+    pub option: u8
+}
+
+impl VotingDependency {
+    // `account` is the voting, or its archive once it was archived
+    pub(crate) fn check(&self, program_id: &Pubkey, account: &AccountInfo) -> ProgramResult {
+        if account.owner != program_id {
+            return Err(Errors::InvalidDependency.into());
+        };
+
+        let data = account.data.borrow();
+        assert_current_version(&data)?;
+        let winning_option = match AccountType::from_discriminator(&data) {
+            Some(AccountType::Vote) if *account.key == self.voting => {
+                let voting_account_data = try_from_slice_unchecked::<VoteMainAccount>(&data)?;
+                if voting_account_data.status != VotingStatus::Finalized {
+                    return Err(Errors::DependencyNotMet.into());
+                };
+
+                voting_account_data.winning_option()
+            },
+            Some(AccountType::VotingArchive) if *account.key == find_voting_archive_pda(&self.voting, program_id).0 => {
+                try_from_slice_unchecked::<VotingArchiveAccount>(&data)?.winning_option
+            },
+            _ => return Err(Errors::InvalidDependency.into())
+        };
+
+        if winning_option != Some(self.option) {
+            return Err(Errors::DependencyNotMet.into());
+        };
+
+        Ok(())
+    }
+}
+
+// What the turnout of a voting is measured against, set by the creator at creation, e.g. a snapshot of
+// a token's circulating supply. Quorums are then shares of it rather than of the voters who showed up.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Electorate {
+    // Eligible voters, the turnout being `total_voters`
+    Voters(u64),
+    // Total vote weight, the turnout being the weight cast, see `TallyState::cast_weight`
+    Weight(u128)
+}
+
+impl Electorate {
+    pub(crate) fn validate(electorate: Option<Self>, quorum_bps: u16) -> ProgramResult {
+        if quorum_bps > 10_000
+            || matches!(electorate, Some(Self::Voters(0) | Self::Weight(0)))
+            || (electorate.is_none() && quorum_bps > 0)
+        {
+            return Err(Errors::InvalidElectorate.into());
+        };
+
+        Ok(())
+    }
+}
+
+// Turnouts a voting announces, counted in voters whatever the weight source. Notification bots watch the
+// program logs for the events instead of diffing tallies.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TurnoutMilestones {
+    // Voters the percentages are measured against
+    pub electorate: u64,
+    // Ascending percentages of the electorate, e.g. 25, 50 and 75
+    pub percents: Vec<u8>,
+    // Voters making a quorum, 0 for none
+    pub quorum: u64
+}
+
+impl TurnoutMilestones {
+    pub(crate) fn validate(&self) -> ProgramResult {
+        if self.percents.len() > MAX_TURNOUT_MILESTONES
+            || self.percents.windows(2).any(|pair| pair[0] >= pair[1])
+            || self.percents.iter().any(|percent| *percent == 0 || *percent > 100)
+            || (self.percents.is_empty() == false && self.electorate == 0)
+            || (self.percents.is_empty() && self.quorum == 0)
+        {
+            return Err(Errors::InvalidTurnoutMilestones.into());
+        };
+
+        Ok(())
+    }
+
+    // Voters needed to reach `percent` of the electorate, rounded up
+    pub fn voters_at(&self, percent: u8) -> u64 {
+        (self.electorate as u128 * percent as u128).div_ceil(100) as u64
+    }
+}
+
+// K of N named attestors co-signing a finalized result, for outcomes that trigger legal or treasury
+// actions off-chain. Until `threshold` of them called `attest_result`, the voting doesn't count as
+// closed, see `VoteMainAccount::check_closed`.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResultAttestors {
+    pub attestors: Vec<Pubkey>,
+    pub threshold: u8,
+    // Bit i is set once `attestors[i]` attested
+    pub attested: u8
+}
+
+impl ResultAttestors {
+    pub(crate) fn validate(&self) -> ProgramResult {
+        if self.attestors.is_empty()
+            || self.attestors.len() > MAX_RESULT_ATTESTORS
+            || self.attestors.iter().enumerate().any(|(index, attestor)| self.attestors[..index].contains(attestor))
+            || self.threshold == 0
+            || self.threshold as usize > self.attestors.len()
+            || self.attested != 0
+        {
+            return Err(Errors::InvalidResultAttestors.into());
+        };
+
+        Ok(())
+    }
+
+    pub fn attested_count(&self) -> u8 {
+        self.attested.count_ones() as u8
+    }
+
+    pub fn is_met(&self) -> bool {
+        self.attested_count() >= self.threshold
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TurnoutMilestone {
+    Percent(u8),
+    Quorum
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VotingStatus {
+    Active,
+    Cancelled,
+    Finalized,
+    // Being assembled by the creator, see `publish_voting`
+    Draft,
+    // Open votings before `starts_at` and after `ends_at`. Only kept in line with the clock for indexers,
+    // see `sync_status`, the checks go by the times.
+    Pending,
+    Ended
+}
+
+// A title in a fixed `MAX_TITLE_BYTES` field after its length in bytes, so the fields before a voting's
+// description sit at fixed offsets and titles are never re-encoded when they change
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq)]
+pub struct VotingTitle {
+    pub len: u8,
+    pub bytes: [u8; MAX_TITLE_BYTES]
+}
+
+impl VotingTitle {
+    pub fn new(title: &str) -> Result<Self, ProgramError> {
+        if title.len() > MAX_TITLE_BYTES {
+            return Err(Errors::TitleTooLong.into());
+        };
+
+        let mut bytes = [0; MAX_TITLE_BYTES];
+        bytes[..title.len()].copy_from_slice(title.as_bytes());
+
+        Ok(Self { len: title.len() as u8, bytes })
+    }
+
+    // Empty for a length past the field or bytes that aren't UTF-8, neither of which `new` writes
+    pub fn as_str(&self) -> &str {
+        self.bytes.get(..self.len as usize)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for VotingTitle {
+    fn default() -> Self {
+        Self { len: 0, bytes: [0; MAX_TITLE_BYTES] }
+    }
+}
+
+impl std::fmt::Display for VotingTitle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Debug for VotingTitle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VotingTitle {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+// Commitment to the voters eligible for a voting, sealed from `starts_at` until its first ballot so who
+// could vote can be settled against it later. `root` is the merkle root of keccak(voter) leaves, as built
+// by `client::AllowlistTree`, over the voters the creator exported from the allowlist, realm or policy.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoterRoll {
+    pub root: [u8; 32],
+    pub voters_count: u64,
+    pub sealed_at: u64
+}
+
+impl VoterRoll {
+    pub(crate) fn check(&self, voter: &Pubkey, proof: &[[u8; 32]]) -> ProgramResult {
+        if verify_merkle_proof(hash(voter.as_ref()).0, proof, &self.root) == false {
+            return Err(Errors::NotOnVoterRoll.into());
+        };
+
+        Ok(())
+    }
+}
+
+// How a finalized voting fared against its quorum and pass threshold
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VotingOutcome {
+    Passed,
+    // No option reached the pass threshold, or the voting has no winner
+    Rejected,
+    QuorumNotMet
+}
+
+// Moderation is advisory, hidden votings still take ballots
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ModerationStatus {
+    Visible,
+    // Hidden by the program admin or, for realm votings, the realm admin
+    Hidden
+}
+
+// Groups votings under a community, the admin controls the defaults of its votings
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RealmAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub admin: Pubkey,
+    pub defaults: VotingSettings,
+    // `LOCK_*` bits of the defaults creators can't override
+    pub locked_settings: u8,
+    // Lamports paid to the admin for every voting created in the realm
+    pub creation_fee: u64,
+    // Only the admin can create votings
+    pub creators_gated: bool,
+    pub votings_count: u64,
+    // Kept by `join_realm` / `leave_realm`, quorums of realm votings are measured against it
+    pub members_count: u64,
+    pub name: String
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MembershipAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub realm: Pubkey,
+    pub member: Pubkey,
+    pub joined_at: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UserVotingAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub payer: Pubkey,
+    pub first_voted_at: u64,
+    pub last_time_voted: u64,
+    pub times_updated: u16,
+    // First option of the ballot, `SPOILED_CHOICE` for a spoiled one
+    pub choice: u8,
+    // Resolved on the first vote and kept when the vote is updated. Capped votings credit the rest with
+    // `add_weight`.
+    pub weight: u64,
+    // Slot the weight was last credited in
+    pub credited_slot: u64,
+    // First ticket of the voter in the voting's random draw, see `RandomDraw`
+    pub draw_offset: u64,
+    // Position of the ballot among the voting's ballots, from `VoteMainAccount::total_voters`. `u64::MAX`
+    // until a committed ballot is revealed.
+    pub vote_index: u64,
+    // Hash of the committed ballot until it is revealed, see `vote_commitment`
+    pub commitment: Option<[u8; 32]>,
+    pub ballot: Vec<u8>,
+    pub voted_to: VotingTitle
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TallySnapshot {
+    pub taken_at: u64,
+    pub tallies: Vec<u64>,
+    // High 64 bits of the tallies of a large-supply voting, empty otherwise
+    pub high: Vec<u64>
+}
+
+// Ring of the last `MAX_TALLY_SNAPSHOTS` snapshots of a voting, `next_index` is overwritten next
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TallySnapshotAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub last_taken_at: u64,
+    pub next_index: u8,
+    pub snapshots: Vec<TallySnapshot>
+}
+
+// Lamports funded by the creator, split evenly between the voters of the winning option
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RewardEscrowAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub total_funded: u64,
+    pub total_claimed: u64,
+    // Set while `fund_rewards` transfers into the escrow, see `Latched`
+    pub in_progress: bool
+}
+
+// Lamports funded by the creator to pay every new voter `VoteMainAccount::incentive_per_vote`. Anyone
+// passing the voting's gates gets paid, so open votings are only as sybil resistant as their gating.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IncentivePoolAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub total_funded: u64,
+    pub total_paid: u64,
+    // Set while `fund_incentives` transfers into the pool, see `Latched`
+    pub in_progress: bool
+}
+
+// Created on `claim_winnings`, so a voter can only claim once
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RewardClaimAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub amount: u64
+}
+
+// Holds the boost lamports of a voting, `amount` sits at `BOOST_AMOUNT_OFFSET` so votings can be ranked by it
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BoostAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub amount: u64,
+    // Set once the boost went to the treasury
+    pub released: bool,
+    // Set while `boost` transfers into the account, see `Latched`
+    pub in_progress: bool
+}
+
+// Lamports a single booster deposited, refunded if the voting is cancelled
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BoostDepositAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub amount: u64
+}
+
+// A page of the votings the voter cast a ballot on, in voting order. The header is followed by raw 32 byte
+// voting keys, each first vote appends one without re-encoding the page, see `voter_index_entries`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoterIndexAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voter: Pubkey,
+    pub page: u16,
+    // The next page once this one holds `MAX_VOTER_INDEX_PAGE_ENTRIES`, `Pubkey::default()` until then
+    pub next: Pubkey
+}
+
+// Created on the creator's first voting. Votings count as active until they're finalized. The rest
+// feeds `get_creator_summary`, turnout and flags are added up as each voting is finalized.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CreatorStatsAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub creator: Pubkey,
+    pub active_votings: u32,
+    pub created_votings: u32,
+    pub finalized_votings: u32,
+    // Restoring a voting takes it off again
+    pub cancelled_votings: u32,
+    pub total_voters: u64,
+    pub flags: u32
+}
+
+// One per claimed slot of a signup voting, `slot` is the claim order
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlotAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub claimant: Pubkey,
+    pub slot: u32,
+    pub claimed_at: u64
+}
+
+// Keyed by claimant, so a voter claims at most one slot
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlotClaimAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub slot: u32
+}
+
+// Programs and instructions passed proposals of a realm may execute, maintained by the realm admin
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExecutionAllowlistAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub realm: Pubkey,
+    pub actions: Vec<AllowedAction>
+}
+
+// A recurring action of a realm (e.g. a grant payout) with placeholders its votings fill in, so the
+// payload is reviewed once and each voting only picks the amount or recipient within the admin's bounds
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProposalTemplateAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub realm: Pubkey,
+    pub id: u16,
+    pub instruction: ProposalInstruction,
+    pub params: Vec<TemplateParam>
+}
+
+// Instructions a realm voting executes in order, one per `execute_next_instruction`, once `option` won
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProposalAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub option: u8,
+    // Execution cursor, index of the next instruction to execute
+    pub next_instruction: u16,
+    // Set for the duration of an instruction's CPI, see `Latched`
+    pub in_progress: bool,
+    pub instructions: Vec<ProposalInstruction>
+}
+
+// Accounts a handler writes around a CPI: their state is written first, then the latch is held for the
+// duration of the call. The runtime only lets the program be re-entered by itself, through a proposal
+// instruction, and an instruction reaching a latched account from inside the call is rejected, so it
+// can neither act on the state being written nor have its own changes overwritten once the call returns.
+// The other CPIs create accounts, mint certificates or log memos and leave no program state half written.
+pub trait Latched: BorshSerialize {
+    const IN_PROGRESS: Errors;
+
+    fn in_progress(&self) -> bool;
+
+    fn set_in_progress(&mut self, in_progress: bool);
+
+    fn check_not_in_progress(&self) -> ProgramResult {
+        if self.in_progress() {
+            return Err(Self::IN_PROGRESS.into());
+        };
+
+        Ok(())
+    }
+}
+
+impl Latched for ProposalAccount {
+    const IN_PROGRESS: Errors = Errors::ProposalInProgress;
+
+    fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    fn set_in_progress(&mut self, in_progress: bool) {
+        self.in_progress = in_progress;
+    }
+}
+
+impl Latched for RewardEscrowAccount {
+    const IN_PROGRESS: Errors = Errors::AccountInProgress;
+
+    fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    fn set_in_progress(&mut self, in_progress: bool) {
+        self.in_progress = in_progress;
+    }
+}
+
+impl Latched for IncentivePoolAccount {
+    const IN_PROGRESS: Errors = Errors::AccountInProgress;
+
+    fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    fn set_in_progress(&mut self, in_progress: bool) {
+        self.in_progress = in_progress;
+    }
+}
+
+impl Latched for BoostAccount {
+    const IN_PROGRESS: Errors = Errors::AccountInProgress;
+
+    fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    fn set_in_progress(&mut self, in_progress: bool) {
+        self.in_progress = in_progress;
+    }
+}
+
+// Token ballots of a voting, for voters who can only transfer tokens. Ballot tokens sent to the token
+// account of an option's ballot address are counted into the tallies by `count_token_ballots`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BallotBoxAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub mint: Pubkey,
+    // Ballot tokens already counted per option
+    pub counted: Vec<u64>
+}
+
+// Shadow tallies rebuilt from the user vote accounts, swapped into the voting once every voter is counted
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecountAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    // Records are counted in ascending key order, so none is counted twice
+    pub last_record: Pubkey,
+    pub records_counted: u64,
+    pub tallies: Vec<u64>,
+    pub tally_state: TallyState
+}
+
+// Display hints written by the creator, so frontends show the voting times the same way everywhere. The
+// program doesn't interpret them, `starts_at` and `ends_at` stay the source of truth.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingMetadataAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    // IANA name the creator announced the voting in, e.g. "Europe/Berlin"
+    pub timezone: String,
+    // Human readable schedule, e.g. "Mon 9:00 - Fri 17:00"
+    pub schedule: String
+}
+
+// Title and description of a voting in another language, written by the creator. The voting's PDA stays
+// keyed on its canonical title, frontends pick the translation matching the reader's language.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocalizedMetadataAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    // Lowercase BCP 47 tag, e.g. "de" or "pt-br"
+    pub language: String,
+    pub title: String,
+    pub description: String
+}
+
+// Where the creator wants to hear about a voting, read by off-chain notifier services. They find every
+// target with `getProgramAccounts` on its discriminator and follow the voting's events. The program
+// never calls out, it only keeps the anchor.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NotificationTargetAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    // Hash of the webhook URL and a secret agreed with the notifier, so the URL itself stays off-chain
+    pub webhook_commitment: Option<[u8; 32]>,
+    // Wallet or inbox the notifier delivers to, e.g. a Dialect or Notifi address
+    pub notify: Option<Pubkey>,
+    pub updated_at: u64
+}
+
+// Voters of a `Gate::UploadedAllowlist`, uploaded by the creator in chunks or set as a merkle root before
+// the voting starts. Ballots are only accepted once it's sealed, so the list can't change under the voters,
+// apart from the additions of a late registration window. Voters are never removed.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoterAllowlistAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    // Voter `n` is on page `n / MAX_ALLOWLIST_PAGE_VOTERS`
+    pub voters_count: u32,
+    pub sealed: bool,
+    // Set by `set_allowlist_root` instead of uploading pages, leaves are keccak(voter) as for `Gate::Allowlist`
+    pub root: Option<[u8; 32]>,
+    // Percentage of the voting window, from `starts_at`, during which the sealed pages still take chunks.
+    // 0 closes the allowlist at `starts_at`, see `set_late_registration`.
+    pub late_registration: u8,
+    // Chunks appended after `starts_at`, in upload order
+    pub late_chunks: Vec<LateAllowlistChunk>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LateAllowlistChunk {
+    // Position of the chunk's first voter in upload order, the chunk ends where the next one starts
+    pub first_voter: u32,
+    pub added_at: u64
+}
+
+// Filled in upload order, a page is full before the next one is created
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoterAllowlistPageAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub page: u16,
+    pub voters: Vec<Pubkey>
+}
+
+// Records of up to `BUCKET_SLOTS` voters of a voting with `record_buckets`, a voter's bucket is picked by
+// `bucket_of`. Allocated at full size up front, the payer covers the rent of every slot, so each ballot
+// only adds its slot's share. Rewards, certificates, random draws, recounts and `get_my_vote` read user vote
+// accounts, so bucketed votings don't get them.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoteBucketAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub index: u8,
+    pub slots: Vec<RecordSlot>
+}
+
+// Counts of the ballots of a sharded voting whose voters `tally::shard_of` puts in shard `index`. Voters
+// pay for its growth. `merge_tally_shards` adds them to the voting once it ended, and the voting's vote
+// chain then commits to the shards' chains.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TallyShardAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub index: u8,
+    pub voters: u64,
+    // Ballots swapped by voters already on record
+    pub updates: u64,
+    pub vote_chain: [u8; 32],
+    // Empty until the first ballot, like the voting's tally state
+    pub tallies: Vec<u64>,
+    pub tally_state: TallyState
+}
+
+// Maps a normalized title to the first voting created with it, realm votings included, so the voting can
+// be found from its title alone. Later votings with the same title leave it as is, integrations can tell
+// them apart from the original by comparing `voting`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TitleIndexAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey
+}
+
+// Holds a title for its reserver until `expires_at`, so announcing a voting doesn't let others create it
+// first. Consumed when the reserver creates a voting with the title, expired ones can be reserved again.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TitleReservationAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub reserver: Pubkey,
+    pub expires_at: u64
+}
+
+// Sealed ballot of an envelope voting, at the voter's user vote PDA. `envelope` is the ballot encrypted
+// to `VoteMainAccount::envelope_key` as an X25519 sealed box (libsodium `crypto_box_seal`).
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EnvelopeAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub cast_at: u64,
+    pub vote_index: u64,
+    pub envelope: Vec<u8>
+}
+
+// Outcome of an archived voting. The voting account itself is shrunk to its `ArchivedVoting` discriminator,
+// which keeps the title taken. `vote_chain` still lets anyone check a set of vote records against it, see
+// `chain_vote_hash`, and `records_root` a single record if the voting was merkleized.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingArchiveAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub creator: Pubkey,
+    pub title: String,
+    pub winning_option: Option<u8>,
+    pub tallies: Vec<u128>,
+    pub total_voters: u64,
+    pub vote_chain: [u8; 32],
+    pub records_root: Option<[u8; 32]>,
+    pub archived_at: u64
+}
+
+// Several votings decided together, e.g. the seats of a board. `finalize_election` records every race's
+// winner once all of them closed, so clients read the whole outcome from one account.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ElectionAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub creator: Pubkey,
+    pub id: u64,
+    pub name: String,
+    // 0 until finalized
+    pub finalized_at: u64,
+    pub races: Vec<ElectionRace>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ElectionRace {
+    pub voting: Pubkey,
+    // Filled in by `finalize_election`, `None` for cancelled votings and votings without a winner
+    pub winning_option: Option<u8>,
+    // The winning option's label, kept so the outcome outlives the voting
+    pub winner: String,
+    pub total_voters: u64
+}
+
+// Merkleization of a finalized voting's records in progress, closed once the root is in the voting
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecordTreeAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub tree: RecordTree
+}
+
+// A spam report, one per flagger and voting. Holds `FLAG_DEPOSIT` on top of its rent until the flagger
+// closes it after the voting is closed, so mass flagging ties up funds.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FlagAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub flagger: Pubkey,
+    pub flagged_at: u64
+}
+
+// Summary of a voting for list views. Its layout is fixed, so the cards of every voting come in one cheap
+// `getProgramAccounts` call, see `scanner::scan_accounts`. `sync_poll_card` creates it and refreshes it
+// after the changes it misses: recounts, envelope results, shard merges, added weight and option edits.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PollCardAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub voting: Pubkey,
+    pub status: VotingStatus,
+    pub ends_at: u64,
+    // `NO_LEADING_OPTION` while no option leads or the tallies are hidden
+    pub leading_option: u8,
+    pub total_voters: u64,
+    pub updated_at: u64
+}
+
+impl PollCardAccount {
+    pub(crate) fn new(voting: &Pubkey, voting_account_data: &VoteMainAccount, current_time: u64) -> Self {
+        Self {
+            discriminator: AccountType::PollCard.discriminator(),
+            version: StateVersion::CURRENT as u8,
+            voting: *voting,
+            status: voting_account_data.status,
+            ends_at: voting_account_data.ends_at,
+            leading_option: voting_account_data.leading_option(current_time).unwrap_or(NO_LEADING_OPTION),
+            total_voters: voting_account_data.total_voters,
+            updated_at: current_time
+        }
+    }
+}
+
+// Last `claim_faucet` of a wallet, see `FAUCET_COOLDOWN`
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FaucetClaimAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub last_claimed_at: u64,
+    pub claims: u32
+}
+
+// Root of the voting registry, counting the votings `create_voting` and `create_realm_voting` appended to
+// its pages. Entry `n` is in page `n / MAX_REGISTRY_PAGE_ENTRIES`, so wallets page through every voting
+// without a program scan.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingRegistryAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub votings_count: u64
+}
+
+// A page of the voting registry. The header is followed by raw `RegistryEntry` records, each new voting
+// appends one without re-encoding the page, see `registry_entries`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VotingRegistryPageAccount {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub page: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegistryEntry {
+    pub voting: Pubkey,
+    // `voting_title_seed` of the title
+    pub title_hash: [u8; 32],
+    // As created, extensions don't update it
+    pub ends_at: u64
+}
+
+// Limits enforced on creation, exported so clients can validate before sending
+pub const MAX_VOTING_TIME: u32 = 1_209_600; // 2 weeks
+pub const MAX_DESCRIPTION_LENGTH: usize = 256;
+
+pub const MIN_OPTIONS: usize = 2;
+pub const MAX_OPTIONS: usize = 10;
+// `UserVotingAccount::choice` of a spoiled ballot, which lists no option
+pub const SPOILED_CHOICE: u8 = u8::MAX;
+// `PollCardAccount::leading_option` while no option leads
+pub const NO_LEADING_OPTION: u8 = u8::MAX;
+
+pub const MAX_CREATORS: usize = 5;
+pub const MAX_REALM_NAME_LENGTH: usize = 32;
+
+pub const MAX_OPTION_LENGTH: usize = 32;
+// Display hints of the voting metadata, in bytes
+pub const MAX_TIMEZONE_LENGTH: usize = 64;
+
+pub const MAX_SCHEDULE_LENGTH: usize = 200;
+// Language tags of the localized metadata, in bytes
+pub const MAX_LANGUAGE_LENGTH: usize = 16;
+
+// Voters per allowlist page and per `append_allowlist_chunk`, the chunk keeps the transaction under its size limit
+pub const MAX_ALLOWLIST_PAGE_VOTERS: usize = 256;
+pub const MAX_ALLOWLIST_CHUNK: usize = 25;
+
+// Votings per voter index page
+pub const MAX_VOTER_INDEX_PAGE_ENTRIES: usize = 128;
+// A sealed box adds an ephemeral public key and a MAC to the ballot
+pub const ENVELOPE_OVERHEAD: usize = 32 + 16;
+
+pub const MAX_ENVELOPE_LENGTH: usize = ENVELOPE_OVERHEAD + MAX_OPTIONS;
+// Title lengths are in chars, and their UTF-8 bytes have to fit the fixed `VotingTitle` field
+pub const MIN_TITLE_LENGTH: usize = 10;
+
+pub const MAX_TITLE_LENGTH: usize = 50;
+pub const MAX_TITLE_BYTES: usize = 128;
+pub const VOTING_TITLE_SPACE: usize = 1 + MAX_TITLE_BYTES;
+
+pub const MAX_TURNOUT_MILESTONES: usize = 4;
+// `ResultAttestors::attested` has a bit per attestor
+pub const MAX_RESULT_ATTESTORS: usize = 8;
+
+pub const MAX_ELECTION_RACES: usize = 16;
+pub const MAX_ELECTION_NAME_LENGTH: usize = 32;
+
+// Constants
+pub const MAX_TALLY_SNAPSHOTS: usize = 24;
+pub const TALLY_SNAPSHOT_INTERVAL: u64 = 3_600; // 1 hour
+
+// Start time tolerance without a config, and the most the admin can set
+pub const DEFAULT_START_TIME_TOLERANCE: u64 = 30;
+pub const MAX_START_TIME_TOLERANCE: u64 = 300; // 5 minutes
+
+pub const TITLE_RESERVATION_TIME: u64 = 900; // 15 minutes
+pub const ARCHIVE_DELAY: u64 = 2_592_000; // 30 days
+
+pub const MAX_REVEAL_WINDOW: u64 = 604_800; // 7 days
+pub const MIN_DEPOSIT_REFUND_WINDOW: u64 = 86_400; // 1 day
+
+pub const GC_DELAY: u64 = 15_552_000; // 180 days
+// Share of a swept account's lamports going to the caller, in basis points
+pub const GC_BOUNTY_BPS: u64 = 500;
+
+pub const FLAG_DEPOSIT: u64 = 10_000_000; // 0.01 SOL
+
+// Account sizes. Every string and vector is capped, so sizes are computed from the caps and
+// lengths past them are rejected instead of overflowing.
+pub const CONFIG_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 16 + 4 + 8 + (1 + 32) + 8 + 8;
+pub const USER_VOTE_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 8 + 2 + 1 + 8 + 8 + 8 + 8 + (1 + 32) + (4 + MAX_OPTIONS) + VOTING_TITLE_SPACE;
+
+pub const REWARD_ESCROW_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 8 + 1;
+pub const INCENTIVE_POOL_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 8 + 1;
+
+pub const TURNOUT_MILESTONES_SPACE: usize = 1 + 8 + (4 + MAX_TURNOUT_MILESTONES) + 8;
+pub const ELECTORATE_SPACE: usize = 1 + 16;
+// Before the first ballot
+pub const TALLY_STATE_SPACE: usize = 4 + 4 + 1 + 4 + 8 + 16 + 16;
+pub const RESULT_ATTESTORS_SPACE: usize = 1 + (4 + MAX_RESULT_ATTESTORS * 32) + 1 + 1;
+
+pub const REWARD_CLAIM_ACCOUNT_SPACE: usize = 8 + 1 + 8;
+pub const REALM_ACCOUNT_SPACE: usize = 8 + 1 + 32 + (8 + 8 + 1 + 1 + 1) + 1 + 8 + 1 + 8 + 8 + (4 + MAX_REALM_NAME_LENGTH);
+
+pub const MEMBERSHIP_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 32 + 8;
+pub const BOOST_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 1 + 1;
+
+pub const BOOST_DEPOSIT_ACCOUNT_SPACE: usize = 8 + 1 + 8;
+pub const SLOT_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 32 + 4 + 8;
+
+pub const SLOT_CLAIM_ACCOUNT_SPACE: usize = 8 + 1 + 4;
+pub const CREATOR_STATS_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 4 + 4 + 4 + 4 + 8 + 4;
+
+pub const VOTER_ALLOWLIST_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 4 + 1 + (1 + 32) + 1 + 4;
+pub const VOTER_ROLL_SPACE: usize = 32 + 8 + 8;
+pub const VOTER_INDEX_HEADER_SPACE: usize = 8 + 1 + 32 + 2 + 32;
+
+pub const TITLE_INDEX_ACCOUNT_SPACE: usize = 8 + 1 + 32;
+pub const TITLE_RESERVATION_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8;
+
+pub const ENVELOPE_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 8 + 8 + (4 + MAX_ENVELOPE_LENGTH);
+pub const VOTE_BUCKET_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 1 + (4 + BUCKET_SLOTS * RECORD_SLOT_SPACE);
+
+// Before the first ballot, the shard grows with it
+pub const TALLY_SHARD_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 1 + 8 + 8 + 32 + 4 + TALLY_STATE_SPACE;
+pub const FAUCET_CLAIM_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + 4;
+
+// A wallet claims from the faucet at most once a day
+pub const FAUCET_COOLDOWN: u64 = 24 * 60 * 60;
+// On top of the rent a claim pays, for the fees of a few transactions and the growth of the voting
+pub const FAUCET_FEE_ALLOWANCE: u64 = 100_000;
+
+pub const ELECTION_RACE_SPACE: usize = 32 + (1 + 1) + (4 + MAX_OPTION_LENGTH) + 8;
+pub const ELECTION_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 8 + (4 + MAX_ELECTION_NAME_LENGTH) + 8 + (4 + MAX_ELECTION_RACES * ELECTION_RACE_SPACE);
+pub const POLL_CARD_ACCOUNT_SPACE: usize = 8 + 1 + 32 + 1 + 8 + 1 + 8 + 8;
+pub const VOTING_REGISTRY_ACCOUNT_SPACE: usize = 8 + 1 + 8;
+pub const REGISTRY_PAGE_HEADER_SPACE: usize = 8 + 1 + 4;
+pub const REGISTRY_ENTRY_SPACE: usize = 32 + 32 + 8;
+pub const MAX_REGISTRY_PAGE_ENTRIES: u64 = 128;
+pub const NOTIFICATION_TARGET_ACCOUNT_SPACE: usize = 8 + 1 + 32 + (1 + 32) + (1 + 32) + 8;
+
+// `options_len` is the summed byte length of the options
+pub const fn voting_account_space(
+    description_len: usize,
+    options_len: usize,
+    options_count: usize,
+    co_creators_count: usize
+) -> Option<usize> {
+    if description_len > MAX_DESCRIPTION_LENGTH
+        || options_count > MAX_OPTIONS
+        || options_len > MAX_OPTIONS * MAX_OPTION_LENGTH
+        || co_creators_count >= MAX_CREATORS
+    {
+        return None;
+    };
+
+    let fixed = 8 + 1 + 32 + 32 + 8 + 8 + 1 + 32 + 8 + 1 + 1 + VOTING_TITLE_SPACE + 1 + WEIGHT_SOURCE_SPACE + (4 + MAX_WEIGHT_MINTS * MINT_RATIO_SPACE) + (1 + 1) + TALLY_MODE_SPACE + ELIGIBILITY_POLICY_SPACE + 4 + 4 + RANDOM_DRAW_SPACE + ORACLE_CONDITION_SPACE + 1 + 1 + 1 + 1 + 1 + (1 + 32) + 8 + (1 + 32) + 1 + (RANDOM_DRAW_SPACE - 1) + (1 + 32 + 1) + (1 + 32) + (1 + 32) + 4 + 1 + 8 + TURNOUT_MILESTONES_SPACE + 1 + 8 + 8 + RESULT_ATTESTORS_SPACE + 1 + 1 + 8 + 1 + (1 + 32) + 8 + 8 + 1 + (1 + APPORTIONMENT_SPACE) + (1 + VOTER_ROLL_SPACE) + (1 + 8) + 4 + 2 + (1 + ELECTORATE_SPACE) + 2 + (1 + 1) + (1 + 1) + TALLY_STATE_SPACE;
+    let variable = (4 + description_len) + (4 + 4 * options_count + options_len) + (4 + 8 * options_count) + 8 + 32 + (4 + 8 * options_count) + (4 + 32 * co_creators_count) + (4 + 8 * options_count) + (4 + options_count) + (4 + 2 * options_count);
+
+    fixed.checked_add(variable)
+}
+
+pub const fn voter_index_account_space(votings_count: usize) -> usize {
+    VOTER_INDEX_HEADER_SPACE + 32 * votings_count
+}
+
+// Voting keys of a voter index page, `data` being the whole account
+pub fn voter_index_entries(data: &[u8]) -> Vec<Pubkey> {
+    data.get(VOTER_INDEX_HEADER_SPACE..)
+        .unwrap_or_default()
+        .chunks_exact(32)
+        .map(|entry| Pubkey::new_from_array(entry.try_into().unwrap()))
+        .collect()
+}
+
+pub const fn registry_page_account_space(entries: usize) -> usize {
+    REGISTRY_PAGE_HEADER_SPACE + REGISTRY_ENTRY_SPACE * entries
+}
+
+// Entries of a registry page, in creation order
+pub fn registry_entries(data: &[u8]) -> Vec<RegistryEntry> {
+    data.get(REGISTRY_PAGE_HEADER_SPACE..)
+        .unwrap_or_default()
+        .chunks_exact(REGISTRY_ENTRY_SPACE)
+        .map(|entry| RegistryEntry::try_from_slice(entry).unwrap())
+        .collect()
+}
+
+// Lamports a `claim_faucet` pays: the wallet's own rent exemption, and the rent of the vote record and
+// voter index a first vote creates
+pub fn faucet_drip(rent: &rent::Rent) -> u64 {
+    rent.minimum_balance(0)
+        + rent.minimum_balance(USER_VOTE_ACCOUNT_SPACE)
+        + rent.minimum_balance(voter_index_account_space(1))
+        + FAUCET_FEE_ALLOWANCE
+}
+
+pub const fn tally_snapshot_account_space(options_count: usize) -> Option<usize> {
+    if options_count > MAX_OPTIONS {
+        return None;
+    };
+
+    match MAX_TALLY_SNAPSHOTS.checked_mul(8 + (4 + 8 * options_count) + (4 + 8 * options_count)) {
+        Some(snapshots) => (8 + 1 + 32 + 8 + 1 + 4usize).checked_add(snapshots),
+        None => None
+    }
+}
+
+// Byte offset of `VoteMainAccount::realm`, for listing the votings of a realm with a memcmp filter
+pub const VOTING_REALM_OFFSET: usize = 90;
+
+// Byte offset of `BoostAccount::amount`, for ranking votings by boost
+pub const BOOST_AMOUNT_OFFSET: usize = 41;
+
+// Byte offsets of `UserVotingAccount` fields, for `getProgramAccounts` memcmp filters
+pub const USER_VOTE_PAYER_OFFSET: usize = 9;
+pub const USER_VOTE_FIRST_VOTED_AT_OFFSET: usize = 41;
+
+pub const USER_VOTE_LAST_TIME_VOTED_OFFSET: usize = 49;
+pub const USER_VOTE_TIMES_UPDATED_OFFSET: usize = 57;
+
+// Seeds
+pub const VOTING_SEED: &[u8] = b"voting_account";
+pub const USER_VOTE_SEED: &[u8] = b"user_vote";
+
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const TALLY_SNAPSHOT_SEED: &[u8] = b"tally_snapshot";
+
+pub const CERTIFICATE_MINT_SEED: &[u8] = b"certificate_mint";
+pub const CERTIFICATE_SEED: &[u8] = b"certificate";
+
+pub const REWARD_ESCROW_SEED: &[u8] = b"reward_escrow";
+pub const REWARD_CLAIM_SEED: &[u8] = b"reward_claim";
+
+pub const REALM_SEED: &[u8] = b"realm";
+pub const MEMBERSHIP_SEED: &[u8] = b"membership";
+
+pub const BOOST_SEED: &[u8] = b"boost";
+pub const BOOST_DEPOSIT_SEED: &[u8] = b"boost_deposit";
+
+pub const VOTER_INDEX_SEED: &[u8] = b"voter_index";
+pub const SLOT_SEED: &[u8] = b"slot";
+
+pub const SLOT_CLAIM_SEED: &[u8] = b"slot_claim";
+pub const EXECUTION_ALLOWLIST_SEED: &[u8] = b"execution_allowlist";
+
+pub const PROPOSAL_TEMPLATE_SEED: &[u8] = b"proposal_template";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const BALLOT_BOX_SEED: &[u8] = b"ballot_box";
+
+pub const BALLOT_ADDRESS_SEED: &[u8] = b"ballot_address";
+pub const RECOUNT_SEED: &[u8] = b"recount";
+
+pub const CREATOR_STATS_SEED: &[u8] = b"creator_stats";
+pub const VOTING_METADATA_SEED: &[u8] = b"voting_metadata";
+
+pub const VOTER_ALLOWLIST_SEED: &[u8] = b"voter_allowlist";
+pub const VOTER_ALLOWLIST_PAGE_SEED: &[u8] = b"voter_allowlist_page";
+
+pub const VOTE_BUCKET_SEED: &[u8] = b"vote_bucket";
+pub const TALLY_SHARD_SEED: &[u8] = b"tally_shard";
+
+pub const KIOSK_SEED: &[u8] = b"kiosk";
+pub const FAUCET_SEED: &[u8] = b"faucet";
+
+pub const FAUCET_CLAIM_SEED: &[u8] = b"faucet_claim";
+pub const ELECTION_SEED: &[u8] = b"election";
+pub const POLL_CARD_SEED: &[u8] = b"poll_card";
+pub const VOTING_REGISTRY_SEED: &[u8] = b"voting_registry";
+pub const VOTING_REGISTRY_PAGE_SEED: &[u8] = b"voting_registry_page";
+pub const NOTIFICATION_TARGET_SEED: &[u8] = b"notification_target";
+pub const TITLE_INDEX_SEED: &[u8] = b"title_index";
+pub const TITLE_RESERVATION_SEED: &[u8] = b"title_reservation";
+
+pub const VOTING_ARCHIVE_SEED: &[u8] = b"voting_archive";
+pub const RECORD_TREE_SEED: &[u8] = b"record_tree";
+
+pub const FLAG_SEED: &[u8] = b"flag";
+pub const INCENTIVE_POOL_SEED: &[u8] = b"incentive_pool";
+
+pub const LOCALIZED_METADATA_SEED: &[u8] = b"localized_metadata";
+
+// NFC normalized, lowercased and with whitespace runs collapsed, so "Budget Vote" and "budget  vote" map to
+// the same voting, and so does "Café" typed with a combining accent
+pub fn normalize_title(title: &str) -> String {
+    title.nfc().collect::<String>().split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join(" ")
+}
+
+// Next link of a voting's vote chain. Replaying the votes and updates of a voting in transaction order
+// from a zero hash has to end at its `vote_chain`, so a dropped or altered vote shows up.
+pub fn chain_vote_hash(previous: &[u8; 32], voter: &Pubkey, ballot: &[u8], weight: u64, timestamp: u64) -> [u8; 32] {
+    hashv(&[
+        previous,
+        voter.as_ref(),
+        ballot,
+        &weight.to_le_bytes(),
+        &timestamp.to_le_bytes()
+    ]).to_bytes()
+}
+
+// What `commit_vote` stores, `salt` keeps the few possible ballots from being guessed off the hash
+pub fn vote_commitment(ballot: &[u8], salt: &[u8; 32], voter: &Pubkey) -> [u8; 32] {
+    hashv(&[
+        ballot,
+        salt,
+        voter.as_ref()
+    ]).to_bytes()
+}
+
+// Titles may be longer than the 32 byte seed limit, so votings are keyed by the normalized title's hash
+pub fn voting_title_seed(title: &str) -> [u8; 32] {
+    hash(normalize_title(title).as_bytes()).to_bytes()
+}
+
+pub fn find_voting_pda(title: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTING_SEED,
+            voting_title_seed(title).as_ref()
+        ],
+        program_id
+    )
+}
+
+// The voting account of `title` for clients that don't need the bump
+pub fn find_voting_address(title: &str, program_id: &Pubkey) -> Pubkey {
+    find_voting_pda(title, program_id).0
+}
+
+pub fn find_voting_archive_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTING_ARCHIVE_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_record_tree_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RECORD_TREE_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_flag_pda(voting: &Pubkey, flagger: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FLAG_SEED,
+            voting.as_ref(),
+            flagger.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_incentive_pool_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            INCENTIVE_POOL_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_localized_metadata_pda(voting: &Pubkey, language: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LOCALIZED_METADATA_SEED,
+            voting.as_ref(),
+            language.as_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_title_index_pda(title: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TITLE_INDEX_SEED,
+            voting_title_seed(title).as_ref()
+        ],
+        program_id
+    )
+}
+
+// Reservations are keyed by `voting_title_seed`, so the title stays private until the voting is created
+pub fn find_title_reservation_pda(title_hash: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TITLE_RESERVATION_SEED,
+            title_hash.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_user_vote_pda(voting: &Pubkey, user: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            USER_VOTE_SEED,
+            voting.as_ref(),
+            user.as_ref()
+        ],
+        program_id
+    )
+}
+
+// The account holding `user`'s ballot in `voting`, for clients that don't need the bump
+pub fn find_user_vote_address(voting: &Pubkey, user: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    find_user_vote_pda(voting, user, program_id).0
+}
+
+pub fn find_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CONFIG_SEED
+        ],
+        program_id
+    )
+}
+
+pub fn find_tally_snapshot_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TALLY_SNAPSHOT_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+// One non-transferable Token-2022 mint per voting, the mint is its own mint authority
+pub fn find_certificate_mint_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CERTIFICATE_MINT_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_certificate_pda(voting: &Pubkey, user: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CERTIFICATE_SEED,
+            voting.as_ref(),
+            user.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_reward_escrow_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REWARD_ESCROW_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_reward_claim_pda(voting: &Pubkey, user: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REWARD_CLAIM_SEED,
+            voting.as_ref(),
+            user.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_realm_pda(name: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REALM_SEED,
+            name.as_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_membership_pda(realm: &Pubkey, member: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MEMBERSHIP_SEED,
+            realm.as_ref(),
+            member.as_ref()
+        ],
+        program_id
+    )
+}
+
+// Realm votings are numbered from 0 in creation order
+pub fn find_realm_voting_pda(realm: &Pubkey, id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTING_SEED,
+            realm.as_ref(),
+            &id.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_boost_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BOOST_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_boost_deposit_pda(voting: &Pubkey, booster: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BOOST_DEPOSIT_SEED,
+            voting.as_ref(),
+            booster.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_voter_index_pda(voter: &Pubkey, page: u16, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTER_INDEX_SEED,
+            voter.as_ref(),
+            &page.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+// Slots are numbered from 0 in claim order, so the claimants can be listed up to `claimed_slots`
+pub fn find_slot_pda(voting: &Pubkey, slot: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SLOT_SEED,
+            voting.as_ref(),
+            &slot.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_slot_claim_pda(voting: &Pubkey, claimant: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SLOT_CLAIM_SEED,
+            voting.as_ref(),
+            claimant.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_execution_allowlist_pda(realm: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            EXECUTION_ALLOWLIST_SEED,
+            realm.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_proposal_template_pda(realm: &Pubkey, id: u16, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PROPOSAL_TEMPLATE_SEED,
+            realm.as_ref(),
+            &id.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_proposal_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PROPOSAL_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+// Signs the instructions of the realm's passed proposals, and holds what they spend
+pub fn find_governance_pda(realm: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            GOVERNANCE_SEED,
+            realm.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_ballot_box_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BALLOT_BOX_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+// Owner of the token account ballot tokens for `option` are sent to, it never signs
+pub fn find_ballot_address(voting: &Pubkey, option: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BALLOT_ADDRESS_SEED,
+            voting.as_ref(),
+            &[ option ]
+        ],
+        program_id
+    )
+}
+
+pub fn find_recount_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RECOUNT_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_creator_stats_pda(creator: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CREATOR_STATS_SEED,
+            creator.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_voting_metadata_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTING_METADATA_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_voter_allowlist_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTER_ALLOWLIST_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_voter_allowlist_page_pda(voting: &Pubkey, page: u16, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTER_ALLOWLIST_PAGE_SEED,
+            voting.as_ref(),
+            &page.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_vote_bucket_pda(voting: &Pubkey, index: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTE_BUCKET_SEED,
+            voting.as_ref(),
+            &[ index ]
+        ],
+        program_id
+    )
+}
+
+pub fn find_tally_shard_pda(voting: &Pubkey, index: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TALLY_SHARD_SEED,
+            voting.as_ref(),
+            &[ index ]
+        ],
+        program_id
+    )
+}
+
+// System account of a voting that QR codes at live events point transfers to, see `vote_by_transfer_claim`
+pub fn find_kiosk_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            KIOSK_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+// A system account, funded with plain transfers
+pub fn find_faucet_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ FAUCET_SEED ], program_id)
+}
+
+pub fn find_faucet_claim_pda(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FAUCET_CLAIM_SEED,
+            wallet.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_election_pda(creator: &Pubkey, id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ELECTION_SEED,
+            creator.as_ref(),
+            &id.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_poll_card_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POLL_CARD_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+
+pub fn find_voting_registry_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ VOTING_REGISTRY_SEED ], program_id)
+}
+
+pub fn find_voting_registry_page_pda(page: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTING_REGISTRY_PAGE_SEED,
+            &page.to_le_bytes()
+        ],
+        program_id
+    )
+}
+
+pub fn find_notification_target_pda(voting: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            NOTIFICATION_TARGET_SEED,
+            voting.as_ref()
+        ],
+        program_id
+    )
+}
+// Metaplex token metadata, read for proposal notes
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+// `Key::MetadataV1`, the first byte of a metadata account, then its update authority and mint
+pub const METADATA_KEY: u8 = 4;
+
+pub const METADATA_UPDATE_AUTHORITY_OFFSET: usize = 1;
+pub const METADATA_MINT_OFFSET: usize = 33;
+
+pub fn find_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref()
+        ],
+        &TOKEN_METADATA_PROGRAM_ID
+    )
+}
+
+// Bits of `RealmAccount::locked_settings`
+pub const LOCK_VOTING_TIME: u8 = 1 << 0;
+pub const LOCK_CREATOR_THRESHOLD: u8 = 1 << 1;
+
+pub const LOCK_ALLOW_UPDATES: u8 = 1 << 2;
+pub const LOCK_MEMBERS_ONLY: u8 = 1 << 3;
+
+// Bits of `VoteMainAccount::init_stage`, set by `add_option`, by sealing the voter allowlist or setting
+// its root, and by `fund_rewards`
+pub const STAGE_OPTIONS: u8 = 1 << 0;
+pub const STAGE_GATING: u8 = 1 << 1;
+
+pub const STAGE_ESCROW: u8 = 1 << 2;
+pub const ALL_STAGES: u8 = STAGE_OPTIONS | STAGE_GATING | STAGE_ESCROW;
+
+// Settings of votings created outside a realm, the creator threshold comes from the instruction
+pub const DEFAULT_VOTING_SETTINGS: VotingSettings = VotingSettings {
+    min_voting_time: 0,
+    max_voting_time: MAX_VOTING_TIME as u64,
+    creator_threshold: 1,
+    allow_updates: true,
+    members_only: false
+};
+
+// Discriminators are the first 8 bytes of keccak("instruction:<name>") / keccak("account:<name>"),
+// stored as the enum values so the compiler rejects two kinds sharing one. New kinds go into `ALL` too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u64)]
+pub enum AccountType {
+    Config = u64::from_le_bytes([155, 231, 25, 33, 17, 201, 5, 146]),
+    Vote = u64::from_le_bytes([20, 241, 215, 118, 48, 90, 178, 191]),
+    UserVoting = u64::from_le_bytes([119, 205, 131, 16, 29, 133, 60, 146]),
+    TallySnapshot = u64::from_le_bytes([247, 192, 186, 246, 72, 223, 209, 244]),
+    RewardEscrow = u64::from_le_bytes([228, 136, 85, 120, 218, 51, 42, 221]),
+    RewardClaim = u64::from_le_bytes([18, 96, 26, 218, 188, 185, 153, 53]),
+    Realm = u64::from_le_bytes([97, 239, 69, 170, 252, 255, 95, 13]),
+    Membership = u64::from_le_bytes([191, 248, 210, 229, 200, 81, 41, 81]),
+    Boost = u64::from_le_bytes([239, 167, 32, 56, 104, 185, 195, 120]),
+    BoostDeposit = u64::from_le_bytes([67, 239, 219, 131, 46, 192, 88, 14]),
+    VoterIndex = u64::from_le_bytes([243, 126, 147, 245, 242, 29, 88, 207]),
+    VoterWeightRecord = u64::from_le_bytes([93, 238, 191, 120, 73, 236, 77, 2]),
+    Slot = u64::from_le_bytes([136, 192, 79, 106, 221, 31, 6, 0]),
+    SlotClaim = u64::from_le_bytes([134, 207, 171, 184, 86, 45, 126, 30]),
+    ExecutionAllowlist = u64::from_le_bytes([82, 204, 53, 26, 78, 241, 69, 11]),
+    Proposal = u64::from_le_bytes([102, 160, 107, 53, 78, 95, 181, 51]),
+    BallotBox = u64::from_le_bytes([98, 205, 176, 168, 49, 78, 151, 140]),
+    Recount = u64::from_le_bytes([69, 85, 60, 211, 65, 167, 176, 67]),
+    CreatorStats = u64::from_le_bytes([224, 105, 145, 120, 58, 178, 109, 8]),
+    VotingMetadata = u64::from_le_bytes([194, 161, 32, 86, 99, 131, 147, 200]),
+    VoterAllowlist = u64::from_le_bytes([0, 27, 219, 129, 191, 15, 72, 106]),
+    VoterAllowlistPage = u64::from_le_bytes([35, 176, 133, 139, 121, 66, 100, 108]),
+    VoteBucket = u64::from_le_bytes([235, 123, 172, 180, 103, 7, 115, 160]),
+    TitleIndex = u64::from_le_bytes([127, 199, 151, 251, 58, 191, 53, 107]),
+    TitleReservation = u64::from_le_bytes([113, 80, 132, 71, 206, 153, 132, 237]),
+    Envelope = u64::from_le_bytes([135, 186, 167, 133, 117, 188, 171, 206]),
+    ProposalTemplate = u64::from_le_bytes([170, 160, 206, 12, 229, 86, 100, 40]),
+    VotingArchive = u64::from_le_bytes([175, 189, 37, 28, 46, 2, 78, 44]),
+    ArchivedVoting = u64::from_le_bytes([201, 194, 91, 244, 13, 118, 249, 58]),
+    RecordTree = u64::from_le_bytes([185, 126, 133, 44, 121, 30, 180, 3]),
+    Flag = u64::from_le_bytes([29, 119, 91, 244, 54, 10, 253, 236]),
+    IncentivePool = u64::from_le_bytes([56, 139, 154, 150, 58, 196, 62, 71]),
+    LocalizedMetadata = u64::from_le_bytes([44, 133, 74, 164, 225, 31, 147, 170]),
+    TallyShard = u64::from_le_bytes([74, 57, 52, 244, 177, 211, 245, 193]),
+    FaucetClaim = u64::from_le_bytes([79, 198, 44, 120, 74, 21, 23, 135]),
+    Election = u64::from_le_bytes([42, 46, 235, 98, 25, 150, 22, 162]),
+    PollCard = u64::from_le_bytes([9, 25, 226, 152, 71, 15, 87, 231]),
+    VotingRegistry = u64::from_le_bytes([25, 28, 37, 65, 210, 158, 126, 206]),
+    VotingRegistryPage = u64::from_le_bytes([59, 183, 17, 212, 213, 41, 31, 52]),
+    NotificationTarget = u64::from_le_bytes([145, 102, 168, 252, 224, 131, 124, 231])
+}
+
+impl AccountType {
+    pub const ALL: [Self; 40] = [
+        Self::Config,
+        Self::Vote,
+        Self::UserVoting,
+        Self::TallySnapshot,
+        Self::RewardEscrow,
+        Self::RewardClaim,
+        Self::Realm,
+        Self::Membership,
+        Self::Boost,
+        Self::BoostDeposit,
+        Self::VoterIndex,
+        Self::VoterWeightRecord,
+        Self::Slot,
+        Self::SlotClaim,
+        Self::ExecutionAllowlist,
+        Self::Proposal,
+        Self::BallotBox,
+        Self::Recount,
+        Self::CreatorStats,
+        Self::VotingMetadata,
+        Self::VoterAllowlist,
+        Self::VoterAllowlistPage,
+        Self::VoteBucket,
+        Self::TitleIndex,
+        Self::TitleReservation,
+        Self::Envelope,
+        Self::ProposalTemplate,
+        Self::VotingArchive,
+        Self::ArchivedVoting,
+        Self::RecordTree,
+        Self::Flag,
+        Self::IncentivePool,
+        Self::LocalizedMetadata,
+        Self::TallyShard,
+        Self::FaucetClaim,
+        Self::Election,
+        Self::PollCard,
+        Self::VotingRegistry,
+        Self::VotingRegistryPage,
+        Self::NotificationTarget
+    ];
+
+    pub const fn discriminator(self) -> [u8; 8] {
+        (self as u64).to_le_bytes()
+    }
+
+    pub fn from_discriminator(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        Self::ALL.into_iter().find(|ty| ty.discriminator() == discriminator)
+    }
+}
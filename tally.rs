@@ -0,0 +1,544 @@
+use std::cmp::Reverse;
+
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{ entrypoint::ProgramResult, keccak::hash, program_error::ProgramError, pubkey::Pubkey };
+
+use crate::Errors;
+
+// Ranked ballots can't be longer than this
+pub const MAX_RANK_DEPTH: usize = 5;
+// Distinct rankings an instant-runoff voting keeps, so finding the winner stays within the compute budget
+pub const MAX_RANKINGS: usize = 256;
+// Max serialized size of a `TallyMode`
+pub const TALLY_MODE_SPACE: usize = 1 + 2;
+// Max decimals of `percentages`, 100% at 6 decimals is 10^8 units
+pub const MAX_PERCENTAGE_DECIMALS: u8 = 6;
+// Max counter accounts a sharded voting spreads its ballots over
+pub const MAX_TALLY_SHARDS: u8 = 16;
+// Serialized size of an `Apportionment`
+pub const APPORTIONMENT_SPACE: usize = 1 + 2;
+// Seats one apportionment hands out, each one is a pass over the options
+pub const MAX_SEATS: u16 = 1_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RankedMethod {
+    InstantRunoff,
+    Borda,
+    Condorcet
+}
+
+// How ballots are counted, chosen when the voting is created. Ballots are lists of option indices:
+// a single option for `Simple`, `Threshold` and `Quadratic`, the approved options for `Approval` and
+// the preference order for `Ranked`. An empty ballot is spoiled in every mode, see `TallyState::spoiled`.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TallyMode {
+    Simple,
+    // The top option only wins with at least this share of the votes, in basis points
+    Threshold(u16),
+    Approval,
+    Ranked(RankedMethod),
+    // A ballot counts the square root of its weight
+    Quadratic
+}
+
+// How a tie for the top count is decided, chosen when the voting is created. Only for the modes decided
+// by the highest count, instant-runoff and Condorcet votings settle their own ties.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TiePolicy {
+    // The tied option with the lowest index wins
+    LowestIndex,
+    // A tie leaves the voting without a winner
+    NoWinner,
+    // The tied option whose count last changed earliest wins, it held the lead first
+    EarliestLeader,
+    // No winner either, the tied options are logged on finalization to hold a runoff voting between them
+    Runoff,
+    // A tied option is drawn from a slot hash with `commit_tie_break` and `reveal_tie_break`
+    Random
+}
+
+// Highest averages methods, they differ in the divisor of an option holding `seats` seats
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SeatMethod {
+    // `seats + 1`, favours the larger options
+    DHondt,
+    // `2 * seats + 1`
+    SainteLague
+}
+
+impl SeatMethod {
+    fn divisor(self, seats: u16) -> u128 {
+        match self {
+            Self::DHondt => seats as u128 + 1,
+            Self::SainteLague => 2 * seats as u128 + 1
+        }
+    }
+}
+
+// Seats shared out among the options on finalization, in proportion to their tallies
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Apportionment {
+    pub method: SeatMethod,
+    // 1 to `MAX_SEATS`
+    pub seats: u16
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RankingCount {
+    pub ranking: Vec<u8>,
+    pub weight: u64
+}
+
+// Mode specific counts, next to the per-option tallies. Empty until the first ballot, since options can
+// change before the voting starts.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TallyState {
+    // Quadratic votes or Borda points per option, or the Condorcet pairwise matrix with
+    // `scores[a * n + b]` the weight preferring `a` over `b`
+    pub scores: Vec<u64>,
+    // Instant-runoff ballots grouped by ranking
+    pub rankings: Vec<RankingCount>,
+    // Large-supply mode, chosen when the voting is created. Option tallies then count up to u128, with
+    // the tallies keeping the low 64 bits.
+    pub large_supply: bool,
+    // High 64 bits of each option's tally in large-supply mode, empty otherwise
+    pub high: Vec<u64>,
+    // Spoiled ballots, they count towards turnout and quorums but towards no option. Protest votes are
+    // told apart from abstention this way.
+    pub spoiled: u64,
+    // Weight of the spoiled ballots, the protest measured the way token-weighted tallies are
+    pub spoiled_weight: u128,
+    // Weight of every counted ballot, spoiled ones included, the turnout of an `Electorate::Weight`
+    pub cast_weight: u128
+}
+
+impl TallyState {
+    // An empty state in the same mode, for counting the ballots again
+    pub fn restarted(&self) -> Self {
+        Self { large_supply: self.large_supply, ..Self::default() }
+    }
+
+    // Full tally of the option, `tallies` being the voting's per-option tallies
+    pub fn total(&self, tallies: &[u64], option: u8) -> u128 {
+        let high = self.high.get(option as usize).copied().unwrap_or(0);
+        (high as u128) << 64 | tallies[option as usize] as u128
+    }
+
+    pub fn totals(&self, tallies: &[u64]) -> Vec<u128> {
+        (0..tallies.len()).map(|option| self.total(tallies, option as u8)).collect()
+    }
+
+    // Adds the counts of a tally shard, `shard_tallies` being the shard's per-option tallies. Sharded
+    // votings have no instant-runoff rankings to merge.
+    pub fn merge(&mut self, tallies: &mut [u64], shard: &TallyState, shard_tallies: &[u64]) -> ProgramResult {
+        // The shard took no ballots
+        if shard_tallies.is_empty() {
+            return Ok(());
+        };
+
+        if shard_tallies.len() != tallies.len() {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        for option in 0..tallies.len() {
+            let total = self.total(tallies, option as u8)
+                .checked_add(shard.total(shard_tallies, option as u8))
+                .ok_or(Errors::TallyOverflow)?;
+
+            if self.large_supply {
+                self.high.resize(tallies.len(), 0);
+                self.high[option] = (total >> 64) as u64;
+            } else if total > u64::MAX as u128 {
+                return Err(Errors::TallyOverflow.into());
+            };
+
+            tallies[option] = total as u64;
+        };
+
+        if shard.scores.is_empty() == false {
+            self.scores.resize(shard.scores.len(), 0);
+            for (score, shard_score) in self.scores.iter_mut().zip(shard.scores.iter()) {
+                apply(score, *shard_score, true)?;
+            };
+        };
+
+        apply(&mut self.spoiled, shard.spoiled, true)?;
+        self.spoiled_weight = self.spoiled_weight.checked_add(shard.spoiled_weight).ok_or(Errors::TallyOverflow)?;
+        self.cast_weight = self.cast_weight.checked_add(shard.cast_weight).ok_or(Errors::TallyOverflow)?;
+
+        Ok(())
+    }
+}
+
+// Shard of a sharded voting counting the voter's ballots. Any voter always lands in the same one.
+pub fn shard_of(voter: &Pubkey, shards: u8) -> u8 {
+    hash(voter.as_ref()).0[0] % shards
+}
+
+impl TallyMode {
+    // Large-supply mode only widens the option tallies, so it's limited to the modes decided by them
+    pub fn validate(&self, large_supply: bool) -> ProgramResult {
+        if let Self::Threshold(bps) = self {
+            if *bps == 0 || *bps > 10_000 {
+                return Err(Errors::InvalidTallyMode.into());
+            };
+        };
+
+        if large_supply && matches!(self, Self::Simple | Self::Threshold(_) | Self::Approval) == false {
+            return Err(Errors::InvalidTallyMode.into());
+        };
+
+        Ok(())
+    }
+
+    pub fn validate_tie_policy(&self, tie_policy: TiePolicy) -> ProgramResult {
+        if tie_policy != TiePolicy::LowestIndex && matches!(self, Self::Ranked(RankedMethod::InstantRunoff | RankedMethod::Condorcet)) {
+            return Err(Errors::InvalidTiePolicy.into());
+        };
+
+        Ok(())
+    }
+
+    // Checks the ballot has the shape the mode expects
+    pub fn validate_ballot(&self, ballot: &[u8], options_count: usize) -> ProgramResult {
+        if ballot.is_empty() {
+            return Ok(());
+        };
+
+        let max_len = match self {
+            Self::Simple | Self::Threshold(_) | Self::Quadratic => 1,
+            Self::Approval => options_count,
+            Self::Ranked(_) => {
+                if ballot.len() > MAX_RANK_DEPTH {
+                    return Err(Errors::RankDepthExceeded.into());
+                };
+
+                options_count
+            }
+        };
+
+        if ballot.len() > max_len {
+            return Err(Errors::InvalidBallot.into());
+        };
+
+        for (index, option) in ballot.iter().enumerate() {
+            if *option as usize >= options_count {
+                return Err(Errors::InvalidOptionIndex.into());
+            };
+
+            if ballot[..index].contains(option) {
+                return Err(Errors::InvalidBallot.into());
+            };
+        };
+
+        Ok(())
+    }
+
+    // Whether the ballot counts towards the option in `tallies`, i.e. its first choice or an approved option
+    pub fn supports(&self, ballot: &[u8], option: u8) -> bool {
+        match self {
+            Self::Approval => ballot.contains(&option),
+            _ => ballot.first() == Some(&option)
+        }
+    }
+
+    // Adds a validated ballot to the counts, or takes it back out when `add` is false
+    pub fn accumulate(
+        &self,
+        tallies: &mut [u64],
+        state: &mut TallyState,
+        ballot: &[u8],
+        weight: u64,
+        add: bool
+    ) -> ProgramResult {
+        apply_u128(&mut state.cast_weight, weight, add)?;
+
+        // A spoiled ballot counts itself and its weight in their own counters, never towards an option
+        if ballot.is_empty() {
+            apply(&mut state.spoiled, 1, add)?;
+            return apply_u128(&mut state.spoiled_weight, weight, add);
+        };
+
+        let options_count = tallies.len();
+
+        for (index, option) in ballot.iter().enumerate() {
+            if self.supports(ballot, *option) && state.large_supply {
+                state.high.resize(options_count, 0);
+                apply_wide(&mut tallies[*option as usize], &mut state.high[*option as usize], weight, add)?;
+            } else if self.supports(ballot, *option) {
+                apply(&mut tallies[*option as usize], weight, add)?;
+            };
+
+            match self {
+                Self::Quadratic => {
+                    state.scores.resize(options_count, 0);
+                    apply(&mut state.scores[*option as usize], isqrt(weight), add)?;
+                },
+                Self::Ranked(RankedMethod::Borda) => {
+                    state.scores.resize(options_count, 0);
+                    let points = (options_count - 1 - index) as u64;
+                    let weight = weight.checked_mul(points).ok_or(Errors::TallyOverflow)?;
+                    apply(&mut state.scores[*option as usize], weight, add)?;
+                },
+                Self::Ranked(RankedMethod::Condorcet) => {
+                    state.scores.resize(options_count * options_count, 0);
+                    // Ranked options beat every option ranked below them, unranked ones included.
+                    for other in 0..options_count as u8 {
+                        if other != *option && ballot[..index].contains(&other) == false {
+                            apply(&mut state.scores[*option as usize * options_count + other as usize], weight, add)?;
+                        };
+                    };
+                },
+                _ => ()
+            };
+        };
+
+        if *self == Self::Ranked(RankedMethod::InstantRunoff) {
+            match state.rankings.iter().position(|count| count.ranking == ballot) {
+                Some(position) => {
+                    apply(&mut state.rankings[position].weight, weight, add)?;
+                    if state.rankings[position].weight == 0 {
+                        state.rankings.swap_remove(position);
+                    };
+                },
+                None if add => {
+                    if state.rankings.len() >= MAX_RANKINGS {
+                        return Err(Errors::TooManyRankings.into());
+                    };
+
+                    state.rankings.push(RankingCount { ranking: ballot.to_vec(), weight });
+                },
+                None => return Err(ProgramError::InvalidAccountData)
+            };
+        };
+
+        Ok(())
+    }
+
+    // Options sharing the highest count, in index order. Empty when nobody voted and for the modes that
+    // settle their own ties.
+    pub fn tied(&self, tallies: &[u64], state: &TallyState) -> Vec<u8> {
+        match self {
+            Self::Simple | Self::Threshold(_) | Self::Approval => top(&state.totals(tallies)),
+            Self::Quadratic | Self::Ranked(RankedMethod::Borda) => top(&state.scores),
+            Self::Ranked(_) => Vec::new()
+        }
+    }
+
+    // `None` when nobody voted, the threshold wasn't met or there is no Condorcet winner
+    pub fn winner(&self, tallies: &[u64], state: &TallyState) -> Option<u8> {
+        match self {
+            Self::Simple | Self::Approval => highest(&state.totals(tallies)),
+            Self::Threshold(bps) => {
+                let totals = state.totals(tallies);
+                let winner = highest(&totals)?;
+                let total = totals.iter().fold(0u128, |total, votes| total.saturating_add(*votes));
+                if totals[winner as usize].saturating_mul(10_000) < total.saturating_mul(*bps as u128) {
+                    return None;
+                };
+
+                Some(winner)
+            },
+            Self::Quadratic | Self::Ranked(RankedMethod::Borda) => highest(&state.scores),
+            Self::Ranked(RankedMethod::Condorcet) => {
+                let options_count = tallies.len();
+                if state.scores.len() != options_count * options_count {
+                    return None;
+                };
+
+                (0..options_count).find(|a| {
+                    (0..options_count).all(|b| *a == b || state.scores[a * options_count + b] > state.scores[b * options_count + a])
+                }).map(|a| a as u8)
+            },
+            Self::Ranked(RankedMethod::InstantRunoff) => instant_runoff(tallies.len(), &state.rankings)
+        }
+    }
+}
+
+// Share of each tally in the sum of the tallies, in fixed point with `decimals` decimals, e.g. 3333 for
+// 33.33% at 2 decimals. Every share is rounded down, then the units left over go one each to the options
+// with the largest remainders, ties to the lower index (largest remainder method). The shares always add
+// up to exactly 100%, or are all 0 when nothing was counted. Frontends use this rule, or read the shares
+// with `get_percentages`, so they all show the same figures.
+pub fn percentages(tallies: &[u128], decimals: u8) -> Result<Vec<u64>, ProgramError> {
+    if decimals > MAX_PERCENTAGE_DECIMALS {
+        return Err(Errors::InvalidPercentageDecimals.into());
+    };
+
+    let scale = 100 * 10u128.pow(decimals as u32);
+    let total = tallies.iter()
+        .try_fold(0u128, |total, tally| total.checked_add(*tally))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if total == 0 {
+        return Ok(vec![0; tallies.len()]);
+    };
+
+    let mut shares = Vec::with_capacity(tallies.len());
+    let mut remainders = Vec::with_capacity(tallies.len());
+    for tally in tallies.iter() {
+        let scaled = tally.checked_mul(scale).ok_or(ProgramError::ArithmeticOverflow)?;
+        shares.push((scaled / total) as u64);
+        remainders.push(scaled % total);
+    };
+
+    // Less than the number of options with a remainder, since the remainders add up to `leftover * total`
+    let leftover = scale as u64 - shares.iter().sum::<u64>();
+    // Stable, so equal remainders stay in option order
+    let mut order = (0..tallies.len()).collect::<Vec<_>>();
+    order.sort_by_key(|option| Reverse(remainders[*option]));
+    for option in order.into_iter().take(leftover as usize) {
+        shares[option] += 1;
+    };
+
+    Ok(shares)
+}
+
+// Seats of each option by the highest averages method: seat after seat goes to the option with the
+// highest `tally / divisor`, ties to the lower index. Options without votes get no seat, nothing is handed
+// out when nothing was counted.
+pub fn apportion(tallies: &[u128], apportionment: Apportionment) -> Result<Vec<u16>, ProgramError> {
+    let Apportionment { method, seats: total_seats } = apportionment;
+
+    let mut seats = vec![0u16; tallies.len()];
+    for _ in 0..total_seats {
+        let mut next: Option<usize> = None;
+        for (option, tally) in tallies.iter().enumerate() {
+            if *tally == 0 {
+                continue;
+            };
+
+            // `tally / divisor > best / best_divisor`, multiplied out to stay exact
+            let higher = match next {
+                Some(best) => {
+                    let quotient = tally.checked_mul(method.divisor(seats[best])).ok_or(ProgramError::ArithmeticOverflow)?;
+                    let best_quotient = tallies[best].checked_mul(method.divisor(seats[option])).ok_or(ProgramError::ArithmeticOverflow)?;
+                    quotient > best_quotient
+                },
+                None => true
+            };
+            if higher {
+                next = Some(option);
+            };
+        };
+
+        let Some(option) = next else {
+            break;
+        };
+        seats[option] += 1;
+    };
+
+    Ok(seats)
+}
+
+// The `count` options with the highest counts, best first. Equal counts go to the lower index, options
+// without votes never place.
+pub fn top_options(counts: &[u128], count: usize) -> Vec<u8> {
+    let mut order = (0..counts.len()).filter(|option| counts[*option] > 0).collect::<Vec<_>>();
+    // Stable, so equal counts stay in option order
+    order.sort_by_key(|option| Reverse(counts[*option]));
+
+    order.into_iter().take(count).map(|option| option as u8).collect()
+}
+
+// Counts can't wrap, a ballot that would overflow one fails with `TallyOverflow`
+fn apply(count: &mut u64, weight: u64, add: bool) -> ProgramResult {
+    *count = match add {
+        true => count.checked_add(weight).ok_or(Errors::TallyOverflow)?,
+        false => count.checked_sub(weight).ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
+    Ok(())
+}
+
+// Same as `apply` for a u128 count
+fn apply_u128(count: &mut u128, weight: u64, add: bool) -> ProgramResult {
+    *count = match add {
+        true => count.checked_add(weight as u128).ok_or(Errors::TallyOverflow)?,
+        false => count.checked_sub(weight as u128).ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
+    Ok(())
+}
+
+// Same as `apply` for a u128 count split into its low and high 64 bits
+fn apply_wide(low: &mut u64, high: &mut u64, weight: u64, add: bool) -> ProgramResult {
+    let count = (*high as u128) << 64 | *low as u128;
+    let count = match add {
+        true => count.checked_add(weight as u128).ok_or(Errors::TallyOverflow)?,
+        false => count.checked_sub(weight as u128).ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
+    *low = count as u64;
+    *high = (count >> 64) as u64;
+
+    Ok(())
+}
+
+// Integer square root, rounded down
+fn isqrt(value: u64) -> u64 {
+    let mut root = value;
+    let mut next = value / 2 + value % 2;
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    };
+
+    root
+}
+
+// The option with the highest count, ties go to the lower index. `None` when all counts are 0.
+fn highest<T: Copy + Default + PartialOrd>(counts: &[T]) -> Option<u8> {
+    let mut winner = None;
+    let mut winner_count = T::default();
+    for (index, count) in counts.iter().enumerate() {
+        if *count > winner_count {
+            winner = Some(index as u8);
+            winner_count = *count;
+        };
+    };
+
+    winner
+}
+
+fn top<T: Copy + Default + PartialOrd>(counts: &[T]) -> Vec<u8> {
+    let Some(winner) = highest(counts) else {
+        return Vec::new();
+    };
+
+    (0..counts.len()).filter(|option| counts[*option] == counts[winner as usize]).map(|option| option as u8).collect()
+}
+
+// Eliminates the option with the fewest first choices until one has a majority of the ballots that still
+// rank a remaining option. Elimination ties go to the higher index.
+fn instant_runoff(options_count: usize, rankings: &[RankingCount]) -> Option<u8> {
+    let mut eliminated = vec![false; options_count];
+    loop {
+        let mut counts = vec![0u128; options_count];
+        for count in rankings.iter() {
+            if let Some(option) = count.ranking.iter().find(|option| eliminated[**option as usize] == false) {
+                counts[*option as usize] += count.weight as u128;
+            };
+        };
+
+        let total: u128 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        };
+
+        let remaining = (0..options_count).filter(|option| eliminated[*option] == false);
+        let leader = remaining.clone().max_by_key(|option| (counts[*option], std::cmp::Reverse(*option)))?;
+        if counts[leader] * 2 > total || remaining.clone().count() == 1 {
+            return Some(leader as u8);
+        };
+
+        let loser = remaining.min_by_key(|option| (counts[*option], std::cmp::Reverse(*option)))?;
+        eliminated[loser] = true;
+    };
+}
@@ -0,0 +1,72 @@
+// Seats are shared out by the highest averages methods and recorded on finalization
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, set_apportionment, set_pass_rules, sync_poll_card, vote, VoteProofs },
+    find_voting_pda,
+    tally::{ apportion, Apportionment, SeatMethod },
+    Errors
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+
+fn seats(tallies: &[u128], method: SeatMethod, seats: u16) -> Vec<u16> {
+    apportion(tallies, Apportionment { method, seats }).unwrap()
+}
+
+#[test]
+fn methods_differ_in_their_divisors() {
+    let tallies = [ 100_000, 80_000, 30_000, 20_000 ];
+    assert_eq!(seats(&tallies, SeatMethod::DHondt, 8), vec![ 4, 3, 1, 0 ]);
+    assert_eq!(seats(&tallies, SeatMethod::SainteLague, 8), vec![ 3, 3, 1, 1 ]);
+}
+
+#[test]
+fn ties_go_to_the_lower_index() {
+    assert_eq!(seats(&[ 3, 3, 0 ], SeatMethod::DHondt, 3), vec![ 2, 1, 0 ]);
+    assert_eq!(seats(&[ 3, 3, 0 ], SeatMethod::SainteLague, 1), vec![ 1, 0, 0 ]);
+}
+
+#[test]
+fn options_without_votes_get_no_seat() {
+    assert_eq!(seats(&[ 0, 0 ], SeatMethod::DHondt, 5), vec![ 0, 0 ]);
+    assert_eq!(seats(&[ 0, 1 ], SeatMethod::SainteLague, 5), vec![ 0, 5 ]);
+}
+
+#[tokio::test]
+async fn finalization_records_the_seats() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 7).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let title = "Council of Fridays";
+    let voting = find_voting_pda(title, &program_id).0;
+    let options = [ "Pizza", "Sushi", "Tacos" ].iter().map(|option| option.to_string()).collect::<Vec<_>>();
+
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options.clone(), DURATION);
+    let result = send(&mut context, &[ set_apportionment(create, SeatMethod::DHondt, 0) ], &[ creator ]).await;
+    assert_error(result, Errors::InvalidApportionment);
+
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options, DURATION);
+    send(&mut context, &[
+        set_apportionment(set_pass_rules(create, 6, 0), SeatMethod::DHondt, 5),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+
+    for (voter, option) in voters.iter().zip([ 0, 0, 0, 1, 1, 2 ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, vec![ option ], title.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+    assert_eq!(results(&mut context, &voting).await.seats, vec![ 3, 2, 0 ]);
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    assert_eq!(results(&mut context, &voting).await.seats, vec![ 3, 2, 0 ]);
+}
@@ -0,0 +1,105 @@
+// Boosts go to the treasury once the voting closes, or back to each booster if it's cancelled
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{ account::Account, pubkey::Pubkey, signature::Signer };
+
+use solana_voting_program::{
+    client::{ boost, cancel_voting, create_voting, refund_boost, release_boost },
+    find_boost_deposit_pda,
+    find_boost_pda,
+    find_config_pda,
+    find_voting_pda,
+    AccountType,
+    BoostAccount,
+    ConfigAccount,
+    Errors,
+    StateVersion,
+    CONFIG_ACCOUNT_SPACE
+};
+use common::{ assert_error, funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 60;
+const CANCELLED: &str = "Lunch on Fridays";
+const CLOSED: &str = "Dinner on Wednesdays";
+
+// A config naming `admin`, without the upgrade authority `initialize_config` needs
+async fn set_config(context: &mut ProgramTestContext, admin: Pubkey) {
+    let config = ConfigAccount {
+        discriminator: AccountType::Config.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        admin,
+        disabled_instructions: 0,
+        max_active_votings: 0,
+        start_time_tolerance: 0,
+        pending_admin: None,
+        creation_deposit: 0,
+        deposit_refund_window: 0
+    };
+    let mut data = vec![ 0; CONFIG_ACCOUNT_SPACE ];
+    config.serialize(&mut &mut data[..]).unwrap();
+
+    let lamports = context.banks_client.get_rent().await.unwrap().minimum_balance(data.len());
+    let account = Account { lamports, data, owner: solana_voting_program::id(), executable: false, rent_epoch: 0 };
+    context.set_account(&find_config_pda(&solana_voting_program::id()).0, &account.into());
+}
+
+async fn boost_account(context: &mut ProgramTestContext, voting: &Pubkey) -> BoostAccount {
+    let account = context.banks_client.get_account(find_boost_pda(voting, &solana_voting_program::id()).0).await.unwrap().unwrap();
+    try_from_slice_unchecked(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn boosts_are_released_or_refunded() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, boosters, treasury) = (&accounts[0], &accounts[1..3], &accounts[3]);
+
+    let options = || vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    for title in [ CANCELLED, CLOSED ] {
+        let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options(), DURATION);
+        send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    };
+
+    let (cancelled, closed) = (find_voting_pda(CANCELLED, &program_id).0, find_voting_pda(CLOSED, &program_id).0);
+    for (booster, amount) in boosters.iter().zip([ 300, 200 ]) {
+        send(&mut context, &[ boost(&program_id, &booster.pubkey(), &cancelled, amount) ], &[ booster ]).await.unwrap();
+    };
+    send(&mut context, &[ boost(&program_id, &boosters[0].pubkey(), &closed, 500) ], &[ &boosters[0] ]).await.unwrap();
+    assert_eq!(boost_account(&mut context, &cancelled).await.amount, 500);
+
+    // Refunds only follow a cancellation, the deposit and its rent go back to the booster
+    let refund = || refund_boost(&program_id, &boosters[0].pubkey(), &cancelled);
+    assert_error(send(&mut context, &[ refund() ], &[ &boosters[0] ]).await, Errors::VotingNotCancelled);
+    send(&mut context, &[ cancel_voting(&program_id, &creator.pubkey(), &cancelled) ], &[ creator ]).await.unwrap();
+
+    let deposit = find_boost_deposit_pda(&cancelled, &boosters[0].pubkey(), &program_id).0;
+    let deposit_rent = context.banks_client.get_balance(deposit).await.unwrap();
+    let booster_before = context.banks_client.get_balance(boosters[0].pubkey()).await.unwrap();
+    send(&mut context, &[ refund() ], &[ &boosters[0] ]).await.unwrap();
+    let booster_after = context.banks_client.get_balance(boosters[0].pubkey()).await.unwrap();
+    assert_eq!(booster_after - booster_before, 300 + deposit_rent);
+    assert!(context.banks_client.get_account(deposit).await.unwrap().is_none());
+    assert_eq!(boost_account(&mut context, &cancelled).await.amount, 200);
+
+    // Releases need a closed voting and go to the config admin
+    set_config(&mut context, treasury.pubkey()).await;
+    let release = |voting: &Pubkey| release_boost(&program_id, voting, &treasury.pubkey());
+    assert_error(send(&mut context, &[ release(&cancelled) ], &[]).await, Errors::VotingCancelled);
+    assert_error(send(&mut context, &[ release(&closed) ], &[]).await, Errors::VotingNotEnded);
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    let treasury_before = context.banks_client.get_balance(treasury.pubkey()).await.unwrap();
+    send(&mut context, &[ release(&closed) ], &[]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(treasury.pubkey()).await.unwrap() - treasury_before, 500);
+
+    // The amount stays for the ranking of closed votings
+    let released = boost_account(&mut context, &closed).await;
+    assert!(released.released);
+    assert_eq!(released.amount, 500);
+    assert_error(send(&mut context, &[ release(&closed) ], &[]).await, Errors::BoostAlreadyReleased);
+}
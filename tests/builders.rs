@@ -0,0 +1,33 @@
+// The builders at the crate root take absolute times and derive every account from the title
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{ create_voting, find_user_vote_address, find_voting_address, vote };
+use common::{ funded, now, program_test, results, send };
+
+const DURATION: u64 = 24 * 60 * 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn builders_create_a_voting_and_cast_a_ballot() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let starts_at = now(&mut context).await;
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), options, starts_at, starts_at + DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+
+    send(&mut context, &[ vote(&program_id, &voter.pubkey(), TITLE.to_string(), vec![ 1 ]) ], &[ voter ]).await.unwrap();
+
+    let voting = find_voting_address(TITLE, &program_id);
+    let user_vote = context.banks_client.get_account(find_user_vote_address(&voting, &voter.pubkey(), &program_id)).await.unwrap();
+    assert!(user_vote.is_some_and(|account| account.owner == program_id));
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.tallies, vec![ 0, 1 ]);
+    assert_eq!(results.total_voters, 1);
+}
@@ -0,0 +1,84 @@
+// Clones take the source's settings, uncapped sources clone without caps
+mod common;
+
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::{ instruction::Instruction, signature::{ Keypair, Signer } };
+use solana_program_test::ProgramTestContext;
+
+use solana_voting_program::{
+    client::{ clone_voting, create_realm, create_realm_voting, create_voting, set_envelope_key, set_hide_tallies, set_pass_rules },
+    find_realm_pda,
+    find_realm_voting_pda,
+    find_voting_pda,
+    Errors,
+    VoteMainAccount
+};
+use common::{ assert_error, funded, now, program_test, send };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+const CLONE_TITLE: &str = "Lunch on Saturdays";
+
+// Creates the source with `edit` applied and returns its clone
+async fn clone_of(context: &mut ProgramTestContext, creator: &Keypair, edit: impl FnOnce(Instruction) -> Instruction) -> VoteMainAccount {
+    let program_id = solana_voting_program::id();
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(context, &[ edit(create) ], &[ creator ]).await.unwrap();
+
+    let source = find_voting_pda(TITLE, &program_id).0;
+    let starts_at = now(context).await + 10;
+    send(context, &[
+        clone_voting(&program_id, &creator.pubkey(), &source, CLONE_TITLE.to_string(), starts_at, starts_at + DURATION, &[])
+    ], &[ creator ]).await.unwrap();
+
+    let clone = context.banks_client.get_account(find_voting_pda(CLONE_TITLE, &program_id).0).await.unwrap().unwrap();
+    try_from_slice_unchecked(&clone.data).unwrap()
+}
+
+#[tokio::test]
+async fn envelope_votings_clone_sealed() {
+    let mut context = program_test().start_with_context().await;
+    let creator = &funded(&mut context, 1).await[0];
+
+    let clone = clone_of(&mut context, creator, |create| set_envelope_key(create, [ 7; 32 ])).await;
+    assert_eq!(clone.envelope_key, Some([ 7; 32 ]));
+    assert_eq!(clone.option_caps, vec![ 0, 0 ]);
+}
+
+#[tokio::test]
+async fn hidden_tallies_clone_hidden() {
+    let mut context = program_test().start_with_context().await;
+    let creator = &funded(&mut context, 1).await[0];
+
+    let clone = clone_of(&mut context, creator, set_hide_tallies).await;
+    assert!(clone.hide_tallies);
+    assert_eq!(clone.option_caps, vec![ 0, 0 ]);
+}
+
+#[tokio::test]
+async fn only_the_creator_clones_their_own_votings() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, stranger) = (&accounts[0], &accounts[1]);
+
+    let clone = clone_of(&mut context, creator, |create| set_pass_rules(create, 3, 6_000)).await;
+    assert_eq!((clone.min_participation, clone.pass_threshold_bps), (3, 6_000));
+    assert_eq!(clone.options, vec![ "Pizza".to_string(), "Sushi".to_string() ]);
+
+    let source = find_voting_pda(TITLE, &program_id).0;
+    let starts_at = now(&mut context).await + 10;
+    let by_stranger = clone_voting(&program_id, &stranger.pubkey(), &source, "Lunch on Sundays".to_string(), starts_at, starts_at + DURATION, &[]);
+    assert_error(send(&mut context, &[ by_stranger ], &[ stranger ]).await, Errors::NotVotingCreator);
+
+    // Realm votings take the realm's settings, only `create_realm_voting` applies them
+    let realm = find_realm_pda("Office", &program_id).0;
+    send(&mut context, &[
+        create_realm(&program_id, &creator.pubkey(), "Office".to_string()),
+        create_realm_voting(&program_id, &creator.pubkey(), &realm, &creator.pubkey(), 0, "Lunch on Mondays".to_string(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION)
+    ], &[ creator ]).await.unwrap();
+
+    let realm_voting = find_realm_voting_pda(&realm, 0, &program_id).0;
+    let realm_clone = clone_voting(&program_id, &creator.pubkey(), &realm_voting, "Lunch on Sundays".to_string(), starts_at, starts_at + DURATION, &[]);
+    assert_error(send(&mut context, &[ realm_clone ], &[ creator ]).await, Errors::RealmVotingNotCloneable);
+}
@@ -0,0 +1,55 @@
+// Committed ballots are only counted once revealed, between the end and the close of the reveal window
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ commit_vote, create_voting, finalize_voting, reveal_vote, set_reveal_window, VoteProofs },
+    find_voting_pda,
+    Errors
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+const REVEAL_WINDOW: u64 = 120;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn only_revealed_ballots_count() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let started_at = now(&mut context).await;
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_reveal_window(create, REVEAL_WINDOW) ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let salts = [ [ 1; 32 ], [ 2; 32 ] ];
+    for ((voter, ballot), salt) in voters.iter().zip([ vec![ 0 ], vec![ 1 ] ]).zip(salts.iter()) {
+        send(&mut context, &[
+            commit_vote(&program_id, &voter.pubkey(), &voting, &ballot, salt, TITLE.to_string(), VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let reveal = |salt: [u8; 32]| reveal_vote(&program_id, &voters[0].pubkey(), &voting, vec![ 0 ], salt);
+    assert_error(send(&mut context, &[ reveal(salts[0]) ], &[ &voters[0] ]).await, Errors::VotingNotEnded);
+
+    warp_to(&mut context, started_at + DURATION + 1).await;
+    assert_error(send(&mut context, &[ reveal(salts[1]) ], &[ &voters[0] ]).await, Errors::InvalidReveal);
+    send(&mut context, &[ reveal(salts[0]) ], &[ &voters[0] ]).await.unwrap();
+
+    let finalize = || finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey());
+    assert_error(send(&mut context, &[ finalize() ], &[]).await, Errors::RevealWindowOpen);
+
+    // The second voter never revealed
+    warp_to(&mut context, started_at + DURATION + REVEAL_WINDOW + 1).await;
+    let late = reveal_vote(&program_id, &voters[1].pubkey(), &voting, vec![ 1 ], salts[1]);
+    assert_error(send(&mut context, &[ late ], &[ &voters[1] ]).await, Errors::RevealWindowClosed);
+    send(&mut context, &[ finalize() ], &[]).await.unwrap();
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.tallies, vec![ 1, 0 ]);
+    assert_eq!(results.winning_option, Some(0));
+}
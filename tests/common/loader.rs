@@ -0,0 +1,103 @@
+// Runs an entrypoint over the input buffer the loader builds for on-chain programs, for the `pinocchio`
+// build. The test runtime hands native programs `AccountInfo`s, so they are serialized here and what
+// the program wrote is copied back.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS },
+    program_error::ProgramError,
+    pubkey::Pubkey
+};
+
+pub fn run(
+    entrypoint: unsafe extern "C" fn(*mut u8) -> u64,
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8]
+) -> ProgramResult {
+    let bytes = serialize(program_id, accounts, data);
+
+    // The entrypoint reads the counts and lengths in place, the buffer is aligned like the loader's
+    let mut input = vec![ 0u64; bytes.len().div_ceil(8) ];
+    for (word, chunk) in input.iter_mut().zip(bytes.chunks(8)) {
+        let mut word_bytes = [0; 8];
+        word_bytes[..chunk.len()].copy_from_slice(chunk);
+        *word = u64::from_le_bytes(word_bytes);
+    };
+
+    let result = unsafe { entrypoint(input.as_mut_ptr() as *mut u8) };
+    if result != SUCCESS {
+        return Err(ProgramError::from(result));
+    };
+
+    let bytes = input.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<_>>();
+    write_back(&bytes, accounts)
+}
+
+fn serialize(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Vec<u8> {
+    let mut input = (accounts.len() as u64).to_le_bytes().to_vec();
+
+    for (index, account) in accounts.iter().enumerate() {
+        // Duplicates only point to the first occurrence
+        if let Some(original) = accounts[..index].iter().position(|earlier| earlier.key == account.key) {
+            input.push(original as u8);
+            input.extend_from_slice(&[0; 7]);
+            continue;
+        };
+
+        input.extend_from_slice(&[ NON_DUP_MARKER, account.is_signer as u8, account.is_writable as u8, account.executable as u8, 0, 0, 0, 0 ]);
+        input.extend_from_slice(account.key.as_ref());
+        input.extend_from_slice(account.owner.as_ref());
+        input.extend_from_slice(&account.lamports().to_le_bytes());
+        input.extend_from_slice(&(account.data_len() as u64).to_le_bytes());
+        input.extend_from_slice(&account.data.borrow());
+        input.resize((input.len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(BPF_ALIGN_OF_U128), 0);
+        input.extend_from_slice(&account.rent_epoch.to_le_bytes());
+    };
+
+    input.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    input.extend_from_slice(data);
+    input.extend_from_slice(program_id.as_ref());
+
+    input
+}
+
+// Lamports, data and owner, the rest can't be changed by the program
+fn write_back(input: &[u8], accounts: &[AccountInfo]) -> ProgramResult {
+    let read_u64 = |offset: usize| u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap());
+
+    let mut offset = 8;
+    for account in accounts {
+        if input[offset] != NON_DUP_MARKER {
+            offset += 8;
+            continue;
+        };
+
+        // Marker, flags and padding, then the key
+        offset += 8 + 32;
+        let owner = Pubkey::try_from(&input[offset..offset + 32]).unwrap();
+        let lamports = read_u64(offset + 32);
+        let data_len = read_u64(offset + 40) as usize;
+        offset += 48;
+        let data = &input[offset..offset + data_len];
+        // The padding follows the data the account was serialized with
+        offset = (offset + account.data_len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(BPF_ALIGN_OF_U128) + 8;
+
+        if account.lamports() != lamports {
+            **account.lamports.borrow_mut() = lamports;
+        };
+
+        if account.data_len() != data_len {
+            account.realloc(data_len, false)?;
+        };
+
+        if account.data.borrow()[..] != *data {
+            account.data.borrow_mut().copy_from_slice(data);
+        };
+
+        if *account.owner != owner {
+            account.assign(&owner);
+        };
+    };
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+// Shared by the integration tests. The program runs inside `solana-program-test`, natively unless an SBF
+// build is given with `SBF_OUT_DIR` as `cargo test-sbf` does. Every instruction goes through a real
+// transaction.
+#![allow(dead_code)]
+
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{ Instruction, InstructionError },
+    pubkey::Pubkey,
+    system_instruction
+};
+use solana_program_test::{ processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext };
+#[cfg(feature = "custom-heap")]
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::{
+    signature::{ Keypair, Signer },
+    transaction::{ Transaction, TransactionError }
+};
+
+use solana_voting_program::{ client::get_results, Errors, InstructionType, ResultsReturnData };
+
+#[cfg(feature = "pinocchio")]
+mod loader;
+
+// Enough for a voter's user vote account, voter index and fees
+pub const FUNDING: u64 = 1_000_000_000;
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("solana_voting_program", solana_voting_program::id(), processor!(process_instruction));
+    program_test.prefer_bpf(cfg!(not(feature = "pinocchio")) && sbf());
+
+    program_test
+}
+
+// Whether the tests run the SBF build, the only one the runtime meters compute units for
+pub fn sbf() -> bool {
+    std::env::var_os("SBF_OUT_DIR").is_some()
+}
+
+// The program ties the account slice to the lifetime of the accounts, the test runtime hands them over
+// with two lifetimes. The slice never outlives the accounts, so the shorter one fits both.
+#[cfg(not(feature = "pinocchio"))]
+fn process_instruction<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+
+    solana_voting_program::process_instruction(program_id, accounts, data)
+}
+
+// The `pinocchio` build is run through its own entrypoint, so the same tests cover both builds
+#[cfg(feature = "pinocchio")]
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    loader::run(solana_voting_program::lazy_entrypoint::entrypoint, program_id, accounts, data)
+}
+
+// Sends `instructions` in one transaction paid by the context's payer, see `log_costs`
+pub async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![ &context.payer ];
+    all_signers.extend_from_slice(signers);
+
+    // The bump allocator of the `custom-heap` build spans the whole frame
+    #[cfg(feature = "custom-heap")]
+    let instructions = &[
+        &[ ComputeBudgetInstruction::request_heap_frame(solana_voting_program::HEAP_FRAME_BYTES) ],
+        instructions
+    ].concat();
+
+    let blockhash = context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+    context.last_blockhash = blockhash;
+    let transaction = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+
+    let processed = context.banks_client.process_transaction_with_metadata(transaction).await?;
+    if let Some(metadata) = processed.metadata {
+        log_costs(instructions, &metadata.log_messages);
+    };
+
+    processed.result.map_err(BanksClientError::TransactionError)
+}
+
+// Compute units each of the program's top-level instructions consumed, from the runtime's
+// "Program <id> consumed <units> of <limit> compute units" logs
+fn consumed_units(log_messages: &[String]) -> Vec<u64> {
+    let program_id = solana_voting_program::id();
+    let mut depth = 0;
+    let mut units = Vec::new();
+    for message in log_messages {
+        let mut words = message.split(' ');
+        if words.next() != Some("Program") {
+            continue;
+        };
+        let Some(Ok(program)) = words.next().map(Pubkey::from_str) else {
+            continue;
+        };
+
+        match words.next() {
+            Some("invoke") => depth += 1,
+            Some("success") | Some("failed:") => depth -= 1,
+            Some("consumed") if depth == 1 && program == program_id => units.push(words.next().unwrap().parse().unwrap()),
+            _ => ()
+        };
+    };
+
+    units
+}
+
+// Logs the compute units of every instruction of the program. Only the SBF build is metered, natively
+// there's nothing to log.
+fn log_costs(instructions: &[Instruction], log_messages: &[String]) {
+    if !sbf() {
+        return;
+    };
+
+    let program_id = solana_voting_program::id();
+    let ours = instructions.iter().filter(|instruction| instruction.program_id == program_id);
+    for (instruction, units) in ours.zip(consumed_units(log_messages)) {
+        let Some(instruction_type) = InstructionType::from_discriminator(&instruction.data) else {
+            continue;
+        };
+        println!("{:?} consumed {} compute units", instruction_type, units);
+    };
+}
+
+// Funded keypairs, in one transaction
+pub async fn funded(context: &mut ProgramTestContext, count: usize) -> Vec<Keypair> {
+    let keypairs = (0..count).map(|_| Keypair::new()).collect::<Vec<_>>();
+    let fundings = keypairs.iter()
+        .map(|keypair| system_instruction::transfer(&context.payer.pubkey(), &keypair.pubkey(), FUNDING))
+        .collect::<Vec<_>>();
+    send(context, &fundings, &[]).await.unwrap();
+
+    keypairs
+}
+
+pub fn error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.map_err(|error| error.unwrap()) {
+        Err(TransactionError::InstructionError(_, error)) => error,
+        other => panic!("expected an instruction error, got {:?}", other)
+    }
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, expected: Errors) {
+    assert_eq!(error(result), InstructionError::Custom(expected.code()));
+}
+
+pub async fn now(context: &mut ProgramTestContext) -> u64 {
+    context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+}
+
+// Moves the cluster clock to `unix_timestamp`, the program reads the time from the clock sysvar
+pub async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: u64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp as i64;
+    context.set_sysvar(&clock);
+}
+
+// Return data of `get_results`
+pub async fn results(context: &mut ProgramTestContext, voting: &Pubkey) -> ResultsReturnData {
+    let transaction = Transaction::new_signed_with_payer(
+        &[ get_results(&solana_voting_program::id(), voting) ],
+        Some(&context.payer.pubkey()),
+        &[ &context.payer ],
+        context.last_blockhash
+    );
+
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let return_data = simulation.simulation_details.and_then(|details| details.return_data).expect("no return data");
+
+    ResultsReturnData::try_from_slice(&return_data.data).unwrap()
+}
@@ -0,0 +1,66 @@
+// The heaviest paths of the ballot lifecycle and its views. On the SBF build `send` logs what each
+// instruction consumed, see `common::log_costs`. Natively only the paths run.
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{
+        create_voting,
+        finalize_voting,
+        get_my_vote,
+        get_percentages,
+        get_results,
+        set_apportionment,
+        sync_poll_card,
+        update_vote,
+        vote,
+        VoteProofs
+    },
+    find_voting_pda,
+    tally::{ SeatMethod, MAX_PERCENTAGE_DECIMALS, MAX_SEATS },
+    MAX_DESCRIPTION_LENGTH,
+    MAX_OPTIONS,
+    MAX_OPTION_LENGTH,
+    MAX_TITLE_LENGTH
+};
+use common::{ funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+
+#[tokio::test]
+async fn heaviest_paths_run() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1 + MAX_OPTIONS).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    // Every text at its longest, the most seats shared out on finalization
+    let title = "T".repeat(MAX_TITLE_LENGTH);
+    let options = (0..MAX_OPTIONS).map(|index| format!("{:0>1$}", index, MAX_OPTION_LENGTH)).collect();
+    let create = create_voting(&program_id, &creator.pubkey(), title.clone(), "D".repeat(MAX_DESCRIPTION_LENGTH), options, DURATION);
+    let voting = find_voting_pda(&title, &program_id).0;
+    send(&mut context, &[
+        set_apportionment(create, SeatMethod::SainteLague, MAX_SEATS),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+
+    // A ballot with a memo on every option, each changed once
+    for (index, voter) in voters.iter().enumerate() {
+        let ballot = vote(&program_id, &voter.pubkey(), &voting, vec![ index as u8 ], title.clone(), true, VoteProofs::default());
+        send(&mut context, &[ ballot ], &[ voter ]).await.unwrap();
+        let option = ((index + 1) % MAX_OPTIONS) as u8;
+        send(&mut context, &[ update_vote(&program_id, &voter.pubkey(), &voting, vec![ option ], true) ], &[ voter ]).await.unwrap();
+    };
+
+    send(&mut context, &[
+        get_results(&program_id, &voting),
+        get_percentages(&program_id, &voting, MAX_PERCENTAGE_DECIMALS),
+        get_my_vote(&program_id, &voters[0].pubkey(), &voting)
+    ], &[]).await.unwrap();
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+    assert_eq!(results(&mut context, &voting).await.seats.iter().sum::<u16>(), MAX_SEATS);
+}
@@ -0,0 +1,74 @@
+// The creation deposit sits on top of the voting's rent, growing the voting can't spend it
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{ account::Account, pubkey::Pubkey, signature::Signer };
+
+use solana_voting_program::{
+    client::{ edit_voting, finalize_voting },
+    create_voting,
+    find_config_pda,
+    find_voting_address,
+    AccountType,
+    ConfigAccount,
+    StateVersion,
+    CONFIG_ACCOUNT_SPACE,
+    MAX_DESCRIPTION_LENGTH,
+    MIN_DEPOSIT_REFUND_WINDOW
+};
+use common::{ funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 60 * 60;
+const DEPOSIT: u64 = 1_000_000;
+const TITLE: &str = "Lunch on Fridays";
+
+// A config asking for `DEPOSIT`, without the upgrade authority `initialize_config` needs
+async fn set_config(context: &mut ProgramTestContext) {
+    let config = ConfigAccount {
+        discriminator: AccountType::Config.discriminator(),
+        version: StateVersion::CURRENT as u8,
+        admin: Pubkey::new_unique(),
+        disabled_instructions: 0,
+        max_active_votings: 0,
+        start_time_tolerance: 0,
+        pending_admin: None,
+        creation_deposit: DEPOSIT,
+        deposit_refund_window: MIN_DEPOSIT_REFUND_WINDOW
+    };
+    let mut data = vec![ 0; CONFIG_ACCOUNT_SPACE ];
+    config.serialize(&mut &mut data[..]).unwrap();
+
+    let lamports = context.banks_client.get_rent().await.unwrap().minimum_balance(data.len());
+    let account = Account { lamports, data, owner: solana_voting_program::id(), executable: false, rent_epoch: 0 };
+    context.set_account(&find_config_pda(&solana_voting_program::id()).0, &account.into());
+}
+
+#[tokio::test]
+async fn grown_votings_keep_their_rent_after_the_refund() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    set_config(&mut context).await;
+    let creator = &funded(&mut context, 1).await[0];
+
+    let starts_at = now(&mut context).await + 60;
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    send(&mut context, &[ create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), options, starts_at, starts_at + DURATION) ], &[ creator ]).await.unwrap();
+    let voting = find_voting_address(TITLE, &program_id);
+    let created = context.banks_client.get_account(voting).await.unwrap().unwrap();
+
+    send(&mut context, &[ edit_voting(&program_id, &creator.pubkey(), &voting, "a".repeat(MAX_DESCRIPTION_LENGTH)) ], &[ creator ]).await.unwrap();
+    let grown = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    assert!(grown.data.len() > created.data.len());
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(grown.lamports, rent.minimum_balance(grown.data.len()) + DEPOSIT);
+
+    warp_to(&mut context, starts_at + DURATION + 1).await;
+    let balance = context.banks_client.get_balance(creator.pubkey()).await.unwrap();
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(creator.pubkey()).await.unwrap(), balance + DEPOSIT);
+
+    let finalized = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    assert!(rent.is_exempt(finalized.lamports, finalized.data.len()));
+}
@@ -0,0 +1,182 @@
+// Dry runs go through every check of the instruction and return a preview, without writing anything
+mod common;
+
+use borsh::BorshDeserialize;
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{ instruction::Instruction, pubkey::Pubkey, signature::{ Keypair, Signer }, transaction::Transaction };
+
+use solana_voting_program::{
+    client::{
+        cast_envelope,
+        claim_slot,
+        commit_vote,
+        create_voting,
+        dry_run,
+        set_envelope_key,
+        set_reveal_window,
+        set_signup_slots,
+        vote,
+        vote_by_transfer,
+        VoteProofs
+    },
+    find_slot_claim_pda,
+    find_user_vote_pda,
+    find_voting_pda,
+    Errors,
+    VoteMainAccount,
+    VotePreviewReturnData,
+    ENVELOPE_OVERHEAD
+};
+use common::{ assert_error, funded, program_test, results, send };
+
+const DURATION: u64 = 24 * 60 * 60;
+const TITLE: &str = "Lunch on Fridays";
+
+// Return data of simulating `instructions`
+async fn preview(context: &mut ProgramTestContext, instructions: &[Instruction], voter: &Keypair) -> VotePreviewReturnData {
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &[ &context.payer, voter ],
+        context.last_blockhash
+    );
+
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    simulation.result.unwrap().unwrap();
+    let return_data = simulation.simulation_details.and_then(|details| details.return_data).expect("no return data");
+
+    VotePreviewReturnData::try_from_slice(&return_data.data).unwrap()
+}
+
+async fn user_vote_exists(context: &mut ProgramTestContext, voting: &Pubkey, voter: &Keypair) -> bool {
+    let user_vote = find_user_vote_pda(voting, &voter.pubkey(), &solana_voting_program::id()).0;
+    context.banks_client.get_account(user_vote).await.unwrap().is_some()
+}
+
+#[tokio::test]
+async fn dry_run_ballots_preview_the_tallies() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    let ballot = |voter: &Keypair, option: u8| vote(&program_id, &voter.pubkey(), &voting, vec![ option ], TITLE.to_string(), false, VoteProofs::default());
+    send(&mut context, &[ ballot(&voters[0], 1) ], &[ &voters[0] ]).await.unwrap();
+
+    let preview = preview(&mut context, &[ dry_run(ballot(&voters[1], 0)) ], &voters[1]).await;
+    assert_eq!(preview.weight, 1);
+    assert_eq!(preview.tallies, vec![ 1, 1 ]);
+    assert!(user_vote_exists(&mut context, &voting, &voters[1]).await == false);
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 0, 1 ]);
+
+    // The checks of the ballot still apply
+    assert_error(send(&mut context, &[ dry_run(ballot(&voters[0], 0)) ], &[ &voters[0] ]).await, Errors::UserAlreadyVoted);
+}
+
+#[tokio::test]
+async fn dry_run_envelopes_preview_the_weight() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_envelope_key(create, [ 7; 32 ]) ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    let envelope = cast_envelope(&program_id, &voter.pubkey(), &voting, vec![ 1; ENVELOPE_OVERHEAD + 1 ], TITLE.to_string(), VoteProofs::default());
+
+    // Sealed ballots aren't counted yet, the preview has no tallies
+    let preview = preview(&mut context, &[ dry_run(envelope.clone()) ], voter).await;
+    assert_eq!(preview.weight, 1);
+    assert!(preview.tallies.is_empty());
+    assert!(user_vote_exists(&mut context, &voting, voter).await == false);
+
+    // A version 2 envelope has no `dry_run` and is cast
+    let mut older = dry_run(envelope);
+    older.data[8] = 2;
+    older.data.pop();
+    send(&mut context, &[ older ], &[ voter ]).await.unwrap();
+    assert!(user_vote_exists(&mut context, &voting, voter).await);
+
+    let voting_account = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    assert_eq!(try_from_slice_unchecked::<VoteMainAccount>(&voting_account.data).unwrap().envelope_weight, 1);
+}
+
+#[tokio::test]
+async fn dry_run_commitments_preview_the_weight() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_reveal_window(create, 60) ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    let commit = commit_vote(&program_id, &voter.pubkey(), &voting, &[ 1 ], &[ 1; 32 ], TITLE.to_string(), VoteProofs::default());
+
+    // Committed ballots aren't counted until revealed, the preview has no tallies
+    let preview = preview(&mut context, &[ dry_run(commit.clone()) ], voter).await;
+    assert_eq!(preview.weight, 1);
+    assert!(preview.tallies.is_empty());
+    assert!(user_vote_exists(&mut context, &voting, voter).await == false);
+
+    let again = dry_run(commit.clone());
+    send(&mut context, &[ commit ], &[ voter ]).await.unwrap();
+    assert_error(send(&mut context, &[ again ], &[ voter ]).await, Errors::UserAlreadyVoted);
+}
+
+#[tokio::test]
+async fn dry_run_slot_claims_leave_the_slots() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_signup_slots(create, 2) ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    let claim = claim_slot(&program_id, &voter.pubkey(), &voting, 0, None);
+    let preview = preview(&mut context, &[ dry_run(claim.clone()) ], voter).await;
+    assert_eq!(preview.weight, 1);
+    assert_eq!(preview.tallies, vec![ 0, 0 ]);
+
+    let slot_claim = find_slot_claim_pda(&voting, &voter.pubkey(), &program_id).0;
+    assert!(context.banks_client.get_account(slot_claim).await.unwrap().is_none());
+    let voting_account = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    assert_eq!(try_from_slice_unchecked::<VoteMainAccount>(&voting_account.data).unwrap().claimed_slots, 0);
+
+    send(&mut context, &[ claim ], &[ voter ]).await.unwrap();
+    let claim = claim_slot(&program_id, &voter.pubkey(), &voting, 1, None);
+    assert_error(send(&mut context, &[ dry_run(claim) ], &[ voter ]).await, Errors::UserAlreadyVoted);
+}
+
+#[tokio::test]
+async fn dry_run_transfer_claims_preview_the_tallies() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    // The transfer and memo go through, the claim only previews the ballot they carry
+    let mut instructions = vote_by_transfer(&program_id, &voter.pubkey(), &voting, &[ 1 ], 1_000_000, TITLE.to_string(), VoteProofs::default());
+    let claim = instructions.pop().unwrap();
+    instructions.push(dry_run(claim));
+
+    let preview = preview(&mut context, &instructions, voter).await;
+    assert_eq!(preview.weight, 1);
+    assert_eq!(preview.tallies, vec![ 0, 1 ]);
+    assert!(user_vote_exists(&mut context, &voting, voter).await == false);
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 0, 0 ]);
+}
@@ -0,0 +1,67 @@
+// Quorums set against an electorate are shares of it, not of the voters who showed up
+mod common;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, set_electorate, vote, VoteProofs },
+    find_voting_pda,
+    Electorate,
+    Errors,
+    VotingOutcome
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+
+// A voting needing `quorum_bps` of `electorate`, with a ballot for each of `ballots`
+async fn voting(context: &mut ProgramTestContext, creator: &Keypair, voters: &[Keypair], title: &str, electorate: Electorate, quorum_bps: u16, ballots: &[Vec<u8>]) {
+    let program_id = solana_voting_program::id();
+    let voting = find_voting_pda(title, &program_id).0;
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(context, &[ set_electorate(create, electorate, quorum_bps) ], &[ creator ]).await.unwrap();
+
+    for (voter, ballot) in voters.iter().zip(ballots) {
+        send(context, &[
+            vote(&program_id, &voter.pubkey(), &voting, ballot.clone(), title.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+}
+
+#[tokio::test]
+async fn quorum_is_measured_against_the_electorate() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), "Lunch on Tuesdays".to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    let result = send(&mut context, &[ set_electorate(create, Electorate::Voters(0), 1_000) ], &[ creator ]).await;
+    assert_error(result, Errors::InvalidElectorate);
+
+    // Spoiled ballots count towards the turnout, by voter or by weight
+    let cases = [
+        ("Lunch on Fridays", Electorate::Voters(10), vec![ vec![ 1 ], vec![ 1 ] ], VotingOutcome::QuorumNotMet, 2_000),
+        ("Lunch on Mondays", Electorate::Voters(10), vec![ vec![ 1 ], vec![ 1 ], vec![] ], VotingOutcome::Passed, 3_000),
+        ("Lunch on Sundays", Electorate::Weight(4), vec![ vec![ 0 ], vec![] ], VotingOutcome::Passed, 5_000)
+    ];
+    for (title, electorate, ballots, _, _) in cases.iter() {
+        let quorum_bps = match electorate {
+            Electorate::Voters(_) => 3_000,
+            Electorate::Weight(_) => 5_000
+        };
+        voting(&mut context, creator, voters, title, *electorate, quorum_bps, ballots).await;
+    };
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    for (title, _, _, outcome, turnout_bps) in cases.iter() {
+        let voting = find_voting_pda(title, &program_id).0;
+        send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+        let results = results(&mut context, &voting).await;
+        assert_eq!(results.outcome, *outcome, "{}", title);
+        assert_eq!(results.turnout_bps, Some(*turnout_bps), "{}", title);
+    };
+}
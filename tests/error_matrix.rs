@@ -0,0 +1,356 @@
+// Error codes clients match on. Each handler below is first checked to go through as built, then every
+// precondition it relies on is broken one at a time: a signer that didn't sign, a program account owned
+// by another program, a PDA swapped for another address holding the same data, an account of the wrong
+// kind, data cut short. Each has to fail with the same error on every handler.
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program::{
+    borsh0_10::try_from_slice_unchecked,
+    instruction::{ Instruction, InstructionError },
+    pubkey::Pubkey,
+    system_program,
+    sysvar
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{ Keypair, Signer },
+    transaction::{ Transaction, TransactionError }
+};
+
+use solana_voting_program::{
+    client::{
+        cancel_voting,
+        clone_voting,
+        close_user_vote,
+        create_voting,
+        extend_voting,
+        finalize_voting,
+        flag_voting,
+        freeze_voting,
+        fund_incentives,
+        get_creator_summary,
+        get_percentages,
+        get_results,
+        get_vote_weight,
+        reserve_title,
+        set_localized_metadata,
+        sync_poll_card,
+        sync_status,
+        update_vote,
+        vote,
+        VoteProofs
+    },
+    find_user_vote_pda,
+    find_voting_pda,
+    AccountType,
+    Errors,
+    InstructionType,
+    VoteMainAccount,
+    VotingStatus
+};
+use common::{ funded, now, program_test, send, warp_to };
+
+const ACTIVE: &str = "Lunch on Fridays";
+const FINALIZED: &str = "Breakfast on Mondays";
+const ENDED: &str = "Dinner on Wednesdays";
+const DURATION: u64 = 24 * 60 * 60;
+const SHORT_DURATION: u64 = 60;
+
+// A voting taking ballots, one that ended and one already finalized. The voter voted on all three.
+struct World {
+    creator: Keypair,
+    voter: Keypair,
+    newcomer: Keypair,
+    active: Pubkey,
+    ended: Pubkey,
+    finalized: Pubkey,
+    now: u64
+}
+
+impl World {
+    fn signers(&self) -> [&Keypair; 3] {
+        [ &self.creator, &self.voter, &self.newcomer ]
+    }
+}
+
+async fn world(context: &mut ProgramTestContext) -> World {
+    let program_id = solana_voting_program::id();
+    let mut keypairs = funded(context, 3).await.into_iter();
+    let (creator, voter, newcomer) = (keypairs.next().unwrap(), keypairs.next().unwrap(), keypairs.next().unwrap());
+
+    let options = || vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    for (title, duration) in [ (ACTIVE, DURATION), (FINALIZED, SHORT_DURATION), (ENDED, SHORT_DURATION) ] {
+        let voting = find_voting_pda(title, &program_id).0;
+        send(context, &[
+            create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options(), duration),
+            sync_poll_card(&program_id, &creator.pubkey(), &voting)
+        ], &[ &creator ]).await.unwrap();
+        send(context, &[
+            vote(&program_id, &voter.pubkey(), &voting, vec![ 0 ], title.to_string(), false, VoteProofs::default())
+        ], &[ &voter ]).await.unwrap();
+    };
+
+    let finalized = find_voting_pda(FINALIZED, &program_id).0;
+    let now = now(context).await + SHORT_DURATION + 1;
+    warp_to(context, now).await;
+    send(context, &[ finalize_voting(&program_id, &finalized, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    World {
+        creator,
+        voter,
+        newcomer,
+        active: find_voting_pda(ACTIVE, &program_id).0,
+        ended: find_voting_pda(ENDED, &program_id).0,
+        finalized,
+        now
+    }
+}
+
+fn handlers(world: &World) -> Vec<(&'static str, Instruction)> {
+    let program_id = solana_voting_program::id();
+    let creator = world.creator.pubkey();
+    let voter = world.voter.pubkey();
+    let newcomer = world.newcomer.pubkey();
+
+    vec![
+        ("create_voting", create_voting(
+            &program_id,
+            &creator,
+            "Coffee on Tuesdays".to_string(),
+            String::new(),
+            vec![ "Yes".to_string(), "No".to_string() ],
+            DURATION
+        )),
+        ("vote", vote(&program_id, &newcomer, &world.active, vec![ 1 ], ACTIVE.to_string(), false, VoteProofs::default())),
+        ("update_vote", update_vote(&program_id, &voter, &world.active, vec![ 1 ], false)),
+        ("get_vote_weight", get_vote_weight(&program_id, &newcomer, &world.active, None)),
+        ("get_results", get_results(&program_id, &world.active)),
+        ("get_percentages", get_percentages(&program_id, &world.active, 2)),
+        ("get_creator_summary", get_creator_summary(&program_id, &creator)),
+        ("fund_incentives", fund_incentives(&program_id, &creator, &world.active, 1_000, 10_000)),
+        ("set_localized_metadata", set_localized_metadata(
+            &program_id,
+            &creator,
+            &world.active,
+            "de",
+            "Mittagessen am Freitag".to_string(),
+            String::new()
+        )),
+        ("flag_voting", flag_voting(&program_id, &newcomer, &world.active)),
+        ("extend_voting", extend_voting(&program_id, &creator, &world.active, None, world.now + DURATION)),
+        ("freeze_voting", freeze_voting(&program_id, &creator, &world.active, None)),
+        ("cancel_voting", cancel_voting(&program_id, &creator, &world.active)),
+        ("sync_status", sync_status(&program_id, &world.ended)),
+        ("sync_poll_card", sync_poll_card(&program_id, &creator, &world.active)),
+        ("clone_voting", clone_voting(&program_id, &creator, &world.active, "Lunch on Saturdays".to_string(), world.now + 10, world.now + DURATION, &[])),
+        ("reserve_title", reserve_title(&program_id, &newcomer, "Coffee on Thursdays")),
+        ("finalize_voting", finalize_voting(&program_id, &world.ended, &creator, &creator)),
+        ("close_user_vote", close_user_vote(&program_id, &voter, &world.finalized))
+    ]
+}
+
+#[derive(Debug)]
+enum Mutation {
+    // The account at this position doesn't sign
+    MissingSigner(usize),
+    // The account at this position is owned by the system program instead
+    WrongOwner(usize),
+    // Another address holding the same account replaces the PDA at this position
+    BadPda(usize),
+    // The account at this position holds another kind of account
+    WrongDiscriminator(usize),
+    // The data stops inside the discriminator
+    ShortData,
+    // The payload is one byte short of its layout, the header alone without its version byte
+    ShortPayload
+}
+
+impl Mutation {
+    fn expected(&self) -> InstructionError {
+        let custom = |error: Errors| InstructionError::Custom(error.code());
+        match self {
+            Self::MissingSigner(_) => custom(Errors::UserSigningNeeded),
+            Self::WrongOwner(_) => custom(Errors::InvalidAccountOwner),
+            Self::BadPda(_) => custom(Errors::InvalidPdaAddress),
+            Self::WrongDiscriminator(_) => InstructionError::InvalidAccountData,
+            Self::ShortData | Self::ShortPayload => custom(Errors::InstructionDataTooShort)
+        }
+    }
+}
+
+// Every precondition of `instruction` that can be broken on its own
+async fn mutations(context: &mut ProgramTestContext, instruction: &Instruction) -> Vec<Mutation> {
+    let mut mutations = vec![ Mutation::ShortData, Mutation::ShortPayload ];
+    for (position, meta) in instruction.accounts.iter().enumerate() {
+        if meta.is_signer {
+            mutations.push(Mutation::MissingSigner(position));
+            continue;
+        };
+
+        match context.banks_client.get_account(meta.pubkey).await.unwrap() {
+            Some(account) if account.owner == solana_voting_program::id() => {
+                mutations.push(Mutation::WrongOwner(position));
+                mutations.push(Mutation::BadPda(position));
+                mutations.push(Mutation::WrongDiscriminator(position));
+            },
+            // Created by the instruction, only its address can be wrong
+            None if meta.pubkey.is_on_curve() == false && sysvar::is_sysvar_id(&meta.pubkey) == false => {
+                mutations.push(Mutation::BadPda(position));
+            },
+            _ => ()
+        };
+    };
+
+    mutations
+}
+
+// The transaction outcome, without landing it
+async fn simulate(context: &mut ProgramTestContext, world: &World, instruction: Instruction) -> Result<(), TransactionError> {
+    let mut signers = vec![ &context.payer ];
+    signers.extend(world.signers().into_iter().filter(|signer| {
+        instruction.accounts.iter().any(|meta| meta.is_signer && meta.pubkey == signer.pubkey())
+    }));
+
+    let transaction = Transaction::new_signed_with_payer(&[ instruction ], Some(&context.payer.pubkey()), &signers, context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+
+    simulation.result.expect("transaction not simulated")
+}
+
+async fn mutated_error(context: &mut ProgramTestContext, world: &World, instruction: &Instruction, mutation: &Mutation) -> Result<(), TransactionError> {
+    let mut instruction = instruction.clone();
+    match *mutation {
+        Mutation::MissingSigner(position) => instruction.accounts[position].is_signer = false,
+        Mutation::ShortData => instruction.data.truncate(7),
+        Mutation::ShortPayload => {
+            instruction.data.pop();
+        },
+        Mutation::BadPda(position) => {
+            let original = instruction.accounts[position].pubkey;
+            let impostor = Pubkey::new_unique();
+            if let Some(account) = context.banks_client.get_account(original).await.unwrap() {
+                context.set_account(&impostor, &account.into());
+            };
+            instruction.accounts[position].pubkey = impostor;
+        },
+        Mutation::WrongOwner(position) | Mutation::WrongDiscriminator(position) => {
+            let address = instruction.accounts[position].pubkey;
+            let original = context.banks_client.get_account(address).await.unwrap().unwrap();
+            let mut mutated = original.clone();
+            match mutation {
+                Mutation::WrongOwner(_) => mutated.owner = system_program::ID,
+                _ => {
+                    let other = if mutated.data[..8] == AccountType::Flag.discriminator() { AccountType::Slot } else { AccountType::Flag };
+                    mutated.data[..8].copy_from_slice(&other.discriminator());
+                }
+            };
+
+            context.set_account(&address, &mutated.into());
+            let result = simulate(context, world, instruction).await;
+            context.set_account(&address, &original.into());
+
+            return result;
+        }
+    };
+
+    simulate(context, world, instruction).await
+}
+
+#[tokio::test]
+async fn every_broken_precondition_has_its_error() {
+    let mut context = program_test().start_with_context().await;
+    let world = world(&mut context).await;
+
+    let mut mismatches = Vec::new();
+    for (name, instruction) in handlers(&world) {
+        if let Err(error) = simulate(&mut context, &world, instruction.clone()).await {
+            panic!("{} fails before any mutation: {:?}", name, error);
+        };
+
+        for mutation in mutations(&mut context, &instruction).await {
+            let expected = TransactionError::InstructionError(0, mutation.expected());
+            let result = mutated_error(&mut context, &world, &instruction, &mutation).await;
+            if result != Err(expected.clone()) {
+                mismatches.push(format!("{} {:?}: expected {:?}, got {:?}", name, mutation, expected, result));
+            };
+        };
+    };
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+// The data checks come before anything else, no accounts needed
+#[tokio::test]
+async fn malformed_data_is_rejected_for_every_instruction() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+
+    for instruction_type in InstructionType::ALL {
+        let short = Instruction { program_id, accounts: Vec::new(), data: instruction_type.discriminator()[..7].to_vec() };
+        let result = send(&mut context, &[ short ], &[]).await;
+        assert_eq!(common::error(result), InstructionError::Custom(Errors::InstructionDataTooShort.code()), "{:?}", instruction_type);
+
+        // Without the payload version
+        let unversioned = Instruction { program_id, accounts: Vec::new(), data: instruction_type.discriminator().to_vec() };
+        let result = send(&mut context, &[ unversioned ], &[]).await;
+        assert_eq!(common::error(result), InstructionError::Custom(Errors::InstructionDataTooShort.code()), "{:?}", instruction_type);
+    };
+
+    let unknown = Instruction { program_id, accounts: Vec::new(), data: vec![ 0xff; 8 ] };
+    assert_eq!(common::error(send(&mut context, &[ unknown ], &[]).await), InstructionError::InvalidInstructionData);
+}
+
+// Ballots are only cast and changed while the voting runs
+#[tokio::test]
+async fn ballots_need_a_running_voting() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let world = world(&mut context).await;
+
+    let ballots = |voting: &Pubkey, title: &str| [
+        vote(&program_id, &world.newcomer.pubkey(), voting, vec![ 1 ], title.to_string(), false, VoteProofs::default()),
+        update_vote(&program_id, &world.voter.pubkey(), voting, vec![ 1 ], false)
+    ];
+    let expected = |error: Errors| Err(TransactionError::InstructionError(0, InstructionError::Custom(error.code())));
+
+    for (voting, title, error) in [ (&world.ended, ENDED, Errors::VotingEnded), (&world.finalized, FINALIZED, Errors::VotingFinalized) ] {
+        for instruction in ballots(voting, title) {
+            assert_eq!(simulate(&mut context, &world, instruction).await, expected(error), "{}", title);
+        };
+    };
+
+    // The running voting stands in for a cancelled and a draft one
+    let original = context.banks_client.get_account(world.active).await.unwrap().unwrap();
+    for (status, error) in [ (VotingStatus::Cancelled, Errors::VotingCancelled), (VotingStatus::Draft, Errors::VotingIsDraft) ] {
+        let mut voting_account = try_from_slice_unchecked::<VoteMainAccount>(&original.data).unwrap();
+        voting_account.status = status;
+        let mut mutated = original.clone();
+        voting_account.serialize(&mut &mut mutated.data[..]).unwrap();
+        context.set_account(&world.active, &mutated.into());
+
+        for instruction in ballots(&world.active, ACTIVE) {
+            assert_eq!(simulate(&mut context, &world, instruction).await, expected(error), "{:?}", status);
+        };
+    };
+    context.set_account(&world.active, &original.into());
+}
+
+// The user vote account at the signer's PDA has to be their own and writable
+#[tokio::test]
+async fn ballots_are_updated_in_the_voters_own_record() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let world = world(&mut context).await;
+    let expected = |error: Errors| Err(TransactionError::InstructionError(0, InstructionError::Custom(error.code())));
+
+    // The voter's record copied to the newcomer's address still names the voter
+    let record = context.banks_client.get_account(find_user_vote_pda(&world.active, &world.voter.pubkey(), &program_id).0).await.unwrap().unwrap();
+    context.set_account(&find_user_vote_pda(&world.active, &world.newcomer.pubkey(), &program_id).0, &record.into());
+    let update = update_vote(&program_id, &world.newcomer.pubkey(), &world.active, vec![ 1 ], false);
+    assert_eq!(simulate(&mut context, &world, update).await, expected(Errors::NotVoteRecordOwner));
+
+    let mut update = update_vote(&program_id, &world.voter.pubkey(), &world.active, vec![ 1 ], false);
+    update.accounts[3].is_writable = false;
+    assert_eq!(simulate(&mut context, &world, update).await, expected(Errors::PDAsAccountMustBeMutable));
+}
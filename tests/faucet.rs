@@ -0,0 +1,61 @@
+// `claim_faucet` pays a wallet without lamports the rent of its first vote, once a day
+#![cfg(feature = "devnet")]
+
+mod common;
+
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{ claim_faucet, create_voting, fund_faucet, vote, VoteProofs },
+    faucet_drip,
+    find_voting_pda,
+    Errors,
+    FAUCET_COOLDOWN
+};
+use common::{ assert_error, funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 7 * 24 * 60 * 60;
+const TITLES: [&str; 2] = [ "Lunch on Fridays", "Lunch on Mondays" ];
+
+#[tokio::test]
+async fn first_time_voters_claim_their_rent() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1).await;
+    let creator = &accounts[0];
+
+    for title in TITLES {
+        let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+        send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    };
+    let votings = TITLES.map(|title| find_voting_pda(title, &program_id).0);
+
+    let wallet = Keypair::new();
+    let result = send(&mut context, &[ claim_faucet(&program_id, &wallet.pubkey(), &votings[0]) ], &[ &wallet ]).await;
+    assert_error(result, Errors::FaucetEmpty);
+
+    let payer = context.payer.pubkey();
+    send(&mut context, &[ fund_faucet(&program_id, &payer, 100_000_000) ], &[]).await.unwrap();
+    send(&mut context, &[ claim_faucet(&program_id, &wallet.pubkey(), &votings[0]) ], &[ &wallet ]).await.unwrap();
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(context.banks_client.get_balance(wallet.pubkey()).await.unwrap(), faucet_drip(&rent));
+
+    // The claim is enough for the vote, the context's payer only pays the fee
+    send(&mut context, &[
+        vote(&program_id, &wallet.pubkey(), &votings[0], vec![ 1 ], TITLES[0].to_string(), false, VoteProofs::default())
+    ], &[ &wallet ]).await.unwrap();
+
+    let result = send(&mut context, &[ claim_faucet(&program_id, &wallet.pubkey(), &votings[0]) ], &[ &wallet ]).await;
+    assert_error(result, Errors::UserAlreadyVoted);
+
+    let result = send(&mut context, &[ claim_faucet(&program_id, &wallet.pubkey(), &votings[1]) ], &[ &wallet ]).await;
+    assert_error(result, Errors::FaucetCooldown);
+
+    let next_day = now(&mut context).await + FAUCET_COOLDOWN;
+    warp_to(&mut context, next_day).await;
+    let balance = context.banks_client.get_balance(wallet.pubkey()).await.unwrap();
+    send(&mut context, &[ claim_faucet(&program_id, &wallet.pubkey(), &votings[1]) ], &[ &wallet ]).await.unwrap();
+
+    assert_eq!(context.banks_client.get_balance(wallet.pubkey()).await.unwrap(), balance + faucet_drip(&rent));
+}
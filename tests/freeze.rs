@@ -0,0 +1,38 @@
+// Freezing sets `frozen_at` in place, in the room the voting reserved for it on creation
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, freeze_voting, sync_poll_card, unfreeze_voting, update_vote, vote, VoteProofs },
+    find_voting_pda,
+    Errors
+};
+use common::{ assert_error, funded, program_test, send };
+
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn voting_freezes_after_ballots() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options, 24 * 60 * 60),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+    send(&mut context, &[
+        vote(&program_id, &voter.pubkey(), &voting, vec![ 0 ], TITLE.to_string(), false, VoteProofs::default())
+    ], &[ voter ]).await.unwrap();
+
+    send(&mut context, &[ freeze_voting(&program_id, &creator.pubkey(), &voting, None) ], &[ creator ]).await.unwrap();
+    let result = send(&mut context, &[ update_vote(&program_id, &voter.pubkey(), &voting, vec![ 1 ], false) ], &[ voter ]).await;
+    assert_error(result, Errors::VotingFrozen);
+
+    send(&mut context, &[ unfreeze_voting(&program_id, &creator.pubkey(), &voting, None) ], &[ creator ]).await.unwrap();
+    send(&mut context, &[ update_vote(&program_id, &voter.pubkey(), &voting, vec![ 1 ], false) ], &[ voter ]).await.unwrap();
+}
@@ -0,0 +1,64 @@
+// Votings ended `GC_DELAY` ago are swept by anyone, the bounty paid out of the rent they free
+mod common;
+
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, gc_expired, vote, VoteProofs },
+    find_creator_stats_pda,
+    find_user_vote_pda,
+    find_voting_pda,
+    AccountType,
+    CreatorStatsAccount,
+    Errors,
+    GC_BOUNTY_BPS,
+    GC_DELAY
+};
+use common::{ assert_error, funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn expired_votings_are_swept_for_a_bounty() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voter, caller) = (&accounts[0], &accounts[1], &accounts[2]);
+
+    let ends_at = now(&mut context).await + DURATION;
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    send(&mut context, &[
+        vote(&program_id, &voter.pubkey(), &voting, vec![ 0 ], TITLE.to_string(), false, VoteProofs::default())
+    ], &[ voter ]).await.unwrap();
+
+    let sweep = || gc_expired(&program_id, &caller.pubkey(), &voting, &creator.pubkey(), &creator.pubkey(), &[ voter.pubkey() ]);
+    warp_to(&mut context, ends_at + GC_DELAY - 1).await;
+    assert_error(send(&mut context, &[ sweep() ], &[ caller ]).await, Errors::VotingNotExpired);
+
+    let user_vote = find_user_vote_pda(&voting, &voter.pubkey(), &program_id).0;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let voting_lamports = context.banks_client.get_balance(voting).await.unwrap() - rent.minimum_balance(8);
+    let record_lamports = context.banks_client.get_balance(user_vote).await.unwrap();
+    let caller_before = context.banks_client.get_balance(caller.pubkey()).await.unwrap();
+
+    warp_to(&mut context, ends_at + GC_DELAY).await;
+    send(&mut context, &[ sweep() ], &[ caller ]).await.unwrap();
+
+    let bounty = |lamports: u64| lamports * GC_BOUNTY_BPS / 10_000;
+    let caller_after = context.banks_client.get_balance(caller.pubkey()).await.unwrap();
+    assert_eq!(caller_after - caller_before, bounty(voting_lamports) + bounty(record_lamports));
+
+    // A tombstone keeps the address taken, the record is closed
+    let tombstone = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    assert_eq!(tombstone.data, AccountType::ArchivedVoting.discriminator().to_vec());
+    assert!(context.banks_client.get_account(user_vote).await.unwrap().is_none());
+
+    // Never finalized, so it no longer counts as open
+    let creator_stats = context.banks_client.get_account(find_creator_stats_pda(&creator.pubkey(), &program_id).0).await.unwrap().unwrap();
+    assert_eq!(try_from_slice_unchecked::<CreatorStatsAccount>(&creator_stats.data).unwrap().active_votings, 0);
+}
@@ -0,0 +1,56 @@
+// Each ballot is paid from the voting's incentive pool while it lasts, the rest goes back to the creator
+mod common;
+
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+use solana_voting_program::{
+    client::{ add_incentive_pool, create_voting, fund_incentives, vote, withdraw_incentives, VoteProofs },
+    find_incentive_pool_pda,
+    find_voting_pda,
+    Errors,
+    IncentivePoolAccount
+};
+use common::{ assert_error, funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+const PER_VOTE: u64 = 1_000;
+
+#[tokio::test]
+async fn ballots_are_paid_until_the_pool_runs_out() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    send(&mut context, &[ create, fund_incentives(&program_id, &creator.pubkey(), &voting, PER_VOTE, 2 * PER_VOTE + 500) ], &[ creator ]).await.unwrap();
+
+    // The third ballot still counts, unpaid
+    for voter in voters.iter() {
+        let mut ballot = vote(&program_id, &voter.pubkey(), &voting, vec![ 0 ], TITLE.to_string(), false, VoteProofs::default());
+        add_incentive_pool(&mut ballot, &program_id, &voting);
+        send(&mut context, &[ ballot ], &[ voter ]).await.unwrap();
+    };
+
+    let pool = find_incentive_pool_pda(&voting, &program_id).0;
+    let pool_account = context.banks_client.get_account(pool).await.unwrap().unwrap();
+    let pool_data = try_from_slice_unchecked::<IncentivePoolAccount>(&pool_account.data).unwrap();
+    assert_eq!((pool_data.total_funded, pool_data.total_paid), (2 * PER_VOTE + 500, 2 * PER_VOTE));
+
+    let withdraw = |user: &Pubkey| withdraw_incentives(&program_id, user, &voting);
+    assert_error(send(&mut context, &[ withdraw(&creator.pubkey()) ], &[ creator ]).await, Errors::VotingNotEnded);
+    assert_error(send(&mut context, &[ withdraw(&voters[0].pubkey()) ], &[ &voters[0] ]).await, Errors::NotVotingCreator);
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    let creator_before = context.banks_client.get_balance(creator.pubkey()).await.unwrap();
+    send(&mut context, &[ withdraw(&creator.pubkey()) ], &[ creator ]).await.unwrap();
+
+    // The unpaid lamports and the pool's rent
+    let creator_after = context.banks_client.get_balance(creator.pubkey()).await.unwrap();
+    assert_eq!(creator_after - creator_before, pool_account.lamports);
+    assert!(context.banks_client.get_account(pool).await.unwrap().is_none());
+}
@@ -0,0 +1,58 @@
+// Accounts in a layout this program doesn't know: loaders turn them away and `migrate_account` has
+// nothing to move them to.
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, migrate_account, sync_status },
+    find_voting_pda,
+    state::StateVersion,
+    Errors
+};
+use common::{ assert_error, funded, program_test, send };
+
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn unknown_version_is_rejected() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let creator = &funded(&mut context, 1).await[0];
+
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options, 24 * 60 * 60)
+    ], &[ creator ]).await.unwrap();
+
+    // The version byte follows the discriminator
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let mut account = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    account.data[8] = StateVersion::CURRENT as u8 + 1;
+    context.set_account(&voting, &account.into());
+
+    assert_error(send(&mut context, &[ sync_status(&program_id, &voting) ], &[]).await, Errors::UnknownAccountVersion);
+    assert_error(
+        send(&mut context, &[ migrate_account(&program_id, &creator.pubkey(), &voting) ], &[ creator ]).await,
+        Errors::UnknownAccountVersion
+    );
+}
+
+#[tokio::test]
+async fn current_version_is_already_migrated() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let creator = &funded(&mut context, 1).await[0];
+
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options, 24 * 60 * 60)
+    ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    assert_error(
+        send(&mut context, &[ migrate_account(&program_id, &creator.pubkey(), &voting) ], &[ creator ]).await,
+        Errors::AccountAlreadyMigrated
+    );
+    send(&mut context, &[ sync_status(&program_id, &voting) ], &[]).await.unwrap();
+}
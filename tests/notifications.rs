@@ -0,0 +1,49 @@
+// `set_notification_target` keeps where the creator wants to hear about a voting, for off-chain notifiers
+mod common;
+
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{ create_voting, set_notification_target },
+    decoder::{ decode_account, DecodedAccount },
+    find_notification_target_pda,
+    find_voting_pda,
+    Errors
+};
+use common::{ assert_error, funded, program_test, send };
+
+const DURATION: u64 = 24 * 60 * 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn creators_register_notification_targets() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, stranger) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let target = find_notification_target_pda(&voting, &program_id).0;
+
+    let inbox = Keypair::new().pubkey();
+    let result = send(&mut context, &[ set_notification_target(&program_id, &stranger.pubkey(), &voting, None, Some(inbox)) ], &[ stranger ]).await;
+    assert_error(result, Errors::NotVotingCreator);
+
+    // Replacing the webhook with an inbox leaves nothing of the webhook behind
+    send(&mut context, &[ set_notification_target(&program_id, &creator.pubkey(), &voting, Some([ 7; 32 ]), None) ], &[ creator ]).await.unwrap();
+    send(&mut context, &[ set_notification_target(&program_id, &creator.pubkey(), &voting, None, Some(inbox)) ], &[ creator ]).await.unwrap();
+
+    let account = context.banks_client.get_account(target).await.unwrap().unwrap();
+    let target_account = match decode_account(&program_id, &account.owner, &account.data).unwrap() {
+        Some(DecodedAccount::NotificationTarget(target_account)) => target_account,
+        decoded => panic!("not a notification target: {:?}", decoded)
+    };
+    assert_eq!(target_account.voting, voting);
+    assert_eq!(target_account.webhook_commitment, None);
+    assert_eq!(target_account.notify, Some(inbox));
+
+    send(&mut context, &[ set_notification_target(&program_id, &creator.pubkey(), &voting, None, None) ], &[ creator ]).await.unwrap();
+    assert!(context.banks_client.get_account(target).await.unwrap().is_none());
+}
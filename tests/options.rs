@@ -0,0 +1,43 @@
+// Votings take between `MIN_OPTIONS` and `MAX_OPTIONS` options, ballots pick them by index
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, vote, VoteProofs },
+    find_voting_pda,
+    Errors,
+    MAX_OPTIONS,
+    MIN_OPTIONS
+};
+use common::{ assert_error, funded, program_test, results, send };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn ballots_pick_an_option_by_index() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let options = |count: usize| (0..count).map(|option| format!("Option {}", option)).collect::<Vec<_>>();
+    let create = |count: usize| create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options(count), DURATION);
+    assert_error(send(&mut context, &[ create(MIN_OPTIONS - 1) ], &[ creator ]).await, Errors::InvalidOptionsCount);
+    assert_error(send(&mut context, &[ create(MAX_OPTIONS + 1) ], &[ creator ]).await, Errors::TooManyOptions);
+    send(&mut context, &[ create(MAX_OPTIONS) ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let ballot = |option: usize| vote(&program_id, &voters[0].pubkey(), &voting, vec![ option as u8 ], TITLE.to_string(), false, VoteProofs::default());
+    assert_error(send(&mut context, &[ ballot(MAX_OPTIONS) ], &[ &voters[0] ]).await, Errors::InvalidOptionIndex);
+    send(&mut context, &[ ballot(MAX_OPTIONS - 1) ], &[ &voters[0] ]).await.unwrap();
+    send(&mut context, &[
+        vote(&program_id, &voters[1].pubkey(), &voting, vec![ 3 ], TITLE.to_string(), false, VoteProofs::default())
+    ], &[ &voters[1] ]).await.unwrap();
+
+    let mut tallies = vec![ 0; MAX_OPTIONS ];
+    tallies[3] = 1;
+    tallies[MAX_OPTIONS - 1] = 1;
+    assert_eq!(results(&mut context, &voting).await.tallies, tallies);
+}
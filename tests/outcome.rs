@@ -0,0 +1,89 @@
+// Finalization records the outcome against the voting's quorum and pass threshold
+mod common;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, set_pass_rules, sync_poll_card, update_vote, vote, VoteProofs },
+    find_voting_pda,
+    Errors,
+    VotingOutcome
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+const OPTIONS: [&str; 3] = [ "Pizza", "Sushi", "Tacos" ];
+
+// A voting needing 3 voters and 60% of the votes, with a ballot for each of `ballots`
+async fn voting(context: &mut ProgramTestContext, creator: &Keypair, voters: &[Keypair], title: &str, ballots: &[u8]) {
+    let program_id = solana_voting_program::id();
+    let voting = find_voting_pda(title, &program_id).0;
+    let options = OPTIONS.iter().map(|option| option.to_string()).collect();
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options, DURATION);
+    send(context, &[
+        set_pass_rules(create, 3, 6_000),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+
+    for (voter, option) in voters.iter().zip(ballots) {
+        send(context, &[
+            vote(&program_id, &voter.pubkey(), &voting, vec![ *option ], title.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+}
+
+#[tokio::test]
+async fn finalization_records_the_outcome() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let cases = [
+        ("Lunch on Fridays", vec![ 1, 1, 0 ], VotingOutcome::Passed, Some(1)),
+        ("Lunch on Mondays", vec![ 0, 0 ], VotingOutcome::QuorumNotMet, None),
+        ("Lunch on Sundays", vec![ 0, 1, 2 ], VotingOutcome::Rejected, None)
+    ];
+    for (title, ballots, _, _) in cases.iter() {
+        voting(&mut context, creator, voters, title, ballots).await;
+    };
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    for (title, _, outcome, winning_option) in cases.iter() {
+        let voting = find_voting_pda(title, &program_id).0;
+        send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+        let results = results(&mut context, &voting).await;
+        assert_eq!(results.outcome, *outcome, "{}", title);
+        assert_eq!(results.winning_option, *winning_option, "{}", title);
+    };
+}
+
+// The counters follow every ballot as it's cast and changed, finalization waits for the end
+#[tokio::test]
+async fn updated_ballots_move_their_count() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let title = "Lunch on Fridays";
+    voting(&mut context, creator, voters, title, &[ 0, 1 ]).await;
+    let voting = find_voting_pda(title, &program_id).0;
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 1, 1, 0 ]);
+
+    send(&mut context, &[ update_vote(&program_id, &voters[0].pubkey(), &voting, vec![ 2 ], false) ], &[ &voters[0] ]).await.unwrap();
+    let results_after = results(&mut context, &voting).await;
+    assert_eq!(results_after.tallies, vec![ 0, 1, 1 ]);
+    assert_eq!(results_after.total_voters, 2);
+
+    let finalize = || finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey());
+    assert_error(send(&mut context, &[ finalize() ], &[]).await, Errors::VotingNotEnded);
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize() ], &[]).await.unwrap();
+    assert_eq!(results(&mut context, &voting).await.outcome, VotingOutcome::QuorumNotMet);
+}
@@ -0,0 +1,78 @@
+// Instruction data carries the payload version, each payload is decoded by the layout of its version
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, vote, VoteProofs },
+    find_user_vote_pda,
+    find_voting_pda,
+    Errors,
+    VotingOutcome,
+    PAYLOAD_VERSION
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 24 * 60 * 60;
+const TITLE: &str = "Lunch on Fridays";
+// `winners_count`, `apportionment`, `min_participation`, `pass_threshold_bps`, `electorate` and `quorum_bps`
+// at their defaults
+const VERSION_2_FIELDS_LEN: usize = 1 + 1 + 4 + 2 + 1 + 2;
+
+#[tokio::test]
+async fn payloads_are_decoded_by_version() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    let ballot = vote(&program_id, &voter.pubkey(), &voting, vec![ 1 ], TITLE.to_string(), false, VoteProofs::default());
+    for version in [ 0, PAYLOAD_VERSION + 1 ] {
+        let mut instruction = ballot.clone();
+        instruction.data[8] = version;
+        let result = send(&mut context, &[ instruction ], &[ voter ]).await;
+        assert_error(result, Errors::UnsupportedPayloadVersion);
+    };
+
+    // A ballot without `dry_run` and `voter_index_page`, which the current version has
+    let mut instruction = ballot.clone();
+    instruction.data.truncate(instruction.data.len() - 5);
+    let result = send(&mut context, &[ instruction ], &[ voter ]).await;
+    assert_error(result, Errors::InstructionDataTooShort);
+
+    send(&mut context, &[ ballot ], &[ voter ]).await.unwrap();
+    let user_vote = find_user_vote_pda(&voting, &voter.pubkey(), &program_id).0;
+    assert!(context.banks_client.get_account(user_vote).await.unwrap().is_some());
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 0, 1 ]);
+}
+
+// A version 1 client leaves out the settings version 2 added, its voting has a single winner and no quorum
+#[tokio::test]
+async fn older_payloads_default_the_fields_added_since() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 2).await;
+    let (creator, voter) = (&accounts[0], &accounts[1]);
+
+    let mut create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    create.data[8] = 1;
+    create.data.truncate(create.data.len() - VERSION_2_FIELDS_LEN);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_pda(TITLE, &program_id).0;
+
+    send(&mut context, &[
+        vote(&program_id, &voter.pubkey(), &voting, vec![ 1 ], TITLE.to_string(), false, VoteProofs::default())
+    ], &[ voter ]).await.unwrap();
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.winners, vec![ 1 ]);
+    assert_eq!(results.outcome, VotingOutcome::Passed);
+}
@@ -0,0 +1,104 @@
+// The recount rebuilds the tallies from the user vote accounts and swaps them in at completion
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ complete_recount, create_voting, finalize_voting, recount_votes, start_recount, vote, VoteProofs },
+    find_user_vote_pda,
+    find_voting_pda,
+    Errors,
+    VoteMainAccount
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn finalized_votings_keep_their_tallies() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    for (voter, ballot) in voters.iter().zip([ vec![ 0 ], vec![ 1 ] ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, ballot, TITLE.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+
+    let mut user_votes: Vec<_> = voters.iter().map(|voter| find_user_vote_pda(&voting, &voter.pubkey(), &program_id).0).collect();
+    user_votes.sort();
+    send(&mut context, &[
+        start_recount(&program_id, &creator.pubkey(), &voting),
+        recount_votes(&program_id, &creator.pubkey(), &voting, &user_votes),
+        complete_recount(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 1, 1 ]);
+
+    // Counted before the voting is finalized, swapped in after
+    send(&mut context, &[
+        start_recount(&program_id, &creator.pubkey(), &voting),
+        recount_votes(&program_id, &creator.pubkey(), &voting, &user_votes)
+    ], &[ creator ]).await.unwrap();
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    let complete = complete_recount(&program_id, &creator.pubkey(), &voting);
+    assert_error(send(&mut context, &[ complete ], &[ creator ]).await, Errors::VotingFinalized);
+    let start = start_recount(&program_id, &creator.pubkey(), &voting);
+    assert_error(send(&mut context, &[ start ], &[ creator ]).await, Errors::VotingFinalized);
+}
+
+#[tokio::test]
+async fn corrupted_tallies_are_rebuilt_from_the_records() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    for (voter, ballot) in voters.iter().zip([ vec![ 0 ], vec![ 1 ], vec![ 1 ] ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, ballot, TITLE.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let mut account = context.banks_client.get_account(voting).await.unwrap().unwrap();
+    let mut voting_account = try_from_slice_unchecked::<VoteMainAccount>(&account.data).unwrap();
+    voting_account.tallies = vec![ 7, 0 ];
+    voting_account.serialize(&mut &mut account.data[..]).unwrap();
+    context.set_account(&voting, &account.into());
+
+    let mut user_votes: Vec<_> = voters.iter().map(|voter| find_user_vote_pda(&voting, &voter.pubkey(), &program_id).0).collect();
+    user_votes.sort();
+    send(&mut context, &[ start_recount(&program_id, &creator.pubkey(), &voting) ], &[ creator ]).await.unwrap();
+
+    // Records are counted once each, in address order across calls
+    let descending = recount_votes(&program_id, &creator.pubkey(), &voting, &[ user_votes[1], user_votes[0] ]);
+    assert_error(send(&mut context, &[ descending ], &[ creator ]).await, Errors::InvalidRecountOrder);
+    send(&mut context, &[ recount_votes(&program_id, &creator.pubkey(), &voting, &user_votes[..2]) ], &[ creator ]).await.unwrap();
+    let counted = recount_votes(&program_id, &creator.pubkey(), &voting, &user_votes[1..2]);
+    assert_error(send(&mut context, &[ counted ], &[ creator ]).await, Errors::InvalidRecountOrder);
+
+    let complete = || complete_recount(&program_id, &creator.pubkey(), &voting);
+    assert_error(send(&mut context, &[ complete() ], &[ creator ]).await, Errors::RecountIncomplete);
+    send(&mut context, &[
+        recount_votes(&program_id, &creator.pubkey(), &voting, &user_votes[2..]),
+        complete()
+    ], &[ creator ]).await.unwrap();
+
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 1, 2 ]);
+}
@@ -0,0 +1,75 @@
+// `create_voting` and `create_realm_voting` list every voting on the registry page taking the next one
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_realm, create_realm_voting, create_voting, set_registry_page },
+    decoder::{ decode_account, DecodedAccount },
+    find_realm_pda,
+    find_realm_voting_pda,
+    find_voting_pda,
+    find_voting_registry_page_pda,
+    find_voting_registry_pda,
+    voting_title_seed,
+    Errors
+};
+use common::{ assert_error, funded, program_test, send };
+
+const DURATION: u64 = 24 * 60 * 60;
+
+#[tokio::test]
+async fn votings_are_registered_in_creation_order() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1).await;
+    let creator = &accounts[0];
+
+    let titles = [ "Lunch on Fridays", "Lunch on Mondays" ];
+    for title in titles {
+        let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+        send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    };
+
+    // Page 0 takes votings until it is full
+    let create = create_voting(&program_id, &creator.pubkey(), "Lunch on Sundays".to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    let result = send(&mut context, &[ set_registry_page(create, 1) ], &[ creator ]).await;
+    assert_error(result, Errors::InvalidPdaAddress);
+
+    let registry = context.banks_client.get_account(find_voting_registry_pda(&program_id).0).await.unwrap().unwrap();
+    assert!(matches!(decode_account(&program_id, &registry.owner, &registry.data), Ok(Some(DecodedAccount::VotingRegistry(_)))));
+
+    let page = context.banks_client.get_account(find_voting_registry_page_pda(0, &program_id).0).await.unwrap().unwrap();
+    let entries = match decode_account(&program_id, &page.owner, &page.data).unwrap() {
+        Some(DecodedAccount::VotingRegistryPage(_, entries)) => entries,
+        decoded => panic!("not a voting registry page: {:?}", decoded)
+    };
+    assert_eq!(entries.len(), titles.len());
+    for (entry, title) in entries.iter().zip(titles) {
+        assert_eq!(entry.voting, find_voting_pda(title, &program_id).0);
+        assert_eq!(entry.title_hash, voting_title_seed(title));
+    };
+}
+
+#[tokio::test]
+async fn realm_votings_are_registered() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1).await;
+    let admin = &accounts[0];
+
+    let realm = find_realm_pda("Office", &program_id).0;
+    send(&mut context, &[
+        create_realm(&program_id, &admin.pubkey(), "Office".to_string()),
+        create_realm_voting(&program_id, &admin.pubkey(), &realm, &admin.pubkey(), 0, "Lunch on Fridays".to_string(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION)
+    ], &[ admin ]).await.unwrap();
+
+    let page = context.banks_client.get_account(find_voting_registry_page_pda(0, &program_id).0).await.unwrap().unwrap();
+    let entries = match decode_account(&program_id, &page.owner, &page.data).unwrap() {
+        Some(DecodedAccount::VotingRegistryPage(_, entries)) => entries,
+        decoded => panic!("not a voting registry page: {:?}", decoded)
+    };
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].voting, find_realm_voting_pda(&realm, 0, &program_id).0);
+    assert_eq!(entries[0].title_hash, voting_title_seed("Lunch on Fridays"));
+}
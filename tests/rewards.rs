@@ -0,0 +1,61 @@
+// Rewards are shared by the winning option's voters pro-rata to their weight, rounded down
+mod common;
+
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::signature::{ Keypair, Signer };
+
+use solana_voting_program::{
+    client::{ claim_winnings, create_voting, fund_rewards, vote, VoteProofs },
+    find_reward_claim_pda,
+    find_reward_escrow_pda,
+    find_voting_pda,
+    Errors,
+    RewardClaimAccount,
+    RewardEscrowAccount
+};
+use common::{ assert_error, funded, now, program_test, send, warp_to };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+const REWARDS: u64 = 1_001;
+
+#[tokio::test]
+async fn winners_share_the_rewards() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    send(&mut context, &[ create, fund_rewards(&program_id, &creator.pubkey(), &voting, REWARDS) ], &[ creator ]).await.unwrap();
+
+    for (voter, ballot) in voters.iter().zip([ vec![ 0 ], vec![ 0 ], vec![ 1 ] ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, ballot, TITLE.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let claim = |voter: &Keypair| claim_winnings(&program_id, &voter.pubkey(), &voting);
+    assert_error(send(&mut context, &[ claim(&voters[0]) ], &[ &voters[0] ]).await, Errors::VotingNotEnded);
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    let escrow = find_reward_escrow_pda(&voting, &program_id).0;
+    let escrow_before = context.banks_client.get_balance(escrow).await.unwrap();
+
+    for voter in voters[..2].iter() {
+        send(&mut context, &[ claim(voter) ], &[ voter ]).await.unwrap();
+
+        let claim_account = context.banks_client.get_account(find_reward_claim_pda(&voting, &voter.pubkey(), &program_id).0).await.unwrap().unwrap();
+        assert_eq!(try_from_slice_unchecked::<RewardClaimAccount>(&claim_account.data).unwrap().amount, REWARDS / 2);
+    };
+    assert_error(send(&mut context, &[ claim(&voters[0]) ], &[ &voters[0] ]).await, Errors::RewardAlreadyClaimed);
+    assert_error(send(&mut context, &[ claim(&voters[2]) ], &[ &voters[2] ]).await, Errors::NotWinningVoter);
+
+    // The lamport lost to rounding stays in the escrow
+    let escrow_account = context.banks_client.get_account(escrow).await.unwrap().unwrap();
+    assert_eq!(escrow_before - escrow_account.lamports, REWARDS - 1);
+    let escrow_data = try_from_slice_unchecked::<RewardEscrowAccount>(&escrow_account.data).unwrap();
+    assert_eq!((escrow_data.total_funded, escrow_data.total_claimed), (REWARDS, REWARDS - 1));
+}
@@ -0,0 +1,47 @@
+// Spoiled ballots count towards turnout and quorums with their weight, but never towards an option
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, set_electorate, set_pass_rules, vote, VoteProofs },
+    find_voting_pda,
+    Electorate,
+    VotingOutcome
+};
+use common::{ funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+#[tokio::test]
+async fn spoiled_ballots_make_the_quorum_but_not_the_winner() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    // 3 of 4 voters make the quorum, the winner needs 60% of the option tallies
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_electorate(set_pass_rules(create, 3, 6_000), Electorate::Voters(4), 7_000) ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    for (voter, ballot) in voters.iter().zip([ vec![ 1 ], vec![], vec![] ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, ballot, TITLE.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.tallies, vec![ 0, 1 ]);
+    assert_eq!(results.total_voters, 3);
+    assert_eq!(results.spoiled_ballots, 2);
+    assert_eq!(results.spoiled_weight, 2);
+    assert_eq!(results.turnout_bps, Some(7_500));
+    assert_eq!(results.outcome, VotingOutcome::Passed);
+    assert_eq!(results.winning_option, Some(1));
+}
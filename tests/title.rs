@@ -0,0 +1,73 @@
+// Titles sit in a fixed field, `update_title` changes their case and spacing before the voting opens
+mod common;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+use solana_voting_program::{
+    client::update_title,
+    create_voting,
+    decoder::{ decode_account, DecodedAccount },
+    find_voting_address,
+    Errors,
+    MAX_TITLE_BYTES,
+    MAX_TITLE_LENGTH
+};
+use common::{ assert_error, funded, now, program_test, send };
+
+const TITLE: &str = "Lunch on Fridays";
+
+async fn title(context: &mut ProgramTestContext, voting: &Pubkey) -> String {
+    let account = context.banks_client.get_account(*voting).await.unwrap().unwrap();
+    match decode_account(&solana_voting_program::id(), &account.owner, &account.data).unwrap() {
+        Some(DecodedAccount::Voting(voting)) => voting.title.to_string(),
+        decoded => panic!("not a voting: {:?}", decoded)
+    }
+}
+
+#[tokio::test]
+async fn titles_keep_their_address() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1).await;
+    let creator = &accounts[0];
+
+    let starts_at = now(&mut context).await + 60 * 60;
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), options.clone(), starts_at, starts_at + 60 * 60);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_address(TITLE, &program_id);
+
+    send(&mut context, &[ update_title(&program_id, &creator.pubkey(), &voting, "LUNCH on  FRIDAYS".to_string()) ], &[ creator ]).await.unwrap();
+    assert_eq!(title(&mut context, &voting).await, "LUNCH on  FRIDAYS");
+
+    let result = send(&mut context, &[ update_title(&program_id, &creator.pubkey(), &voting, "Lunch on Mondays".to_string()) ], &[ creator ]).await;
+    assert_error(result, Errors::InvalidPdaAddress);
+
+    // Few enough chars, but too many bytes for the fixed field
+    let long = "€".repeat(MAX_TITLE_LENGTH);
+    assert!(long.len() > MAX_TITLE_BYTES);
+    let create = create_voting(&program_id, &creator.pubkey(), long, options, starts_at, starts_at + 60 * 60);
+    assert_error(send(&mut context, &[ create ], &[ creator ]).await, Errors::TitleTooLong);
+}
+
+// "Café" typed with the precomposed é and with a combining accent is the same title
+#[tokio::test]
+async fn composed_and_decomposed_titles_match() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 1).await;
+    let creator = &accounts[0];
+
+    let (composed, decomposed) = ("Caf\u{e9} on Fridays", "Cafe\u{301} on Fridays");
+    assert_eq!(find_voting_address(composed, &program_id), find_voting_address(decomposed, &program_id));
+
+    let starts_at = now(&mut context).await + 60 * 60;
+    let options = vec![ "Pizza".to_string(), "Sushi".to_string() ];
+    let create = create_voting(&program_id, &creator.pubkey(), composed.to_string(), options, starts_at, starts_at + 60 * 60);
+    send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    let voting = find_voting_address(composed, &program_id);
+
+    send(&mut context, &[ update_title(&program_id, &creator.pubkey(), &voting, decomposed.to_string()) ], &[ creator ]).await.unwrap();
+    assert_eq!(title(&mut context, &voting).await, decomposed);
+}
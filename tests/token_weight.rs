@@ -0,0 +1,81 @@
+// Token-weighted votings count each ballot with the voter's balance of the voting's mint
+mod common;
+
+use solana_program::{ borsh0_10::try_from_slice_unchecked, program_option::COption, program_pack::Pack };
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{ account::Account, pubkey::Pubkey, signature::{ Keypair, Signer } };
+use spl_token_2022::state::{ Account as TokenAccount, AccountState };
+
+use solana_voting_program::{
+    client::{ add_weight_account, create_voting, set_weight_source, vote, VoteProofs },
+    find_user_vote_pda,
+    find_voting_pda,
+    weight::WeightSource,
+    Errors,
+    UserVotingAccount
+};
+use common::{ assert_error, funded, program_test, results, send };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+// A token account of `owner` holding `amount` of `mint`, set directly instead of minting
+async fn token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let token_account = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None
+    };
+    let mut data = vec![ 0; TokenAccount::LEN ];
+    token_account.pack_into_slice(&mut data);
+
+    let address = Pubkey::new_unique();
+    let lamports = context.banks_client.get_rent().await.unwrap().minimum_balance(data.len());
+    context.set_account(&address, &Account { lamports, data, owner: spl_token_2022::id(), executable: false, rent_epoch: 0 }.into());
+
+    address
+}
+
+#[tokio::test]
+async fn ballots_weigh_the_voters_balance() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let mint = Pubkey::new_unique();
+    let create = create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+    send(&mut context, &[ set_weight_source(create, WeightSource::TokenBalance(mint)) ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    let ballot = |voter: &Keypair, option: u8, weight_account: &Pubkey| {
+        let mut ballot = vote(&program_id, &voter.pubkey(), &voting, vec![ option ], TITLE.to_string(), false, VoteProofs::default());
+        add_weight_account(&mut ballot, weight_account);
+        ballot
+    };
+
+    // Another mint, another owner's account or an empty one don't weigh
+    let other_mint = token_account(&mut context, &Pubkey::new_unique(), &voters[0].pubkey(), 300).await;
+    assert_error(send(&mut context, &[ ballot(&voters[0], 0, &other_mint) ], &[ &voters[0] ]).await, Errors::InvalidWeightAccount);
+    let others = token_account(&mut context, &mint, &voters[1].pubkey(), 300).await;
+    assert_error(send(&mut context, &[ ballot(&voters[0], 0, &others) ], &[ &voters[0] ]).await, Errors::InvalidWeightAccount);
+    let empty = token_account(&mut context, &mint, &voters[2].pubkey(), 0).await;
+    assert_error(send(&mut context, &[ ballot(&voters[2], 0, &empty) ], &[ &voters[2] ]).await, Errors::ZeroVoteWeight);
+
+    for (voter, (option, amount)) in voters[..2].iter().zip([ (0, 300), (1, 50) ]) {
+        let weight_account = token_account(&mut context, &mint, &voter.pubkey(), amount).await;
+        send(&mut context, &[ ballot(voter, option, &weight_account) ], &[ voter ]).await.unwrap();
+    };
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.tallies, vec![ 300, 50 ]);
+    assert_eq!(results.total_voters, 2);
+
+    let user_vote = context.banks_client.get_account(find_user_vote_pda(&voting, &voters[0].pubkey(), &program_id).0).await.unwrap().unwrap();
+    assert_eq!(try_from_slice_unchecked::<UserVotingAccount>(&user_vote.data).unwrap().weight, 300);
+}
@@ -0,0 +1,123 @@
+// The read-only views, simulated for their return data. With `--features pinocchio` they are answered
+// by the lazy entrypoint straight from the input, these pin it to the same checks and results.
+mod common;
+
+use borsh::BorshDeserialize;
+use solana_program::instruction::{ AccountMeta, Instruction, InstructionError };
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::Signer,
+    transaction::{ Transaction, TransactionError }
+};
+
+use solana_voting_program::{
+    client::{ create_voting, get_my_vote, get_option_capacity, get_percentages, get_results, vote, VoteProofs },
+    find_voting_pda,
+    Errors,
+    MyVoteReturnData,
+    OptionCapacityReturnData,
+    PercentagesReturnData
+};
+use common::{ funded, program_test, results, send };
+
+const DURATION: u64 = 60;
+const TITLE: &str = "Lunch on Fridays";
+
+// Return data of the simulated view, or the error it failed with
+async fn view(context: &mut ProgramTestContext, instruction: Instruction) -> Result<Vec<u8>, InstructionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[ instruction ],
+        Some(&context.payer.pubkey()),
+        &[ &context.payer ],
+        context.last_blockhash
+    );
+
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    match simulation.result.unwrap() {
+        Ok(()) => Ok(simulation.simulation_details.and_then(|details| details.return_data).map(|return_data| return_data.data).unwrap_or_default()),
+        Err(TransactionError::InstructionError(0, error)) => Err(error),
+        Err(error) => panic!("{:?}", error)
+    }
+}
+
+#[tokio::test]
+async fn views_read_the_voting_and_ballots() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters, newcomer) = (&accounts[0], &accounts[1..3], &accounts[3]);
+
+    let options = [ "Pizza", "Sushi", "Tacos" ].iter().map(|option| option.to_string()).collect::<Vec<_>>();
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options, DURATION)
+    ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    for (voter, option) in voters.iter().zip([ 0, 1 ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, vec![ option ], TITLE.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    assert_eq!(results(&mut context, &voting).await.tallies, vec![ 1, 1, 0 ]);
+
+    let percentages = view(&mut context, get_percentages(&program_id, &voting, 0)).await.unwrap();
+    assert_eq!(PercentagesReturnData::try_from_slice(&percentages).unwrap().percentages, vec![ 50, 50, 0 ]);
+
+    let capacity = view(&mut context, get_option_capacity(&program_id, &voting, 1)).await.unwrap();
+    let capacity = OptionCapacityReturnData::try_from_slice(&capacity).unwrap();
+    assert_eq!((capacity.tally, capacity.remaining), (1, None));
+    assert_eq!(
+        view(&mut context, get_option_capacity(&program_id, &voting, 3)).await,
+        Err(InstructionError::Custom(Errors::InvalidOptionIndex.code()))
+    );
+
+    let my_vote = view(&mut context, get_my_vote(&program_id, &voters[1].pubkey(), &voting)).await.unwrap();
+    let my_vote = MyVoteReturnData::try_from_slice(&my_vote).unwrap();
+    assert_eq!((my_vote.choice, my_vote.weight, my_vote.times_updated), (1, 1, 0));
+    assert_eq!(
+        view(&mut context, get_my_vote(&program_id, &newcomer.pubkey(), &voting)).await,
+        Err(InstructionError::Custom(Errors::InvalidAccountOwner.code()))
+    );
+}
+
+#[tokio::test]
+async fn views_check_their_accounts() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 3).await;
+    let (creator, voter, newcomer) = (&accounts[0], &accounts[1], &accounts[2]);
+
+    let options = [ "Yes", "No" ].iter().map(|option| option.to_string()).collect::<Vec<_>>();
+    send(&mut context, &[
+        create_voting(&program_id, &creator.pubkey(), TITLE.to_string(), String::new(), options, DURATION)
+    ], &[ creator ]).await.unwrap();
+
+    let voting = find_voting_pda(TITLE, &program_id).0;
+    send(&mut context, &[
+        vote(&program_id, &voter.pubkey(), &voting, vec![ 0 ], TITLE.to_string(), false, VoteProofs::default())
+    ], &[ voter ]).await.unwrap();
+
+    let mut extra_account = get_results(&program_id, &voting);
+    extra_account.accounts.push(AccountMeta::new_readonly(voting, false));
+    assert_eq!(view(&mut context, extra_account).await, Err(InstructionError::InvalidArgument));
+
+    let mut no_account = get_results(&program_id, &voting);
+    no_account.accounts.clear();
+    assert_eq!(view(&mut context, no_account).await, Err(InstructionError::NotEnoughAccountKeys));
+
+    assert_eq!(
+        view(&mut context, get_results(&program_id, &creator.pubkey())).await,
+        Err(InstructionError::Custom(Errors::InvalidAccountOwner.code()))
+    );
+
+    // The voter's ballot looked up for someone else
+    let mut other_user = get_my_vote(&program_id, &voter.pubkey(), &voting);
+    other_user.accounts[0].pubkey = newcomer.pubkey();
+    assert_eq!(view(&mut context, other_user).await, Err(InstructionError::Custom(Errors::InvalidPdaAddress.code())));
+
+    // The voting passed twice, as the voting and as the ballot
+    let mut duplicated = get_my_vote(&program_id, &voter.pubkey(), &voting);
+    duplicated.accounts[2].pubkey = voting;
+    assert_eq!(view(&mut context, duplicated).await, Err(InstructionError::Custom(Errors::InvalidPdaAddress.code())));
+}
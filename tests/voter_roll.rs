@@ -0,0 +1,64 @@
+// `seal_voter_roll` commits to the eligible voters once the voting opens, ballots then prove they're on it
+mod common;
+
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+use solana_voting_program::{
+    client::{ create_voting, seal_voter_roll, vote, AllowlistTree, VoteProofs },
+    eligibility::EligibilityProof,
+    find_voting_pda,
+    Errors
+};
+use common::{ assert_error, funded, program_test, send };
+
+const DURATION: u64 = 24 * 60 * 60;
+const TITLES: [&str; 2] = [ "Lunch on Fridays", "Lunch on Mondays" ];
+
+#[tokio::test]
+async fn ballots_prove_the_sealed_roll() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 4).await;
+    let (creator, voters, outsider) = (&accounts[0], &accounts[1..3], &accounts[3]);
+
+    for title in TITLES {
+        let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), vec![ "Pizza".to_string(), "Sushi".to_string() ], DURATION);
+        send(&mut context, &[ create ], &[ creator ]).await.unwrap();
+    };
+    let votings = TITLES.map(|title| find_voting_pda(title, &program_id).0);
+
+    let roll = AllowlistTree::new(&voters.iter().map(|voter| voter.pubkey()).collect::<Vec<_>>());
+    let result = send(&mut context, &[ seal_voter_roll(&program_id, &outsider.pubkey(), &votings[0], roll.root(), 2) ], &[ outsider ]).await;
+    assert_error(result, Errors::NotVotingCreator);
+
+    send(&mut context, &[ seal_voter_roll(&program_id, &creator.pubkey(), &votings[0], roll.root(), 2) ], &[ creator ]).await.unwrap();
+    let result = send(&mut context, &[ seal_voter_roll(&program_id, &creator.pubkey(), &votings[0], [ 0; 32 ], 0) ], &[ creator ]).await;
+    assert_error(result, Errors::VoterRollSealed);
+
+    let proofs = |voter: &Pubkey| VoteProofs {
+        weight: None,
+        eligibility: Some(EligibilityProof { clause: 0, allowlist_proof: Vec::new(), roll_proof: roll.proof(voter).unwrap_or_default() })
+    };
+    let result = send(&mut context, &[
+        vote(&program_id, &outsider.pubkey(), &votings[0], vec![ 0 ], TITLES[0].to_string(), false, proofs(&outsider.pubkey()))
+    ], &[ outsider ]).await;
+    assert_error(result, Errors::NotOnVoterRoll);
+
+    let result = send(&mut context, &[
+        vote(&program_id, &voters[0].pubkey(), &votings[0], vec![ 0 ], TITLES[0].to_string(), false, VoteProofs::default())
+    ], &[ &voters[0] ]).await;
+    assert_error(result, Errors::NotOnVoterRoll);
+
+    for voter in voters {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &votings[0], vec![ 1 ], TITLES[0].to_string(), false, proofs(&voter.pubkey()))
+        ], &[ voter ]).await.unwrap();
+    };
+
+    // Once a ballot is in, the roll can't be sealed anymore
+    send(&mut context, &[
+        vote(&program_id, &outsider.pubkey(), &votings[1], vec![ 0 ], TITLES[1].to_string(), false, VoteProofs::default())
+    ], &[ outsider ]).await.unwrap();
+    let result = send(&mut context, &[ seal_voter_roll(&program_id, &creator.pubkey(), &votings[1], roll.root(), 2) ], &[ creator ]).await;
+    assert_error(result, Errors::VoterRollClosed);
+}
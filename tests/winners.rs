@@ -0,0 +1,58 @@
+// Votings electing more than one option record their winners on finalization
+mod common;
+
+use solana_sdk::signature::Signer;
+
+use solana_voting_program::{
+    client::{ create_voting, finalize_voting, set_winners_count, sync_poll_card, vote, VoteProofs },
+    find_voting_pda,
+    tally::top_options,
+    Errors
+};
+use common::{ assert_error, funded, now, program_test, results, send, warp_to };
+
+const DURATION: u64 = 60;
+
+#[test]
+fn top_options_are_ordered_by_count_then_index() {
+    assert_eq!(top_options(&[ 1, 2, 2, 0 ], 2), vec![ 1, 2 ]);
+    assert_eq!(top_options(&[ 3, 2, 2, 0 ], 2), vec![ 0, 1 ]);
+    // Options without votes don't place
+    assert_eq!(top_options(&[ 0, 4, 0 ], 3), vec![ 1 ]);
+}
+
+#[tokio::test]
+async fn finalization_records_the_winners() {
+    let program_id = solana_voting_program::id();
+    let mut context = program_test().start_with_context().await;
+    let accounts = funded(&mut context, 6).await;
+    let (creator, voters) = (&accounts[0], &accounts[1..]);
+
+    let title = "Lunch for two weeks";
+    let voting = find_voting_pda(title, &program_id).0;
+    let options = [ "Pizza", "Sushi", "Tacos" ].iter().map(|option| option.to_string()).collect::<Vec<_>>();
+
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options.clone(), DURATION);
+    let result = send(&mut context, &[ set_winners_count(create, 4) ], &[ creator ]).await;
+    assert_error(result, Errors::InvalidWinnersCount);
+
+    let create = create_voting(&program_id, &creator.pubkey(), title.to_string(), String::new(), options, DURATION);
+    send(&mut context, &[
+        set_winners_count(create, 2),
+        sync_poll_card(&program_id, &creator.pubkey(), &voting)
+    ], &[ creator ]).await.unwrap();
+
+    for (voter, option) in voters.iter().zip([ 2, 2, 0, 1, 1 ]) {
+        send(&mut context, &[
+            vote(&program_id, &voter.pubkey(), &voting, vec![ option ], title.to_string(), false, VoteProofs::default())
+        ], &[ voter ]).await.unwrap();
+    };
+
+    let ended_at = now(&mut context).await + DURATION + 1;
+    warp_to(&mut context, ended_at).await;
+    send(&mut context, &[ finalize_voting(&program_id, &voting, &creator.pubkey(), &creator.pubkey()) ], &[]).await.unwrap();
+
+    let results = results(&mut context, &voting).await;
+    assert_eq!(results.winners, vec![ 1, 2 ]);
+    assert_eq!(results.winning_option, Some(1));
+}
@@ -0,0 +1,122 @@
+// Properties of the voting window over arbitrary (starts_at, ends_at, current_time) triples, with the
+// generated times leaning on the edges: 0, the u32 range `MAX_VOTING_TIME` is declared in, and the top
+// of u64 where sums overflow.
+use proptest::prelude::*;
+use solana_program::{ entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey };
+
+use crate::*;
+
+fn time() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        any::<u64>(),
+        0..=3u64,
+        (u32::MAX as u64 - 3)..=(u32::MAX as u64 + 3),
+        (MAX_VOTING_TIME as u64 - 3)..=(MAX_VOTING_TIME as u64 + 3),
+        (u64::MAX - 3)..=u64::MAX
+    ]
+}
+
+// A start, an end a bounded distance from it either way, and a time around both
+fn window() -> impl Strategy<Value = (u64, u64, u64)> {
+    (time(), -3i64..=(2 * MAX_VOTING_TIME as i64), -3i64..=3, any::<bool>(), time()).prop_map(|(starts_at, duration, offset, near, far)| {
+        let ends_at = starts_at.saturating_add_signed(duration);
+        let anchor = if duration % 2 == 0 { starts_at } else { ends_at };
+        let current_time = if near { anchor.saturating_add_signed(offset) } else { far };
+
+        (starts_at, ends_at, current_time)
+    })
+}
+
+pub(crate) fn create_instruction(starts_at: u64, ends_at: u64) -> CreateVotingInstruction {
+    CreateVotingInstruction::new("Lunch on Fridays".to_string(), vec![ "Pizza".to_string(), "Sushi".to_string() ], starts_at, ends_at)
+}
+
+// Any window, including those `create_voting` rejects, as an account could hold after an extension
+fn voting(starts_at: u64, ends_at: u64, reveal_window: u64) -> VoteMainAccount {
+    let ix_data = CreateVotingInstruction { reveal_window, ..create_instruction(starts_at, ends_at) };
+
+    VoteMainAccount::new(Pubkey::new_unique(), ix_data, Pubkey::default(), 0, &DEFAULT_VOTING_SETTINGS).unwrap()
+}
+
+fn error(result: ProgramResult) -> Option<ProgramError> {
+    result.err()
+}
+
+proptest! {
+    #[test]
+    fn ballots_only_inside_the_window((starts_at, ends_at, current_time) in window()) {
+        let voting = voting(starts_at, ends_at, 0);
+        let inside = starts_at <= current_time && current_time < ends_at;
+
+        prop_assert_eq!(voting.check_accepts_votes(current_time).is_ok(), inside);
+        if current_time < starts_at {
+            prop_assert_eq!(error(voting.check_accepts_votes(current_time)), Some(Errors::VotingNotStarted.into()));
+        };
+    }
+
+    #[test]
+    fn status_follows_the_clock((starts_at, ends_at, current_time) in window()) {
+        let mut voting = voting(starts_at, ends_at, 0);
+        voting.sync_status(current_time);
+
+        let expected = if current_time < starts_at {
+            VotingStatus::Pending
+        } else if ends_at <= current_time {
+            VotingStatus::Ended
+        } else {
+            VotingStatus::Active
+        };
+        prop_assert_eq!(voting.status, expected);
+        prop_assert_eq!(voting.check_accepts_votes(current_time).is_ok(), expected == VotingStatus::Active);
+
+        // A second sync at the same time changes nothing
+        prop_assert!(voting.sync_status(current_time) == false);
+    }
+
+    #[test]
+    fn creation_takes_only_forward_windows_within_the_cap(
+        (starts_at, ends_at, current_time) in window(),
+        tolerance in prop_oneof![ Just(0u64), Just(DEFAULT_START_TIME_TOLERANCE), Just(MAX_START_TIME_TOLERANCE) ]
+    ) {
+        let ix_data = create_instruction(starts_at, ends_at);
+        let result = validate_new_voting(&ix_data, 1, &DEFAULT_VOTING_SETTINGS, current_time, tolerance);
+
+        if result.is_ok() {
+            prop_assert!(starts_at < ends_at);
+            prop_assert!(ends_at - starts_at <= MAX_VOTING_TIME as u64);
+            prop_assert!(starts_at.saturating_add(tolerance) >= current_time);
+        };
+
+        if ends_at <= starts_at && starts_at.saturating_add(tolerance) >= current_time {
+            prop_assert_eq!(error(result), Some(Errors::InvalidEndingTime.into()));
+        } else if ends_at > starts_at && ends_at - starts_at > MAX_VOTING_TIME as u64 && starts_at.saturating_add(tolerance) >= current_time {
+            prop_assert_eq!(error(result), Some(Errors::MaxVotingTimeExceeded.into()));
+        };
+    }
+
+    #[test]
+    fn reveals_only_after_the_end((starts_at, ends_at, current_time) in window(), reveal_window in time()) {
+        let voting = voting(starts_at, ends_at, reveal_window);
+        let open = ends_at <= current_time && current_time < ends_at.saturating_add(reveal_window);
+
+        prop_assert_eq!(voting.check_reveal_open(current_time).is_ok(), open);
+        // Ballots and reveals never overlap
+        prop_assert!(voting.check_reveal_open(current_time).is_err() || voting.check_accepts_votes(current_time).is_err());
+    }
+
+    #[test]
+    fn late_registration_ends_inside_the_window(
+        starts_at in time(),
+        duration in 1..=(MAX_VOTING_TIME as u64),
+        percent in 0..=100u8
+    ) {
+        prop_assume!(starts_at.checked_add(duration).is_some());
+        let voting = voting(starts_at, starts_at + duration, 0);
+        let ends_at = voting.late_registration_ends_at(percent);
+
+        prop_assert!(starts_at <= ends_at && ends_at <= starts_at + duration);
+        if percent == 100 {
+            prop_assert_eq!(ends_at, starts_at + duration);
+        };
+    }
+}
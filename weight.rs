@@ -0,0 +1,339 @@
+use borsh::{ BorshDeserialize, BorshSerialize };
+use solana_program::{
+    account_info::{ next_account_info, AccountInfo },
+    borsh0_10::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    keccak::hashv,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    stake::{ self, state::StakeStateV2 }
+};
+use spl_token_2022::{ extension::StateWithExtensions, state::{ Account as TokenAccount, AccountState, Mint } };
+
+use crate::{ checks::assert_owned_by, AccountType, Errors };
+
+// How much a ballot weighs, chosen when the voting is created. New modes only need a `resolve` arm,
+// tallies just add up whatever it returns.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum WeightSource {
+    // Every ballot weighs the same
+    Fixed(u64),
+    // Balance of the voter's token account of the mint, SPL Token or Token-2022
+    TokenBalance(Pubkey),
+    // Delegated lamports of an active stake account the voter is the staker of
+    Stake,
+    // Weights committed up front, leaves are keccak(voter, weight as u64 le) and pairs are hashed sorted
+    MerkleSnapshot([u8; 32]),
+    // A `VoterWeightRecord` owned by the plugin program
+    Plugin(Pubkey),
+    // Balance of the voter's token account of one of the voting's `weight_mints`, scaled by the mint's ratio
+    MultiToken,
+    // Staked lamports, either the delegation of an active stake account as for `Stake`, or the lamports
+    // the voter's liquid staking tokens are worth in their stake pool. `weight_mints` are the pool mints
+    // accepted, their ratios apply on top of the pool's rate.
+    StakedSol
+}
+
+// Exchange ratio of a mint on `MultiToken` votings, e.g. 12_000 for a staked derivative counting 1.2x
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MintRatio {
+    pub mint: Pubkey,
+    // In basis points, at most `MAX_MINT_RATIO_BPS`
+    pub ratio_bps: u32
+}
+
+// Sent with the ballot on `MerkleSnapshot` votings
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct WeightProof {
+    pub weight: u64,
+    pub proof: Vec<[u8; 32]>
+}
+
+// The voting's settings `resolve` reads next to the source
+#[derive(Debug, Clone, Copy)]
+pub struct WeightSettings<'b> {
+    pub weight_mints: &'b [MintRatio],
+    pub precision: Option<u8>,
+    // Frozen token accounts are rejected and amounts approved to a delegate don't count, so custodians
+    // and freeze authorities can't vote balances they control
+    pub reject_encumbered: bool
+}
+
+// Written by weight plugins for a single voter and voting
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VoterWeightRecord {
+    pub discriminator: [u8; 8],
+    pub voting: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64
+}
+
+// Max serialized size of a `WeightSource`
+pub const WEIGHT_SOURCE_SPACE: usize = 1 + 32;
+// Limits of a voting's `weight_mints`
+pub const MAX_WEIGHT_MINTS: usize = 4;
+pub const MAX_MINT_RATIO_BPS: u32 = 100_000;
+pub const MINT_RATIO_SPACE: usize = 32 + 4;
+// Max decimals token weights can be converted to
+pub const MAX_WEIGHT_PRECISION: u8 = 9;
+
+// The SPL stake pool program, running the pools behind most liquid staking tokens
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+// Offsets in its `StakePool` account, read directly to not depend on the pool crate
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+const STAKE_POOL_MINT_OFFSET: usize = 162;
+const STAKE_POOL_TOTAL_LAMPORTS_OFFSET: usize = 258;
+const STAKE_POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+
+impl WeightSource {
+    // `weight_mints` are only set on `MultiToken` and `StakedSol` votings, each mint once. `precision` only
+    // applies to token balances, `reject_encumbered` to token accounts.
+    pub fn validate(&self, settings: &WeightSettings) -> ProgramResult {
+        let WeightSettings { weight_mints, precision, reject_encumbered } = *settings;
+
+        if *self == Self::Fixed(0) {
+            return Err(Errors::InvalidWeightSource.into());
+        };
+
+        if reject_encumbered && matches!(self, Self::TokenBalance(_) | Self::MultiToken | Self::StakedSol) == false {
+            return Err(Errors::InvalidWeightSource.into());
+        };
+
+        if let Some(precision) = precision {
+            if matches!(self, Self::TokenBalance(_) | Self::MultiToken) == false || precision > MAX_WEIGHT_PRECISION {
+                return Err(Errors::InvalidWeightSource.into());
+            };
+        };
+
+        let mints_valid = match self {
+            Self::MultiToken | Self::StakedSol => {
+                weight_mints.is_empty() == false
+                    && weight_mints.len() <= MAX_WEIGHT_MINTS
+                    && weight_mints.iter().enumerate().all(|(index, mint_ratio)| {
+                        mint_ratio.ratio_bps > 0
+                            && mint_ratio.ratio_bps <= MAX_MINT_RATIO_BPS
+                            && weight_mints[..index].iter().all(|other| other.mint != mint_ratio.mint)
+                    })
+            },
+            _ => weight_mints.is_empty()
+        };
+        if mints_valid == false {
+            return Err(Errors::InvalidWeightMints.into());
+        };
+
+        Ok(())
+    }
+
+    // Takes the weight accounts of the source from `accounts`. Token and stake balances are read when the
+    // ballot is cast, so the same tokens can vote again from another wallet, `MerkleSnapshot` avoids that.
+    // With a `precision`, the mint follows the token account and balances are converted from the mint's
+    // decimals, so large supplies of 9-decimal mints still fit the tallies.
+    pub fn resolve<'a>(
+        &self,
+        voting: &Pubkey,
+        voter: &Pubkey,
+        accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+        proof: Option<&WeightProof>,
+        settings: &WeightSettings
+    ) -> Result<u64, ProgramError> {
+        let WeightSettings { weight_mints, precision, reject_encumbered } = *settings;
+
+        let weight = match (self, proof) {
+            (Self::Fixed(weight), None) => *weight,
+            (Self::TokenBalance(mint), None) => {
+                let token_account_data = owned_token_account(next_account_info(accounts)?, mint, voter, Errors::InvalidWeightAccount)?;
+                let amount = countable_balance(&token_account_data, reject_encumbered)?;
+                normalize_decimals(amount, mint, accounts, precision)?
+            },
+            (Self::Stake, None) => {
+                active_stake(next_account_info(accounts)?, voter, Errors::InvalidWeightAccount)?
+            },
+            (Self::MerkleSnapshot(root), Some(proof)) => {
+                let leaf = hashv(&[voter.as_ref(), &proof.weight.to_le_bytes()]).0;
+                if verify_merkle_proof(leaf, &proof.proof, root) == false {
+                    return Err(Errors::InvalidWeightProof.into());
+                };
+
+                proof.weight
+            },
+            (Self::Plugin(plugin_program), None) => {
+                let record = next_account_info(accounts)?;
+                assert_owned_by(record, plugin_program)?;
+
+                let data = record.data.borrow();
+                if AccountType::from_discriminator(&data) != Some(AccountType::VoterWeightRecord) {
+                    return Err(ProgramError::InvalidAccountData);
+                };
+
+                let record_data = try_from_slice_unchecked::<VoterWeightRecord>(&data)?;
+                if record_data.voting != *voting || record_data.voter != *voter {
+                    return Err(Errors::InvalidWeightAccount.into());
+                };
+
+                record_data.weight
+            },
+            (Self::MultiToken, None) => {
+                let (amount, mint) = scaled_token_balance(next_account_info(accounts)?, weight_mints, voter, reject_encumbered)?;
+                normalize_decimals(amount, &mint, accounts, precision)?
+            },
+            (Self::StakedSol, None) => {
+                let weight_account = next_account_info(accounts)?;
+                if *weight_account.owner == stake::program::id() {
+                    active_stake(weight_account, voter, Errors::InvalidWeightAccount)?
+                } else {
+                    let (amount, mint) = scaled_token_balance(weight_account, weight_mints, voter, reject_encumbered)?;
+                    pool_token_lamports(amount, &mint, next_account_info(accounts)?)?
+                }
+            },
+            _ => return Err(Errors::InvalidWeightProof.into())
+        };
+
+        if weight == 0 {
+            return Err(Errors::ZeroVoteWeight.into());
+        };
+
+        Ok(weight)
+    }
+}
+
+// Balance of an SPL Token or Token-2022 account, `error` if it isn't the owner's account of the mint
+pub(crate) fn token_balance(token_account: &AccountInfo, mint: &Pubkey, owner: &Pubkey, error: Errors) -> Result<u64, ProgramError> {
+    Ok(owned_token_account(token_account, mint, owner, error)?.amount)
+}
+
+fn owned_token_account(token_account: &AccountInfo, mint: &Pubkey, owner: &Pubkey, error: Errors) -> Result<TokenAccount, ProgramError> {
+    spl_token_2022::check_spl_token_program_account(token_account.owner)?;
+
+    let data = token_account.data.borrow();
+    let token_account_data = StateWithExtensions::<TokenAccount>::unpack(&data)?.base;
+    if token_account_data.mint != *mint || token_account_data.owner != *owner {
+        return Err(error.into());
+    };
+
+    Ok(token_account_data)
+}
+
+// The part of the balance that weighs, see `WeightSettings::reject_encumbered`
+fn countable_balance(token_account_data: &TokenAccount, reject_encumbered: bool) -> Result<u64, ProgramError> {
+    if reject_encumbered == false {
+        return Ok(token_account_data.amount);
+    };
+
+    if token_account_data.state == AccountState::Frozen {
+        return Err(Errors::FrozenWeightAccount.into());
+    };
+
+    match token_account_data.delegate.is_some() {
+        true => Ok(token_account_data.amount.saturating_sub(token_account_data.delegated_amount)),
+        false => Ok(token_account_data.amount)
+    }
+}
+
+// Balance of the voter's token account of any of the mints, times the mint's ratio, and the mint
+fn scaled_token_balance(
+    token_account: &AccountInfo,
+    weight_mints: &[MintRatio],
+    voter: &Pubkey,
+    reject_encumbered: bool
+) -> Result<(u64, Pubkey), ProgramError> {
+    spl_token_2022::check_spl_token_program_account(token_account.owner)?;
+
+    let data = token_account.data.borrow();
+    let token_account_data = StateWithExtensions::<TokenAccount>::unpack(&data)?.base;
+    let mint_ratio = weight_mints.iter().find(|mint_ratio| mint_ratio.mint == token_account_data.mint);
+    match mint_ratio {
+        Some(mint_ratio) if token_account_data.owner == *voter => {
+            let amount = countable_balance(&token_account_data, reject_encumbered)?;
+            let amount = u64::try_from(amount as u128 * mint_ratio.ratio_bps as u128 / 10_000)
+                .or(Err(ProgramError::ArithmeticOverflow))?;
+
+            Ok((amount, mint_ratio.mint))
+        },
+        _ => Err(Errors::InvalidWeightAccount.into())
+    }
+}
+
+// Converts a token amount from the decimals of `mint`, the next account, to `precision` decimals. Amounts
+// are kept as they are without a precision.
+fn normalize_decimals<'a>(
+    amount: u64,
+    mint: &Pubkey,
+    accounts: &mut std::slice::Iter<'a, AccountInfo<'a>>,
+    precision: Option<u8>
+) -> Result<u64, ProgramError> {
+    let precision = match precision {
+        Some(precision) => precision,
+        None => return Ok(amount)
+    };
+
+    let mint_account = next_account_info(accounts)?;
+    spl_token_2022::check_spl_token_program_account(mint_account.owner)?;
+    if mint_account.key != mint {
+        return Err(Errors::InvalidWeightAccount.into());
+    };
+
+    let decimals = StateWithExtensions::<Mint>::unpack(&mint_account.data.borrow())?.base.decimals;
+    let normalized = if decimals >= precision {
+        // Scales past u128 leave nothing of the amount
+        10u128.checked_pow((decimals - precision) as u32).map_or(0, |scale| amount as u128 / scale)
+    } else {
+        amount as u128 * 10u128.pow((precision - decimals) as u32)
+    };
+
+    u64::try_from(normalized).or(Err(Errors::TallyOverflow.into()))
+}
+
+// Lamports `amount` tokens of the pool minting `mint` are worth, at the rate of the pool's last update
+fn pool_token_lamports(amount: u64, mint: &Pubkey, stake_pool: &AccountInfo) -> Result<u64, ProgramError> {
+    assert_owned_by(stake_pool, &STAKE_POOL_PROGRAM_ID)?;
+
+    let data = stake_pool.data.borrow();
+    if data.len() < STAKE_POOL_TOKEN_SUPPLY_OFFSET + 8
+        || data[0] != STAKE_POOL_ACCOUNT_TYPE
+        || data[STAKE_POOL_MINT_OFFSET..STAKE_POOL_MINT_OFFSET + 32] != *mint.as_ref()
+    {
+        return Err(Errors::InvalidWeightAccount.into());
+    };
+
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let total_lamports = read_u64(STAKE_POOL_TOTAL_LAMPORTS_OFFSET);
+    let pool_token_supply = read_u64(STAKE_POOL_TOKEN_SUPPLY_OFFSET);
+    if pool_token_supply == 0 {
+        return Ok(0);
+    };
+
+    u64::try_from(amount as u128 * total_lamports as u128 / pool_token_supply as u128)
+        .or(Err(Errors::TallyOverflow.into()))
+}
+
+// Delegated lamports of a stake account, `error` if it isn't active or `staker` isn't its staker
+pub(crate) fn active_stake(stake_account: &AccountInfo, staker: &Pubkey, error: Errors) -> Result<u64, ProgramError> {
+    assert_owned_by(stake_account, &stake::program::id())?;
+
+    match try_from_slice_unchecked::<StakeStateV2>(&stake_account.data.borrow())? {
+        StakeStateV2::Stake(meta, stake, _)
+            if meta.authorized.staker == *staker && stake.delegation.deactivation_epoch == u64::MAX =>
+        {
+            Ok(stake.delegation.stake)
+        },
+        _ => Err(error.into())
+    }
+}
+
+// Pairs are hashed in sorted order, so proofs don't need to say which side each sibling is on
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let mut node = leaf;
+    for sibling in proof.iter() {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).0
+        } else {
+            hashv(&[sibling, &node]).0
+        };
+    };
+
+    node == *root
+}